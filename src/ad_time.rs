@@ -0,0 +1,105 @@
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+
+/// 100ns intervals between 1601-01-01 (FILETIME epoch) and 1970-01-01
+const FILETIME_UNIX_OFFSET: i64 = 116_444_736_000_000_000;
+const FILETIME_TICKS_PER_SECOND: i64 = 10_000_000;
+
+/// Display format for all timestamps in reports, always with an explicit UTC offset
+pub const DISPLAY_FORMAT: &str = "%d-%m-%Y %H:%M:%S %Z (UTC%:z)";
+
+/// Format a timestamp for display in `timezone` (--timezone); every output format uses this
+pub fn format_timestamp(timestamp: &DateTime<Utc>, timezone: Tz) -> String {
+    timestamp.with_timezone(&timezone).format(DISPLAY_FORMAT).to_string()
+}
+
+/// Date part of `DISPLAY_FORMAT`, for findings that only need the day
+pub const DISPLAY_DATE_FORMAT: &str = "%d-%m-%Y";
+
+/// Format the calendar date of a timestamp as seen in `timezone`
+pub fn format_date(timestamp: &DateTime<Utc>, timezone: Tz) -> String {
+    timestamp.with_timezone(&timezone).format(DISPLAY_DATE_FORMAT).to_string()
+}
+
+/// Convert an AD FILETIME attribute (pwdLastSet, lastLogonTimestamp, accountExpires, ...)
+/// to a UTC timestamp, truncated to whole seconds
+///
+/// 0 and i64::MAX are sentinels for "never" / "never expires" and yield None, as do
+/// negative values and anything that is not an integer.
+pub fn parse_filetime(value: &str) -> Option<DateTime<Utc>> {
+    let ticks = value.trim().parse::<i64>().ok()?;
+    if ticks <= 0 || ticks == i64::MAX {
+        return None;
+    }
+
+    let unix_ticks = ticks - FILETIME_UNIX_OFFSET;
+    DateTime::from_timestamp(unix_ticks.div_euclid(FILETIME_TICKS_PER_SECOND), 0)
+}
+
+/// Convert an AD interval attribute (maxPwdAge, msDS-MaximumPasswordAge, ...) to a duration
+///
+/// Intervals are stored as negative 100ns tick counts. 0 and i64::MIN mean "no limit" and
+/// yield None, as does anything that is not an integer.
+pub fn parse_filetime_interval(value: &str) -> Option<Duration> {
+    let ticks = value.trim().parse::<i64>().ok()?;
+    if ticks == 0 || ticks == i64::MIN {
+        return None;
+    }
+
+    Some(Duration::seconds(ticks.abs() / FILETIME_TICKS_PER_SECOND))
+}
+
+/// Convert an LDAP GeneralizedTime attribute (whenCreated, whenChanged) to UTC
+///
+/// Accepts `YYYYMMDDHHMMSSZ`, an optional fraction of a second (`.0Z`, `.123Z`, `,5Z`)
+/// and a numeric offset instead of `Z` (`+0200`, `-05`). Values without a time zone
+/// are local time on the server and are rejected.
+pub fn parse_generalized_time(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    let (datetime, rest) = (value.get(..14)?, value.get(14..)?);
+    if !datetime.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let naive = NaiveDateTime::parse_from_str(datetime, "%Y%m%d%H%M%S").ok()?;
+
+    let (nanos, zone) = match rest.strip_prefix(['.', ',']) {
+        Some(fraction) => {
+            let digits = fraction.find(|c: char| !c.is_ascii_digit()).unwrap_or(fraction.len());
+            if digits == 0 {
+                return None;
+            }
+            // Nanosecond precision is plenty; extra digits are dropped
+            let padded = format!("{:0<9}", &fraction[..digits.min(9)]);
+            (padded.parse::<i64>().ok()?, &fraction[digits..])
+        }
+        None => (0, rest),
+    };
+
+    let offset_seconds = parse_zone(zone)?;
+    let utc = naive.and_utc() + Duration::nanoseconds(nanos) - Duration::seconds(offset_seconds);
+    Some(utc)
+}
+
+/// `Z`, `+HH`, `-HH`, `+HHMM` or `-HHMM` as seconds east of UTC
+fn parse_zone(zone: &str) -> Option<i64> {
+    if zone == "Z" {
+        return Some(0);
+    }
+
+    let sign = match zone.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _ => return None,
+    };
+    let digits = &zone[1..];
+    if !(digits.len() == 2 || digits.len() == 4) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let hours: i64 = digits[..2].parse().ok()?;
+    let minutes: i64 = digits.get(2..).filter(|m| !m.is_empty()).map_or(Ok(0), str::parse).ok()?;
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+    Some(sign * (hours * 3600 + minutes * 60))
+}
@@ -0,0 +1,148 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use crate::report_data::EnhancedReportData;
+use crate::windows_auth::WindowsAuth;
+
+/// Outcome recorded for a user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditStatus {
+    Ok,
+    Failed,
+    Skipped,
+}
+
+/// One JSON line of the audit log
+///
+/// `prev_hash` is the SHA-256 of the previous line as written (empty for the first line),
+/// so editing or deleting an earlier entry breaks the chain from that point on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub operator: String,
+    pub sam_account_name: String,
+    pub status: AuditStatus,
+    pub risk_score: Option<u8>,
+    pub output_path: Option<String>,
+    pub error: Option<String>,
+    pub prev_hash: String,
+}
+
+impl AuditEntry {
+    fn new(sam_account_name: &str, status: AuditStatus) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            operator: String::new(),
+            sam_account_name: sam_account_name.to_string(),
+            status,
+            risk_score: None,
+            output_path: None,
+            error: None,
+            prev_hash: String::new(),
+        }
+    }
+
+    pub fn success(data: &EnhancedReportData, output_path: &str) -> Self {
+        Self {
+            risk_score: data.risk_assessment.as_ref().map(|r| r.overall_score),
+            output_path: Some(output_path.to_string()),
+            ..Self::new(&data.user().sam_account_name, AuditStatus::Ok)
+        }
+    }
+
+    pub fn failure(username: &str, error: &anyhow::Error) -> Self {
+        Self {
+            error: Some(format!("{:#}", error)),
+            ..Self::new(username, AuditStatus::Failed)
+        }
+    }
+
+    /// A user left out on purpose; `reason` goes in the error field
+    pub fn skipped(username: &str, reason: &str) -> Self {
+        Self {
+            error: Some(reason.to_string()),
+            ..Self::new(username, AuditStatus::Skipped)
+        }
+    }
+}
+
+/// Append-only, hash-chained JSON-lines log of who generated which report and when
+///
+/// Each append takes an exclusive lock on the file, so concurrent runs writing the same
+/// log neither interleave lines nor fork the hash chain.
+pub struct AuditLog {
+    path: PathBuf,
+    operator: String,
+}
+
+impl AuditLog {
+    /// Open (creating if needed) the log at `path`; entries are attributed to `operator`
+    pub fn open(path: &Path, operator: impl Into<String>) -> Result<Self> {
+        Self::open_file(path)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            operator: operator.into(),
+        })
+    }
+
+    pub fn append(&self, mut entry: AuditEntry) -> Result<()> {
+        let mut file = Self::open_file(&self.path)?;
+        file.lock()
+            .context(format!("Failed to lock audit log: {}", self.path.display()))?;
+
+        entry.operator = self.operator.clone();
+        entry.prev_hash = Self::last_line(&mut file)?
+            .map(|line| line_hash(&line))
+            .unwrap_or_default();
+        let line = serde_json::to_string(&entry).context("Failed to serialize audit log entry")?;
+
+        // Append mode puts the line at the end whatever the read position
+        writeln!(file, "{}", line)
+            .and_then(|_| file.sync_data())
+            .context(format!("Failed to write audit log: {}", self.path.display()))
+        // The lock is released when `file` is dropped
+    }
+
+    fn open_file(path: &Path) -> Result<File> {
+        OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)
+            .context(format!("Failed to open audit log: {}", path.display()))
+    }
+
+    fn last_line(file: &mut File) -> Result<Option<String>> {
+        file.seek(SeekFrom::Start(0)).context("Failed to read audit log")?;
+        let mut last = None;
+        for line in BufReader::new(&*file).lines() {
+            let line = line.context("Failed to read audit log")?;
+            if !line.trim().is_empty() {
+                last = Some(line);
+            }
+        }
+        Ok(last)
+    }
+}
+
+/// Hex SHA-256 of an audit log line, without its newline
+pub fn line_hash(line: &str) -> String {
+    Sha256::digest(line.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Who is running the tool: DOMAIN\user where it can be determined, else the OS login name
+pub fn current_operator() -> String {
+    WindowsAuth::get_current_user_dn()
+        .ok()
+        .or_else(|| std::env::var("USERNAME").ok())
+        .or_else(|| std::env::var("USER").ok())
+        .unwrap_or_else(|| "unknown".to_string())
+}
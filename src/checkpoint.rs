@@ -0,0 +1,56 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Record of the users a batch has already reported on, so an interrupted run can resume
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchCheckpoint {
+    pub completed: BTreeSet<String>,
+    pub updated: Option<DateTime<Utc>>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl BatchCheckpoint {
+    /// Load the checkpoint at `path`, starting empty if it doesn't exist yet
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut checkpoint = if path.exists() {
+            let contents = fs::read_to_string(path)
+                .context(format!("Failed to read checkpoint: {}", path.display()))?;
+            serde_json::from_str(&contents)
+                .context(format!("Failed to parse checkpoint: {}", path.display()))?
+        } else {
+            Self::default()
+        };
+
+        checkpoint.path = path.to_path_buf();
+        Ok(checkpoint)
+    }
+
+    /// Whether a user was completed by a previous run (SAM names are case-insensitive)
+    pub fn is_completed(&self, username: &str) -> bool {
+        self.completed.contains(&username.to_lowercase())
+    }
+
+    /// Mark a user as done and persist the checkpoint immediately
+    pub fn mark_completed(&mut self, username: &str) -> Result<()> {
+        self.completed.insert(username.to_lowercase());
+        self.updated = Some(Utc::now());
+        self.save()
+    }
+
+    fn save(&self) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .context("Failed to serialize checkpoint")?;
+
+        // Write to a sibling file first so an interruption never leaves a truncated checkpoint
+        let temp_path = self.path.with_extension("tmp");
+        fs::write(&temp_path, json)
+            .context(format!("Failed to write checkpoint: {}", temp_path.display()))?;
+        fs::rename(&temp_path, &self.path)
+            .context(format!("Failed to update checkpoint: {}", self.path.display()))
+    }
+}
@@ -0,0 +1,53 @@
+use crate::models::ADUser;
+
+/// Flags identity attributes (displayName, mail) that are missing or look like placeholders
+///
+/// Reviewers rely on these attributes to tell who owns an account, so blank or
+/// "test"/"temp"-style values often point to orphaned or test accounts.
+#[derive(Debug, Clone)]
+pub struct IdentityQualityCheck {
+    placeholder_patterns: Vec<String>,
+}
+
+impl IdentityQualityCheck {
+    pub const DEFAULT_PLACEHOLDER_PATTERNS: [&'static str; 7] = [
+        "test", "temp", "do not use", "placeholder", "dummy", "tbd", "n/a",
+    ];
+
+    /// Case-insensitive substrings that mark a value as a placeholder
+    pub fn new(placeholder_patterns: Vec<String>) -> Self {
+        Self {
+            placeholder_patterns: placeholder_patterns
+                .into_iter()
+                .map(|p| p.trim().to_lowercase())
+                .filter(|p| !p.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Human-readable problems with the user's identity attributes (empty when all is well)
+    pub fn issues(&self, user: &ADUser) -> Vec<String> {
+        [("Display name", &user.display_name), ("Email", &user.email)]
+            .into_iter()
+            .filter_map(|(label, value)| match value.as_deref().map(str::trim) {
+                None | Some("") => Some(format!("{} is missing", label)),
+                Some(v) => self.placeholder_match(v)
+                    .map(|pattern| format!("{} looks like a placeholder (matches \"{}\")", label, pattern)),
+            })
+            .collect()
+    }
+
+    fn placeholder_match(&self, value: &str) -> Option<&str> {
+        let value = value.to_lowercase();
+        self.placeholder_patterns
+            .iter()
+            .find(|pattern| value.contains(pattern.as_str()))
+            .map(String::as_str)
+    }
+}
+
+impl Default for IdentityQualityCheck {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_PLACEHOLDER_PATTERNS.iter().map(|p| p.to_string()).collect())
+    }
+}
@@ -1,175 +1,286 @@
 use anyhow::Result;
+use serde::Serialize;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
 use tracing::{info, warn, error};
 
+/// Compiled-in features, for automation that wraps the tool
+#[derive(Debug, Clone, Serialize)]
+pub struct Capabilities {
+    pub version: String,
+    pub platform: String,
+    pub arch: String,
+    pub gssapi: bool,
+    pub auth_methods: Vec<String>,
+    pub output_formats: Vec<String>,
+}
+
+/// Outcome of a single preflight check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    Skip,       // Not applicable on this platform
+}
+
+/// One preflight check, e.g. `{"name": "fqdn", "status": "pass", "detail": "..."}`
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,     // Extra context and remediation hints
+}
+
+impl CheckResult {
+    fn new(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self { name, status, detail: detail.into(), notes: Vec::new() }
+    }
+
+    fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    #[cfg(not(windows))]
+    fn not_applicable(name: &'static str) -> Self {
+        Self::new(name, CheckStatus::Skip, format!("Not applicable on {}", std::env::consts::OS))
+    }
+}
+
+/// All preflight results for a server; the run passes when no check failed
+#[derive(Debug, Clone, Serialize)]
+pub struct PreflightReport {
+    pub server: String,
+    pub passed: bool,
+    pub checks: Vec<CheckResult>,
+}
+
 /// Diagnostics for troubleshooting Kerberos/GSSAPI authentication issues
 pub struct Diagnostics;
 
 impl Diagnostics {
+    /// Give up on the TCP reachability check after this long
+    const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+    /// TCP connects slower than this are reported as a warning
+    const SLOW_CONNECT_MS: u128 = 500;
+
     /// Run comprehensive pre-flight checks for GSSAPI authentication
-    pub fn run_preflight_checks(server: &str) -> Result<()> {
+    pub fn run_preflight_checks(server: &str, port: u16) -> Result<()> {
         info!("Running GSSAPI authentication preflight checks...\n");
 
-        // Check 1: Platform support
-        Self::check_platform();
-
-        // Check 2: Domain-joined status (Windows)
-        #[cfg(windows)]
-        Self::check_domain_joined();
-
-        // Check 3: Server FQDN validation
-        Self::check_server_fqdn(server);
-
-        // Check 4: Environment variables
-        #[cfg(windows)]
-        Self::check_environment_variables();
-
-        // Check 5: Network connectivity (basic)
-        #[cfg(windows)]
-        Self::check_network_connectivity(server);
+        for check in Self::preflight_report(server, port).checks {
+            Self::log_check(&check);
+        }
 
         info!("Preflight checks completed.\n");
         Ok(())
     }
 
+    /// Evaluate every preflight check without logging, for machine-readable output
+    ///
+    /// `port` is the LDAP port the report run would use (389/636, GC or --port).
+    pub fn preflight_report(server: &str, port: u16) -> PreflightReport {
+        let checks = vec![
+            // Check 1: Platform support
+            Self::check_platform(),
+            // Check 2: Domain-joined status (Windows)
+            Self::check_domain_joined(),
+            // Check 3: Server FQDN validation
+            Self::check_server_fqdn(server),
+            // Check 4: Environment variables (Windows)
+            Self::check_environment_variables(),
+            // Check 5: DNS resolution and TCP reachability of the LDAP port
+            Self::check_network_connectivity(server, port),
+        ];
+
+        PreflightReport {
+            server: server.to_string(),
+            passed: checks.iter().all(|c| c.status != CheckStatus::Fail),
+            checks,
+        }
+    }
+
+    /// Pretty-print a check; checks that don't apply to this platform are left out
+    fn log_check(check: &CheckResult) {
+        let title = match check.name {
+            "platform" => "Platform Check",
+            "domain_joined" => "Domain Status Check",
+            "fqdn" => "Server FQDN Validation",
+            "environment" => "Environment Variables",
+            "connectivity" => "Network Connectivity Check",
+            other => other,
+        };
+
+        let lines = std::iter::once(&check.detail).chain(&check.notes);
+        match check.status {
+            CheckStatus::Skip => return,
+            CheckStatus::Pass => {
+                info!("✓ {}:", title);
+                lines.for_each(|line| info!("  {}", line));
+            }
+            CheckStatus::Warn => {
+                info!("✓ {}:", title);
+                lines.for_each(|line| warn!("  {}", line));
+            }
+            CheckStatus::Fail => {
+                info!("✓ {}:", title);
+                lines.for_each(|line| error!("  ✗ {}", line));
+            }
+        }
+        info!("");
+    }
+
     /// Check platform support for GSSAPI
-    fn check_platform() {
-        info!("✓ Platform Check:");
+    fn check_platform() -> CheckResult {
         #[cfg(windows)]
         {
-            info!("  Running on Windows - GSSAPI/Kerberos supported");
+            CheckResult::new("platform", CheckStatus::Pass, "Running on Windows - GSSAPI/Kerberos supported")
         }
         #[cfg(not(windows))]
         {
-            warn!("  Not running on Windows - GSSAPI/Kerberos not available");
-            info!("  On Unix/Linux: Use explicit credentials (-u, -p) or configure Kerberos");
+            match crate::windows_auth::WindowsAuth::find_kerberos_ccache() {
+                Some(ccache) => {
+                    let mut status = CheckStatus::Pass;
+                    let mut notes = Vec::new();
+                    match crate::windows_auth::WindowsAuth::default_kerberos_realm() {
+                        Some(realm) => notes.push(format!("Default realm: {}", realm)),
+                        None => notes.push("No default_realm in krb5.conf - the realm is taken from the ticket".to_string()),
+                    }
+                    if cfg!(feature = "gssapi") {
+                        notes.push("GSSAPI/Kerberos bind available via system credential cache".to_string());
+                    } else {
+                        status = CheckStatus::Warn;
+                        notes.push("Build lacks the 'gssapi' feature - rebuild with '--features gssapi'".to_string());
+                    }
+                    let mut result = CheckResult::new("platform", status, format!("Kerberos credential cache found: {}", ccache));
+                    result.notes = notes;
+                    result
+                }
+                None => CheckResult::new("platform", CheckStatus::Warn,
+                    "No Kerberos credential cache found (KRB5CCNAME or /tmp/krb5cc_<uid>)")
+                    .with_note("On Unix/Linux: Run 'kinit user@REALM' or use explicit credentials (-u, -p)"),
+            }
         }
-        info!("");
     }
 
     /// Check if machine is domain-joined (Windows)
-    #[cfg(windows)]
-    fn check_domain_joined() {
-        info!("✓ Domain Status Check:");
-        match (std::env::var("USERDOMAIN"), std::env::var("USERDNSDOMAIN")) {
-            (Ok(domain), Ok(dns_domain)) => {
-                info!("  Domain: {} ({})", domain, dns_domain);
-                info!("  ✓ Machine appears to be domain-joined");
-            }
-            _ => {
-                warn!("  Unable to detect domain membership");
-                warn!("  Machine may not be domain-joined");
-                warn!("  Ensure this is a domain-joined Windows machine");
+    fn check_domain_joined() -> CheckResult {
+        #[cfg(windows)]
+        {
+            match (std::env::var("USERDOMAIN"), std::env::var("USERDNSDOMAIN")) {
+                (Ok(domain), Ok(dns_domain)) => {
+                    CheckResult::new("domain_joined", CheckStatus::Pass, format!("Domain: {} ({})", domain, dns_domain))
+                        .with_note("Machine appears to be domain-joined")
+                }
+                _ => CheckResult::new("domain_joined", CheckStatus::Warn, "Unable to detect domain membership")
+                    .with_note("Machine may not be domain-joined")
+                    .with_note("Ensure this is a domain-joined Windows machine"),
             }
         }
-        info!("");
+        #[cfg(not(windows))]
+        {
+            CheckResult::not_applicable("domain_joined")
+        }
     }
 
     /// Validate server FQDN format
-    fn check_server_fqdn(server: &str) {
-        info!("✓ Server FQDN Validation:");
-        info!("  Server: {}", server);
-
-        if server.contains('.') {
-            info!("  ✓ Server appears to be fully qualified (contains domain)");
-        } else if server.contains("\\\\") || server.starts_with("\\\\") {
-            warn!("  Server appears to be a UNC path (\\\\server)");
-            warn!("  Use FQDN format instead: ad.company.com");
-        } else {
-            warn!("  Server does not appear to be fully qualified");
-            warn!("  GSSAPI requires FQDN (e.g., 'ad.company.com', not 'ad-server')");
-            warn!("  Short hostnames and IP addresses will not work with GSSAPI");
-        }
+    fn check_server_fqdn(server: &str) -> CheckResult {
+        let detail = format!("Server: {}", server);
 
         // Check for IP address
         if server.chars().all(|c| c.is_numeric() || c == '.') {
-            error!("  ✗ Server appears to be an IP address");
-            error!("  GSSAPI authentication REQUIRES the server's FQDN");
-            error!("  Kerberos cannot authenticate to IP addresses");
+            return CheckResult::new("fqdn", CheckStatus::Fail, detail)
+                .with_note("Server appears to be an IP address")
+                .with_note("GSSAPI authentication REQUIRES the server's FQDN")
+                .with_note("Kerberos cannot authenticate to IP addresses");
         }
 
-        info!("");
+        if server.contains('.') {
+            CheckResult::new("fqdn", CheckStatus::Pass, detail)
+                .with_note("Server appears to be fully qualified (contains domain)")
+        } else if server.contains("\\\\") {
+            CheckResult::new("fqdn", CheckStatus::Warn, detail)
+                .with_note("Server appears to be a UNC path (\\\\server)")
+                .with_note("Use FQDN format instead: ad.company.com")
+        } else {
+            CheckResult::new("fqdn", CheckStatus::Warn, detail)
+                .with_note("Server does not appear to be fully qualified")
+                .with_note("GSSAPI requires FQDN (e.g., 'ad.company.com', not 'ad-server')")
+                .with_note("Short hostnames and IP addresses will not work with GSSAPI")
+        }
     }
 
     /// Check environment variables (Windows)
-    #[cfg(windows)]
-    fn check_environment_variables() {
-        info!("✓ Environment Variables:");
-
-        let username = std::env::var("USERNAME").ok();
-        let userdomain = std::env::var("USERDOMAIN").ok();
-        let userdnsdomain = std::env::var("USERDNSDOMAIN").ok();
-        let logonserver = std::env::var("LOGONSERVER").ok();
+    fn check_environment_variables() -> CheckResult {
+        #[cfg(windows)]
+        {
+            let username = std::env::var("USERNAME").ok();
+            let userdomain = std::env::var("USERDOMAIN").ok();
+            let userdnsdomain = std::env::var("USERDNSDOMAIN").ok();
+            let logonserver = std::env::var("LOGONSERVER").ok();
+
+            let mut result = match (username, userdomain) {
+                (Some(u), Some(d)) => CheckResult::new("environment", CheckStatus::Pass, format!("Current User: {}\\{}", d, u)),
+                _ => CheckResult::new("environment", CheckStatus::Warn, "Could not determine current user"),
+            };
+
+            match userdnsdomain {
+                Some(dns) => result = result.with_note(format!("DNS Domain: {}", dns)),
+                None => {
+                    result.status = CheckStatus::Warn;
+                    result = result.with_note("USERDNSDOMAIN not set (may affect GSSAPI)");
+                }
+            }
 
-        if let (Some(u), Some(d)) = (username, userdomain) {
-            info!("  Current User: {}\\{}", d, u);
-        } else {
-            warn!("  Could not determine current user");
-        }
+            if let Some(logon) = logonserver {
+                let cleaned = logon.trim_start_matches("\\\\");
+                result = result.with_note(format!("Logon Server: {}", cleaned));
+            }
 
-        if let Some(dns) = userdnsdomain {
-            info!("  DNS Domain: {}", dns);
-        } else {
-            warn!("  USERDNSDOMAIN not set (may affect GSSAPI)");
+            result
         }
-
-        if let Some(logon) = logonserver {
-            let cleaned = logon.trim_start_matches("\\\\");
-            info!("  Logon Server: {}", cleaned);
+        #[cfg(not(windows))]
+        {
+            CheckResult::not_applicable("environment")
         }
-
-        info!("");
     }
 
-    /// Basic network connectivity check
-    #[cfg(windows)]
-    fn check_network_connectivity(server: &str) {
-        info!("✓ Network Connectivity Check:");
-        info!("  Attempting to validate server reachability...");
-
-        // Try to parse as hostname
-        match server.parse::<std::net::IpAddr>() {
+    /// Resolve the server and time a TCP connect to the LDAP port that will be used
+    fn check_network_connectivity(server: &str, port: u16) -> CheckResult {
+        let addrs: Vec<SocketAddr> = match (server, port).to_socket_addrs() {
+            Ok(addrs) => addrs.collect(),
+            Err(_) => {
+                return CheckResult::new("connectivity", CheckStatus::Fail, format!("Could not resolve server: {}", server))
+                    .with_note("Check DNS configuration and verify server FQDN")
+                    .with_note(format!("Verify DNS resolution: nslookup {}", server));
+            }
+        };
+        let Some(addr) = addrs.first() else {
+            return CheckResult::new("connectivity", CheckStatus::Fail, format!("Could not resolve server hostname: {}", server))
+                .with_note(format!("Verify DNS resolution: nslookup {}", server));
+        };
+
+        let started = Instant::now();
+        match TcpStream::connect_timeout(addr, Self::CONNECT_TIMEOUT) {
             Ok(_) => {
-                // It's an IP - try to connect
-                if let Ok(addrs) = std::net::ToSocketAddrs::to_socket_addrs(&format!(
-                    "{}:389",
-                    server
-                )) {
-                    if addrs.collect::<Vec<_>>().is_empty() {
-                        warn!("  Could not resolve server address");
-                    } else {
-                        info!("  ✓ Server appears reachable on LDAP port (389)");
-                    }
+                let latency_ms = started.elapsed().as_millis();
+                let status = if latency_ms > Self::SLOW_CONNECT_MS { CheckStatus::Warn } else { CheckStatus::Pass };
+                let result = CheckResult::new("connectivity", status, format!("Connected to {}:{} in {} ms", server, port, latency_ms))
+                    .with_note(format!("IP: {}", addr.ip()));
+                if status == CheckStatus::Warn {
+                    result.with_note("Slow TCP connect - the domain controller or network path may be overloaded")
                 } else {
-                    warn!("  Could not resolve server address");
-                }
-            }
-            Err(_) => {
-                // It's a hostname - try DNS lookup
-                match std::net::ToSocketAddrs::to_socket_addrs(&format!(
-                    "{}:389",
-                    server
-                )) {
-                    Ok(addrs) => {
-                        let addrs_vec: Vec<_> = addrs.collect();
-                        if addrs_vec.is_empty() {
-                            warn!("  Could not resolve server hostname: {}", server);
-                            warn!("  Verify DNS resolution: nslookup {}", server);
-                        } else {
-                            info!("  ✓ Server resolved: {}", server);
-                            if let Some(addr) = addrs_vec.first() {
-                                info!("    IP: {}", addr.ip());
-                            }
-                        }
-                    }
-                    Err(_) => {
-                        warn!("  Could not resolve server: {}", server);
-                        warn!("  Check DNS configuration and verify server FQDN");
-                    }
+                    result
                 }
             }
+            Err(e) => CheckResult::new("connectivity", CheckStatus::Fail, format!("Cannot connect to {}:{} ({})", server, port, e))
+                .with_note(format!("IP: {}", addr.ip()))
+                .with_note(format!("Check firewall rules for port {}", port)),
         }
-
-        info!("");
     }
 
     /// Display troubleshooting guidance
@@ -221,6 +332,25 @@ impl Diagnostics {
         info!("");
     }
 
+    /// Describe the capabilities of this build (mirrors the cfg checks in `show_auth_info`)
+    pub fn capabilities(output_formats: Vec<String>) -> Capabilities {
+        let gssapi = cfg!(feature = "gssapi");
+
+        let mut auth_methods = vec!["simple".to_string()];
+        if gssapi {
+            auth_methods.push("gssapi".to_string());
+        }
+
+        Capabilities {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            platform: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            gssapi,
+            auth_methods,
+            output_formats,
+        }
+    }
+
     /// Display current authentication method info
     pub fn show_auth_info() {
         info!("Authentication Configuration:");
@@ -229,10 +359,15 @@ impl Diagnostics {
             info!("✓ GSSAPI/Kerberos support: ENABLED");
             info!("  Use --use-gssapi to authenticate with current Windows user");
         }
-        #[cfg(not(all(windows, feature = "gssapi")))]
+        #[cfg(all(not(windows), feature = "gssapi"))]
+        {
+            info!("✓ GSSAPI/Kerberos support: ENABLED");
+            info!("  Use --use-gssapi to authenticate with the ticket in your Kerberos credential cache");
+        }
+        #[cfg(not(feature = "gssapi"))]
         {
             info!("ℹ GSSAPI/Kerberos support: NOT AVAILABLE");
-            info!("  (Requires Windows platform and 'gssapi' feature)");
+            info!("  (Requires the 'gssapi' feature; on Linux/macOS also a 'kinit' ticket)");
         }
         info!("✓ Simple authentication: ALWAYS AVAILABLE");
         info!("  Use --username and --password for explicit credentials");
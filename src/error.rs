@@ -0,0 +1,62 @@
+use ldap3::LdapError;
+use thiserror::Error;
+
+/// Failure categories surfaced by the library layer, so embedders can react to them
+#[derive(Debug, Error)]
+pub enum AdReportError {
+    /// The server could not be reached or the connection broke mid-operation
+    #[error("{0}")]
+    ConnectionFailed(String),
+    /// Authentication was rejected or is not possible in this build/environment
+    #[error("{0}")]
+    BindFailed(String),
+    /// No matching entry (or the entry is outside the search scope)
+    #[error("{0}")]
+    UserNotFound(String),
+    /// The bind identity lacks rights to read the directory
+    #[error("{0}")]
+    AccessDenied(String),
+    /// More than one account matches the requested name and none was chosen
+    #[error("{0}")]
+    AmbiguousAccount(String),
+    /// The directory returned data that could not be interpreted
+    #[error("{0}")]
+    ParseError(String),
+    /// The server did not answer in time
+    #[error("{0}")]
+    Timeout(String),
+}
+
+pub type Result<T> = std::result::Result<T, AdReportError>;
+
+impl AdReportError {
+    /// Classify an LDAP error raised while searching or reading the directory
+    pub fn from_ldap(err: LdapError, context: &str) -> Self {
+        let message = format!("{}: {}", context, err);
+        match &err {
+            LdapError::Timeout { .. } => Self::Timeout(message),
+            LdapError::LdapResult { result } => match result.rc {
+                // noSuchObject
+                32 => Self::UserNotFound(message),
+                // strongerAuthRequired, inappropriateAuthentication, invalidCredentials,
+                // insufficientAccessRights
+                8 | 48 | 49 | 50 => Self::AccessDenied(message),
+                // operationsError 000004DC: AD requires a successful bind first
+                1 if result.text.contains("000004DC") => Self::AccessDenied(message),
+                // timeLimitExceeded
+                3 => Self::Timeout(message),
+                _ => Self::ConnectionFailed(message),
+            },
+            _ => Self::ConnectionFailed(message),
+        }
+    }
+
+    /// Classify an LDAP error raised by a bind operation
+    pub fn from_bind(err: LdapError, context: &str) -> Self {
+        match err {
+            LdapError::Timeout { .. } => Self::Timeout(format!("{}: {}", context, err)),
+            LdapError::LdapResult { .. } => Self::BindFailed(format!("{}: {}", context, err)),
+            _ => Self::ConnectionFailed(format!("{}: {}", context, err)),
+        }
+    }
+}
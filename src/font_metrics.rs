@@ -0,0 +1,90 @@
+use printpdf::{BuiltinFont, Mm};
+
+// Glyph advance widths (1/1000 em) for printable ASCII (0x20..=0x7E),
+// taken from the Adobe Core 14 AFM files (WinAnsi encoding)
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, // ' '..'/'
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, // '0'..'?'
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778, // '@'..'O'
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556, // 'P'..'_'
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556, // '`'..'o'
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,      // 'p'..'~'
+];
+
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278, // ' '..'/'
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611, // '0'..'?'
+    975, 722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, 722, 778, // '@'..'O'
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333, 278, 333, 584, 556, // 'P'..'_'
+    333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611, // '`'..'o'
+    611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,      // 'p'..'~'
+];
+
+// Courier family is monospaced
+const COURIER_WIDTH: u16 = 600;
+
+// Width used for characters outside printable ASCII (bullets, box drawing, accents)
+const FALLBACK_WIDTH: u16 = 556;
+
+const POINTS_PER_MM: f32 = 72.0 / 25.4;
+
+/// Advance width of a single character in 1/1000 em for a builtin font
+fn char_width(c: char, font: BuiltinFont) -> u16 {
+    let index = (c as u32).wrapping_sub(0x20) as usize;
+
+    match font {
+        BuiltinFont::Courier
+        | BuiltinFont::CourierBold
+        | BuiltinFont::CourierOblique
+        | BuiltinFont::CourierBoldOblique => COURIER_WIDTH,
+        BuiltinFont::HelveticaBold | BuiltinFont::HelveticaBoldOblique => {
+            HELVETICA_BOLD_WIDTHS.get(index).copied().unwrap_or(FALLBACK_WIDTH)
+        }
+        // Other builtin faces are approximated with Helvetica metrics
+        _ => HELVETICA_WIDTHS.get(index).copied().unwrap_or(FALLBACK_WIDTH),
+    }
+}
+
+/// Rendered width of `text` in millimetres at `size` points using builtin AFM metrics
+pub fn text_width_mm(text: &str, font: BuiltinFont, size: f32) -> Mm {
+    let units: u32 = text.chars().map(|c| char_width(c, font) as u32).sum();
+    let width_pt = units as f32 / 1000.0 * size;
+    Mm(width_pt / POINTS_PER_MM)
+}
+
+/// `text` cut short with "..." so it fits in `width` at `size` points, or None if it already fits
+pub fn fit_to_width(text: &str, font: BuiltinFont, size: f32, width: Mm) -> Option<String> {
+    if text_width_mm(text, font, size) <= width {
+        return None;
+    }
+
+    let budget = width - text_width_mm("...", font, size);
+    let mut fitted = String::new();
+    for c in text.chars() {
+        fitted.push(c);
+        if text_width_mm(&fitted, font, size) > budget {
+            fitted.pop();
+            break;
+        }
+    }
+    Some(format!("{}...", fitted.trim_end()))
+}
+
+/// Split `text` at spaces into lines that each fit in `width` at `size` points;
+/// a single word wider than `width` is kept whole on its own line
+pub fn wrap_to_width(text: &str, font: BuiltinFont, size: f32, width: Mm) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+        if !line.is_empty() && text_width_mm(&candidate, font, size) > width {
+            lines.push(std::mem::replace(&mut line, word.to_string()));
+        } else {
+            line = candidate;
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
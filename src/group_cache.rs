@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use crate::models::ADGroup;
+
+/// A group entry as fetched from the directory (without its nested groups)
+#[derive(Debug, Clone)]
+pub struct CachedGroup {
+    pub group: ADGroup,
+    pub member_of: Vec<String>,
+    fetched_at: Instant,
+}
+
+/// Per-run cache of resolved groups keyed by distinguished name
+///
+/// Only the group's own attributes and its `memberOf` DNs are stored, so the recursive
+/// walk (and its cycle detection) runs exactly as before - cached nodes just skip the
+/// LDAP round-trip. Entries expire after `ttl` and the cache never holds more than
+/// `max_entries` groups (oldest entries are evicted first).
+///
+/// Keys are the DN a group was requested under (a memberOf value), compared
+/// case-insensitively like AD does, so the server returning the DN in another form
+/// never turns a repeat lookup into a miss.
+pub struct GroupCache {
+    entries: HashMap<String, CachedGroup>,
+    max_entries: usize,
+    ttl: Duration,
+    hits: usize,
+    misses: usize,
+}
+
+impl GroupCache {
+    pub const DEFAULT_MAX_ENTRIES: usize = 5000;
+    pub const DEFAULT_TTL_SECS: u64 = 900;
+
+    pub fn new(max_entries: usize, ttl: Duration) -> Self {
+        Self {
+            entries: HashMap::new(),
+            max_entries,
+            ttl,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Cache key for a DN
+    fn key(dn: &str) -> String {
+        dn.trim().to_lowercase()
+    }
+
+    /// Look up a group by DN, dropping it if it has expired
+    pub fn get(&mut self, dn: &str) -> Option<CachedGroup> {
+        let key = Self::key(dn);
+        match self.entries.get(&key) {
+            Some(entry) if entry.fetched_at.elapsed() <= self.ttl => {
+                self.hits += 1;
+                Some(entry.clone())
+            }
+            Some(_) => {
+                self.entries.remove(&key);
+                self.misses += 1;
+                None
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Store a group freshly fetched for `dn`, evicting the oldest entry when full
+    pub fn insert(&mut self, dn: &str, group: ADGroup, member_of: Vec<String>) {
+        if self.max_entries == 0 {
+            return;
+        }
+
+        let key = Self::key(dn);
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.max_entries {
+            if let Some(oldest) = self.entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.fetched_at)
+                .map(|(dn, _)| dn.clone())
+            {
+                self.entries.remove(&oldest);
+            }
+        }
+
+        self.entries.insert(key, CachedGroup {
+            group,
+            member_of,
+            fetched_at: Instant::now(),
+        });
+    }
+
+    pub fn max_entries(&self) -> usize {
+        self.max_entries
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Cache (hits, misses, entries) for the run so far
+    pub fn stats(&self) -> (usize, usize, usize) {
+        (self.hits, self.misses, self.entries.len())
+    }
+}
+
+impl Default for GroupCache {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_MAX_ENTRIES, Duration::from_secs(Self::DEFAULT_TTL_SECS))
+    }
+}
@@ -0,0 +1,54 @@
+use regex::Regex;
+use crate::models::{ADGroup, ADUser};
+
+/// Name-based include/exclude rules for the groups shown in a report
+///
+/// A group is kept when it matches `include` (if set) and does not match `exclude`.
+/// Filtering applies at every nesting level; a dropped group takes its nested groups with it.
+#[derive(Debug, Clone, Default)]
+pub struct GroupFilter {
+    include: Option<Regex>,
+    exclude: Option<Regex>,
+}
+
+impl GroupFilter {
+    pub fn new(include: Option<Regex>, exclude: Option<Regex>) -> Self {
+        Self { include, exclude }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.include.is_some() || self.exclude.is_some()
+    }
+
+    pub fn matches(&self, group_name: &str) -> bool {
+        let included = self.include.as_ref().is_none_or(|re| re.is_match(group_name));
+        let excluded = self.exclude.as_ref().is_some_and(|re| re.is_match(group_name));
+        included && !excluded
+    }
+
+    /// Copy of the user with non-matching groups removed, plus the number of groups hidden
+    pub fn apply(&self, user: &ADUser) -> (ADUser, usize) {
+        let mut filtered = user.clone();
+        if !self.is_active() {
+            return (filtered, 0);
+        }
+
+        filtered.primary_group = user.primary_group.clone()
+            .filter(|g| self.matches(&g.name));
+        filtered.groups = self.filter_groups(&user.groups);
+
+        let hidden = user.all_groups().len() - filtered.all_groups().len();
+        (filtered, hidden)
+    }
+
+    fn filter_groups(&self, groups: &[ADGroup]) -> Vec<ADGroup> {
+        groups.iter()
+            .filter(|g| self.matches(&g.name))
+            .map(|g| {
+                let mut kept = g.clone();
+                kept.nested_groups = self.filter_groups(&g.nested_groups);
+                kept
+            })
+            .collect()
+    }
+}
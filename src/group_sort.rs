@@ -0,0 +1,70 @@
+use std::cmp::{Ordering, Reverse};
+use crate::models::{ADGroup, ADUser, GroupScope, GroupType};
+use crate::risk_calculator::RiskCalculator;
+
+/// Order in which group memberships are listed in a report
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GroupSortOrder {
+    /// Alphabetical, case-insensitive
+    Name,
+    /// Highest risk contribution first, counting the groups nested beneath
+    #[default]
+    Risk,
+    /// Universal, then Global, then Domain Local
+    Scope,
+    /// Security groups before distribution lists
+    Type,
+}
+
+impl GroupSortOrder {
+    /// Sort the user's direct groups, and each group's nested groups within their parent;
+    /// ties fall back to the group name
+    pub fn apply(self, user: &mut ADUser) {
+        let calculator = RiskCalculator::new();
+        self.sort(&mut user.groups, &calculator);
+        if let Some(primary) = user.primary_group.as_mut() {
+            self.sort(&mut primary.nested_groups, &calculator);
+        }
+    }
+
+    fn sort(self, groups: &mut [ADGroup], calculator: &RiskCalculator) {
+        for group in groups.iter_mut() {
+            self.sort(&mut group.nested_groups, calculator);
+        }
+
+        let by_name = |a: &ADGroup, b: &ADGroup| a.name.to_lowercase().cmp(&b.name.to_lowercase());
+        match self {
+            GroupSortOrder::Name => groups.sort_by(by_name),
+            GroupSortOrder::Risk => groups.sort_by(|a, b| {
+                Reverse(subtree_risk(a, calculator)).cmp(&Reverse(subtree_risk(b, calculator)))
+                    .then_with(|| by_name(a, b))
+            }),
+            GroupSortOrder::Scope => groups.sort_by(|a, b| compare_scope(a, b).then_with(|| by_name(a, b))),
+            GroupSortOrder::Type => groups.sort_by(|a, b| compare_type(a, b).then_with(|| by_name(a, b))),
+        }
+    }
+}
+
+/// Highest contribution of the group or anything nested beneath it
+fn subtree_risk(group: &ADGroup, calculator: &RiskCalculator) -> u8 {
+    group.nested_groups.iter()
+        .map(|nested| subtree_risk(nested, calculator))
+        .fold(calculator.group_contribution(group).risk_contribution, u8::max)
+}
+
+fn compare_scope(a: &ADGroup, b: &ADGroup) -> Ordering {
+    let rank = |group: &ADGroup| match group.scope {
+        GroupScope::Universal => 0,
+        GroupScope::Global => 1,
+        GroupScope::DomainLocal => 2,
+    };
+    rank(a).cmp(&rank(b))
+}
+
+fn compare_type(a: &ADGroup, b: &ADGroup) -> Ordering {
+    let rank = |group: &ADGroup| match group.group_type {
+        GroupType::Security => 0,
+        GroupType::Distribution => 1,
+    };
+    rank(a).cmp(&rank(b))
+}
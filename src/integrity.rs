@@ -0,0 +1,75 @@
+use anyhow::{Context, Result};
+use printpdf::lopdf::{Document, Object};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use crate::report_data::EnhancedReportData;
+
+/// Prefix of the content hash in the PDF's Info `/Identifier` entry
+const IDENTIFIER_PREFIX: &str = "sha256:";
+
+/// Report content written next to a PDF with --integrity-hash; the embedded hash covers
+/// exactly these bytes, so a recipient can recompute it without rerunning the query
+///
+/// This is all of the report data (computer details, provenance, baseline changes, ...),
+/// not just the snapshot kept for baselines, so nothing the PDF shows can be changed unnoticed.
+pub fn content_json(data: &EnhancedReportData) -> Result<Vec<u8>> {
+    serde_json::to_vec_pretty(data)
+        .context("Failed to serialize report content")
+}
+
+/// Lowercase hex SHA-256, as printed by `sha256sum`
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Value stored in the PDF's Info `/Identifier` entry
+pub fn identifier(hash: &str) -> String {
+    format!("{}{}", IDENTIFIER_PREFIX, hash)
+}
+
+/// Content hash embedded in a rendered PDF, if it was generated with --integrity-hash
+pub fn embedded_hash(pdf: &[u8]) -> Result<Option<String>> {
+    let doc = Document::load_mem(pdf).context("Failed to parse PDF")?;
+    let Ok(info) = doc.trailer.get(b"Info").and_then(Object::as_reference) else {
+        return Ok(None);
+    };
+    let identifier = doc.get_dictionary(info)
+        .and_then(|info| info.get(b"Identifier"))
+        .and_then(Object::as_str)
+        .map(|value| String::from_utf8_lossy(value).into_owned())
+        .ok();
+
+    Ok(identifier.and_then(|value| value.strip_prefix(IDENTIFIER_PREFIX).map(str::to_string)))
+}
+
+/// Sidecar paths for a report: the content JSON and its `sha256sum`-style checksum file
+pub fn sidecar_paths(report_path: &Path) -> (PathBuf, PathBuf) {
+    (report_path.with_extension("json"), report_path.with_extension("sha256"))
+}
+
+/// One `sha256sum -c` line for the content JSON
+pub fn checksum_line(hash: &str, json_path: &Path) -> String {
+    let name = json_path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    format!("{}  {}\n", hash, name)
+}
+
+/// Outcome of comparing a PDF against its content JSON
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verification {
+    /// The embedded hash matches the JSON
+    Match { hash: String },
+    /// The PDF or the JSON was altered after generation
+    Mismatch { embedded: String, computed: String },
+    /// The PDF carries no content hash
+    NoEmbeddedHash,
+}
+
+/// Recompute the content hash from `json` and compare it with the one embedded in `pdf`
+pub fn verify(pdf: &[u8], json: &[u8]) -> Result<Verification> {
+    let computed = sha256_hex(json);
+    Ok(match embedded_hash(pdf)? {
+        None => Verification::NoEmbeddedHash,
+        Some(embedded) if embedded.eq_ignore_ascii_case(&computed) => Verification::Match { hash: computed },
+        Some(embedded) => Verification::Mismatch { embedded, computed },
+    })
+}
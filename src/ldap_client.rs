@@ -1,35 +1,277 @@
-use anyhow::{Context, Result};
-use chrono::{DateTime, Utc};
+use ldap3::adapters::{Adapter, EntriesOnly, PagedResults};
 use ldap3::{
-    LdapConnAsync, LdapConnSettings, Ldap, Scope, SearchEntry,
+    ldap_escape, LdapConnAsync, LdapConnSettings, Ldap, Scope, SearchEntry, SearchOptions,
 };
-use std::collections::HashSet;
+use secrecy::{ExposeSecret, SecretString};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
 use std::pin::Pin;
 use std::future::Future;
-use crate::models::{ADUser, ADGroup, GroupType, GroupScope, UserRight, RightSource};
+use std::time::Duration;
+use std::sync::Arc;
+use crate::ad_time::{parse_filetime, parse_filetime_interval, parse_generalized_time};
+use crate::error::{AdReportError, Result};
+use crate::group_cache::GroupCache;
+use crate::rate_limit::RateLimiter;
+use crate::logon_hours::LogonHours;
+use crate::socks_tunnel;
+use crate::sid::{binary_sid_to_string, rid_of, well_known_rid_name};
+use crate::models::{domain_of_dn, ADComputer, ADUser, ADGroup, GroupType, GroupScope, MissingAttributes, PasswordPolicy, UserRight, RightSource};
+#[cfg(not(windows))]
+use crate::windows_auth::WindowsAuth;
+
+/// What the connected domain controller reports about itself in rootDSE
+#[derive(Debug, Clone, Default)]
+pub struct ServerIdentity {
+    /// DNS domain name, from defaultNamingContext
+    pub domain: Option<String>,
+    /// The controller's own FQDN (dnsHostName)
+    pub host_name: Option<String>,
+}
+
+/// Oldest TLS protocol version accepted for LDAPS and for TLS through a SOCKS5 tunnel
+///
+/// Cipher suites are left to the platform TLS library (SChannel, Secure Transport or
+/// OpenSSL), which only offers those valid for the versions allowed here.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TlsMinVersion {
+    #[default]
+    #[value(name = "1.2")]
+    Tls12,
+    #[value(name = "1.3")]
+    Tls13,
+}
+
+impl TlsMinVersion {
+    /// TLS connector that refuses to negotiate anything older than this version
+    pub fn connector(self) -> Result<native_tls::TlsConnector> {
+        let protocol = match self {
+            TlsMinVersion::Tls12 => native_tls::Protocol::Tlsv12,
+            TlsMinVersion::Tls13 => native_tls::Protocol::Tlsv13,
+        };
+        native_tls::TlsConnector::builder()
+            .min_protocol_version(Some(protocol))
+            .build()
+            .map_err(|e| AdReportError::ConnectionFailed(format!("Failed to set up TLS: {}", e)))
+    }
+
+    /// Clearer message for a failed connection to `target` when the TLS library reports a
+    /// protocol version mismatch, i.e. the server only offers versions older than this one
+    pub fn explain_rejection(self, target: &str, message: &str) -> Option<String> {
+        let lower = message.to_lowercase();
+        ["protocol version", "unsupported protocol", "no protocols available", "wrong version number"]
+            .iter()
+            .any(|marker| lower.contains(marker))
+            .then(|| format!("{} does not offer TLS {} or later (--tls-min-version): {}", target, self, message))
+    }
+}
+
+impl std::fmt::Display for TlsMinVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TlsMinVersion::Tls12 => write!(f, "1.2"),
+            TlsMinVersion::Tls13 => write!(f, "1.3"),
+        }
+    }
+}
+
+/// How the client authenticated, so other domain controllers can be bound the same way
+#[derive(Clone)]
+enum BindIdentity {
+    Gssapi,
+    Simple { username: String, password: SecretString },
+}
 
 pub struct LdapClient {
     ldap: Ldap,
     base_dn: String,
+    search_base: Option<String>,
+    object_class: String,
+    group_cache: GroupCache,
+    include_raw: bool,
+    /// Attributes requested on top of the account set (--attributes)
+    extra_attributes: Vec<String>,
+    count_members: bool,
+    use_token_groups: bool,
+    use_tls: bool,
+    tls_min_version: TlsMinVersion,
+    global_catalog: bool,
+    bind_identity: Option<BindIdentity>,
+    home_domain_clients: HashMap<String, LdapClient>,
+    follow_referrals: bool,
+    socks5_proxy: Option<String>,
+    /// Referral hops between the server the run connected to and this one
+    referral_depth: usize,
+    /// Connections to referred servers by "ldap[s]://host:port"
+    referral_clients: HashMap<String, LdapClient>,
+    /// Domains that trust this one, read once per run
+    trusting_domains: Option<Vec<String>>,
+    /// Password policies by DN (domain root or PSO), read once per run; None when unreadable
+    password_policies: HashMap<String, Option<PasswordPolicy>>,
+    /// DN to report on when several accounts share the requested name (--disambiguate)
+    disambiguate_dn: Option<String>,
+    /// Shared by every connection of the run (--rate-limit)
+    rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 impl LdapClient {
+    /// Upper bound on accounts fetched when looking for "did you mean" suggestions
+    const SUGGESTION_CANDIDATE_LIMIT: i32 = 500;
+    /// Largest edit distance still offered as a suggestion
+    const MAX_SUGGESTION_DISTANCE: usize = 2;
+    /// User attributes AD maintains on every account; if one is missing, the bind account
+    /// is not allowed to read it
+    const ALWAYS_PRESENT_USER_ATTRIBUTES: [&'static str; 10] = [
+        "distinguishedName",
+        "sAMAccountName",
+        "objectClass",
+        "objectSid",
+        "userAccountControl",
+        "pwdLastSet",
+        "accountExpires",
+        "whenCreated",
+        "whenChanged",
+        "primaryGroupID",
+    ];
+    /// Attributes read for every account, user or computer
+    const ACCOUNT_ATTRIBUTES: [&'static str; 22] = [
+        "distinguishedName",
+        "sAMAccountName",
+        "objectClass",
+        "objectSid",
+        "userPrincipalName",
+        "servicePrincipalName",
+        "displayName",
+        "mail",
+        "department",
+        "title",
+        "description",
+        "userAccountControl",
+        "logonHours",
+        "pwdLastSet",
+        "msDS-UserPasswordExpiryTimeComputed",
+        "msDS-ResultantPSO",
+        "accountExpires",
+        "lastLogonTimestamp",
+        "whenCreated",
+        "whenChanged",
+        "memberOf",
+        "primaryGroupID",
+    ];
+    /// Host and delegation attributes read on top for computer accounts
+    const COMPUTER_ATTRIBUTES: [&'static str; 5] = [
+        "dNSHostName",
+        "operatingSystem",
+        "operatingSystemVersion",
+        "msDS-AllowedToDelegateTo",
+        "msDS-AllowedToActOnBehalfOfOtherIdentity",
+    ];
+    /// Page size for the member count searches, below the usual 1000-entry server limit
+    const MEMBER_COUNT_PAGE_SIZE: i32 = 500;
+    /// Referral hops followed away from the original server, which also cuts referral loops
+    const MAX_REFERRAL_DEPTH: usize = 3;
+
+    /// Connect to `server`, on `port` if given, otherwise 636 (TLS) or 389
     pub async fn connect(
         server: &str,
+        port: Option<u16>,
+        use_tls: bool,
+        tls_min_version: TlsMinVersion,
+    ) -> Result<Self> {
+        Self::open(server, port.unwrap_or(Self::default_port(use_tls, false)), use_tls, tls_min_version, None).await
+    }
+
+    /// Like `connect` (or `connect_global_catalog`), with every connection of the run,
+    /// including those to other domains and referred servers, tunnelled through the
+    /// SOCKS5 proxy at `proxy` ("host:port")
+    pub async fn connect_via_socks5(
+        server: &str,
+        port: Option<u16>,
+        use_tls: bool,
+        tls_min_version: TlsMinVersion,
+        global_catalog: bool,
+        proxy: &str,
+    ) -> Result<Self> {
+        let port = port.unwrap_or(Self::default_port(use_tls, global_catalog));
+        let mut client = Self::open(server, port, use_tls, tls_min_version, Some(proxy)).await?;
+        client.global_catalog = global_catalog;
+        Ok(client)
+    }
+
+    /// Connect to the global catalog (3268, or 3269 with TLS) to find users anywhere in the forest
+    ///
+    /// The GC only holds a partial attribute set, so `get_user` locates the account there and
+    /// then reads it from a domain controller of the user's home domain.
+    pub async fn connect_global_catalog(
+        server: &str,
+        port: Option<u16>,
         use_tls: bool,
+        tls_min_version: TlsMinVersion,
     ) -> Result<Self> {
-        let ldap_url = if use_tls {
-            format!("ldaps://{}:636", server)
+        let port = port.unwrap_or(Self::default_port(use_tls, true));
+        let mut client = Self::open(server, port, use_tls, tls_min_version, None).await?;
+        client.global_catalog = true;
+        Ok(client)
+    }
+
+    /// Standard port for LDAP (389), LDAPS (636) or the global catalog (3268/3269)
+    pub fn default_port(use_tls: bool, global_catalog: bool) -> u16 {
+        match (global_catalog, use_tls) {
+            (false, false) => 389,
+            (false, true) => 636,
+            (true, false) => 3268,
+            (true, true) => 3269,
+        }
+    }
+
+    fn ldap_url(server: &str, port: u16, use_tls: bool) -> String {
+        // IPv6 literals need brackets inside a URL
+        let host = if server.contains(':') && !server.starts_with('[') {
+            format!("[{}]", server)
         } else {
-            format!("ldap://{}:389", server)
+            server.to_string()
+        };
+        let scheme = if use_tls { "ldaps" } else { "ldap" };
+        format!("{}://{}:{}", scheme, host, port)
+    }
+
+    /// Connect to `server:port` directly, or through a tunnel to the SOCKS5 proxy;
+    /// with a tunnel TLS is handled by the tunnel, so ldap3 connects in plain LDAP
+    async fn open(
+        server: &str,
+        port: u16,
+        use_tls: bool,
+        tls_min_version: TlsMinVersion,
+        socks5_proxy: Option<&str>,
+    ) -> Result<Self> {
+        let Some(proxy) = socks5_proxy else {
+            let url = Self::ldap_url(server, port, use_tls);
+            let settings = LdapConnSettings::new().set_connector(tls_min_version.connector()?);
+            let mut client = Self::connect_url(server, &url, settings).await.map_err(|e| match e {
+                AdReportError::ConnectionFailed(message) if use_tls => AdReportError::ConnectionFailed(
+                    tls_min_version.explain_rejection(&format!("{}:{}", server, port), &message).unwrap_or(message)
+                ),
+                e => e,
+            })?;
+            client.use_tls = use_tls;
+            client.tls_min_version = tls_min_version;
+            return Ok(client);
         };
 
-        let settings = LdapConnSettings::new();
+        let tunnel_url = socks_tunnel::open(proxy, server, port, use_tls.then_some(tls_min_version)).await?;
+        let mut client = Self::connect_url(server, &tunnel_url, LdapConnSettings::new()).await?;
+        client.use_tls = use_tls;
+        client.tls_min_version = tls_min_version;
+        client.socks5_proxy = Some(proxy.to_string());
+        Ok(client)
+    }
+
+    async fn connect_url(server: &str, ldap_url: &str, settings: LdapConnSettings) -> Result<Self> {
         let (conn, mut ldap) = LdapConnAsync::with_settings(
             settings,
-            &ldap_url,
+            ldap_url,
         ).await
-            .context("Failed to connect to LDAP server")?;
+            .map_err(|e| AdReportError::from_ldap(e, "Failed to connect to LDAP server"))?;
 
         ldap3::drive!(conn);
 
@@ -41,106 +283,592 @@ impl LdapClient {
         Ok(Self {
             ldap,
             base_dn,
+            search_base: None,
+            object_class: "user".to_string(),
+            group_cache: GroupCache::default(),
+            include_raw: false,
+            extra_attributes: Vec::new(),
+            count_members: false,
+            use_token_groups: false,
+            use_tls: false,
+            tls_min_version: TlsMinVersion::default(),
+            global_catalog: false,
+            bind_identity: None,
+            home_domain_clients: HashMap::new(),
+            follow_referrals: false,
+            socks5_proxy: None,
+            referral_depth: 0,
+            disambiguate_dn: None,
+            rate_limiter: None,
+            referral_clients: HashMap::new(),
+            trusting_domains: None,
+            password_policies: HashMap::new(),
         })
     }
 
+    /// Configure the per-run group cache (max_entries of 0 disables caching)
+    pub fn set_group_cache_limits(&mut self, max_entries: usize, ttl: Duration) {
+        self.group_cache = GroupCache::new(max_entries, ttl);
+    }
+
+    /// Group cache (hits, misses, entries) for the run so far
+    pub fn group_cache_stats(&self) -> (usize, usize, usize) {
+        self.group_cache.stats()
+    }
+
+    /// Override the discovered naming context for user searches (e.g., to scope to an OU)
+    pub fn set_search_base(&mut self, search_base: &str) {
+        self.search_base = Some(search_base.to_string());
+    }
+
+    /// Replace the objectClass used to match user entries (default: "user")
+    pub fn set_object_class(&mut self, object_class: &str) {
+        self.object_class = object_class.to_string();
+    }
+
+    /// Capture the user's raw attribute map on `ADUser::raw_attributes`
+    pub fn set_include_raw(&mut self, include_raw: bool) {
+        self.include_raw = include_raw;
+    }
+
+    /// Also fetch these attributes for every account and keep their values in
+    /// `ADUser::raw_attributes` (the full raw map with `set_include_raw`)
+    pub fn set_extra_attributes(&mut self, attributes: Vec<String>) {
+        self.extra_attributes = attributes;
+    }
+
+    /// Look up how many members each resolved group has (one extra paged search per group)
+    pub fn set_count_members(&mut self, count_members: bool) {
+        self.count_members = count_members;
+    }
+
+    /// Cross-check memberships against the user's tokenGroups, adding any the memberOf
+    /// walk missed (one SID lookup per group in the token)
+    pub fn set_use_token_groups(&mut self, use_token_groups: bool) {
+        self.use_token_groups = use_token_groups;
+    }
+
+    /// Follow referrals in account and group searches to the referred servers, binding
+    /// with the same credentials; set before binding so they are kept for those servers
+    pub fn set_follow_referrals(&mut self, follow_referrals: bool) {
+        self.follow_referrals = follow_referrals;
+    }
+
+    /// Report on this DN when more than one account matches the requested name; without it
+    /// such lookups fail with `AdReportError::AmbiguousAccount`
+    pub fn set_disambiguate(&mut self, dn: Option<String>) {
+        self.disambiguate_dn = dn;
+    }
+
+    /// Pace every LDAP operation (binds and searches, including those on home-domain and
+    /// referred connections) through `limiter`; set before binding so the bind is paced too
+    pub fn set_rate_limiter(&mut self, limiter: Option<Arc<RateLimiter>>) {
+        self.rate_limiter = limiter;
+    }
+
+    /// Wait for the rate limiter, if any, before sending an LDAP operation
+    async fn throttle(&self) {
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire().await;
+        }
+    }
+
+    /// Base DN used for user searches
+    pub fn user_search_base(&self) -> &str {
+        self.search_base.as_deref().unwrap_or(&self.base_dn)
+    }
+
+    fn user_filter(&self, username: &str) -> String {
+        format!("(&(objectClass={})(sAMAccountName={}))", self.object_class, ldap_escape(username))
+    }
+
+    /// " Did you mean: a, b?" for accounts within a small edit distance of a missing username
+    ///
+    /// Candidates share the first or last two characters, which catches most typos
+    /// without enumerating the directory. Lookup errors just mean no suggestions.
+    async fn did_you_mean(&mut self, search_base: &str, username: &str) -> String {
+        let chars: Vec<char> = username.chars().collect();
+        if chars.len() < 3 {
+            return String::new();
+        }
+        let prefix: String = chars[..2].iter().collect();
+        let suffix: String = chars[chars.len() - 2..].iter().collect();
+        let filter = format!(
+            "(&(objectClass={})(|(sAMAccountName={}*)(sAMAccountName=*{})))",
+            self.object_class, ldap_escape(prefix), ldap_escape(suffix)
+        );
+
+        self.throttle().await;
+        let search = self.ldap
+            .with_search_options(SearchOptions::new().sizelimit(Self::SUGGESTION_CANDIDATE_LIMIT))
+            .search(search_base, Scope::Subtree, &filter, vec!["sAMAccountName"])
+            .await;
+        let Ok(ldap3::SearchResult(rs, _res)) = search else {
+            return String::new();
+        };
+
+        let wanted = username.to_lowercase();
+        let mut matches: Vec<(usize, String)> = rs
+            .into_iter()
+            .filter_map(|entry| Self::get_attr(&SearchEntry::construct(entry), "sAMAccountName"))
+            .map(|name| (edit_distance(&wanted, &name.to_lowercase()), name))
+            .filter(|(distance, _)| *distance <= Self::MAX_SUGGESTION_DISTANCE)
+            .collect();
+        matches.sort();
+        matches.truncate(3);
+
+        if matches.is_empty() {
+            return String::new();
+        }
+        let names: Vec<String> = matches.into_iter().map(|(_, name)| name).collect();
+        format!(" Did you mean: {}?", names.join(", "))
+    }
+
 
     /// Bind using GSSAPI/Kerberos authentication (Windows integrated)
     /// Requires:
-    /// - Windows domain-joined machine
-    /// - Valid Kerberos ticket (automatically obtained)
+    /// - Windows domain-joined machine, or a Unix Kerberos credential cache
+    ///   (e.g., after `kinit`) with the `gssapi` feature enabled
+    /// - Valid Kerberos ticket (automatically obtained on Windows)
     /// - Server FQDN (not IP address or short hostname)
     pub async fn bind_gssapi(&mut self, server_fqdn: &str) -> Result<()> {
+        self.sasl_gssapi_bind(server_fqdn).await?;
+        self.bind_identity = Some(BindIdentity::Gssapi);
+        Ok(())
+    }
+
+    async fn sasl_gssapi_bind(&mut self, server_fqdn: &str) -> Result<()> {
         #[cfg(windows)]
         {
             // Perform SASL GSSAPI bind using current user's Kerberos credentials
+            self.throttle().await;
             self.ldap
                 .sasl_gssapi_bind(server_fqdn)
                 .await
-                .context(
+                .map_err(|e| AdReportError::from_bind(e,
                     "GSSAPI bind failed. This usually indicates:\n\
                      1. Server FQDN is incorrect (provide full domain name, not IP)\n\
                      2. Machine is not domain-joined\n\
                      3. Kerberos ticket unavailable (reboot or use 'kinit' on Unix)\n\
                      4. Service Principal Name (SPN) not registered in AD\n\
                      5. Network connectivity to domain controller lost"
-                )?
+                ))?
+                .success()
+                .map_err(|e| AdReportError::from_bind(e, "GSSAPI bind authentication failed"))?;
+            Ok(())
+        }
+        #[cfg(all(not(windows), feature = "gssapi"))]
+        {
+            let ccache = WindowsAuth::find_kerberos_ccache()
+                .ok_or_else(Self::gssapi_unavailable_error)?;
+            tracing::debug!("Using Kerberos credential cache: {}", ccache);
+
+            // Perform SASL GSSAPI bind using the TGT in the system credential cache
+            self.throttle().await;
+            self.ldap
+                .sasl_gssapi_bind(server_fqdn)
+                .await
+                .map_err(|e| AdReportError::from_bind(e,
+                    "GSSAPI bind failed. This usually indicates:\n\
+                     1. Server FQDN is incorrect (provide full domain name, not IP)\n\
+                     2. Kerberos ticket expired (run 'klist' to check, 'kinit' to renew)\n\
+                     3. krb5.conf realm/KDC configuration does not match the domain\n\
+                     4. Service Principal Name (SPN) not registered in AD\n\
+                     5. Network connectivity to domain controller lost"
+                ))?
                 .success()
-                .context("GSSAPI bind authentication failed")?;
+                .map_err(|e| AdReportError::from_bind(e, "GSSAPI bind authentication failed"))?;
             Ok(())
         }
-        #[cfg(not(windows))]
+        #[cfg(all(not(windows), not(feature = "gssapi")))]
         {
-            Err(anyhow::anyhow!(
-                "GSSAPI/Kerberos authentication requires:\n\
-                 - Windows platform\n\
-                 - Domain-joined machine\n\
-                 - Proper SPN registration in Active Directory\n\n\
-                 Alternative: Use explicit credentials with --username and --password options"
-            ))
+            let _ = server_fqdn;
+            if let Some(ccache) = WindowsAuth::find_kerberos_ccache() {
+                return Err(AdReportError::BindFailed(format!(
+                    "Found Kerberos credential cache ({}), but this build does not include GSSAPI support.\n\
+                     Rebuild with '--features gssapi' to use it, or use --username and --password options",
+                    ccache
+                )));
+            }
+            Err(Self::gssapi_unavailable_error())
         }
     }
 
+    #[cfg(not(windows))]
+    fn gssapi_unavailable_error() -> AdReportError {
+        AdReportError::BindFailed(
+            "GSSAPI/Kerberos authentication requires:\n\
+             - Windows platform and a domain-joined machine, or\n\
+             - A Kerberos credential cache on Linux/macOS (run 'kinit user@REALM', check with 'klist')\n\
+             - Proper SPN registration in Active Directory\n\n\
+             Alternative: Use explicit credentials with --username and --password options"
+                .to_string()
+        )
+    }
+
     /// Bind using simple authentication (username/password)
     /// Fallback for non-Windows platforms or when GSSAPI is unavailable
-    pub async fn bind_simple(&mut self, username: &str, password: &str) -> Result<()> {
+    ///
+    /// The password is kept after the bind to bind to other servers the same way (the home
+    /// domain found through the global catalog, referred servers and trusting domains); it
+    /// is zeroized when the client drops
+    pub async fn bind_simple(&mut self, username: &str, password: &SecretString) -> Result<()> {
+        if self.socks5_proxy.is_some() && !socks_tunnel::PEER_VERIFIED {
+            return Err(AdReportError::BindFailed(
+                "Simple bind through --socks5 is not supported on this platform: the tunnel's local end \
+                 cannot be restricted to this process, so the password could be read by others. \
+                 Use --use-gssapi instead".to_string()
+            ));
+        }
+        self.throttle().await;
         self.ldap
-            .simple_bind(username, password)
+            .simple_bind(username, password.expose_secret())
             .await
-            .context("Failed to connect for simple bind")?
+            .map_err(|e| AdReportError::from_bind(e, "Failed to connect for simple bind"))?
             .success()
-            .context("Simple bind authentication failed")?;
+            .map_err(|e| AdReportError::from_bind(e, "Simple bind authentication failed"))?;
+        self.bind_identity = Some(BindIdentity::Simple {
+            username: username.to_string(),
+            password: password.clone(),
+        });
         Ok(())
     }
 
     pub async fn get_user(&mut self, username: &str) -> Result<ADUser> {
-        // Search for user
-        let filter = format!("(&(objectClass=user)(sAMAccountName={}))", username);
-        let attributes = vec![
-            "distinguishedName",
-            "sAMAccountName",
-            "userPrincipalName",
-            "displayName",
-            "mail",
-            "department",
-            "title",
-            "description",
-            "userAccountControl",
-            "lastLogonTimestamp",
-            "whenCreated",
-            "whenChanged",
-            "memberOf",
-            "primaryGroupID",
-        ];
+        if self.global_catalog {
+            return self.get_user_from_forest(username).await;
+        }
+        self.get_user_in_domain(username).await
+    }
 
+    /// Locate the user in the global catalog, then read it from its home domain
+    async fn get_user_from_forest(&mut self, username: &str) -> Result<ADUser> {
+        let filter = self.user_filter(username);
+        self.throttle().await;
         let (rs, _res) = self.ldap
             .search(
-                &self.base_dn,
+                "",
                 Scope::Subtree,
                 &filter,
-                attributes,
+                vec!["distinguishedName"],
             )
             .await
-            .context("Failed to search for user")?
+            .map_err(|e| AdReportError::from_ldap(e, "Failed to search the global catalog"))?
             .success()
-            .context("User search failed")?;
+            .map_err(|e| AdReportError::from_ldap(e, "Global catalog search failed"))?;
+
+        let entries = rs.into_iter().map(SearchEntry::construct).collect();
+        let Some(entry) = self.choose_account(entries, username)? else {
+            let suggestion = self.did_you_mean("", username).await;
+            return Err(AdReportError::UserNotFound(format!(
+                "User '{}' not found anywhere in the forest (global catalog).{}",
+                username, suggestion
+            )));
+        };
+        let user_dn = entry.dn;
+
+        let naming_context = Self::naming_context_of(&user_dn)
+            .ok_or_else(|| AdReportError::ParseError(format!("Cannot determine the domain of {}", user_dn)))?;
+        let domain_dns = Self::dns_name_of(&naming_context);
+        tracing::debug!("Found {} in domain {} via global catalog", username, domain_dns);
+
+        if !self.home_domain_clients.contains_key(&naming_context) {
+            let home = self.connect_home_domain(&domain_dns, &naming_context).await?;
+            self.home_domain_clients.insert(naming_context.clone(), home);
+        }
+
+        let home = self.home_domain_clients
+            .get_mut(&naming_context)
+            .ok_or_else(|| AdReportError::ConnectionFailed("Home domain connection missing".to_string()))?;
+        home.get_user_in_domain(username).await
+    }
+
+    /// Open and bind a connection to a DC of another domain in the forest
+    async fn connect_home_domain(&self, domain_dns: &str, naming_context: &str) -> Result<LdapClient> {
+        let port = Self::default_port(self.use_tls, false);
+        let mut home = LdapClient::open(domain_dns, port, self.use_tls, self.tls_min_version, self.socks5_proxy.as_deref())
+            .await
+            .map_err(|e| AdReportError::ConnectionFailed(
+                format!("Failed to connect to a domain controller for {}: {}", domain_dns, e)
+            ))?;
+
+        self.bind_like_self(&mut home, domain_dns).await?;
+        home.search_base = Some(naming_context.to_string());
+        Ok(home)
+    }
+
+    /// Bind `other` the way this client was bound and give it the same lookup settings
+    async fn bind_like_self(&self, other: &mut LdapClient, server_fqdn: &str) -> Result<()> {
+        other.follow_referrals = self.follow_referrals;
+        other.rate_limiter = self.rate_limiter.clone();
+        match &self.bind_identity {
+            Some(BindIdentity::Gssapi) => other.bind_gssapi(server_fqdn).await?,
+            Some(BindIdentity::Simple { username, password }) => other.bind_simple(username, password).await?,
+            None => return Err(AdReportError::BindFailed(format!("No credentials kept to bind to {}", server_fqdn))),
+        }
+
+        other.object_class = self.object_class.clone();
+        other.include_raw = self.include_raw;
+        other.extra_attributes = self.extra_attributes.clone();
+        other.count_members = self.count_members;
+        other.use_token_groups = self.use_token_groups;
+        other.disambiguate_dn = self.disambiguate_dn.clone();
+        other.group_cache = GroupCache::new(self.group_cache.max_entries(), self.group_cache.ttl());
+        Ok(())
+    }
+
+    /// Connect and bind to the first reachable server in `referrals`, returning its
+    /// connection key and the base DN the referral points at
+    ///
+    /// None when referral following is off, the hop limit is reached or no referred
+    /// server answers. Referred servers use TLS when this run does or the referral is
+    /// `ldaps://`, so a referral never downgrades the connection to plaintext.
+    async fn follow_referral(&mut self, referrals: &[String], fallback_base: &str) -> Option<(String, String)> {
+        if referrals.is_empty() {
+            return None;
+        }
+        if !self.follow_referrals {
+            tracing::warn!("Search returned referrals that were not followed (use --follow-referrals): {}", referrals.join(", "));
+            return None;
+        }
+        if self.referral_depth >= Self::MAX_REFERRAL_DEPTH {
+            tracing::warn!("Not following referrals after {} hops: {}", self.referral_depth, referrals.join(", "));
+            return None;
+        }
+
+        for url in referrals {
+            let Some((host, port, use_tls, base)) = Self::parse_referral(url, self.use_tls) else {
+                tracing::warn!("Skipping malformed referral: {}", url);
+                continue;
+            };
+            let key = format!("{}://{}:{}", if use_tls { "ldaps" } else { "ldap" }, host, port);
+            tracing::info!("Following referral to {}", url);
+
+            if !self.referral_clients.contains_key(&key) {
+                let mut referred = match Self::open(&host, port, use_tls, self.tls_min_version, self.socks5_proxy.as_deref()).await {
+                    Ok(referred) => referred,
+                    Err(e) => {
+                        tracing::warn!("Could not connect to referred server {}: {}", key, e);
+                        continue;
+                    }
+                };
+                if let Err(e) = self.bind_like_self(&mut referred, &host).await {
+                    tracing::warn!("Could not bind to referred server {}: {}", key, e);
+                    continue;
+                }
+                referred.referral_depth = self.referral_depth + 1;
+                self.referral_clients.insert(key.clone(), referred);
+            }
+
+            return Some((key, base.unwrap_or_else(|| fallback_base.to_string())));
+        }
+        None
+    }
+
+    /// Host, port, whether to use TLS and (percent-decoded) base DN of an
+    /// "ldap[s]://host[:port]/dn?..." referral
+    fn parse_referral(url: &str, use_tls: bool) -> Option<(String, u16, bool, Option<String>)> {
+        let (scheme, rest) = url.split_once("://")?;
+        let use_tls = use_tls || scheme.eq_ignore_ascii_case("ldaps");
+        let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+
+        let (host, port) = match authority.strip_prefix('[') {
+            Some(bracketed) => {
+                let (host, after) = bracketed.split_once(']')?;
+                (host, after.strip_prefix(':'))
+            }
+            None => match authority.rsplit_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (authority, None),
+            },
+        };
+        if host.is_empty() {
+            return None;
+        }
+        let port = match port {
+            Some(port) => port.parse().ok()?,
+            None => Self::default_port(use_tls, false),
+        };
+
+        let dn = path.split('?').next().unwrap_or("");
+        let base = (!dn.is_empty()).then(|| Self::percent_decode(dn));
+        Some((host.to_string(), port, use_tls, base))
+    }
+
+    /// Undo the %XX escapes of a DN in a referral URL
+    fn percent_decode(text: &str) -> String {
+        let bytes = text.as_bytes();
+        let mut decoded = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            let hex = bytes.get(i + 1..i + 3)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+            match (bytes[i], hex) {
+                (b'%', Some(byte)) => {
+                    decoded.push(byte);
+                    i += 3;
+                }
+                (byte, _) => {
+                    decoded.push(byte);
+                    i += 1;
+                }
+            }
+        }
+        String::from_utf8_lossy(&decoded).into_owned()
+    }
+
+    async fn get_user_in_domain(&mut self, username: &str) -> Result<ADUser> {
+        // Search for user
+        let filter = self.user_filter(username);
+        let search_base = self.user_search_base().to_string();
+        match self.read_account(&search_base, &filter, username, &[]).await? {
+            Some((user, _entry)) => Ok(user),
+            None => {
+                let suggestion = self.did_you_mean(&search_base, username).await;
+                Err(AdReportError::UserNotFound(format!(
+                    "User '{}' not found under {}.{} (if the account lives in another domain of the forest, retry with --global-catalog)",
+                    username, search_base, suggestion
+                )))
+            }
+        }
+    }
+
+    /// Look up a computer account by name, with or without the trailing '$'
+    ///
+    /// The account is read exactly like a user (groups, rights, password policy); the host
+    /// and delegation attributes are added on top. Only the bound domain is searched.
+    pub async fn get_computer(&mut self, name: &str) -> Result<ADComputer> {
+        let sam_account_name = if name.ends_with('$') { name.to_string() } else { format!("{}$", name) };
+        let filter = format!("(&(objectClass=computer)(sAMAccountName={}))", ldap_escape(&sam_account_name));
+        let search_base = self.user_search_base().to_string();
+
+        let Some((account, entry)) = self.read_account(&search_base, &filter, &sam_account_name, &Self::COMPUTER_ATTRIBUTES).await? else {
+            return Err(AdReportError::UserNotFound(format!(
+                "Computer '{}' not found under {}.", sam_account_name, search_base
+            )));
+        };
+
+        let mut computer = ADComputer::new(account);
+        computer.dns_host_name = Self::get_attr(&entry, "dNSHostName");
+        computer.operating_system = Self::get_attr(&entry, "operatingSystem");
+        computer.operating_system_version = Self::get_attr(&entry, "operatingSystemVersion");
+        if let Some(uac) = Self::get_attr(&entry, "userAccountControl").and_then(|v| v.parse::<u32>().ok()) {
+            computer.is_domain_controller = (uac & 0x2000) != 0; // ADS_UF_SERVER_TRUST_ACCOUNT
+            computer.delegation.unconstrained = (uac & 0x80000) != 0; // ADS_UF_TRUSTED_FOR_DELEGATION
+            computer.delegation.protocol_transition = (uac & 0x1000000) != 0; // ADS_UF_TRUSTED_TO_AUTHENTICATE_FOR_DELEGATION
+        }
+        computer.delegation.constrained_to = entry.attrs
+            .get("msDS-AllowedToDelegateTo")
+            .cloned()
+            .unwrap_or_default();
+        computer.delegation.resource_based = Self::get_bin_attr(&entry, "msDS-AllowedToActOnBehalfOfOtherIdentity").is_some();
+
+        Ok(computer)
+    }
+
+    /// The one entry a lookup by name should report on
+    ///
+    /// A base spanning several domains, or stale copies of an account, can return more than
+    /// one match; picking the first would report on whichever the server listed first.
+    fn choose_account(&self, mut entries: Vec<SearchEntry>, name: &str) -> Result<Option<SearchEntry>> {
+        if entries.len() <= 1 {
+            return Ok(entries.pop());
+        }
+
+        if let Some(dn) = &self.disambiguate_dn {
+            if let Some(index) = entries.iter().position(|entry| entry.dn.eq_ignore_ascii_case(dn)) {
+                return Ok(Some(entries.swap_remove(index)));
+            }
+        }
+
+        let mut dns: Vec<&str> = entries.iter().map(|entry| entry.dn.as_str()).collect();
+        dns.sort_unstable();
+        let hint = match &self.disambiguate_dn {
+            Some(dn) => format!("--disambiguate {} is not one of them", dn),
+            None => "choose one with --disambiguate <DN>".to_string(),
+        };
+        Err(AdReportError::AmbiguousAccount(format!(
+            "{} accounts match '{}'; {}:\n  {}",
+            dns.len(), name, hint, dns.join("\n  ")
+        )))
+    }
+
+    /// Find one account with `filter` and read its attributes, group memberships and rights,
+    /// requesting `extra_attributes` on top of the user set; None when nothing matches
+    async fn read_account(
+        &mut self,
+        search_base: &str,
+        filter: &str,
+        name: &str,
+        extra_attributes: &[&'static str],
+    ) -> Result<Option<(ADUser, SearchEntry)>> {
+        let mut attributes: Vec<&str> = Self::ACCOUNT_ATTRIBUTES.to_vec();
+        attributes.extend_from_slice(extra_attributes);
+        let requested_extras: Vec<String> = self.extra_attributes.iter()
+            .filter(|extra| !attributes.iter().any(|a| a.eq_ignore_ascii_case(extra)))
+            .cloned()
+            .collect();
+        attributes.extend(requested_extras.iter().map(String::as_str));
+
+        self.throttle().await;
+        let (rs, res) = self.ldap
+            .search(
+                search_base,
+                Scope::Subtree,
+                filter,
+                attributes.clone(),
+            )
+            .await
+            .map_err(|e| AdReportError::from_ldap(e, "Failed to search for user"))?
+            .non_error()
+            .map_err(|e| AdReportError::from_ldap(e, "User search failed"))?;
+
+        // Confirm the account exists before resolving any group memberships
+        let entries = rs.into_iter().map(SearchEntry::construct).collect();
+        let Some(search_entry) = self.choose_account(entries, name)? else {
+            // search() gathers continuation references and referral results into `refs`
+            let Some((key, base)) = self.follow_referral(&res.refs, search_base).await else {
+                return Ok(None);
+            };
+            let Some(referred) = self.referral_clients.get_mut(&key) else {
+                return Ok(None);
+            };
+            return Box::pin(referred.read_account(&base, filter, name, extra_attributes)).await;
+        };
+
+        tracing::debug!("Found {}; resolving group memberships", search_entry.dn);
+        let missing_attributes = Self::missing_attributes(&search_entry, &attributes);
 
-        let entry = rs
-            .into_iter()
-            .next()
-            .context("User not found")?;
-        
-        let search_entry = SearchEntry::construct(entry);
-        
         // Parse user attributes
         let mut user = ADUser::new(
             search_entry.dn.clone(),
             Self::get_attr(&search_entry, "sAMAccountName")
-                .unwrap_or_else(|| username.to_string()),
+                .unwrap_or_else(|| name.to_string()),
         );
+        user.missing_attributes = missing_attributes;
+
+        if self.include_raw {
+            user.raw_attributes = Some(Self::raw_attribute_map(&search_entry));
+        } else if !self.extra_attributes.is_empty() {
+            // Only the requested extras, so the appendix shows what was asked for
+            let mut raw = Self::raw_attribute_map(&search_entry);
+            raw.retain(|name, _| self.extra_attributes.iter().any(|extra| extra.eq_ignore_ascii_case(name)));
+            user.raw_attributes = Some(raw);
+        }
 
         // Populate user fields
+        user.object_classes = search_entry.attrs
+            .get("objectClass")
+            .cloned()
+            .unwrap_or_default();
+        user.object_sid = Self::get_bin_attr(&search_entry, "objectSid")
+            .and_then(|bytes| binary_sid_to_string(&bytes).ok());
         user.user_principal_name = Self::get_attr(&search_entry, "userPrincipalName");
+        user.service_principal_names = search_entry.attrs
+            .get("servicePrincipalName")
+            .cloned()
+            .unwrap_or_default();
         user.display_name = Self::get_attr(&search_entry, "displayName");
         user.email = Self::get_attr(&search_entry, "mail");
         user.department = Self::get_attr(&search_entry, "department");
@@ -154,19 +882,39 @@ impl LdapClient {
                 user.account_locked = (uac & 0x10) != 0; // ADS_UF_LOCKOUT
                 user.password_expired = (uac & 0x800000) != 0; // ADS_UF_PASSWORD_EXPIRED
                 user.password_never_expires = (uac & 0x10000) != 0; // ADS_UF_DONT_EXPIRE_PASSWD
+                user.smartcard_required = (uac & 0x40000) != 0; // ADS_UF_SMARTCARD_REQUIRED
+                user.no_preauth_required = (uac & 0x400000) != 0; // ADS_UF_DONT_REQUIRE_PREAUTH
             }
         }
 
-        // Parse timestamps
-        user.last_logon = Self::parse_ad_timestamp(
-            Self::get_attr(&search_entry, "lastLogonTimestamp").as_deref()
-        );
-        user.created = Self::parse_ldap_timestamp(
-            Self::get_attr(&search_entry, "whenCreated").as_deref()
-        );
-        user.modified = Self::parse_ldap_timestamp(
-            Self::get_attr(&search_entry, "whenChanged").as_deref()
-        );
+        user.logon_hours = Self::get_bin_attr(&search_entry, "logonHours")
+            .and_then(|bytes| LogonHours::from_bytes(&bytes).ok());
+
+        // Parse timestamps (FILETIME sentinels 0 / i64::MAX become None)
+        user.last_logon = match Self::get_attr(&search_entry, "lastLogonTimestamp").as_deref().and_then(parse_filetime) {
+            Some(last_logon) => Some(last_logon),
+            None => self.last_logon_on_this_dc(&search_entry.dn).await,
+        };
+        user.password_last_set = Self::get_attr(&search_entry, "pwdLastSet").as_deref()
+            .and_then(parse_filetime);
+        // Constructed attribute honoring fine-grained password policies
+        user.password_expiry = Self::get_attr(&search_entry, "msDS-UserPasswordExpiryTimeComputed").as_deref()
+            .and_then(parse_filetime);
+        // A fine-grained PSO overrides the domain policy; it is only readable with
+        // sufficient rights, in which case the policy is reported as unknown
+        user.password_policy = match Self::get_attr(&search_entry, "msDS-ResultantPSO") {
+            Some(pso_dn) => self.password_policy(&pso_dn, true).await,
+            None => {
+                let domain_root = self.base_dn.clone();
+                self.password_policy(&domain_root, false).await
+            }
+        };
+        user.account_expires = Self::get_attr(&search_entry, "accountExpires").as_deref()
+            .and_then(parse_filetime);
+        user.created = Self::get_attr(&search_entry, "whenCreated").as_deref()
+            .and_then(parse_generalized_time);
+        user.modified = Self::get_attr(&search_entry, "whenChanged").as_deref()
+            .and_then(parse_generalized_time);
 
         // Get group memberships
         let member_of = search_entry.attrs
@@ -174,109 +922,479 @@ impl LdapClient {
             .cloned()
             .unwrap_or_default();
         
-        let mut processed_groups = HashSet::new();
+        // Unresolvable groups are recorded rather than failing the whole report
         for group_dn in member_of {
-            if let Ok(group) = self.get_group_recursive(&group_dn, &mut processed_groups).await {
-                user.groups.push(group);
+            match self.get_group_recursive(&group_dn, &mut Vec::new(), &mut user.resolution_warnings).await {
+                Ok(group) => user.groups.push(group),
+                Err(e) => user.resolution_warnings.push(format!("{}: {}", group_dn, e)),
             }
         }
 
         // Get primary group
         if let Some(primary_group_id) = Self::get_attr(&search_entry, "primaryGroupID") {
-            if let Ok(primary_group) = self.get_primary_group(&primary_group_id).await {
-                user.primary_group = Some(primary_group);
+            user.primary_group_id = primary_group_id.parse().ok();
+            match self.get_primary_group(&primary_group_id, user.object_sid.as_deref()).await {
+                Ok(primary_group) => user.primary_group = Some(primary_group),
+                Err(e) => user.resolution_warnings.push(format!("Primary group (RID {}): {}", primary_group_id, e)),
             }
         }
 
+        if let Some(sid) = user.object_sid.clone() {
+            self.add_trusting_domain_groups(&mut user, &sid).await;
+        }
+
+        if self.use_token_groups {
+            if let Err(e) = self.add_token_groups(&mut user).await {
+                user.resolution_warnings.push(format!("tokenGroups: {}", e));
+            }
+        }
+
+        // A group can be both direct and nested, or nested under several parents
+        user.dedupe_groups();
+
         // Populate user rights based on group memberships
         user.user_rights = self.determine_user_rights(&user);
 
-        Ok(user)
+        Ok(Some((user, search_entry)))
+    }
+
+    /// Read the non-replicated lastLogon attribute from the DC we are bound to
+    ///
+    /// lastLogonTimestamp is only written once the first logon replicates (and lags by up
+    /// to 14 days), so a fresh account can have logged on without it being set. The
+    /// fallback costs one base-scope search and is only made when the timestamp is absent;
+    /// any failure just leaves the account as never logged on.
+    async fn last_logon_on_this_dc(&mut self, dn: &str) -> Option<DateTime<Utc>> {
+        self.throttle().await;
+        match self.ldap
+            .search(dn, Scope::Base, "(objectClass=*)", vec!["lastLogon"])
+            .await
+            .and_then(|result| result.success())
+        {
+            Ok((rs, _res)) => rs.into_iter().next()
+                .and_then(|entry| Self::get_attr(&SearchEntry::construct(entry), "lastLogon"))
+                .as_deref()
+                .and_then(parse_filetime)
+                .inspect(|_| tracing::debug!("lastLogonTimestamp unset for {}; using lastLogon", dn)),
+            Err(e) => {
+                tracing::debug!("Could not read lastLogon for {}: {}", dn, e);
+                None
+            }
+        }
+    }
+
+    /// Read the constructed tokenGroups attribute (every security group SID the account
+    /// effectively holds, transitively and across trusts) and add the groups that the
+    /// memberOf walk did not reach as direct memberships, listed in `token_only_groups`
+    async fn add_token_groups(&mut self, user: &mut ADUser) -> Result<()> {
+        // tokenGroups is only computed for base-scope searches
+        self.throttle().await;
+        let (rs, _res) = self.ldap
+            .search(&user.distinguished_name, Scope::Base, "(objectClass=*)", vec!["tokenGroups"])
+            .await
+            .map_err(|e| AdReportError::from_ldap(e, "Failed to read tokenGroups"))?
+            .success()
+            .map_err(|e| AdReportError::from_ldap(e, "tokenGroups search failed"))?;
+        let entry = rs
+            .into_iter()
+            .next()
+            .ok_or_else(|| AdReportError::UserNotFound(user.distinguished_name.clone()))?;
+        let search_entry = SearchEntry::construct(entry);
+
+        let mut known: HashSet<String> = user.all_groups().iter()
+            .map(|g| g.distinguished_name.to_lowercase())
+            .collect();
+        let primary_sid = user.object_sid.as_deref()
+            .and_then(|sid| sid.rsplit_once('-'))
+            .zip(user.primary_group_id)
+            .map(|((domain, _), rid)| format!("{}-{}", domain, rid));
+
+        for value in Self::get_bin_attrs(&search_entry, "tokenGroups") {
+            let sid = match binary_sid_to_string(&value) {
+                Ok(sid) => sid,
+                Err(e) => {
+                    user.resolution_warnings.push(format!("tokenGroups: {}", e));
+                    continue;
+                }
+            };
+            if primary_sid.as_deref() == Some(sid.as_str()) {
+                continue;
+            }
+
+            let group = match self.find_group_dn_by_sid(&sid).await? {
+                Some(dn) if known.contains(&dn.to_lowercase()) => continue,
+                Some(dn) => match self.fetch_group(&dn).await {
+                    Ok((group, _member_of)) => group,
+                    Err(e) => {
+                        user.resolution_warnings.push(format!("{} (from tokenGroups): {}", dn, e));
+                        continue;
+                    }
+                },
+                // Foreign security principals and deleted groups have no group entry here
+                None => {
+                    // Builtin RIDs name the same group everywhere; other SIDs stay as they are
+                    let builtin_name = rid_of(&sid)
+                        .filter(|_| sid.starts_with("S-1-5-32-"))
+                        .and_then(well_known_rid_name);
+                    let name = builtin_name.map(str::to_string).unwrap_or_else(|| sid.clone());
+                    let mut group = ADGroup::new(sid.clone(), name);
+                    group.description = Some("SID from tokenGroups with no group entry in this domain".to_string());
+                    group
+                }
+            };
+
+            known.insert(group.distinguished_name.to_lowercase());
+            user.token_only_groups.push(group.name.clone());
+            user.groups.push(group);
+        }
+
+        Ok(())
+    }
+
+    /// DN of the group (or builtin group) with this string SID under the naming context
+    async fn find_group_dn_by_sid(&mut self, sid: &str) -> Result<Option<String>> {
+        self.throttle().await;
+        let (rs, _res) = self.ldap
+            .search(
+                &self.base_dn,
+                Scope::Subtree,
+                &format!("(&(objectClass=group)(objectSid={}))", ldap_escape(sid)),
+                vec!["distinguishedName"],
+            )
+            .await
+            .map_err(|e| AdReportError::from_ldap(e, "Failed to look up group SID"))?
+            .success()
+            .map_err(|e| AdReportError::from_ldap(e, "Group SID lookup failed"))?;
+
+        Ok(rs.into_iter().next().map(|entry| SearchEntry::construct(entry).dn))
+    }
+
+    /// Password policy stored at `dn`: the domain root (maxPwdAge, minPwdLength, pwdProperties)
+    /// or a password settings object; cached so each policy is read once per run
+    async fn password_policy(&mut self, dn: &str, is_pso: bool) -> Option<PasswordPolicy> {
+        let key = dn.to_lowercase();
+        if let Some(cached) = self.password_policies.get(&key) {
+            return cached.clone();
+        }
+
+        let (max_age_attr, min_length_attr, complexity_attr) = if is_pso {
+            ("msDS-MaximumPasswordAge", "msDS-MinimumPasswordLength", "msDS-PasswordComplexityEnabled")
+        } else {
+            ("maxPwdAge", "minPwdLength", "pwdProperties")
+        };
+        self.throttle().await;
+        let policy = match self.ldap
+            .search(dn, Scope::Base, "(objectClass=*)", vec!["cn", max_age_attr, min_length_attr, complexity_attr])
+            .await
+            .and_then(|result| result.success())
+        {
+            Ok((rs, _res)) => rs.into_iter().next().map(|entry| {
+                let entry = SearchEntry::construct(entry);
+                let source = match Self::get_attr(&entry, "cn") {
+                    Some(name) if is_pso => format!("Fine-grained policy '{}'", name),
+                    _ => "Default domain policy".to_string(),
+                };
+                PasswordPolicy {
+                    source,
+                    max_age_days: Self::get_attr(&entry, max_age_attr).as_deref()
+                        .and_then(parse_filetime_interval)
+                        .map(|age| age.num_days()),
+                    min_length: Self::get_attr(&entry, min_length_attr)
+                        .and_then(|length| length.parse().ok()),
+                    complexity_required: Self::get_attr(&entry, complexity_attr).and_then(|value| {
+                        if is_pso {
+                            Some(value.eq_ignore_ascii_case("TRUE"))
+                        } else {
+                            // DOMAIN_PASSWORD_COMPLEX
+                            value.parse::<u32>().ok().map(|properties| properties & 0x1 != 0)
+                        }
+                    }),
+                }
+            }),
+            Err(e) => {
+                tracing::debug!("Could not read password policy at {}: {}", dn, e);
+                None
+            }
+        };
+
+        self.password_policies.insert(key, policy.clone());
+        policy
     }
 
+    /// Resolve a group and everything it is nested in; `path` is the stack of parent DNs
+    /// from the user's direct group down to this one. It is recorded on each group as its
+    /// inheritance path and cuts cycles without dropping groups reached via another parent.
+    /// Nested groups that fail to resolve are skipped and noted in `warnings`
     fn get_group_recursive<'a>(
         &'a mut self,
         group_dn: &'a str,
-        processed: &'a mut HashSet<String>,
+        path: &'a mut Vec<String>,
+        warnings: &'a mut Vec<String>,
     ) -> Pin<Box<dyn Future<Output = Result<ADGroup>> + 'a>> {
         Box::pin(async move {
-            if processed.contains(group_dn) {
-                return Err(anyhow::anyhow!("Circular group reference detected"));
-            }
-            processed.insert(group_dn.to_string());
-
-            let attributes = vec![
-                "distinguishedName",
-                "cn",
-                "description",
-                "groupType",
-                "memberOf",
-            ];
-
-            let (rs, _res) = self.ldap
-                .search(
-                    group_dn,
-                    Scope::Base,
-                    "(objectClass=group)",
-                    attributes,
-                )
-                .await
-                .context("Failed to search for group")?
-                .success()
-                .context("Group search failed")?;
+            let (mut group, member_of) = self.fetch_group(group_dn).await?;
+            group.inheritance_path = path.clone();
 
-            let entry = rs
-                .into_iter()
-                .next()
-                .context("Group not found")?;
-            
-            let search_entry = SearchEntry::construct(entry);
-            
-            let mut group = ADGroup::new(
-                search_entry.dn.clone(),
-                Self::get_attr(&search_entry, "cn")
-                    .unwrap_or_else(|| "Unknown".to_string()),
-            );
-
-            group.description = Self::get_attr(&search_entry, "description");
-
-            // Parse group type
-            if let Some(gt_str) = Self::get_attr(&search_entry, "groupType") {
-                if let Ok(gt) = gt_str.parse::<i32>() {
-                    group.group_type = if (gt & 0x80000000u32 as i32) != 0 {
-                        GroupType::Security
-                    } else {
-                        GroupType::Distribution
-                    };
-
-                    group.scope = match gt & 0x7 {
-                        2 => GroupScope::Global,
-                        4 => GroupScope::DomainLocal,
-                        8 => GroupScope::Universal,
-                        _ => GroupScope::Global,
-                    };
+            // Get nested groups; a DN already on the path is a cycle, not missing data
+            path.push(group_dn.to_string());
+            for nested_dn in &member_of {
+                if path.iter().any(|dn| dn.eq_ignore_ascii_case(nested_dn)) {
+                    continue;
                 }
-            }
-
-            // Get nested groups
-            if let Some(member_of) = search_entry.attrs.get("memberOf") {
-                for nested_dn in member_of {
-                    if let Ok(nested_group) = self.get_group_recursive(nested_dn, processed).await {
-                        group.nested_groups.push(nested_group);
-                    }
+                match self.get_group_recursive(nested_dn, path, warnings).await {
+                    Ok(nested_group) => group.nested_groups.push(nested_group),
+                    Err(e) => warnings.push(format!("{} (nested in {}): {}", nested_dn, group.name, e)),
                 }
             }
+            path.pop();
 
             Ok(group)
         })
     }
 
-    async fn get_primary_group(&mut self, primary_group_id: &str) -> Result<ADGroup> {
-        // Convert primary group ID to RID and search for group
-        // This is a simplified implementation
-        let filter = format!("(&(objectClass=group)(primaryGroupToken={}))", primary_group_id);
-        
+    /// Add the groups of trusting domains that hold the account through a foreign security
+    /// principal, marking each with the account's SID in `foreign_sid`
+    ///
+    /// A trusting domain records an outside member as `CN=<SID>,CN=ForeignSecurityPrincipals`
+    /// in its own directory, so neither the account's memberOf nor its own domain lists these
+    /// groups. Domains that cannot be read are noted in the user's resolution warnings.
+    async fn add_trusting_domain_groups(&mut self, user: &mut ADUser, sid: &str) {
+        let partners = match self.trusting_domains().await {
+            Ok(partners) => partners,
+            Err(e) => {
+                user.resolution_warnings.push(format!("Trusting domains: {}", e));
+                return;
+            }
+        };
+
+        for partner in partners {
+            let naming_context = Self::naming_context_of_domain(&partner);
+            let fsp_dn = format!("CN={},CN=ForeignSecurityPrincipals,{}", sid, naming_context);
+            let member_of = match self.trusting_domain_client(&partner, &naming_context).await {
+                Ok(trusting) => trusting.foreign_principal_member_of(&fsp_dn).await
+                    .map(|member_of| (trusting, member_of)),
+                Err(e) => Err(e),
+            };
+            let (trusting, member_of) = match member_of {
+                Ok(found) => found,
+                Err(e) => {
+                    user.resolution_warnings.push(format!("{} (foreign principal in {}): {}", fsp_dn, partner, e));
+                    continue;
+                }
+            };
+
+            for group_dn in member_of {
+                match trusting.get_group_recursive(&group_dn, &mut Vec::new(), &mut user.resolution_warnings).await {
+                    Ok(mut group) => {
+                        group.foreign_sid = Some(sid.to_string());
+                        user.groups.push(group);
+                    }
+                    Err(e) => user.resolution_warnings.push(format!("{} (through foreign principal {}): {}", group_dn, sid, e)),
+                }
+            }
+        }
+    }
+
+    /// DNS names of the domains that trust this one (inbound trusts), from the trustedDomain
+    /// objects under CN=System; read once per run
+    async fn trusting_domains(&mut self) -> Result<Vec<String>> {
+        if let Some(partners) = &self.trusting_domains {
+            return Ok(partners.clone());
+        }
+
+        self.throttle().await;
         let (rs, _res) = self.ldap
+            .search(
+                &format!("CN=System,{}", self.base_dn),
+                Scope::OneLevel,
+                "(objectClass=trustedDomain)",
+                vec!["trustPartner", "trustDirection"],
+            )
+            .await
+            .map_err(|e| AdReportError::from_ldap(e, "Failed to read trusted domains"))?
+            .success()
+            .map_err(|e| AdReportError::from_ldap(e, "Trusted domain search failed"))?;
+
+        let partners: Vec<String> = rs.into_iter()
+            .map(SearchEntry::construct)
+            // TRUST_DIRECTION_INBOUND: the partner trusts this domain
+            .filter(|entry| Self::get_attr(entry, "trustDirection")
+                .and_then(|direction| direction.parse::<u32>().ok())
+                .is_some_and(|direction| direction & 0x1 != 0))
+            .filter_map(|entry| Self::get_attr(&entry, "trustPartner"))
+            .collect();
+        self.trusting_domains = Some(partners.clone());
+        Ok(partners)
+    }
+
+    /// Connection to a DC of the trusting domain `partner`, opened on first use
+    async fn trusting_domain_client(&mut self, partner: &str, naming_context: &str) -> Result<&mut LdapClient> {
+        if !self.home_domain_clients.contains_key(naming_context) {
+            let trusting = self.connect_home_domain(partner, naming_context).await?;
+            self.home_domain_clients.insert(naming_context.to_string(), trusting);
+        }
+        self.home_domain_clients
+            .get_mut(naming_context)
+            .ok_or_else(|| AdReportError::ConnectionFailed("Trusting domain connection missing".to_string()))
+    }
+
+    /// memberOf of the foreign security principal at `fsp_dn`; empty when this domain has
+    /// none, i.e. grants the account nothing
+    async fn foreign_principal_member_of(&mut self, fsp_dn: &str) -> Result<Vec<String>> {
+        self.throttle().await;
+        let result = self.ldap
+            .search(fsp_dn, Scope::Base, "(objectClass=foreignSecurityPrincipal)", vec!["memberOf"])
+            .await
+            .map_err(|e| AdReportError::from_ldap(e, "Failed to read foreign security principal"))?
+            .success()
+            .map_err(|e| AdReportError::from_ldap(e, "Foreign security principal search failed"));
+
+        match result {
+            Ok((rs, _res)) => Ok(rs.into_iter()
+                .next()
+                .and_then(|entry| SearchEntry::construct(entry).attrs.remove("memberOf"))
+                .unwrap_or_default()),
+            Err(AdReportError::UserNotFound(_)) => Ok(Vec::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Fetch a single group entry and its memberOf DNs, consulting the per-run cache first
+    async fn fetch_group(&mut self, group_dn: &str) -> Result<(ADGroup, Vec<String>)> {
+        if let Some(cached) = self.group_cache.get(group_dn) {
+            return Ok((cached.group, cached.member_of));
+        }
+
+        let attributes = vec![
+            "distinguishedName",
+            "cn",
+            "description",
+            "groupType",
+            "memberOf",
+            "primaryGroupToken",
+        ];
+
+        self.throttle().await;
+        let (rs, res) = self.ldap
+            .search(
+                group_dn,
+                Scope::Base,
+                "(objectClass=group)",
+                attributes,
+            )
+            .await
+            .map_err(|e| AdReportError::from_ldap(e, "Failed to search for group"))?
+            .non_error()
+            .map_err(|e| AdReportError::from_ldap(e, "Group search failed"))?;
+
+        let Some(entry) = rs.into_iter().next() else {
+            // A group in a partition this server does not hold is read where the referral points
+            if let Some((key, _base)) = self.follow_referral(&res.refs, group_dn).await {
+                if let Some(referred) = self.referral_clients.get_mut(&key) {
+                    let (group, member_of) = Box::pin(referred.fetch_group(group_dn)).await?;
+                    self.group_cache.insert(group_dn, group.clone(), member_of.clone());
+                    return Ok((group, member_of));
+                }
+            }
+            return Err(AdReportError::UserNotFound(format!("Group not found: {}", group_dn)));
+        };
+
+        let search_entry = SearchEntry::construct(entry);
+
+        let mut group = ADGroup::new(
+            search_entry.dn.clone(),
+            Self::get_attr(&search_entry, "cn")
+                .unwrap_or_else(|| "Unknown".to_string()),
+        );
+
+        group.description = Self::get_attr(&search_entry, "description");
+
+        // Parse group type
+        if let Some(gt_str) = Self::get_attr(&search_entry, "groupType") {
+            if let Ok(gt) = gt_str.parse::<i32>() {
+                group.group_type = if (gt & 0x80000000u32 as i32) != 0 {
+                    GroupType::Security
+                } else {
+                    GroupType::Distribution
+                };
+
+                group.scope = match gt & 0x7 {
+                    2 => GroupScope::Global,
+                    4 => GroupScope::DomainLocal,
+                    8 => GroupScope::Universal,
+                    _ => GroupScope::Global,
+                };
+            }
+        }
+
+        let member_of = search_entry.attrs
+            .get("memberOf")
+            .cloned()
+            .unwrap_or_default();
+
+        if self.count_members {
+            // A count that cannot be read is left blank rather than failing the group
+            let primary_group_token = Self::get_attr(&search_entry, "primaryGroupToken")
+                .and_then(|token| token.parse().ok());
+            group.member_count = self.count_group_members(&group.distinguished_name, primary_group_token).await.ok();
+        }
+
+        self.group_cache.insert(group_dn, group.clone(), member_of.clone());
+
+        Ok((group, member_of))
+    }
+
+    /// Count the entries whose memberOf names `group_dn`, paging through the results and
+    /// requesting no attributes, so large groups never have their member list transferred
+    ///
+    /// AD leaves an account's primary group out of its memberOf, so with the group's
+    /// `primary_group_token` the accounts whose primaryGroupID matches it are counted too.
+    async fn count_group_members(&mut self, group_dn: &str, primary_group_token: Option<u32>) -> Result<usize> {
+        let filter = match primary_group_token {
+            Some(token) => format!("(|(memberOf={})(primaryGroupID={}))", ldap_escape(group_dn), token),
+            None => format!("(memberOf={})", ldap_escape(group_dn)),
+        };
+        let adapters: Vec<Box<dyn Adapter<_, _>>> = vec![
+            Box::new(EntriesOnly::new()),
+            Box::new(PagedResults::new(Self::MEMBER_COUNT_PAGE_SIZE)),
+        ];
+
+        self.throttle().await;
+        let mut search = self.ldap
+            .streaming_search_with(adapters, &self.base_dn, Scope::Subtree, &filter, vec!["1.1"])
+            .await
+            .map_err(|e| AdReportError::from_ldap(e, "Failed to count group members"))?;
+
+        let mut count = 0;
+        while search.next().await
+            .map_err(|e| AdReportError::from_ldap(e, "Failed to count group members"))?
+            .is_some()
+        {
+            count += 1;
+        }
+        search.finish().await
+            .success()
+            .map_err(|e| AdReportError::from_ldap(e, "Group member count failed"))?;
+
+        Ok(count)
+    }
+
+    async fn get_primary_group(&mut self, primary_group_id: &str, user_sid: Option<&str>) -> Result<ADGroup> {
+        // The primary group's SID is the user's domain SID with the primaryGroupID as RID
+        let rid: u32 = primary_group_id.parse()
+            .map_err(|_| AdReportError::ParseError(format!("Invalid primaryGroupID: {}", primary_group_id)))?;
+        let domain_sid = user_sid
+            .filter(|sid| rid_of(sid).is_some())
+            .and_then(|sid| sid.rsplit_once('-'))
+            .map(|(domain, _)| domain.to_string());
+
+        let filter = match &domain_sid {
+            Some(domain) => format!("(&(objectClass=group)(objectSid={}-{}))", domain, rid),
+            None => format!("(&(objectClass=group)(primaryGroupToken={}))", primary_group_id),
+        };
+
+        self.throttle().await;
+        let search = self.ldap
             .search(
                 &self.base_dn,
                 Scope::Subtree,
@@ -284,25 +1402,35 @@ impl LdapClient {
                 vec!["distinguishedName", "cn", "description"],
             )
             .await
-            .context("Failed to search for primary group")?
-            .success()
-            .context("Primary group search failed")?;
+            .map_err(|e| AdReportError::from_ldap(e, "Failed to search for primary group"))?
+            .success();
+
+        let entry = match search {
+            Ok((rs, _res)) => rs.into_iter().next(),
+            Err(_) => None,
+        };
+
+        let Some(entry) = entry else {
+            // Fall back to the well-known name so the report still shows the primary group
+            let name = well_known_rid_name(rid)
+                .ok_or_else(|| AdReportError::UserNotFound(format!("Primary group {} not found", rid)))?;
+            let dn = match &domain_sid {
+                Some(domain) => format!("{}-{}", domain, rid),
+                None => name.to_string(),
+            };
+            return Ok(ADGroup::new(dn, name.to_string()));
+        };
 
-        let entry = rs
-            .into_iter()
-            .next()
-            .context("Primary group not found")?;
-        
         let search_entry = SearchEntry::construct(entry);
-        
+
         let mut group = ADGroup::new(
             search_entry.dn.clone(),
             Self::get_attr(&search_entry, "cn")
                 .unwrap_or_else(|| "Domain Users".to_string()),
         );
-        
+
         group.description = Self::get_attr(&search_entry, "description");
-        
+
         Ok(group)
     }
 
@@ -373,6 +1501,53 @@ impl LdapClient {
         rights
     }
 
+    /// Collect textual attributes as-is and hex-encode binary ones
+    fn raw_attribute_map(entry: &SearchEntry) -> HashMap<String, Vec<String>> {
+        let mut raw = entry.attrs.clone();
+        for (name, values) in &entry.bin_attrs {
+            let encoded = values
+                .iter()
+                .map(|v| format!("0x{}", v.iter().map(|b| format!("{:02x}", b)).collect::<String>()))
+                .collect();
+            raw.insert(name.clone(), encoded);
+        }
+        raw
+    }
+
+    /// Requested attributes with no values in the entry, split into unreadable and not set
+    fn missing_attributes(entry: &SearchEntry, requested: &[&str]) -> MissingAttributes {
+        let has_values = |attr: &str| {
+            entry.attrs.iter().any(|(name, values)| name.eq_ignore_ascii_case(attr) && !values.is_empty())
+                || entry.bin_attrs.iter().any(|(name, values)| name.eq_ignore_ascii_case(attr) && !values.is_empty())
+        };
+
+        let mut missing = MissingAttributes::default();
+        for attr in requested.iter().filter(|attr| !has_values(attr)) {
+            if Self::ALWAYS_PRESENT_USER_ATTRIBUTES.iter().any(|a| a.eq_ignore_ascii_case(attr)) {
+                missing.unreadable.push(attr.to_string());
+            } else {
+                missing.not_set.push(attr.to_string());
+            }
+        }
+        missing
+    }
+
+    /// All values of a multi-valued binary attribute, from both `bin_attrs` and `attrs`
+    fn get_bin_attrs(entry: &SearchEntry, attr: &str) -> Vec<Vec<u8>> {
+        let binary = entry.bin_attrs.get(attr).into_iter().flatten().cloned();
+        let textual = entry.attrs.get(attr).into_iter().flatten().map(|v| v.clone().into_bytes());
+        binary.chain(textual).collect()
+    }
+
+    /// Binary attribute value; ldap3 keeps values that happen to be valid UTF-8 in `attrs`
+    fn get_bin_attr(entry: &SearchEntry, attr: &str) -> Option<Vec<u8>> {
+        entry.bin_attrs
+            .get(attr)
+            .and_then(|v| v.first())
+            .cloned()
+            .or_else(|| Self::get_attr(entry, attr).map(String::into_bytes))
+    }
+
     fn get_attr(entry: &SearchEntry, attr: &str) -> Option<String> {
         entry.attrs
             .get(attr)
@@ -380,6 +1555,37 @@ impl LdapClient {
             .cloned()
     }
 
+    /// Ask the server which domain it serves and what it is called; run after binding,
+    /// as some controllers only return these attributes to authenticated clients
+    pub async fn server_identity(&mut self) -> Result<ServerIdentity> {
+        self.throttle().await;
+        let (rs, _res) = self.ldap
+            .search(
+                "",
+                Scope::Base,
+                "(objectClass=*)",
+                vec!["defaultNamingContext", "dnsHostName"],
+            )
+            .await
+            .map_err(|e| AdReportError::from_ldap(e, "Failed to query rootDSE"))?
+            .success()
+            .map_err(|e| AdReportError::from_ldap(e, "rootDSE query failed"))?;
+
+        let entry = rs
+            .into_iter()
+            .next()
+            .ok_or_else(|| AdReportError::ParseError("rootDSE entry not found".to_string()))?;
+
+        let search_entry = SearchEntry::construct(entry);
+
+        Ok(ServerIdentity {
+            domain: Self::get_attr(&search_entry, "defaultNamingContext")
+                .and_then(|nc| domain_of_dn(&nc)),
+            host_name: Self::get_attr(&search_entry, "dnsHostName")
+                .map(|name| name.to_lowercase()),
+        })
+    }
+
     /// Query rootDSE to get the proper base DN (naming context)
     async fn get_base_dn_from_rootdse(ldap: &mut Ldap) -> Result<String> {
         // Query rootDSE (empty DN with base scope)
@@ -391,19 +1597,40 @@ impl LdapClient {
                 vec!["defaultNamingContext"],
             )
             .await
-            .context("Failed to query rootDSE")?
+            .map_err(|e| AdReportError::from_ldap(e, "Failed to query rootDSE"))?
             .success()
-            .context("rootDSE query failed")?;
+            .map_err(|e| AdReportError::from_ldap(e, "rootDSE query failed"))?;
 
         let entry = rs
             .into_iter()
             .next()
-            .context("rootDSE entry not found")?;
+            .ok_or_else(|| AdReportError::ParseError("rootDSE entry not found".to_string()))?;
 
         let search_entry = SearchEntry::construct(entry);
 
         Self::get_attr(&search_entry, "defaultNamingContext")
-            .context("defaultNamingContext not found in rootDSE")
+            .ok_or_else(|| AdReportError::ParseError("defaultNamingContext not found in rootDSE".to_string()))
+    }
+
+    /// Domain naming context of an entry, i.e. its trailing DC= components
+    fn naming_context_of(dn: &str) -> Option<String> {
+        let components: Vec<&str> = dn.split(',').map(str::trim).collect();
+        let first_dc = components.iter().position(|c| c.len() > 3 && c[..3].eq_ignore_ascii_case("DC="))?;
+        Some(components[first_dc..].join(","))
+    }
+
+    /// "DC=child,DC=corp,DC=com" -> "child.corp.com"
+    fn dns_name_of(naming_context: &str) -> String {
+        naming_context
+            .split(',')
+            .filter_map(|c| c.trim().get(3..))
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    /// "child.corp.com" -> "DC=child,DC=corp,DC=com"
+    fn naming_context_of_domain(domain_dns: &str) -> String {
+        domain_dns.split('.').map(|label| format!("DC={}", label)).collect::<Vec<_>>().join(",")
     }
 
     fn extract_base_dn(server: &str) -> String {
@@ -421,26 +1648,21 @@ impl LdapClient {
         let dc_parts: Vec<String> = domain_parts.iter().map(|p| format!("DC={}", p)).collect();
         dc_parts.join(",")
     }
+}
 
-    fn parse_ad_timestamp(timestamp: Option<&str>) -> Option<DateTime<Utc>> {
-        timestamp.and_then(|ts| {
-            ts.parse::<i64>().ok().and_then(|ticks| {
-                // AD timestamp is in 100-nanosecond intervals since 1601-01-01
-                let unix_ticks = ticks - 116444736000000000i64;
-                let seconds = unix_ticks / 10000000;
-                DateTime::from_timestamp(seconds, 0)
-            })
-        })
-    }
+/// Levenshtein distance between two strings (by character)
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
 
-    fn parse_ldap_timestamp(timestamp: Option<&str>) -> Option<DateTime<Utc>> {
-        timestamp.and_then(|ts| {
-            // LDAP timestamp format: YYYYMMDDHHmmSS.0Z
-            chrono::NaiveDateTime::parse_from_str(
-                &ts.replace(".0Z", ""),
-                "%Y%m%d%H%M%S"
-            ).ok()
-            .map(|dt| DateTime::from_naive_utc_and_offset(dt, Utc))
-        })
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
     }
-}
\ No newline at end of file
+
+    previous[b.len()]
+}
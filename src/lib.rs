@@ -1,8 +1,30 @@
 pub mod models;
+pub mod error;
 pub mod ldap_client;
+pub mod group_cache;
 pub mod pdf_generator;
+pub mod markdown_generator;
+pub mod output_sink;
+pub mod font_metrics;
 pub mod windows_auth;
 pub mod permission_analyzer;
 pub mod risk_calculator;
+pub mod risk_rules;
 pub mod report_data;
-pub mod diagnostics;
\ No newline at end of file
+pub mod diagnostics;
+pub mod sid;
+pub mod redaction;
+pub mod snapshot;
+pub mod checkpoint;
+pub mod group_filter;
+pub mod group_sort;
+pub mod data_quality;
+pub mod logon_hours;
+pub mod summary_csv;
+pub mod ad_time;
+pub mod audit_log;
+pub mod socks_tunnel;
+pub mod webhook;
+pub mod integrity;
+pub mod table_generator;
+pub mod rate_limit;
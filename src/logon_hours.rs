@@ -0,0 +1,92 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+const DAY_NAMES: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+/// Weekly logon schedule from the binary logonHours attribute
+///
+/// Layout: 21 bytes = 168 bits, one per hour of the week in UTC, starting
+/// Sunday 00:00. Within each byte the least significant bit comes first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogonHours {
+    bytes: Vec<u8>,
+}
+
+impl LogonHours {
+    pub const LEN: usize = 21;
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != Self::LEN {
+            return Err(anyhow::anyhow!(
+                "logonHours must be {} bytes, got {}",
+                Self::LEN, bytes.len()
+            ));
+        }
+        Ok(Self { bytes: bytes.to_vec() })
+    }
+
+    /// Whether logon is allowed during `hour` (0-23 UTC) of `day` (0 = Sunday)
+    pub fn is_allowed(&self, day: usize, hour: usize) -> bool {
+        let bit = (day % 7) * 24 + hour % 24;
+        self.bytes.get(bit / 8).is_some_and(|b| b & (1 << (bit % 8)) != 0)
+    }
+
+    /// Every hour of the week is allowed
+    pub fn is_unrestricted(&self) -> bool {
+        self.bytes.iter().all(|b| *b == 0xFF)
+    }
+
+    /// Human-readable weekly schedule, e.g. "Mon-Fri 08:00-18:00 UTC"
+    pub fn summary(&self) -> String {
+        if self.is_unrestricted() {
+            return "No restrictions".to_string();
+        }
+        if self.bytes.iter().all(|b| *b == 0) {
+            return "Logon denied at all times".to_string();
+        }
+
+        // Walk the week Monday first and merge consecutive days with the same hours
+        let mut groups: Vec<(usize, usize, String)> = Vec::new();
+        for day in (1..=7).map(|d| d % 7) {
+            let hours = self.day_ranges(day);
+            match groups.last_mut() {
+                Some((_, last_day, last_hours)) if *last_hours == hours && *last_day == (day + 6) % 7 => {
+                    *last_day = day;
+                }
+                _ => groups.push((day, day, hours)),
+            }
+        }
+
+        let parts: Vec<String> = groups
+            .into_iter()
+            .filter(|(_, _, hours)| !hours.is_empty())
+            .map(|(first, last, hours)| {
+                if first == last {
+                    format!("{} {}", DAY_NAMES[first], hours)
+                } else {
+                    format!("{}-{} {}", DAY_NAMES[first], DAY_NAMES[last], hours)
+                }
+            })
+            .collect();
+
+        format!("{} UTC", parts.join("; "))
+    }
+
+    /// Allowed hour ranges of one day, e.g. "08:00-12:00, 13:00-18:00" (empty if none)
+    fn day_ranges(&self, day: usize) -> String {
+        let mut ranges = Vec::new();
+        let mut start = None;
+        for hour in 0..=24 {
+            let allowed = hour < 24 && self.is_allowed(day, hour);
+            match (allowed, start) {
+                (true, None) => start = Some(hour),
+                (false, Some(from)) => {
+                    ranges.push(format!("{:02}:00-{:02}:00", from, hour));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        ranges.join(", ")
+    }
+}
@@ -1,28 +1,88 @@
 use anyhow::{Context, Result};
-use chrono::{DateTime, Utc};
-use clap::Parser;
+use chrono::Utc;
+use chrono_tz::Tz;
+use clap::{Parser, ValueEnum};
 use rpassword::prompt_password;
 use regex::Regex;
+use secrecy::SecretString;
 use std::fs::File;
-use std::io::{Write, BufRead, BufReader};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 use tracing::{info, debug, warn, error};
 use tracing_subscriber;
 
 mod models;
+mod error;
 mod ldap_client;
+mod group_cache;
 mod pdf_generator;
+mod markdown_generator;
+mod output_sink;
+mod font_metrics;
 mod windows_auth;
 mod permission_analyzer;
 mod risk_calculator;
+mod risk_rules;
 mod report_data;
 mod diagnostics;
-
-use ldap_client::LdapClient;
-use pdf_generator::PdfGenerator;
+mod sid;
+mod redaction;
+mod snapshot;
+mod checkpoint;
+mod group_filter;
+mod group_sort;
+mod data_quality;
+mod logon_hours;
+mod summary_csv;
+mod ad_time;
+mod audit_log;
+mod socks_tunnel;
+mod webhook;
+mod integrity;
+mod table_generator;
+mod rate_limit;
+
+use error::AdReportError;
+use models::ADComputer;
+use ldap_client::{LdapClient, ServerIdentity, TlsMinVersion};
+use group_cache::GroupCache;
+use pdf_generator::{PdfGenerator, LayoutConfig, Palette, RenderedReport};
+use markdown_generator::MarkdownGenerator;
+use output_sink::{DirSink, FileSink, OutputSink, StdoutSink, ZipSink};
+use integrity::Verification;
+use table_generator::TableGenerator;
+use rate_limit::RateLimiter;
 use windows_auth::{WindowsAuth, should_use_gssapi, get_default_ldap_server};
-use risk_calculator::RiskCalculator;
-use report_data::EnhancedReportData;
+use risk_calculator::{EscalationRule, RiskCalculator, RiskThresholds};
+use risk_rules::CustomRiskRule;
+use report_data::{EnhancedReportData, AccountStateSummary, BatchRiskSummary, KerberoastableSummary, ReportProvenance, ReportSection, UnusedAccountsSummary};
 use diagnostics::Diagnostics;
+use redaction::Redactor;
+use snapshot::{ReportSnapshot, ReportChanges};
+use checkpoint::BatchCheckpoint;
+use group_filter::GroupFilter;
+use group_sort::GroupSortOrder;
+use data_quality::IdentityQualityCheck;
+use webhook::{BatchNotification, ReportNotification, Webhook};
+use summary_csv::{SummaryCsvWriter, SummaryRow};
+use audit_log::{AuditEntry, AuditLog};
+
+/// Report output formats
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    Pdf,
+    /// Markdown document with the same sections and tables as the PDF
+    Markdown,
+    /// Machine-readable output (currently only for --diagnose)
+    Json,
+    /// Identity, status, risk score, top groups and top findings printed as terminal tables;
+    /// no file is written (single account only)
+    Table,
+}
 
 #[derive(Parser, Debug)]
 #[clap(
@@ -32,17 +92,23 @@ use diagnostics::Diagnostics;
     long_about = None
 )]
 struct Args {
-    /// LDAP/AD server hostname or IP address (auto-detected on Windows if not provided)
-    #[arg(short = 's', long)]
+    /// LDAP/AD server hostname or IP address, optionally with a port ("host:port", "[v6]:port")
+    /// (auto-detected on Windows if not provided)
+    #[arg(short = 's', long, env = "AD_REPORT_SERVER", hide_env_values = true)]
     server: Option<String>,
 
+    /// LDAP port (default: 389, or 636 with TLS; 3268/3269 with --global-catalog)
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..))]
+    port: Option<u16>,
+
     /// Username for LDAP authentication (e.g., "DOMAIN\\username" or "username@domain.com")
     /// Optional when using Windows authentication
-    #[arg(short = 'u', long)]
+    #[arg(short = 'u', long, env = "AD_REPORT_USERNAME", hide_env_values = true)]
     username: Option<String>,
 
-    /// Password for LDAP authentication (will prompt if not provided)
-    #[arg(short = 'p', long, hide = true)]
+    /// Password for LDAP authentication (will prompt if not provided); prefer
+    /// AD_REPORT_PASSWORD over the flag to keep it out of shell history and process listings
+    #[arg(short = 'p', long, hide = true, env = "AD_REPORT_PASSWORD", hide_env_values = true)]
     password: Option<String>,
 
     /// Target user to generate report for (SAM account name)
@@ -53,40 +119,384 @@ struct Args {
     #[arg(short = 'l', long, conflicts_with = "target_user")]
     user_list: Option<String>,
 
+    /// Target computer to generate a report for (name, with or without the trailing '$'),
+    /// covering OS, delegation and group memberships
+    #[arg(long, conflicts_with_all = ["target_user", "user_list"])]
+    target_computer: Option<String>,
+
     /// Output PDF file path (optional - will auto-generate if not provided)
     #[arg(short = 'o', long)]
     output: Option<String>,
 
-    /// Domain name
+    /// Directory to write reports to under their generated names (created if missing)
+    #[arg(long, conflicts_with = "output")]
+    output_dir: Option<String>,
+
+    /// Write the report to standard output instead of a file (logs go to stderr)
+    #[arg(long, conflicts_with_all = ["output", "output_dir", "user_list"])]
+    stdout: bool,
+
+    /// Domain name shown in reports (default: the domain the server reports in rootDSE)
     #[arg(short = 'd', long)]
     domain: Option<String>,
 
+    /// Base DN for user searches (overrides the naming context discovered from rootDSE)
+    #[arg(short = 'b', long, value_name = "DN")]
+    search_base: Option<String>,
+
+    /// objectClass used to match user entries (e.g., "inetOrgPerson")
+    #[arg(long, value_name = "CLASS", default_value = "user")]
+    object_class: String,
+
+    /// Search the forest-wide global catalog (port 3268/3269) and read users from their home domain
+    #[arg(long)]
+    global_catalog: bool,
+
+    /// Reach the domain controller through a SOCKS5 proxy (host:port), e.g. an SSH dynamic
+    /// forward on a jump host; DC names are resolved by the proxy
+    #[arg(long, value_name = "HOST:PORT")]
+    socks5: Option<String>,
+
+    /// Send at most this many LDAP operations per second across the run (binds, searches and
+    /// lookups on every connection), to stay under DC throttling and monitoring thresholds
+    #[arg(long, value_name = "QUERIES_PER_SECOND", value_parser = parse_rate_limit)]
+    rate_limit: Option<f64>,
+
+    /// Distinguished name to report on when several accounts match the requested name
+    /// (e.g., a search base spanning domains, or a stale duplicate)
+    #[arg(long, value_name = "DN", conflicts_with = "user_list")]
+    disambiguate: Option<String>,
+
+    /// Follow LDAP referrals to other servers (same credentials, at most 3 hops) when an
+    /// account or group lives in a partition the bound server does not hold
+    #[arg(long)]
+    follow_referrals: bool,
+
     /// Use TLS for LDAP connection
     #[arg(long, default_value = "true")]
     use_tls: bool,
 
+    /// Oldest TLS version accepted from the server (LDAPS and TLS through --socks5);
+    /// servers offering only older protocols fail the handshake
+    #[arg(long, value_enum, value_name = "VERSION", default_value = "1.2")]
+    tls_min_version: TlsMinVersion,
+
+    /// Connect without TLS (lab use only); a simple bind then also needs
+    /// --i-understand-plaintext-is-insecure, as the password crosses the network in the clear
+    #[arg(long)]
+    plaintext: bool,
+
+    /// Allow a simple bind over a --plaintext connection
+    #[arg(long, requires = "plaintext")]
+    i_understand_plaintext_is_insecure: bool,
+
     /// Use Kerberos/GSSAPI authentication (Windows integrated, no password required)
-    /// Only works on domain-joined Windows machines
+    /// Works on domain-joined Windows machines, or on Linux/macOS builds with the
+    /// 'gssapi' feature when a Kerberos ticket is cached (e.g., after `kinit`)
     #[arg(long)]
     use_gssapi: bool,
 
+    /// Report output format: `pdf`, `markdown`, `table` (printed to the terminal), or `json`
+    /// (only supported with --diagnose)
+    #[arg(long, value_enum, default_value = "pdf")]
+    format: OutputFormat,
+
+    /// Print a JSON description of compiled-in features (version, platform, auth methods, formats) and exit
+    #[arg(long)]
+    capabilities: bool,
+
     /// Run diagnostics for GSSAPI authentication and exit
     /// Shows preflight checks and troubleshooting guide
     #[arg(long)]
     diagnose: bool,
 
+    /// Maximum number of groups kept in the per-run group cache (0 disables caching)
+    #[arg(long, value_name = "N", default_value_t = GroupCache::DEFAULT_MAX_ENTRIES)]
+    group_cache_size: usize,
+
+    /// Seconds a cached group stays valid before it is fetched again
+    #[arg(long, value_name = "SECONDS", default_value_t = GroupCache::DEFAULT_TTL_SECS)]
+    group_cache_ttl: u64,
+
     /// Include detailed risk assessment in report
-    #[arg(long)]
+    ///
+    /// Precedence: --no-risk-analysis always turns it off; otherwise --risk-analysis or
+    /// AD_REPORT_RISK_ANALYSIS=true (also 1/yes/on) turns it on; with neither it is off
+    #[arg(long, env = "AD_REPORT_RISK_ANALYSIS", value_parser = clap::builder::BoolishValueParser::new())]
     risk_analysis: bool,
 
+    /// Leave out the risk assessment, overriding --risk-analysis and AD_REPORT_RISK_ANALYSIS
+    #[arg(long)]
+    no_risk_analysis: bool,
+
+    /// Order of the listed group memberships; nested groups sort within their parent
+    #[arg(long, value_enum, value_name = "MODE", default_value_t = GroupSortOrder::Risk)]
+    sort_groups: GroupSortOrder,
+
+    /// Comma-separated report sections to include
+    #[arg(long, value_enum, value_name = "SECTIONS", value_delimiter = ',', default_values_t = ReportSection::ALL)]
+    sections: Vec<ReportSection>,
+
+    /// Leave the Group Memberships section out of the report
+    #[arg(long)]
+    no_group_section: bool,
+
+    /// Leave the Risk Assessment section out of the report
+    #[arg(long)]
+    no_risk_section: bool,
+
+    /// Only show groups whose name matches this regex
+    #[arg(long, value_name = "REGEX")]
+    include_groups: Option<Regex>,
+
+    /// Hide groups whose name matches this regex
+    #[arg(long, value_name = "REGEX")]
+    exclude_groups: Option<Regex>,
+
+    /// Apply --include-groups/--exclude-groups to risk scoring too (default: display only)
+    #[arg(long)]
+    filter_groups_in_risk: bool,
+
+    /// Append the raw LDAP attributes of each user to the report (binary values hex-encoded)
+    #[arg(long)]
+    include_raw: bool,
+
+    /// Extra LDAP attributes to fetch for each account (e.g., "extensionAttribute1,employeeID"),
+    /// listed in the raw-attributes appendix; the attributes the report needs are always read
+    #[arg(long, value_name = "ATTRIBUTES", value_delimiter = ',', value_parser = parse_attribute_name)]
+    attributes: Vec<String>,
+
+    /// Append a glossary of risk levels, risk factor types and group categories to the report
+    #[arg(long)]
+    include_legend: bool,
+
+    /// Show how many members each group has (one extra paged search per group)
+    #[arg(long)]
+    count_members: bool,
+
+    /// Cross-check memberships against the user's tokenGroups and add any the memberOf walk misses
+    #[arg(long)]
+    use_token_groups: bool,
+
+    /// Checkpoint file recording completed users; users already in it are skipped on rerun
+    #[arg(long, value_name = "CHECKPOINT")]
+    resume: Option<String>,
+
+    /// Keep processing a batch after an access-denied error instead of aborting
+    #[arg(long)]
+    ignore_access_denied: bool,
+
+    /// Don't generate reports for disabled accounts; they are tallied as skipped instead
+    #[arg(long)]
+    skip_disabled: bool,
+
+    /// Don't generate reports for computer objects and trust accounts that match the user
+    /// search; without it they are reported with a warning
+    #[arg(long)]
+    skip_non_user_accounts: bool,
+
+    /// Write a one-page PDF summarizing risk levels across the batch (requires --risk-analysis)
+    #[arg(long, value_name = "PATH")]
+    batch_summary_pdf: Option<String>,
+
+    /// List accounts created at least this many days ago that have never logged on in
+    /// the end-of-run summary
+    #[arg(long, value_name = "DAYS", default_value_t = UnusedAccountsSummary::DEFAULT_MIN_AGE_DAYS,
+        value_parser = clap::value_parser!(i64).range(0..))]
+    unused_account_days: i64,
+
+    /// Stream every generated report into this zip archive instead of separate files; the
+    /// summary CSV and batch summary PDF are added at the end of the run
+    #[arg(long, value_name = "ARCHIVE", conflicts_with_all = ["stdout", "output", "output_dir", "resume"])]
+    zip: Option<String>,
+
+    /// Write a CSV line per user (status, risk score and breakdown, output path) as each one finishes;
+    /// appended to rather than replaced when used with --resume
+    #[arg(long, value_name = "PATH")]
+    summary_csv: Option<String>,
+
+    /// Append a JSON line per user (time, operator, target, risk score, output path, outcome)
+    /// to this hash-chained audit log; the file is locked while each line is written
+    #[arg(long, value_name = "PATH")]
+    audit_log: Option<String>,
+
+    /// POST a JSON summary (user, risk score and level, finding counts, output path) of each
+    /// generated report to this URL; failures are logged and never fail the run
+    #[arg(long, value_name = "URL")]
+    webhook_url: Option<String>,
+
+    /// Send one payload with every generated report at the end of the run instead of one per report
+    #[arg(long, requires = "webhook_url")]
+    webhook_batch: bool,
+
+    /// Seconds to wait for the webhook endpoint before giving up on a payload
+    #[arg(long, value_name = "SECONDS", default_value_t = Webhook::DEFAULT_TIMEOUT_SECS)]
+    webhook_timeout: u64,
+
+    /// Comma-separated groups whose membership reduces the risk score
+    #[arg(long, value_name = "GROUPS", value_delimiter = ',', default_value = RiskCalculator::PROTECTED_USERS_GROUP)]
+    hardening_groups: Vec<String>,
+
+    /// Risk level bands as "medium,high,critical" lower bounds (e.g., "30,60,80") or a JSON file path
+    #[arg(long, value_name = "THRESHOLDS", value_parser = parse_risk_thresholds)]
+    risk_thresholds: Option<RiskThresholds>,
+
+    /// JSON file of toxic group combinations to flag as privilege escalation paths
+    /// (replaces the built-in rules)
+    #[arg(long, value_name = "PATH", value_parser = parse_escalation_rules)]
+    escalation_rules: Option<::std::vec::Vec<EscalationRule>>,
+
+    /// TOML file of custom risk rules (group pattern, UAC flag and/or inactivity -> risk factor)
+    #[arg(long, value_name = "PATH", value_parser = parse_risk_rules)]
+    risk_rules: Option<::std::vec::Vec<CustomRiskRule>>,
+
+    /// Multiply all report font sizes (and line spacing) by this factor, e.g. 1.5 for large print
+    #[arg(long, value_name = "FACTOR", default_value_t = 1.0, value_parser = parse_font_scale)]
+    font_scale: f32,
+
+    /// Show the overall risk score as plain text instead of a donut gauge
+    #[arg(long)]
+    no_risk_gauge: bool,
+
+    /// Risk color scheme; `colorblind` uses a blue/purple/orange scale and adds
+    /// per-level bar patterns and labels
+    #[arg(long, value_enum, default_value = "standard")]
+    palette: Palette,
+
+    /// Number of groups listed under "Highest-Risk Group Memberships" (0 hides the list)
+    #[arg(long, default_value_t = PdfGenerator::DEFAULT_TOP_RISK_GROUPS)]
+    top_risk_groups: usize,
+
+    /// List at most this many direct groups in the report, riskiest first (counts still cover all)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    max_groups_shown: Option<u32>,
+
+    /// Cover page title (default: "ACTIVE DIRECTORY USER ACCESS REPORT")
+    #[arg(long)]
+    report_title: Option<String>,
+
+    /// State the bind identity and search base in every page footer, for audit provenance
+    #[arg(long)]
+    show_provenance: bool,
+
+    /// Engagement context shown under the cover title (e.g., "Q3 Privileged Access Review - SEC-1234")
+    #[arg(long)]
+    report_context: Option<String>,
+
+    /// Text drawn large and diagonally behind every page of the report (e.g., "DRAFT")
+    #[arg(long, value_name = "TEXT")]
+    watermark: Option<String>,
+
+    /// Produce a dense one-page summary (identity, risk score, top 5 groups, top 3 findings)
+    #[arg(long)]
+    compact: bool,
+
+    /// Embed a SHA-256 of the report content in the PDF and write it alongside as
+    /// `<report>.json` plus a `<report>.sha256` checksum file, for tamper-evidence
+    #[arg(long, conflicts_with = "stdout")]
+    integrity_hash: bool,
+
+    /// Check a PDF against its content JSON (from --integrity-hash) and exit;
+    /// fails if either file was altered
+    #[arg(long, num_args = 2, value_names = ["PDF", "JSON"])]
+    verify: Option<Vec<String>>,
+
+    /// Comma-separated, case-insensitive substrings that mark displayName/email as placeholders
+    #[arg(long, value_name = "PATTERNS", value_delimiter = ',',
+        default_values_t = IdentityQualityCheck::DEFAULT_PLACEHOLDER_PATTERNS.map(String::from))]
+    placeholder_patterns: Vec<String>,
+
+    /// IANA timezone used for timestamps in reports (e.g., "Europe/London")
+    #[arg(long, value_name = "TZ", default_value = "UTC", value_parser = parse_timezone)]
+    timezone: Tz,
+
+    /// Directory of per-user JSON snapshots; the previous one is compared against and then replaced
+    #[arg(long, value_name = "DIR")]
+    baseline_dir: Option<String>,
+
+    /// Mask PII (email, UPN, display name, department) in generated reports
+    #[arg(long)]
+    redact: bool,
+
+    /// With --redact, replace SAM account names with a stable hash so reports can still be cross-referenced
+    #[arg(long, requires = "redact")]
+    redact_hash_sam: bool,
+
     /// Enable verbose logging
     #[arg(short = 'v', long)]
     verbose: bool,
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    let args = Args::parse();
+async fn main() -> ExitCode {
+    match run(Args::parse()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            let (code, summary) = exit_status(&e);
+            eprintln!("Error: {}", summary);
+            eprintln!("  {:#}", e);
+            ExitCode::from(code)
+        }
+    }
+}
+
+/// Exit code and human summary for a failed run, by library error category
+fn exit_status(err: &anyhow::Error) -> (u8, &'static str) {
+    let category = err.chain().find_map(|cause| cause.downcast_ref::<AdReportError>());
+    match category {
+        Some(AdReportError::ConnectionFailed(_)) => (3, "could not connect to the LDAP server"),
+        Some(AdReportError::BindFailed(_)) => (4, "authentication with the LDAP server failed"),
+        Some(AdReportError::UserNotFound(_)) => (5, "user not found"),
+        Some(AdReportError::AccessDenied(_)) => (6, "access denied; the bind account cannot read the directory"),
+        Some(AdReportError::ParseError(_)) => (7, "unexpected data returned by the directory"),
+        Some(AdReportError::Timeout(_)) => (8, "the LDAP server did not respond in time"),
+        Some(AdReportError::AmbiguousAccount(_)) => (9, "more than one account matches; choose one with --disambiguate"),
+        None => (1, "ad-report did not complete"),
+    }
+}
+
+async fn run(mut args: Args) -> Result<()> {
+
+    // Capability query is machine-readable, so answer it before logging starts
+    if args.capabilities {
+        let output_formats = OutputFormat::value_variants()
+            .iter()
+            .filter_map(|f| f.to_possible_value())
+            .map(|v| v.get_name().to_string())
+            .collect();
+        let capabilities = Diagnostics::capabilities(output_formats);
+        println!("{}", serde_json::to_string_pretty(&capabilities)?);
+        return Ok(());
+    }
+
+    // Verification only reads local files
+    if let Some(paths) = &args.verify {
+        return verify_report(Path::new(&paths[0]), Path::new(&paths[1]));
+    }
+
+    // JSON diagnostics go to stdout, so they also run before logging starts
+    if args.diagnose && args.format == OutputFormat::Json {
+        let server = args.server.clone()
+            .or_else(get_default_ldap_server)
+            .unwrap_or_else(|| "ad.example.com".to_string());
+        let (server, port) = split_server_port(&server, args.port)?;
+
+        let report = Diagnostics::preflight_report(&server, ldap_port(port, &args));
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        if !report.passed {
+            return Err(anyhow::anyhow!("Preflight checks failed"));
+        }
+        return Ok(());
+    }
+    if args.format == OutputFormat::Json && !args.diagnose {
+        return Err(anyhow::anyhow!("--format json is currently only supported with --diagnose"));
+    }
+    if args.format == OutputFormat::Table && (args.user_list.is_some() || args.output.is_some() || args.zip.is_some()) {
+        return Err(anyhow::anyhow!("--format table prints one account to the terminal; it cannot be combined with --user-list, --output or --zip"));
+    }
+    if args.integrity_hash && args.format != OutputFormat::Pdf {
+        return Err(anyhow::anyhow!("--integrity-hash is only supported for PDF reports"));
+    }
 
     // Initialize logging
     let log_level = if args.verbose {
@@ -95,9 +505,13 @@ async fn main() -> Result<()> {
         tracing::Level::INFO
     };
     
-    tracing_subscriber::fmt()
-        .with_max_level(log_level)
-        .init();
+    // Keep stdout clean for the report itself when it is written there
+    let subscriber = tracing_subscriber::fmt().with_max_level(log_level);
+    if args.stdout || args.format == OutputFormat::Table {
+        subscriber.with_writer(std::io::stderr).init();
+    } else {
+        subscriber.init();
+    }
 
     info!("Starting Active Directory user report generation");
 
@@ -112,15 +526,16 @@ async fn main() -> Result<()> {
             })
         });
 
-        Diagnostics::run_preflight_checks(&server)?;
+        let (server, port) = split_server_port(&server, args.port)?;
+        Diagnostics::run_preflight_checks(&server, ldap_port(port, &args))?;
         Diagnostics::show_troubleshooting_guide();
         Diagnostics::show_auth_info();
         return Ok(());
     }
 
     // Validate that target user(s) are provided
-    if args.target_user.is_none() && args.user_list.is_none() {
-        return Err(anyhow::anyhow!("Either --target-user or --user-list must be provided"));
+    if args.target_user.is_none() && args.user_list.is_none() && args.target_computer.is_none() {
+        return Err(anyhow::anyhow!("One of --target-user, --user-list or --target-computer must be provided"));
     }
 
     // Determine server and authentication method
@@ -129,8 +544,12 @@ async fn main() -> Result<()> {
             panic!("LDAP server must be provided when not on a Windows domain")
         })
     });
+    let (server, port) = split_server_port(&server, args.port)?;
 
-    info!("Server: {}", server);
+    match port {
+        Some(port) => info!("Server: {}:{}", server, port),
+        None => info!("Server: {}", server),
+    }
 
     // Determine authentication method
     let use_gssapi_flag = should_use_gssapi(&args.username, args.use_gssapi);
@@ -138,20 +557,30 @@ async fn main() -> Result<()> {
     if use_gssapi_flag {
         // GSSAPI/Kerberos authentication (Windows integrated)
         info!("GSSAPI authentication requested");
+        if args.plaintext {
+            warn!("--plaintext: TLS is disabled, so directory data (but not the Kerberos credentials) is sent unencrypted");
+        }
 
         // Validate server FQDN for GSSAPI
         let server_fqdn = WindowsAuth::validate_server_dns(&server)
             .context("Invalid server FQDN for GSSAPI authentication")?;
 
         // Get current user info
-        let (domain, username) = WindowsAuth::get_current_user()
+        let (realm, username) = WindowsAuth::get_current_user()
             .context("Failed to get current user information")?;
 
-        info!("Current user: {}\\{}", domain, username);
+        // The realm only labels the bind identity, so an unknown one falls back to --domain
+        let domain = realm.or_else(|| args.domain.clone());
+        let current_user = match &domain {
+            Some(domain) => format!("{}\\{}", domain, username),
+            None => username,
+        };
+        info!("Current user: {}", current_user);
+        let bind_identity = format!("{} (Kerberos)", current_user);
         info!("Authenticating using Kerberos/GSSAPI...");
 
         debug!("Connecting to LDAP server...");
-        let mut client = LdapClient::connect(&server, args.use_tls)
+        let mut client = connect_client(&server, port, &args)
             .await
             .context("Failed to connect to LDAP server")?;
 
@@ -164,19 +593,32 @@ async fn main() -> Result<()> {
 
         info!("Successfully authenticated with Kerberos/GSSAPI");
 
-        // Extract domain for reporting
-        let report_domain = args.domain.clone().unwrap_or_else(|| domain);
+        let (report_domain, domain_controller) = report_identity(&mut client, &server, &args, || domain.unwrap_or_else(|| server.clone())).await;
 
         // Continue with user processing using authenticated client
-        process_users(&mut client, &server, &report_domain, &args).await?;
+        process_users(&mut client, &domain_controller, &report_domain, &bind_identity, &args).await?;
     } else {
         // Simple authentication (username/password)
+        if args.plaintext {
+            if !args.i_understand_plaintext_is_insecure {
+                return Err(anyhow::anyhow!(
+                    "Refusing a simple bind over --plaintext: the password would cross the network unencrypted. \
+                     Drop --plaintext, use --use-gssapi, or add --i-understand-plaintext-is-insecure (lab use only)"
+                ));
+            }
+            warn!("==================================================================");
+            warn!("!! --plaintext: TLS is DISABLED. The simple-bind password and all !!");
+            warn!("!! directory data will traverse the network UNENCRYPTED.          !!");
+            warn!("==================================================================");
+        }
+
         let (username, password) = if let Some(u) = args.username.clone() {
-            // Username provided
-            let pwd = match args.password.clone() {
-                Some(p) => p,
+            // Username provided; the password moves straight into a zeroize-on-drop secret
+            let pwd = match args.password.take() {
+                Some(p) => SecretString::from(p),
                 None => {
                     prompt_password(&format!("Enter password for {}: ", u))
+                        .map(SecretString::from)
                         .context("Failed to read password")?
                 }
             };
@@ -185,19 +627,8 @@ async fn main() -> Result<()> {
             panic!("Either --use-gssapi or --username must be provided")
         };
 
-        // Extract domain from username or use provided domain
-        let domain = args.domain.clone().unwrap_or_else(|| {
-            if username.contains('\\') {
-                username.split('\\').next().unwrap_or("").to_string()
-            } else if username.contains('@') {
-                username.split('@').last().unwrap_or(&server).to_string()
-            } else {
-                WindowsAuth::get_current_domain().unwrap_or_else(|| server.clone())
-            }
-        });
-
         debug!("Connecting to LDAP server...");
-        let mut client = LdapClient::connect(&server, args.use_tls)
+        let mut client = connect_client(&server, port, &args)
             .await
             .context("Failed to connect to LDAP server")?;
 
@@ -207,21 +638,146 @@ async fn main() -> Result<()> {
         client.bind_simple(&username, &password)
             .await
             .context("Failed to authenticate with LDAP")?;
+        drop(password);
 
         info!("Successfully authenticated");
 
+        // Fall back to the domain in the username only if the server does not say
+        let (domain, domain_controller) = report_identity(&mut client, &server, &args, || {
+            if username.contains('\\') {
+                username.split('\\').next().unwrap_or("").to_string()
+            } else if username.contains('@') {
+                username.split('@').last().unwrap_or(&server).to_string()
+            } else {
+                WindowsAuth::get_current_domain().unwrap_or_else(|| server.clone())
+            }
+        }).await;
+
         // Continue with user processing using authenticated client
-        process_users(&mut client, &server, &domain, &args).await?;
+        process_users(&mut client, &domain_controller, &domain, &username, &args).await?;
     }
 
     Ok(())
 }
 
+/// Connect to the server's LDAP port, or its global catalog port with --global-catalog
+async fn connect_client(server: &str, port: Option<u16>, args: &Args) -> Result<LdapClient, AdReportError> {
+    if args.global_catalog {
+        info!("Using global catalog for forest-wide user lookup");
+    }
+    let mut client = if let Some(proxy) = &args.socks5 {
+        info!("Connecting through SOCKS5 proxy {}", proxy);
+        LdapClient::connect_via_socks5(server, port, use_tls(args), args.tls_min_version, args.global_catalog, proxy).await?
+    } else if args.global_catalog {
+        LdapClient::connect_global_catalog(server, port, use_tls(args), args.tls_min_version).await?
+    } else {
+        LdapClient::connect(server, port, use_tls(args), args.tls_min_version).await?
+    };
+    // Before the bind, so the credentials are kept for referred servers
+    client.set_follow_referrals(args.follow_referrals);
+    if let Some(per_second) = args.rate_limit {
+        info!("Rate limiting LDAP operations to {} per second", per_second);
+        client.set_rate_limiter(Some(Arc::new(RateLimiter::new(per_second))));
+    }
+    Ok(client)
+}
+
+/// TLS is on unless explicitly turned off with --plaintext
+fn use_tls(args: &Args) -> bool {
+    args.use_tls && !args.plaintext
+}
+
+/// Risk analysis is on with --risk-analysis or AD_REPORT_RISK_ANALYSIS, unless --no-risk-analysis is given
+fn risk_analysis(args: &Args) -> bool {
+    args.risk_analysis && !args.no_risk_analysis
+}
+
+/// Redaction applied to every output of the run, if --redact is given
+fn redactor(args: &Args) -> Option<Redactor> {
+    args.redact.then(|| Redactor::new().with_hashed_sam_account_name(args.redact_hash_sam))
+}
+
+/// Port a run will connect to: the explicit one, or the default for TLS/plaintext and --global-catalog
+fn ldap_port(port: Option<u16>, args: &Args) -> u16 {
+    port.unwrap_or_else(|| LdapClient::default_port(use_tls(args), args.global_catalog))
+}
+
+/// Split "host:port" / "[v6]:port" from --server and reconcile it with --port
+fn split_server_port(server: &str, port_flag: Option<u16>) -> Result<(String, Option<u16>)> {
+    let (host, port_text) = if let Some(rest) = server.strip_prefix('[') {
+        // Bracketed IPv6 literal, optionally followed by :port
+        let (host, after) = rest.split_once(']')
+            .ok_or_else(|| anyhow::anyhow!("Malformed server '{}': missing ']' after IPv6 address", server))?;
+        match after {
+            "" => (host, None),
+            _ => {
+                let port = after.strip_prefix(':')
+                    .ok_or_else(|| anyhow::anyhow!("Malformed server '{}': expected ':port' after ']'", server))?;
+                (host, Some(port))
+            }
+        }
+    } else if server.matches(':').count() == 1 {
+        let (host, port) = server.split_once(':').unwrap_or((server, ""));
+        (host, Some(port))
+    } else {
+        // Plain hostname, or a bare IPv6 address without a port
+        (server, None)
+    };
+
+    if host.is_empty() {
+        return Err(anyhow::anyhow!("Malformed server '{}': missing host name", server));
+    }
+
+    let server_port = port_text
+        .map(|p| match p.parse::<u16>() {
+            Ok(port) if port > 0 => Ok(port),
+            _ => Err(anyhow::anyhow!("Invalid port '{}' in server '{}' (expected 1-65535)", p, server)),
+        })
+        .transpose()?;
+
+    let port = match (server_port, port_flag) {
+        (Some(a), Some(b)) if a != b => {
+            return Err(anyhow::anyhow!("Conflicting ports: --server specifies {} but --port is {}", a, b));
+        }
+        (server_port, port_flag) => server_port.or(port_flag),
+    };
+
+    Ok((host.to_string(), port))
+}
+
+/// Domain and domain controller names for the report
+///
+/// An explicit --domain wins; otherwise the domain is what the bound server reports in
+/// rootDSE, with `guessed_domain` (from the login name) used only if that query fails.
+/// The controller is the server's dnsHostName, else the name we connected to.
+async fn report_identity(
+    client: &mut LdapClient,
+    server: &str,
+    args: &Args,
+    guessed_domain: impl FnOnce() -> String,
+) -> (String, String) {
+    let identity = client.server_identity().await.unwrap_or_else(|e| {
+        warn!("Could not read the domain from rootDSE, using the login name instead: {}", e);
+        ServerIdentity::default()
+    });
+
+    let domain = match (args.domain.clone(), identity.domain) {
+        (Some(domain), _) => domain,
+        (None, Some(domain)) => domain,
+        (None, None) => guessed_domain(),
+    };
+    let domain_controller = identity.host_name.unwrap_or_else(|| server.to_string());
+    info!("Domain: {} (controller {})", domain, domain_controller);
+
+    (domain, domain_controller)
+}
+
 /// Process all target users and generate reports
 async fn process_users(
     client: &mut LdapClient,
     server: &str,
     domain: &str,
+    bind_identity: &str,
     args: &Args,
 ) -> Result<()> {
     // Determine target users
@@ -233,56 +789,250 @@ async fn process_users(
         users
     } else if let Some(target_user) = &args.target_user {
         vec![target_user.clone()]
+    } else if let Some(target_computer) = &args.target_computer {
+        vec![target_computer.clone()]
     } else {
-        return Err(anyhow::anyhow!("Either --target-user or --user-list must be provided"));
+        return Err(anyhow::anyhow!("One of --target-user, --user-list or --target-computer must be provided"));
+    };
+
+    // Who is querying which part of the directory, for --show-provenance
+    let provenance = args.show_provenance.then(|| ReportProvenance {
+        bind_identity: bind_identity.to_string(),
+        search_base: client.user_search_base().to_string(),
+    });
+
+    // Skip users completed by an earlier, interrupted run
+    let mut checkpoint = match &args.resume {
+        Some(path) => Some(BatchCheckpoint::load(Path::new(path))?),
+        None => None,
     };
+    let target_users = match &checkpoint {
+        Some(checkpoint) => {
+            let total = target_users.len();
+            let remaining: Vec<String> = target_users
+                .into_iter()
+                .filter(|user| !checkpoint.is_completed(user))
+                .collect();
+            if remaining.len() < total {
+                info!("Resuming: skipping {} users already completed", total - remaining.len());
+            }
+            remaining
+        }
+        None => target_users,
+    };
+
+    // Apply search scope customization
+    if let Some(search_base) = &args.search_base {
+        info!("Using search base: {}", search_base);
+        client.set_search_base(search_base);
+    }
+    client.set_object_class(&args.object_class);
+    client.set_include_raw(args.include_raw);
+    client.set_extra_attributes(args.attributes.clone());
+    client.set_count_members(args.count_members);
+    client.set_use_token_groups(args.use_token_groups);
+    client.set_disambiguate(args.disambiguate.clone());
+    client.set_group_cache_limits(args.group_cache_size, Duration::from_secs(args.group_cache_ttl));
 
     // Track success and failure counts
     let mut successful = 0;
     let mut failed = 0;
+    let mut skipped_disabled = 0;
+    let mut skipped_non_user = 0;
     let mut generated_files = Vec::new();
+    let mut risk_summary = BatchRiskSummary::new();
+    let mut kerberoastable = KerberoastableSummary::new();
+    let mut unused_accounts = UnusedAccountsSummary::new(args.unused_account_days);
+    let mut account_states = AccountStateSummary::new();
+    let mut aborted = false;
+    let mut last_error = None;
+
+    // Rows are flushed as each user finishes, so a crashed run still leaves partial results
+    let summary_csv = match &args.summary_csv {
+        Some(path) => Some(SummaryCsvWriter::create(Path::new(path), args.resume.is_some())?),
+        None => None,
+    };
+
+    let audit_log = match &args.audit_log {
+        Some(path) => Some(AuditLog::open(Path::new(path), audit_log::current_operator())?),
+        None => None,
+    };
+    let webhook = match &args.webhook_url {
+        Some(url) => Some(Webhook::new(url, Duration::from_secs(args.webhook_timeout))?),
+        None => None,
+    };
+    let mut notifications = Vec::new();
+
+    // Users without a record (skipped or failed) are named in the CSV and audit log as well
+    let redactor = redactor(&args);
+
+    let audit = |entry: AuditEntry| {
+        if let Some(log) = &audit_log {
+            if let Err(e) = log.append(entry) {
+                warn!("{:#}", e);
+            }
+        }
+    };
 
     // Check if custom output path is specified (only valid for single user)
     if args.output.is_some() && target_users.len() > 1 {
         warn!("Custom output path (-o) is ignored when processing multiple users");
     }
 
+    // First Ctrl-C lets the in-flight user finish and stops the batch; a second one aborts
+    let interrupted = Arc::new(AtomicBool::new(false));
+    tokio::spawn({
+        let interrupted = Arc::clone(&interrupted);
+        async move {
+            if tokio::signal::ctrl_c().await.is_err() {
+                return;
+            }
+            warn!("Interrupted: finishing the current user, then stopping (press Ctrl-C again to abort now)");
+            interrupted.store(true, Ordering::SeqCst);
+            if tokio::signal::ctrl_c().await.is_ok() {
+                eprintln!("Aborted");
+                std::process::exit(130);
+            }
+        }
+    });
+
+    let file_sink = output_sink(args, target_users.len() == 1);
+    let zip = args.zip.as_ref().map(ZipSink::create).transpose()?;
+    let sink: &dyn OutputSink = match &zip {
+        Some(zip) => zip,
+        None => file_sink.as_ref(),
+    };
+
     // Process each target user
     for (index, target_user) in target_users.iter().enumerate() {
+        if interrupted.load(Ordering::SeqCst) {
+            break;
+        }
         info!("[{}/{}] Processing user: {}", index + 1, target_users.len(), target_user);
 
-        let custom_output = if target_users.len() == 1 {
-            args.output.as_ref().map(|s| s.as_str())
-        } else {
-            None
-        };
-
         match process_user(
             client,
             target_user,
             &domain,
             &server,
             &args,
-            custom_output,
+            sink,
+            provenance.as_ref(),
         ).await {
-            Ok(output_path) => {
+            Ok(UserOutcome::Skipped(reason)) => {
+                let reported_user = redactor.map_or_else(|| target_user.clone(), |r| r.redact_account_name(target_user));
+                match reason {
+                    SkipReason::Disabled => {
+                        skipped_disabled += 1;
+                        account_states.record_skipped_disabled();
+                    }
+                    SkipReason::NonUserAccount(_) => skipped_non_user += 1,
+                }
+                if let Some(csv) = &summary_csv {
+                    if let Err(e) = csv.append(&SummaryRow::skipped(&reported_user, reason.as_str())) {
+                        warn!("{:#}", e);
+                    }
+                }
+                audit(AuditEntry::skipped(&reported_user, reason.as_str()));
+                info!("[{}/{}] - Skipped ({}): {}", index + 1, target_users.len(), reason.as_str(), target_user);
+
+                if let Some(checkpoint) = checkpoint.as_mut() {
+                    if let Err(e) = checkpoint.mark_completed(target_user) {
+                        warn!("Failed to update checkpoint: {:#}", e);
+                    }
+                }
+            }
+            Ok(UserOutcome::Generated(processed)) => {
                 successful += 1;
-                generated_files.push(output_path.clone());
-                info!("[{}/{}] ✓ Report saved: {}", index + 1, target_users.len(), output_path);
+                if let Some(ref risk) = processed.report_data.risk_assessment {
+                    risk_summary.record(&processed.report_data.user().sam_account_name, risk);
+                }
+                kerberoastable.record(processed.report_data.user());
+                account_states.record(processed.report_data.user());
+                unused_accounts.record(processed.report_data.user(), processed.report_data.generation_time());
+                if let Some(csv) = &summary_csv {
+                    if let Err(e) = csv.append(&SummaryRow::success(&processed.report_data, &processed.output_path)) {
+                        warn!("{:#}", e);
+                    }
+                }
+                audit(AuditEntry::success(&processed.report_data, &processed.output_path));
+                if let Some(webhook) = &webhook {
+                    let notification = ReportNotification::new(&processed.report_data, &processed.output_path);
+                    if args.webhook_batch {
+                        notifications.push(notification);
+                    } else {
+                        webhook.notify(&notification).await;
+                    }
+                }
+                info!("[{}/{}] ✓ Report saved: {}", index + 1, target_users.len(), processed.output_path);
+                generated_files.push(processed.output_path);
+
+                if let Some(checkpoint) = checkpoint.as_mut() {
+                    if let Err(e) = checkpoint.mark_completed(target_user) {
+                        warn!("Failed to update checkpoint: {:#}", e);
+                    }
+                }
             }
             Err(e) => {
                 failed += 1;
                 error!("[{}/{}] ✗ Failed to process {}: {}", index + 1, target_users.len(), target_user, e);
+                let reported_user = redactor.map_or_else(|| target_user.clone(), |r| r.redact_account_name(target_user));
+                let redacted_error = redactor.map(|r| anyhow::anyhow!(r.redact_mentions(&format!("{:#}", e), target_user)));
+                let reported_error = redacted_error.as_ref().unwrap_or(&e);
+                if let Some(csv) = &summary_csv {
+                    if let Err(e) = csv.append(&SummaryRow::failure(&reported_user, reported_error)) {
+                        warn!("{:#}", e);
+                    }
+                }
+                audit(AuditEntry::failure(&reported_user, reported_error));
+
+                // Access denied usually means the bind identity can't read any user - stop early
+                let access_denied = matches!(
+                    e.downcast_ref::<AdReportError>(),
+                    Some(AdReportError::AccessDenied(_))
+                );
+                if access_denied && !args.ignore_access_denied && index + 1 < target_users.len() {
+                    error!("Access denied while reading {}; the bind account likely lacks read rights for this directory.", target_user);
+                    error!("Aborting the remaining {} users (use --ignore-access-denied to continue anyway).",
+                        target_users.len() - index - 1);
+                    aborted = true;
+                    last_error = Some(e);
+                    break;
+                }
+                last_error = Some(e);
             }
         }
     }
 
+    if let Some(webhook) = webhook.as_ref().filter(|_| args.webhook_batch) {
+        webhook.notify(&BatchNotification { successful, failed, reports: &notifications }).await;
+    }
+
     // Summary
     info!("");
     info!("=== Report Generation Summary ===");
-    info!("Total users processed: {}", target_users.len());
+    info!("Total users processed: {}", successful + failed + skipped_disabled + skipped_non_user);
     info!("Successful: {}", successful);
     info!("Failed: {}", failed);
+    if args.skip_disabled {
+        info!("Skipped (disabled): {}", skipped_disabled);
+    }
+    if args.skip_non_user_accounts {
+        info!("Skipped (computer/trust accounts): {}", skipped_non_user);
+    }
+    if aborted {
+        warn!("Not processed: {} (batch aborted after an access-denied error)",
+            target_users.len() - successful - failed - skipped_disabled - skipped_non_user);
+    } else if interrupted.load(Ordering::SeqCst) {
+        warn!("Not processed: {} (batch interrupted)",
+            target_users.len() - successful - failed - skipped_disabled - skipped_non_user);
+        if let Some(path) = &args.resume {
+            info!("Resume with --resume {} to continue where this run stopped", path);
+        }
+    }
+
+    let (cache_hits, cache_misses, cache_entries) = client.group_cache_stats();
+    debug!("Group cache: {} hits, {} misses, {} entries", cache_hits, cache_misses, cache_entries);
 
     if !generated_files.is_empty() {
         info!("");
@@ -292,11 +1042,119 @@ async fn process_users(
         }
     }
 
+    if !risk_summary.is_empty() {
+        info!("");
+        info!("Risk distribution:");
+        for (level, count) in risk_summary.distribution() {
+            info!("  {:?}: {}", level, count);
+        }
+
+        info!("");
+        info!("Top riskiest users:");
+        for (rank, entry) in risk_summary.top_riskiest(10).iter().enumerate() {
+            info!("  {}. {} - {}/100 ({:?})", rank + 1, entry.sam_account_name, entry.score, entry.risk_level);
+        }
+    }
+
+    if !account_states.is_empty() {
+        info!("");
+        info!("Account states:");
+        for (label, count) in account_states.rows() {
+            info!("  {}: {}", label, count);
+        }
+    }
+
+    if !kerberoastable.is_empty() {
+        info!("");
+        warn!("Kerberoastable user accounts (SPNs set): {}", kerberoastable.accounts.len());
+        for account in &kerberoastable.accounts {
+            info!("  - {}: {}", account.sam_account_name, account.service_principal_names.join(", "));
+            if !account.weak_password_settings.is_empty() {
+                warn!("      weak password settings: {}", account.weak_password_settings.join("; "));
+            }
+        }
+    }
+
+    if !unused_accounts.is_empty() {
+        info!("");
+        warn!("Accounts never used since creation (older than {} days): {}",
+            unused_accounts.min_age_days, unused_accounts.accounts.len());
+        for account in &unused_accounts.accounts {
+            info!("  - {}: created {} ({} days ago), never logged on",
+                account.sam_account_name, account.created.format("%Y-%m-%d"), account.age_days);
+        }
+    }
+
+    let mut batch_summary_written = false;
+    if let Some(summary_path) = &args.batch_summary_pdf {
+        if risk_summary.is_empty() {
+            warn!("Batch summary PDF skipped: no risk assessments (use --risk-analysis)");
+        } else {
+            let mut pdf_gen = PdfGenerator::new()
+                .context("Failed to initialize PDF generator")?
+                .with_timezone(args.timezone)
+                .with_layout(layout_config(args))
+                .with_palette(args.palette);
+            let pdf_bytes = pdf_gen.generate_batch_summary(&risk_summary, &account_states)
+                .context("Failed to generate batch summary PDF")?;
+            FileSink::new(summary_path)
+                .write(summary_path, &pdf_bytes)
+                .context("Failed to write batch summary PDF")?;
+            info!("Batch summary saved: {}", summary_path);
+            batch_summary_written = true;
+        }
+    }
+
+    if let Some(zip) = &zip {
+        if let Some(path) = &args.summary_csv {
+            zip.add_file(Path::new(path))?;
+        }
+        if let Some(path) = args.batch_summary_pdf.as_ref().filter(|_| batch_summary_written) {
+            zip.add_file(Path::new(path))?;
+        }
+        zip.finish()?;
+        info!("Reports archived: {}", args.zip.as_deref().unwrap_or_default());
+    }
+
     if failed > 0 {
         warn!("Some reports failed to generate. Check the logs above for details.");
     }
 
-    Ok(())
+    // Nothing was generated: surface the failure so the exit code reflects its category
+    match last_error {
+        Some(e) if successful == 0 => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// A successfully generated report and the data it was built from
+struct ProcessedUser {
+    output_path: String,
+    report_data: EnhancedReportData,
+}
+
+/// What happened to a user that was read without error
+enum UserOutcome {
+    Generated(Box<ProcessedUser>),
+    Skipped(SkipReason),
+}
+
+/// Why a user was read but no report was generated
+#[derive(Clone, Copy)]
+enum SkipReason {
+    /// Disabled account, with --skip-disabled
+    Disabled,
+    /// Computer object or trust account, with --skip-non-user-accounts
+    NonUserAccount(&'static str),
+}
+
+impl SkipReason {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SkipReason::Disabled => "account disabled",
+            SkipReason::NonUserAccount(kind) => kind,
+        }
+    }
 }
 
 /// Process a single user and generate their report
@@ -306,22 +1164,90 @@ async fn process_user(
     domain: &str,
     server: &str,
     args: &Args,
-    custom_output: Option<&str>,
-) -> Result<String> {
-    // Get user information
-    debug!("Retrieving user information for: {}", target_user);
-    let user = client.get_user(target_user)
-        .await
-        .context(format!("Failed to retrieve user information for {}", target_user))?;
+    sink: &dyn OutputSink,
+    provenance: Option<&ReportProvenance>,
+) -> Result<UserOutcome> {
+    // Get user information (for a computer, its account plus host and delegation details)
+    let (user, computer) = if args.target_computer.is_some() {
+        debug!("Retrieving computer information for: {}", target_user);
+        let computer = client.get_computer(target_user)
+            .await
+            .context(format!("Failed to retrieve computer information for {}", target_user))?;
+        (computer.account.clone(), Some(computer))
+    } else {
+        debug!("Retrieving user information for: {}", target_user);
+        let user = client.get_user(target_user)
+            .await
+            .context(format!("Failed to retrieve user information for {}", target_user))?;
+        (user, None)
+    };
 
     debug!("User {} has {} direct group memberships", target_user, user.groups.len());
     debug!("User {} has {} rights/privileges", target_user, user.user_rights.len());
+    if !user.resolution_warnings.is_empty() {
+        warn!("{}: {} group(s) could not be resolved; the report notes the gap", target_user, user.resolution_warnings.len());
+        for warning in &user.resolution_warnings {
+            debug!("  {}", warning);
+        }
+    }
+    if !user.missing_attributes.unreadable.is_empty() {
+        warn!(
+            "{}: attributes not readable by the bind account: {} (a higher-privileged account may be needed)",
+            target_user, user.missing_attributes.unreadable.join(", ")
+        );
+    }
+
+    if let Some(kind) = user.non_user_kind().filter(|_| computer.is_none()) {
+        if args.skip_non_user_accounts {
+            return Ok(UserOutcome::Skipped(SkipReason::NonUserAccount(kind)));
+        }
+        warn!("{} is a {}, not a user account; reporting it anyway (use --skip-non-user-accounts to leave it out)",
+            target_user, kind);
+    }
+
+    if args.skip_disabled && !user.account_enabled {
+        return Ok(UserOutcome::Skipped(SkipReason::Disabled));
+    }
 
-    // Perform risk assessment
-    let risk_assessment = if args.risk_analysis {
+    // Group name filters always shape the display; the score only when requested
+    let group_filter = GroupFilter::new(args.include_groups.clone(), args.exclude_groups.clone());
+    let (mut filtered_user, hidden_groups) = group_filter.apply(&user);
+    if hidden_groups > 0 {
+        debug!("Hiding {} groups for {} by name filter", hidden_groups, target_user);
+    }
+    let user = if args.filter_groups_in_risk { filtered_user.clone() } else { user };
+
+    let identity_check = IdentityQualityCheck::new(args.placeholder_patterns.clone());
+    let identity_issues = if computer.is_some() { Vec::new() } else { identity_check.issues(&user) };
+
+    // Perform risk assessment; a failure costs the risk section, not the whole report
+    let mut risk_unavailable = None;
+    let risk_assessment = if risk_analysis(args) {
         debug!("Calculating risk assessment for {}...", target_user);
-        let risk_calculator = RiskCalculator::new();
-        Some(risk_calculator.calculate_risk(&user))
+        let risk_calculator = RiskCalculator::new()
+            .with_hardening_groups(args.hardening_groups.clone())
+            .with_escalation_rules(args.escalation_rules.clone().unwrap_or_else(EscalationRule::defaults))
+            .with_custom_rules(args.risk_rules.clone().unwrap_or_default())
+            .with_thresholds(args.risk_thresholds.unwrap_or_default())
+            .with_identity_check(identity_check)
+            .with_timezone(args.timezone);
+        // Per-group scoring travels with the groups into the JSON snapshot
+        risk_calculator.annotate_groups(&mut filtered_user);
+        let assessment = match &computer {
+            Some(computer) => risk_calculator.calculate_computer_risk(&ADComputer {
+                account: user.clone(),
+                ..computer.clone()
+            }),
+            None => risk_calculator.calculate_risk(&user),
+        };
+        match assessment {
+            Ok(assessment) => Some(assessment),
+            Err(e) => {
+                warn!("Risk analysis failed for {}; generating the report without it: {:#}", target_user, e);
+                risk_unavailable = Some(format!("{:#}", e));
+                None
+            }
+        }
     } else {
         None
     };
@@ -332,49 +1258,277 @@ async fn process_user(
             target_user, risk.overall_score, risk.risk_level);
     }
 
+    let mut user = filtered_user;
+    args.sort_groups.apply(&mut user);
+
+    // Redact after the risk assessment so findings are computed from the real record
+    let user = match redactor(args) {
+        Some(redactor) => redactor.redact_user(user),
+        None => user,
+    };
+
     // Create enhanced report data
-    let report_data = EnhancedReportData::new(
+    let mut report_data = EnhancedReportData::new_at(
         user,
         domain.to_string(),
         server.to_string(),
         risk_assessment,
-    );
+        Utc::now(),
+    )
+    .with_hidden_groups(hidden_groups)
+    .with_identity_issues(identity_issues);
+    if let Some(computer) = computer {
+        report_data = report_data.with_computer(computer);
+    }
+    if let Some(provenance) = provenance {
+        report_data = report_data.with_provenance(provenance.clone());
+    }
+    if let Some(reason) = risk_unavailable {
+        report_data = report_data.with_risk_unavailable(reason);
+    }
+
+    // Compare against the previous snapshot for this user, if any
+    let snapshot_path = args.baseline_dir.as_ref()
+        .map(|dir| ReportSnapshot::path_in(Path::new(dir), &report_data.user().sam_account_name));
+    if let Some(path) = &snapshot_path {
+        match ReportSnapshot::load(path) {
+            Ok(Some(previous)) => {
+                debug!("Comparing {} against baseline from {}", target_user, previous.generation_time);
+                let changes = ReportChanges::between(&previous, &report_data);
+                report_data = report_data.with_baseline_changes(changes);
+            }
+            Ok(None) => debug!("No baseline snapshot for {} at {}", target_user, path.display()),
+            Err(e) => warn!("Ignoring unreadable baseline for {}: {:#}", target_user, e),
+        }
+    }
+
+    // Hash the content before rendering so the PDF can carry it
+    let content = if args.integrity_hash {
+        let json = integrity::content_json(&report_data)?;
+        let hash = integrity::sha256_hex(&json);
+        Some((json, hash))
+    } else {
+        None
+    };
+
+    // Terminal tables are printed rather than saved, whatever sink the run uses
+    let sink: &dyn OutputSink = if args.format == OutputFormat::Table { &StdoutSink } else { sink };
+    let rendered = if args.format == OutputFormat::Table {
+        debug!("Rendering terminal table for {}...", target_user);
+        let table = TableGenerator::new()
+            .with_timezone(args.timezone)
+            .with_sections(selected_sections(args))
+            .generate_report(&report_data)
+            .context("Failed to render terminal table")?;
+        RenderedReport { bytes: table.into_bytes(), filename: String::new() }
+    } else if args.format == OutputFormat::Markdown {
+        debug!("Generating Markdown report for {}...", target_user);
+        let mut md_gen = MarkdownGenerator::new()
+            .with_timezone(args.timezone)
+            .with_sections(selected_sections(args));
+        if let Some(title) = &args.report_title {
+            md_gen = md_gen.with_report_title(title);
+        }
+        if let Some(context) = &args.report_context {
+            md_gen = md_gen.with_report_context(context);
+        }
+        md_gen.render(&report_data)
+            .context("Failed to generate Markdown report")?
+    } else {
+        render_pdf(args, &report_data, content.as_ref().map(|(_, hash)| hash.as_str()))?
+    };
+
+    // Delivery is the sink's job; the generators only hand back bytes and a filename
+    sink.write(&rendered.filename, &rendered.bytes)?;
+    let output_path = sink.location(&rendered.filename);
+
+    if let Some((json, hash)) = &content {
+        let (json_path, _) = integrity::sidecar_paths(Path::new(&output_path));
+        sink.write_alongside(&rendered.filename, "json", json)
+            .context(format!("Failed to write report content: {}", json_path.display()))?;
+        sink.write_alongside(&rendered.filename, "sha256", integrity::checksum_line(hash, &json_path).as_bytes())
+            .context("Failed to write checksum file")?;
+        debug!("Content SHA-256 for {}: {}", target_user, hash);
+    }
+
+    // Record this report as the baseline for the next run
+    if let Some(path) = &snapshot_path {
+        ReportSnapshot::from_report(&report_data)
+            .save(path)
+            .context("Failed to write baseline snapshot")?;
+    }
 
-    // Generate PDF
-    debug!("Generating PDF report for {}...", target_user);
+    Ok(UserOutcome::Generated(Box::new(ProcessedUser {
+        output_path,
+        report_data,
+    })))
+}
+
+/// Render the PDF report with the layout options from the command line
+fn render_pdf(args: &Args, report_data: &EnhancedReportData, content_hash: Option<&str>) -> Result<RenderedReport> {
+    debug!("Generating PDF report for {}...", report_data.user().sam_account_name);
     let mut pdf_gen = PdfGenerator::new()
-        .context("Failed to initialize PDF generator")?;
+        .context("Failed to initialize PDF generator")?
+        .with_timezone(args.timezone)
+        .with_sections(selected_sections(args))
+        .with_layout(layout_config(args))
+        .with_palette(args.palette)
+        .with_top_risk_groups(args.top_risk_groups);
+    if let Some(max_groups) = args.max_groups_shown {
+        pdf_gen = pdf_gen.with_max_groups_shown(max_groups as usize);
+    }
+    if let Some(title) = &args.report_title {
+        pdf_gen = pdf_gen.with_report_title(title);
+    }
+    if let Some(context) = &args.report_context {
+        pdf_gen = pdf_gen.with_report_context(context);
+    }
+    if let Some(watermark) = &args.watermark {
+        pdf_gen = pdf_gen.with_watermark(watermark);
+    }
+    if args.compact {
+        pdf_gen = pdf_gen.compact();
+    }
+    if args.include_legend {
+        pdf_gen = pdf_gen.with_legend();
+    }
+    if let Some(hash) = content_hash {
+        pdf_gen = pdf_gen.with_content_hash(hash);
+    }
+
+    pdf_gen.render(report_data)
+        .context("Failed to generate PDF report")
+}
+
+/// --verify: compare the hash embedded in a PDF with its content JSON and, when present,
+/// the `.sha256` checksum file next to the PDF
+fn verify_report(pdf_path: &Path, json_path: &Path) -> Result<()> {
+    let pdf = std::fs::read(pdf_path).context(format!("Failed to read PDF: {}", pdf_path.display()))?;
+    let json = std::fs::read(json_path).context(format!("Failed to read content JSON: {}", json_path.display()))?;
+
+    let hash = match integrity::verify(&pdf, &json)? {
+        Verification::Match { hash } => hash,
+        Verification::Mismatch { embedded, computed } => {
+            return Err(anyhow::anyhow!(
+                "Integrity check FAILED: {} embeds {} but {} hashes to {}",
+                pdf_path.display(), embedded, json_path.display(), computed
+            ));
+        }
+        Verification::NoEmbeddedHash => {
+            return Err(anyhow::anyhow!(
+                "{} has no embedded content hash; generate it with --integrity-hash", pdf_path.display()
+            ));
+        }
+    };
+
+    let (_, checksum_path) = integrity::sidecar_paths(pdf_path);
+    if let Ok(checksum) = std::fs::read_to_string(&checksum_path) {
+        let recorded = checksum.split_whitespace().next().unwrap_or_default();
+        if !recorded.eq_ignore_ascii_case(&hash) {
+            return Err(anyhow::anyhow!(
+                "Integrity check FAILED: {} records {} but the report content hashes to {}",
+                checksum_path.display(), recorded, hash
+            ));
+        }
+    }
+
+    println!("Integrity check passed: {} (SHA-256 {})", pdf_path.display(), hash);
+    Ok(())
+}
 
-    let pdf_bytes = pdf_gen.generate_report(&report_data)
-        .context("Failed to generate PDF report")?;
+/// Where reports go: stdout, the --output file (single target only), or a directory
+fn output_sink(args: &Args, single_target: bool) -> Box<dyn OutputSink> {
+    match (&args.output, &args.output_dir) {
+        _ if args.stdout => Box::new(StdoutSink),
+        (Some(path), _) if single_target => Box::new(FileSink::new(path)),
+        (_, Some(dir)) => Box::new(DirSink::new(dir)),
+        _ => Box::new(DirSink::default()),
+    }
+}
+
+/// Sections requested with --sections, minus any --no-*-section exclusions
+fn selected_sections(args: &Args) -> Vec<ReportSection> {
+    args.sections
+        .iter()
+        .copied()
+        .filter(|section| match section {
+            ReportSection::Groups => !args.no_group_section,
+            ReportSection::Risk => !args.no_risk_section,
+            _ => true,
+        })
+        .collect()
+}
 
-    // Generate output filename
-    let output_path = match custom_output {
-        Some(path) => path.to_string(),
-        None => generate_filename(target_user, &report_data.generation_time()),
+/// Parse --risk-thresholds from a JSON file path or an inline "medium,high,critical" list
+fn parse_risk_thresholds(value: &str) -> Result<RiskThresholds, String> {
+    let path = Path::new(value);
+    let thresholds = if path.is_file() {
+        RiskThresholds::from_file(path)
+    } else {
+        value.parse::<RiskThresholds>()
     };
+    thresholds.map_err(|e| format!("{:#}", e))
+}
+
+/// Parse --escalation-rules from a JSON file path
+fn parse_escalation_rules(value: &str) -> Result<Vec<EscalationRule>, String> {
+    EscalationRule::from_file(Path::new(value)).map_err(|e| format!("{:#}", e))
+}
 
-    // Save PDF to file
-    let mut file = File::create(&output_path)
-        .context("Failed to create output file")?;
+/// Parse --risk-rules from a TOML file path
+fn parse_risk_rules(value: &str) -> Result<Vec<CustomRiskRule>, String> {
+    CustomRiskRule::from_file(Path::new(value)).map_err(|e| format!("{:#}", e))
+}
 
-    file.write_all(&pdf_bytes)
-        .context("Failed to write PDF to file")?;
+/// Page layout from --font-scale and --no-risk-gauge
+fn layout_config(args: &Args) -> LayoutConfig {
+    let layout = LayoutConfig::default().with_font_scale(args.font_scale);
+    if args.no_risk_gauge {
+        layout.without_risk_gauge()
+    } else {
+        layout
+    }
+}
 
-    Ok(output_path)
+/// Parse --rate-limit: operations per second, fractions allowed (e.g., 0.5)
+fn parse_rate_limit(value: &str) -> Result<f64, String> {
+    let per_second: f64 = value.parse()
+        .map_err(|_| format!("Invalid rate limit '{}'", value))?;
+    if !per_second.is_finite() || per_second < RateLimiter::MIN_PER_SECOND {
+        return Err(format!("Rate limit must be at least {} queries per second", RateLimiter::MIN_PER_SECOND));
+    }
+    Ok(per_second)
 }
 
-/// Generate a sanitized filename for the PDF report based on the target user
-fn generate_filename(target_user: &str, timestamp: &DateTime<Utc>) -> String {
-    // Sanitize username for filesystem compatibility
-    let re = Regex::new(r#"[<>:"/\\|?*]"#).unwrap();
-    let clean_username = re.replace_all(target_user, "_").to_string();
+/// Parse --font-scale, keeping it within the range the layout can accommodate
+fn parse_font_scale(value: &str) -> Result<f32, String> {
+    let scale: f32 = value.parse()
+        .map_err(|_| format!("Invalid font scale '{}'", value))?;
+    if !(LayoutConfig::MIN_FONT_SCALE..=LayoutConfig::MAX_FONT_SCALE).contains(&scale) {
+        return Err(format!("Font scale must be between {} and {}",
+            LayoutConfig::MIN_FONT_SCALE, LayoutConfig::MAX_FONT_SCALE));
+    }
+    Ok(scale)
+}
 
-    // Generate timestamp string
-    let time_str = timestamp.format("%Y%m%d_%H%M%S");
+/// An LDAP attribute description: a letter, then letters, digits and '-', with optional
+/// ";option" suffixes (e.g., "extensionAttribute1", "userCertificate;binary")
+fn parse_attribute_name(value: &str) -> Result<String, String> {
+    let name = value.trim();
+    let valid = name.split(';').all(|part| {
+        part.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+            && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    });
+    if !valid {
+        return Err(format!("Invalid attribute name '{}'", value));
+    }
+    Ok(name.to_string())
+}
 
-    // Create filename
-    format!("{}_ad_report_{}.pdf", clean_username, time_str)
+/// Parse an IANA timezone name for --timezone
+fn parse_timezone(name: &str) -> Result<Tz, String> {
+    name.parse::<Tz>()
+        .map_err(|_| format!("Unknown timezone '{}' (expected an IANA name such as 'Europe/London')", name))
 }
 
 /// Read list of usernames from a text file (one per line)
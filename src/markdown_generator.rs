@@ -0,0 +1,334 @@
+use anyhow::Result;
+use chrono_tz::Tz;
+use std::fmt::Write;
+use std::path::Path;
+use crate::ad_time;
+use crate::models::ADGroup;
+use crate::pdf_generator::{report_filename, PdfGenerator, RenderedReport};
+use crate::report_data::{EnhancedReportData, ReportSection};
+
+/// Renders the report as a Markdown document (headers and tables) from the same
+/// `EnhancedReportData` the PDF uses, for wikis, tickets and diff-friendly archives
+pub struct MarkdownGenerator {
+    timezone: Tz,
+    sections: Vec<ReportSection>,
+    report_title: Option<String>,
+    report_context: Option<String>,
+}
+
+impl MarkdownGenerator {
+    pub fn new() -> Self {
+        Self {
+            timezone: Tz::UTC,
+            sections: ReportSection::ALL.to_vec(),
+            report_title: None,
+            report_context: None,
+        }
+    }
+
+    /// Replace the default document title
+    pub fn with_report_title(mut self, title: impl Into<String>) -> Self {
+        self.report_title = Some(title.into());
+        self
+    }
+
+    /// Engagement or ticket reference shown under the title
+    pub fn with_report_context(mut self, context: impl Into<String>) -> Self {
+        self.report_context = Some(context.into());
+        self
+    }
+
+    /// Only emit the given report sections (default: all)
+    pub fn with_sections(mut self, sections: Vec<ReportSection>) -> Self {
+        self.sections = sections;
+        self
+    }
+
+    /// Render all timestamps in the given IANA timezone (default: UTC)
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    fn includes(&self, section: ReportSection) -> bool {
+        self.sections.contains(&section)
+    }
+
+
+    /// Render a report in memory; the file name matches the PDF one with a `.md` extension
+    pub fn render(&self, data: &EnhancedReportData) -> Result<RenderedReport> {
+        let pdf_name = report_filename(&data.user().sam_account_name, &data.generation_time());
+        Ok(RenderedReport {
+            bytes: self.generate_report(data)?.into_bytes(),
+            filename: Path::new(&pdf_name).with_extension("md").to_string_lossy().into_owned(),
+        })
+    }
+
+    pub fn generate_report(&self, data: &EnhancedReportData) -> Result<String> {
+        let mut md = String::new();
+        let user = data.user();
+
+        let title = match (&self.report_title, &data.computer) {
+            (Some(title), _) => title.as_str(),
+            (None, Some(_)) => PdfGenerator::DEFAULT_COMPUTER_REPORT_TITLE,
+            (None, None) => PdfGenerator::DEFAULT_REPORT_TITLE,
+        };
+        writeln!(md, "# {}\n", title)?;
+        if let Some(context) = &self.report_context {
+            writeln!(md, "_{}_\n", escape(context))?;
+        }
+        writeln!(md, "- **Account:** {}", escape(&user.sam_account_name))?;
+        writeln!(md, "- **Domain:** {}", escape(data.domain_name()))?;
+        writeln!(md, "- **Domain Controller:** {}", escape(data.domain_controller()))?;
+        writeln!(md, "- **Generated:** {}", ad_time::format_timestamp(&data.generation_time(), self.timezone))?;
+        if let Some(provenance) = &data.provenance {
+            writeln!(md, "- **Generated by:** {}", escape(&provenance.bind_identity))?;
+            writeln!(md, "- **Search base:** {}", escape(&provenance.search_base))?;
+        }
+        writeln!(md)?;
+
+        if let Some(changes) = &data.baseline_changes {
+            writeln!(md, "## Changes Since Last Report\n")?;
+            writeln!(md, "Previous report: {}\n", ad_time::format_timestamp(&changes.previous_generation_time, self.timezone))?;
+            if let (Some(previous), Some(current), Some(delta)) = (changes.previous_score, changes.current_score, changes.score_delta()) {
+                writeln!(md, "Risk score: {} -> {} ({:+})\n", previous, current, delta)?;
+            }
+            if changes.added_groups.is_empty() && changes.removed_groups.is_empty() {
+                writeln!(md, "No group membership changes\n")?;
+            } else {
+                for group in &changes.added_groups {
+                    writeln!(md, "- Added: {}", escape(group))?;
+                }
+                for group in &changes.removed_groups {
+                    writeln!(md, "- Removed: {}", escape(group))?;
+                }
+                writeln!(md)?;
+            }
+        }
+
+        if self.includes(ReportSection::User) {
+            let rows = match &data.computer {
+                Some(computer) => vec![
+                    ("SAM Account Name", user.sam_account_name.clone()),
+                    ("DNS Host Name", computer.dns_host_name.clone().unwrap_or("N/A".to_string())),
+                    ("Operating System", computer.operating_system_summary().unwrap_or("N/A".to_string())),
+                    ("Role", if computer.is_domain_controller { "Domain controller" } else { "Member computer" }.to_string()),
+                    ("Delegation", computer.delegation.summary()),
+                    ("Description", user.description.clone().unwrap_or("N/A".to_string())),
+                ],
+                None => vec![
+                    ("SAM Account Name", user.sam_account_name.clone()),
+                    ("Display Name", user.display_name.clone().unwrap_or("N/A".to_string())),
+                    ("Email", user.email.clone().unwrap_or("N/A".to_string())),
+                    ("Department", user.department.clone().unwrap_or("N/A".to_string())),
+                    ("Title", user.title.clone().unwrap_or("N/A".to_string())),
+                ],
+            };
+            let heading = if data.computer.is_some() { "Computer Information" } else { "User Information" };
+            writeln!(md, "## {}\n", heading)?;
+            write_table(&mut md, &["Field", "Value"], rows.into_iter().map(|(label, value)| vec![label.to_string(), value]))?;
+            writeln!(md, "Distinguished Name: {}\n", code_span(&user.distinguished_name))?;
+            for issue in &data.identity_issues {
+                writeln!(md, "> **Note:** {}\n", escape(issue))?;
+            }
+        }
+
+        if self.includes(ReportSection::Status) {
+            let now = data.generation_time();
+            let rows = [
+                ("Account Enabled", user.account_enabled),
+                ("Account Locked", user.account_locked),
+                ("Password Expired", user.is_password_expired(now)),
+                ("Password Never Expires", user.password_never_expires),
+                ("Smartcard Required", user.smartcard_required),
+                ("Kerberos Preauth Disabled", user.no_preauth_required),
+            ];
+            writeln!(md, "## Account Status\n")?;
+            write_table(&mut md, &["Status", "Value"], rows.into_iter()
+                .map(|(label, value)| vec![label.to_string(), if value { "Yes" } else { "No" }.to_string()]))?;
+            if let Some(discrepancy) = user.password_expiry_discrepancy(now) {
+                writeln!(md, "> **Note:** {}; the computed date is used\n", discrepancy)?;
+            }
+            let created = user.created.map(|d| ad_time::format_timestamp(&d, self.timezone)).unwrap_or_else(|| "N/A".to_string());
+            let last_logon = user.last_logon.map(|d| ad_time::format_timestamp(&d, self.timezone)).unwrap_or_else(|| "Never".to_string());
+            let password_expires = match user.password_expiry {
+                Some(expiry) => ad_time::format_timestamp(&expiry, self.timezone),
+                None if user.password_never_expires => "Never".to_string(),
+                None => "N/A".to_string(),
+            };
+            writeln!(md, "- Created: {}", created)?;
+            writeln!(md, "- Last Logon: {}", last_logon)?;
+            writeln!(md, "- Password Expires: {}", password_expires)?;
+            let password_policy = user.password_policy.as_ref()
+                .map(|p| p.summary())
+                .unwrap_or_else(|| "Not available".to_string());
+            writeln!(md, "- Password Policy: {}", escape(&password_policy))?;
+            if let Some(age) = user.password_age_days(now) {
+                match user.password_policy.as_ref().and_then(|p| p.max_age_days) {
+                    Some(max_age) if user.days_over_password_policy(now).is_some() => {
+                        writeln!(md, "- Password Age: {} days (exceeds the {}-day policy)", age, max_age)?;
+                    }
+                    _ => writeln!(md, "- Password Age: {} days", age)?,
+                }
+            }
+            writeln!(md, "- Logon Hours: {}\n", escape(&user.logon_hours_summary()))?;
+        }
+
+        if let (None, Some(reason)) = (&data.risk_assessment, &data.risk_unavailable) {
+            if self.includes(ReportSection::Risk) {
+                writeln!(md, "## Risk Assessment\n")?;
+                writeln!(md, "> **Risk analysis unavailable:** {}\n", escape(reason))?;
+            }
+        }
+
+        let risk_assessment = data.risk_assessment.as_ref()
+            .filter(|_| self.includes(ReportSection::Risk));
+        if let Some(risk) = risk_assessment {
+            writeln!(md, "## Risk Assessment\n")?;
+            writeln!(md, "**Risk score: {}/100 ({:?})**\n", risk.overall_score, risk.risk_level)?;
+            let bands: Vec<String> = risk.thresholds.bands().into_iter()
+                .map(|(level, low, high)| format!("{:?} {}-{}", level, low, high))
+                .collect();
+            writeln!(md, "Risk bands: {}\n", bands.join(", "))?;
+
+            let redundancy = &risk.permission_redundancy;
+            if redundancy.total_permissions > 0 {
+                writeln!(md, "Permission redundancy: {:.0}% ({} of {} permissions granted by more than one group)\n",
+                    redundancy.percent, redundancy.overlapped_permissions, redundancy.total_permissions)?;
+            }
+
+            let combinations = &risk.dangerous_combinations;
+            if !combinations.shown.is_empty() {
+                writeln!(md, "### Most Dangerous Combinations\n")?;
+                if combinations.is_truncated() {
+                    writeln!(md, "{} of {} shown\n", combinations.shown.len(), combinations.total)?;
+                }
+                for combination in &combinations.shown {
+                    writeln!(md, "- **{}**", escape(combination))?;
+                }
+                writeln!(md)?;
+            }
+
+            writeln!(md, "### Findings\n")?;
+            if risk.contributing_factors.is_empty() {
+                writeln!(md, "No risk factors found\n")?;
+            } else {
+                write_table(&mut md, &["Severity", "Type", "Finding", "Contribution"], risk.contributing_factors.iter()
+                    .map(|f| vec![
+                        format!("{:?}", f.severity),
+                        f.factor_type.label().to_string(),
+                        f.description.clone(),
+                        f.risk_contribution.to_string(),
+                    ]))?;
+            }
+
+            if !risk.group_contributions.is_empty() {
+                writeln!(md, "### Highest-Risk Group Memberships\n")?;
+                write_table(&mut md, &["Group", "Severity", "Reason", "Contribution"], risk.group_contributions.iter()
+                    .map(|g| vec![
+                        g.group_name.clone(),
+                        format!("{:?}", g.severity),
+                        g.reason.clone(),
+                        g.risk_contribution.to_string(),
+                    ]))?;
+            }
+
+            if !risk.mitigating_factors.is_empty() {
+                writeln!(md, "### Mitigating Factors (score {} -> {})\n", risk.unmitigated_score, risk.overall_score)?;
+                for mitigation in &risk.mitigating_factors {
+                    writeln!(md, "- {} (-{})", escape(&mitigation.description), mitigation.score_reduction)?;
+                }
+                writeln!(md)?;
+            }
+        }
+
+        if self.includes(ReportSection::Groups) {
+            writeln!(md, "## Group Memberships\n")?;
+            if !user.resolution_warnings.is_empty() {
+                writeln!(md, "> **Data completeness:** {} group(s) could not be resolved - memberships may be under-reported\n",
+                    user.resolution_warnings.len())?;
+            }
+            if let Some(primary) = &user.primary_group {
+                writeln!(md, "Primary Group: **{}**\n", escape(&primary.name))?;
+            }
+
+            if user.groups.is_empty() && user.primary_group.is_none() {
+                writeln!(md, "No group memberships found\n")?;
+            } else {
+                writeln!(md, "Direct Groups: {} | Nested Groups: {}\n", user.direct_group_count(), user.nested_group_count())?;
+                let token_only = &user.token_only_groups;
+                let rows = user.groups.iter().flat_map(|group| {
+                    let marker = if token_only.contains(&group.name) { " *" } else { "" };
+                    std::iter::once(group_row(group, format!("{}{}", group.name, marker), "Direct"))
+                        .chain(group.descendants().map(move |nested| {
+                            group_row(nested, nested.name.clone(), &format!("Nested: {}", user.inheritance_chain(nested)))
+                        }))
+                });
+                write_table(&mut md, &["Group", "Type", "Scope", "Members", "Membership"], rows)?;
+                if !token_only.is_empty() {
+                    writeln!(md, "\\* found only through tokenGroups, not via memberOf\n")?;
+                }
+            }
+            if data.hidden_groups > 0 {
+                writeln!(md, "{} group(s) hidden by --include-groups/--exclude-groups filters\n", data.hidden_groups)?;
+            }
+        }
+
+        Ok(md)
+    }
+}
+
+impl Default for MarkdownGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn group_row(group: &ADGroup, name: String, membership: &str) -> Vec<String> {
+    vec![
+        name,
+        format!("{:?}", group.group_type),
+        format!("{:?}", group.scope),
+        group.member_count.map(|c| c.to_string()).unwrap_or_else(|| "-".to_string()),
+        membership.to_string(),
+    ]
+}
+
+/// Write a GitHub-flavoured Markdown table followed by a blank line
+fn write_table(md: &mut String, headers: &[&str], rows: impl IntoIterator<Item = Vec<String>>) -> std::fmt::Result {
+    writeln!(md, "| {} |", headers.join(" | "))?;
+    writeln!(md, "|{}", "---|".repeat(headers.len()))?;
+    for row in rows {
+        let cells: Vec<String> = row.iter().map(|c| escape(c)).collect();
+        writeln!(md, "| {} |", cells.join(" | "))?;
+    }
+    writeln!(md)
+}
+
+/// Wrap text in an inline code span, fenced with more backticks than any run inside it
+///
+/// Backslash escapes don't apply inside code spans, so a DN containing '`' needs a longer
+/// fence; a space pads the content when it starts or ends with a backtick.
+fn code_span(text: &str) -> String {
+    let text = text.replace(['\r', '\n'], " ");
+    let longest_run = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run + 1);
+    let pad = if text.starts_with('`') || text.ends_with('`') { " " } else { "" };
+    format!("{fence}{pad}{text}{pad}{fence}")
+}
+
+/// Keep directory-supplied text from breaking table cells or being read as Markdown
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '|' | '*' | '_' | '`' | '[' | ']' | '<' | '>' | '#' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\r' | '\n' => escaped.push(' '),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -1,11 +1,20 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use crate::logon_hours::LogonHours;
+use crate::risk_calculator::GroupRiskAnnotation;
+use crate::sid::rid_of;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ADUser {
     pub distinguished_name: String,
     pub sam_account_name: String,
+    /// objectClass values, e.g. ["top", "person", "organizationalPerson", "user"]
+    #[serde(default)]
+    pub object_classes: Vec<String>,
+    pub object_sid: Option<String>,
     pub user_principal_name: Option<String>,
+    pub service_principal_names: Vec<String>,
     pub display_name: Option<String>,
     pub email: Option<String>,
     pub department: Option<String>,
@@ -15,12 +24,39 @@ pub struct ADUser {
     pub account_locked: bool,
     pub password_expired: bool,
     pub password_never_expires: bool,
+    pub smartcard_required: bool,
+    pub no_preauth_required: bool,
+    /// Allowed logon hours; None when the attribute is absent (no restriction)
+    pub logon_hours: Option<LogonHours>,
+    pub password_last_set: Option<DateTime<Utc>>,
+    pub password_expiry: Option<DateTime<Utc>>,
+    pub account_expires: Option<DateTime<Utc>>,
     pub last_logon: Option<DateTime<Utc>>,
     pub created: Option<DateTime<Utc>>,
     pub modified: Option<DateTime<Utc>>,
     pub groups: Vec<ADGroup>,
     pub primary_group: Option<ADGroup>,
+    /// RID from primaryGroupID, kept even when the group itself cannot be resolved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary_group_id: Option<u32>,
     pub user_rights: Vec<UserRight>,
+    /// Domain or fine-grained password policy in effect; None when it could not be read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_policy: Option<PasswordPolicy>,
+    /// Groups that could not be resolved while reading memberships ("<DN>: <error>");
+    /// non-empty means the membership data is incomplete
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub resolution_warnings: Vec<String>,
+    /// Requested attributes absent from the user entry, split by likely cause
+    #[serde(default, skip_serializing_if = "MissingAttributes::is_empty")]
+    pub missing_attributes: MissingAttributes,
+    /// Groups found only in tokenGroups (--use-token-groups), added as direct memberships
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub token_only_groups: Vec<String>,
+    /// Raw LDAP attribute values as returned by the directory (opt-in via --include-raw)
+    /// Binary attributes are hex-encoded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_attributes: Option<HashMap<String, Vec<String>>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +67,20 @@ pub struct ADGroup {
     pub group_type: GroupType,
     pub scope: GroupScope,
     pub nested_groups: Vec<ADGroup>,
+    /// Number of direct members in the group's own domain, when --count-members asked for it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub member_count: Option<usize>,
+    /// Risk contribution and matched category, when risk analysis ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub risk: Option<GroupRiskAnnotation>,
+    /// DNs of the groups this membership was inherited through, from the user's direct
+    /// group down to the immediate parent (empty for direct memberships)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inheritance_path: Vec<String>,
+    /// SID of the foreign security principal this membership came through, when a trusting
+    /// domain granted it to the account from outside (the account's own SID)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub foreign_sid: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,6 +89,18 @@ pub enum GroupType {
     Distribution,
 }
 
+impl GroupType {
+    pub const ALL: [GroupType; 2] = [GroupType::Security, GroupType::Distribution];
+
+    /// Name and definition for the report glossary
+    pub fn glossary_entry(&self) -> (&'static str, &'static str) {
+        match self {
+            GroupType::Security => ("Security group", "Can be granted permissions; membership changes effective access."),
+            GroupType::Distribution => ("Distribution group", "Email list only; cannot be used to grant permissions."),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum GroupScope {
     DomainLocal,
@@ -46,6 +108,29 @@ pub enum GroupScope {
     Universal,
 }
 
+impl GroupScope {
+    pub const ALL: [GroupScope; 3] = [GroupScope::Global, GroupScope::DomainLocal, GroupScope::Universal];
+
+    /// Name and definition for the report glossary
+    pub fn glossary_entry(&self) -> (&'static str, &'static str) {
+        match self {
+            GroupScope::Global => ("Global", "Members from its own domain; usable for permissions anywhere in the forest."),
+            GroupScope::DomainLocal => ("Domain Local", "Members from any domain; usable for permissions only in its own domain."),
+            GroupScope::Universal => ("Universal", "Members from any domain; usable anywhere and replicated to the global catalog."),
+        }
+    }
+}
+
+/// Counts of a user's effective groups by scope and type
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroupCategoryCounts {
+    pub global: usize,
+    pub domain_local: usize,
+    pub universal: usize,
+    pub security: usize,
+    pub distribution: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserRight {
     pub name: String,
@@ -60,7 +145,138 @@ pub enum RightSource {
     Default,
 }
 
-#[derive(Debug, Clone)]
+/// A computer account: the account itself, read like a user (see `ADUser::non_user_kind`),
+/// plus the host and Kerberos delegation settings that only computers carry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ADComputer {
+    pub account: ADUser,
+    pub dns_host_name: Option<String>,
+    pub operating_system: Option<String>,
+    pub operating_system_version: Option<String>,
+    /// SERVER_TRUST_ACCOUNT: a domain controller, which is trusted for delegation by design
+    pub is_domain_controller: bool,
+    pub delegation: Delegation,
+}
+
+impl ADComputer {
+    pub fn new(account: ADUser) -> Self {
+        Self {
+            account,
+            dns_host_name: None,
+            operating_system: None,
+            operating_system_version: None,
+            is_domain_controller: false,
+            delegation: Delegation::default(),
+        }
+    }
+
+    /// "Windows Server 2022 Standard (10.0 (20348))", or whichever part is known
+    pub fn operating_system_summary(&self) -> Option<String> {
+        match (&self.operating_system, &self.operating_system_version) {
+            (Some(os), Some(version)) => Some(format!("{} ({})", os, version)),
+            (Some(os), None) => Some(os.clone()),
+            (None, Some(version)) => Some(version.clone()),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Kerberos delegation configured on an account
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Delegation {
+    /// TRUSTED_FOR_DELEGATION: tickets of anyone authenticating here can be reused anywhere
+    pub unconstrained: bool,
+    /// TRUSTED_TO_AUTH_FOR_DELEGATION: constrained delegation with protocol transition
+    pub protocol_transition: bool,
+    /// Services in msDS-AllowedToDelegateTo (constrained delegation)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub constrained_to: Vec<String>,
+    /// msDS-AllowedToActOnBehalfOfOtherIdentity is set (resource-based constrained delegation)
+    pub resource_based: bool,
+}
+
+impl Delegation {
+    /// Any kind of delegation is set
+    pub fn is_configured(&self) -> bool {
+        self.unconstrained || self.protocol_transition || !self.constrained_to.is_empty() || self.resource_based
+    }
+
+    /// One-line description for reports, e.g. "Constrained (2 services, protocol transition)"
+    pub fn summary(&self) -> String {
+        if !self.is_configured() {
+            return "None".to_string();
+        }
+
+        let mut kinds = Vec::new();
+        if self.unconstrained {
+            kinds.push("Unconstrained".to_string());
+        }
+        if !self.constrained_to.is_empty() || self.protocol_transition {
+            let transition = if self.protocol_transition { ", protocol transition" } else { "" };
+            kinds.push(format!("Constrained ({} services{})", self.constrained_to.len(), transition));
+        }
+        if self.resource_based {
+            kinds.push("Resource-based".to_string());
+        }
+        kinds.join(", ")
+    }
+}
+
+/// Requested user attributes that the directory did not return
+///
+/// AD leaves out attributes that are not set and, silently, attributes the bind account
+/// may not read. Attributes every user object carries can only be missing for the latter.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MissingAttributes {
+    /// Always set on AD user objects, so missing means not readable with current permissions
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unreadable: Vec<String>,
+    /// Optional attributes that are not set (or are also hidden from the bind account)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub not_set: Vec<String>,
+}
+
+impl MissingAttributes {
+    pub fn is_empty(&self) -> bool {
+        self.unreadable.is_empty() && self.not_set.is_empty()
+    }
+}
+
+/// Password policy that applies to an account: the domain default or a fine-grained
+/// password settings object (PSO)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasswordPolicy {
+    /// "Default domain policy" or "Fine-grained policy '<PSO name>'"
+    pub source: String,
+    /// None when passwords never expire under this policy
+    pub max_age_days: Option<i64>,
+    pub min_length: Option<u32>,
+    /// Whether passwords must meet the complexity requirements; None when not readable
+    #[serde(default)]
+    pub complexity_required: Option<bool>,
+}
+
+impl PasswordPolicy {
+    /// "max age 90 days, min length 12, complexity required (Default domain policy)"
+    pub fn summary(&self) -> String {
+        let max_age = match self.max_age_days {
+            Some(days) => format!("max age {} days", days),
+            None => "no maximum age".to_string(),
+        };
+        let min_length = match self.min_length {
+            Some(length) => format!("min length {}", length),
+            None => "no minimum length".to_string(),
+        };
+        let complexity = match self.complexity_required {
+            Some(true) => ", complexity required",
+            Some(false) => ", no complexity",
+            None => "",
+        };
+        format!("{}, {}{} ({})", max_age, min_length, complexity, self.source)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct ReportData {
     pub user: ADUser,
     pub generation_time: DateTime<Utc>,
@@ -73,7 +289,10 @@ impl ADUser {
         Self {
             distinguished_name: dn,
             sam_account_name: sam,
+            object_classes: Vec::new(),
+            object_sid: None,
             user_principal_name: None,
+            service_principal_names: Vec::new(),
             display_name: None,
             email: None,
             department: None,
@@ -83,20 +302,124 @@ impl ADUser {
             account_locked: false,
             password_expired: false,
             password_never_expires: false,
+            smartcard_required: false,
+            no_preauth_required: false,
+            logon_hours: None,
+            password_last_set: None,
+            password_expiry: None,
+            account_expires: None,
             last_logon: None,
             created: None,
             modified: None,
             groups: Vec::new(),
             primary_group: None,
+            primary_group_id: None,
             user_rights: Vec::new(),
+            password_policy: None,
+            resolution_warnings: Vec::new(),
+            missing_attributes: MissingAttributes::default(),
+            token_only_groups: Vec::new(),
+            raw_attributes: None,
+        }
+    }
+
+    /// What the object really is when it is not a person's account: computer objects and
+    /// trust accounts also match user searches but don't belong in user audits
+    pub fn non_user_kind(&self) -> Option<&'static str> {
+        let has_class = |class: &str| self.object_classes.iter().any(|c| c.eq_ignore_ascii_case(class));
+        if has_class("trustedDomain") {
+            Some("trust account")
+        } else if has_class("computer") {
+            Some("computer account")
+        } else {
+            None
+        }
+    }
+
+    /// primaryGroupID values AD assigns to this kind of account: Domain Users for users,
+    /// Domain Guests for the built-in Guest, Domain Computers or a DC group for computers
+    pub fn expected_primary_group_rids(&self) -> &'static [u32] {
+        if self.non_user_kind() == Some("computer account") {
+            &[515, 516, 521]
+        } else if self.object_sid.as_deref().and_then(rid_of) == Some(501) {
+            &[514]
+        } else {
+            &[513]
+        }
+    }
+
+    /// The primaryGroupID when it is not the default for the account; a changed primary
+    /// group grants membership that never shows up in memberOf
+    pub fn anomalous_primary_group_id(&self) -> Option<u32> {
+        self.primary_group_id
+            .filter(|rid| !self.expected_primary_group_rids().contains(rid))
+    }
+
+    /// DNS domain the account lives in, from the DC= components of its DN
+    pub fn domain(&self) -> Option<String> {
+        domain_of_dn(&self.distinguished_name)
+    }
+
+    /// Whole days since the password was last set
+    pub fn password_age_days(&self, now: DateTime<Utc>) -> Option<i64> {
+        self.password_last_set.map(|set| (now - set).num_days())
+    }
+
+    /// The password is past expiry at `now`: judged by the computed expiry date when the
+    /// directory returned one, otherwise by the PASSWORD_EXPIRED flag, which is often stale
+    pub fn is_password_expired(&self, now: DateTime<Utc>) -> bool {
+        match self.password_expiry {
+            Some(expiry) => expiry <= now,
+            None => self.password_expired,
+        }
+    }
+
+    /// How the PASSWORD_EXPIRED flag disagrees with the computed expiry date, if it does
+    pub fn password_expiry_discrepancy(&self, now: DateTime<Utc>) -> Option<&'static str> {
+        let computed_expired = self.password_expiry? <= now;
+        match (self.password_expired, computed_expired) {
+            (false, true) => Some("the PASSWORD_EXPIRED flag is not set, but the computed expiry date has passed"),
+            (true, false) => Some("the PASSWORD_EXPIRED flag is set, but the computed expiry date is still ahead"),
+            _ => None,
+        }
+    }
+
+    /// Days the password is past the maximum age of the policy in effect (None if within it,
+    /// or if the age or policy is unknown)
+    pub fn days_over_password_policy(&self, now: DateTime<Utc>) -> Option<i64> {
+        let max_age = self.password_policy.as_ref()?.max_age_days?;
+        let age = self.password_age_days(now)?;
+        (age > max_age).then_some(age - max_age)
+    }
+
+    /// Logon is limited to part of the week
+    pub fn has_logon_hour_restriction(&self) -> bool {
+        self.logon_hours.as_ref().is_some_and(|hours| !hours.is_unrestricted())
+    }
+
+    /// Weekly logon schedule for display
+    pub fn logon_hours_summary(&self) -> String {
+        match &self.logon_hours {
+            Some(hours) => hours.summary(),
+            None => "No restrictions".to_string(),
         }
     }
 
+    /// Computer accounts have a SAM account name ending in '$'
+    pub fn is_computer_account(&self) -> bool {
+        self.sam_account_name.ends_with('$')
+    }
+
+    /// A user (not computer) account with SPNs can have service tickets requested and cracked offline
+    pub fn is_kerberoastable(&self) -> bool {
+        !self.service_principal_names.is_empty() && !self.is_computer_account()
+    }
+
     pub fn all_groups(&self) -> Vec<&ADGroup> {
         let mut all_groups = Vec::new();
         
         if let Some(primary) = &self.primary_group {
-            all_groups.push(primary);
+            Self::collect_groups(primary, &mut all_groups);
         }
         
         for group in &self.groups {
@@ -106,6 +429,125 @@ impl ADUser {
         all_groups
     }
 
+    /// Walk the same groups as `all_groups`, in the same order, without collecting them
+    pub fn iter_groups(&self) -> GroupIter<'_> {
+        let mut stack: Vec<&ADGroup> = self.groups.iter().rev().collect();
+        stack.extend(&self.primary_group);
+        GroupIter { stack }
+    }
+
+    /// Number of entries `all_groups` returns (primary, direct and nested, duplicates included)
+    pub fn group_count(&self) -> usize {
+        fn count(group: &ADGroup) -> usize {
+            1 + group.nested_groups.iter().map(count).sum::<usize>()
+        }
+        self.primary_group.iter().chain(&self.groups).map(count).sum()
+    }
+
+    /// Why the user holds `group`: "DB-Admins <- App-Admins <- jdoe", the group first and
+    /// the user last, with the groups it was inherited through in between
+    pub fn inheritance_chain(&self, group: &ADGroup) -> String {
+        let name_of = |dn: &str| {
+            self.iter_groups()
+                .find(|g| g.distinguished_name.eq_ignore_ascii_case(dn))
+                .map(|g| g.name.clone())
+                .unwrap_or_else(|| rdn_value(dn).to_string())
+        };
+        std::iter::once(group.name.clone())
+            .chain(group.inheritance_path.iter().rev().map(|dn| name_of(dn)))
+            .chain(std::iter::once(self.sam_account_name.clone()))
+            .collect::<Vec<_>>()
+            .join(" <- ")
+    }
+
+    /// Whether the user has any group membership at all, including the primary group
+    pub fn has_groups(&self) -> bool {
+        self.primary_group.is_some() || !self.groups.is_empty()
+    }
+
+    /// Collapse groups reached more than once (keyed by DN, case-insensitively)
+    ///
+    /// The primary group and direct memberships take precedence; a nested group is kept
+    /// under the first parent that reaches it (siblings before deeper levels) and pruned,
+    /// with its subtree, everywhere else.
+    pub fn dedupe_groups(&mut self) {
+        // The primary group is looked up without its nesting; adopt it from the tree if reached there
+        let adopted = self.primary_group.as_ref()
+            .filter(|primary| primary.nested_groups.is_empty())
+            .and_then(|primary| self.iter_groups()
+                .find(|g| g.distinguished_name.eq_ignore_ascii_case(&primary.distinguished_name)
+                    && !g.nested_groups.is_empty())
+                .map(|g| g.nested_groups.clone()));
+        if let (Some(primary), Some(nested)) = (&mut self.primary_group, adopted) {
+            primary.nested_groups = nested;
+        }
+
+        let mut seen = HashSet::new();
+        if let Some(primary) = &self.primary_group {
+            seen.insert(primary.distinguished_name.to_lowercase());
+        }
+        self.groups.retain(|g| seen.insert(g.distinguished_name.to_lowercase()));
+
+        if let Some(primary) = &mut self.primary_group {
+            Self::prune_seen_groups(primary, &mut seen);
+        }
+        for group in &mut self.groups {
+            Self::prune_seen_groups(group, &mut seen);
+        }
+    }
+
+    /// Unique direct memberships, excluding the primary group
+    pub fn direct_group_count(&self) -> usize {
+        let primary = self.primary_group.as_ref().map(|g| g.distinguished_name.to_lowercase());
+        self.groups.iter()
+            .map(|g| g.distinguished_name.to_lowercase())
+            .filter(|dn| Some(dn) != primary.as_ref())
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// Unique groups reached only through nesting, at any depth
+    pub fn nested_group_count(&self) -> usize {
+        let mut direct: HashSet<String> = self.groups.iter()
+            .map(|g| g.distinguished_name.to_lowercase())
+            .collect();
+        if let Some(primary) = &self.primary_group {
+            direct.insert(primary.distinguished_name.to_lowercase());
+        }
+
+        self.iter_groups()
+            .map(|g| g.distinguished_name.to_lowercase())
+            .filter(|dn| !direct.contains(dn))
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// Break down all effective groups (primary, direct and nested) by scope and type
+    pub fn group_category_counts(&self) -> GroupCategoryCounts {
+        let mut counts = GroupCategoryCounts::default();
+
+        for group in self.iter_groups() {
+            match group.scope {
+                GroupScope::Global => counts.global += 1,
+                GroupScope::DomainLocal => counts.domain_local += 1,
+                GroupScope::Universal => counts.universal += 1,
+            }
+            match group.group_type {
+                GroupType::Security => counts.security += 1,
+                GroupType::Distribution => counts.distribution += 1,
+            }
+        }
+
+        counts
+    }
+
+    fn prune_seen_groups(group: &mut ADGroup, seen: &mut HashSet<String>) {
+        group.nested_groups.retain(|g| seen.insert(g.distinguished_name.to_lowercase()));
+        for nested in &mut group.nested_groups {
+            Self::prune_seen_groups(nested, seen);
+        }
+    }
+
     fn collect_groups<'a>(group: &'a ADGroup, collection: &mut Vec<&'a ADGroup>) {
         collection.push(group);
         for nested in &group.nested_groups {
@@ -114,6 +556,21 @@ impl ADUser {
     }
 }
 
+/// Depth-first walk over a user's group tree; see `ADUser::iter_groups`
+pub struct GroupIter<'a> {
+    stack: Vec<&'a ADGroup>,
+}
+
+impl<'a> Iterator for GroupIter<'a> {
+    type Item = &'a ADGroup;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let group = self.stack.pop()?;
+        self.stack.extend(group.nested_groups.iter().rev());
+        Some(group)
+    }
+}
+
 impl ADGroup {
     pub fn new(dn: String, name: String) -> Self {
         Self {
@@ -123,6 +580,51 @@ impl ADGroup {
             group_type: GroupType::Security,
             scope: GroupScope::Global,
             nested_groups: Vec::new(),
+            member_count: None,
+            risk: None,
+            inheritance_path: Vec::new(),
+            foreign_sid: None,
+        }
+    }
+
+    /// Every group nested in this one, at any depth, depth-first in listing order
+    pub fn descendants(&self) -> GroupIter<'_> {
+        GroupIter { stack: self.nested_groups.iter().rev().collect() }
+    }
+
+    /// Type and scope for display, followed by the member count when it is known
+    pub fn details(&self) -> String {
+        match self.member_count {
+            Some(1) => format!("{:?}, {:?}, 1 member", self.group_type, self.scope),
+            Some(count) => format!("{:?}, {:?}, {} members", self.group_type, self.scope, count),
+            None => format!("{:?}, {:?}", self.group_type, self.scope),
         }
     }
+
+    /// DNS domain the group lives in, from the DC= components of its DN
+    /// (None for groups only known by SID, such as an unresolved primary group)
+    pub fn domain(&self) -> Option<String> {
+        domain_of_dn(&self.distinguished_name)
+    }
+}
+
+/// "CN=App Admins,OU=Groups,DC=corp,DC=com" -> "App Admins"
+fn rdn_value(dn: &str) -> &str {
+    let rdn = dn.split(',').next().unwrap_or(dn);
+    rdn.split_once('=').map_or(rdn, |(_, value)| value).trim()
+}
+
+/// "CN=x,OU=y,DC=child,DC=corp,DC=com" -> "child.corp.com", lowercased
+pub(crate) fn domain_of_dn(dn: &str) -> Option<String> {
+    let labels: Vec<String> = dn
+        .split(',')
+        .map(str::trim)
+        .filter(|c| c.len() > 3 && c[..3].eq_ignore_ascii_case("DC="))
+        .map(|c| c[3..].to_lowercase())
+        .collect();
+    if labels.is_empty() {
+        None
+    } else {
+        Some(labels.join("."))
+    }
 }
\ No newline at end of file
@@ -0,0 +1,185 @@
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Destination for rendered reports
+///
+/// Generators only produce bytes and a suggested file name (`RenderedReport`); a sink
+/// decides where they go, so a new destination is one more implementation rather than
+/// another `File::create` in the CLI.
+pub trait OutputSink: Send + Sync {
+    /// Deliver one report; `filename` is the generator's suggested name
+    fn write(&self, filename: &str, bytes: &[u8]) -> Result<()>;
+
+    /// Where a report with this name ends up, for logs, the summary CSV and the audit log
+    fn location(&self, filename: &str) -> String;
+
+    /// Deliver a companion file (e.g., the --integrity-hash content JSON) next to the report
+    /// named `filename`, under the same name with `extension`
+    fn write_alongside(&self, filename: &str, extension: &str, bytes: &[u8]) -> Result<()> {
+        write_atomically(&Path::new(&self.location(filename)).with_extension(extension), bytes)
+    }
+}
+
+/// Writes every report to one fixed path, ignoring the suggested name (`--output`)
+#[derive(Debug, Clone)]
+pub struct FileSink {
+    path: PathBuf,
+}
+
+impl FileSink {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl OutputSink for FileSink {
+    fn write(&self, _filename: &str, bytes: &[u8]) -> Result<()> {
+        write_atomically(&self.path, bytes)
+    }
+
+    fn location(&self, _filename: &str) -> String {
+        self.path.display().to_string()
+    }
+}
+
+/// Writes each report under its suggested name in a directory, creating it if needed
+/// (`--output-dir`; the default is the current directory)
+#[derive(Debug, Clone, Default)]
+pub struct DirSink {
+    dir: PathBuf,
+}
+
+impl DirSink {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl OutputSink for DirSink {
+    fn write(&self, filename: &str, bytes: &[u8]) -> Result<()> {
+        if !self.dir.as_os_str().is_empty() {
+            std::fs::create_dir_all(&self.dir)
+                .context(format!("Failed to create output directory: {}", self.dir.display()))?;
+        }
+        write_atomically(&self.dir.join(filename), bytes)
+    }
+
+    fn location(&self, filename: &str) -> String {
+        self.dir.join(filename).display().to_string()
+    }
+}
+
+/// Writes reports to standard output for piping (`--stdout`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdoutSink;
+
+impl OutputSink for StdoutSink {
+    fn write(&self, _filename: &str, bytes: &[u8]) -> Result<()> {
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(bytes).context("Failed to write report to stdout")?;
+        stdout.flush().context("Failed to write report to stdout")
+    }
+
+    fn location(&self, _filename: &str) -> String {
+        "<stdout>".to_string()
+    }
+
+    fn write_alongside(&self, _filename: &str, extension: &str, _bytes: &[u8]) -> Result<()> {
+        Err(anyhow::anyhow!("Cannot write a .{} file alongside a report sent to stdout", extension))
+    }
+}
+
+/// Streams every report into one zip archive as it is produced (`--zip`)
+///
+/// Entries are compressed straight into `<archive>.tmp`; `finish` writes the central
+/// directory and renames the archive into place, so a crashed run never leaves a
+/// truncated zip under the requested name.
+pub struct ZipSink {
+    path: PathBuf,
+    temp_path: PathBuf,
+    writer: Mutex<Option<ZipWriter<File>>>,
+}
+
+impl ZipSink {
+    pub fn create(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut temp_path = path.as_os_str().to_owned();
+        temp_path.push(".tmp");
+        let temp_path = PathBuf::from(temp_path);
+
+        let file = File::create(&temp_path)
+            .context(format!("Failed to create zip archive: {}", path.display()))?;
+        Ok(Self { path, temp_path, writer: Mutex::new(Some(ZipWriter::new(file))) })
+    }
+
+    /// Copy a file written elsewhere during the run (summary CSV, batch summary PDF) into the archive
+    pub fn add_file(&self, source: &Path) -> Result<()> {
+        let bytes = std::fs::read(source)
+            .context(format!("Failed to read {} for the zip archive", source.display()))?;
+        let name = source.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        self.write(&name, &bytes)
+    }
+
+    /// Complete the archive and move it into place
+    pub fn finish(&self) -> Result<()> {
+        let writer = self.writer.lock().map_err(|_| anyhow::anyhow!("Zip archive writer poisoned"))?.take();
+        let Some(writer) = writer else {
+            return Ok(());
+        };
+        let file = writer.finish().context("Failed to finish zip archive")?;
+        file.sync_all().context("Failed to flush zip archive to disk")?;
+        std::fs::rename(&self.temp_path, &self.path)
+            .context(format!("Failed to move zip archive into place: {}", self.path.display()))
+    }
+}
+
+impl OutputSink for ZipSink {
+    fn write(&self, filename: &str, bytes: &[u8]) -> Result<()> {
+        let mut writer = self.writer.lock().map_err(|_| anyhow::anyhow!("Zip archive writer poisoned"))?;
+        let writer = writer.as_mut().ok_or_else(|| anyhow::anyhow!("Zip archive already finished"))?;
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        writer.start_file(filename, options)
+            .context(format!("Failed to add {} to the zip archive", filename))?;
+        writer.write_all(bytes)
+            .context(format!("Failed to add {} to the zip archive", filename))
+    }
+
+    fn location(&self, filename: &str) -> String {
+        self.path.join(filename).display().to_string()
+    }
+
+    fn write_alongside(&self, filename: &str, extension: &str, bytes: &[u8]) -> Result<()> {
+        let name = Path::new(filename).with_extension(extension);
+        self.write(&name.to_string_lossy(), bytes)
+    }
+}
+
+/// Write a report to a temporary sibling file and rename it into place once the bytes are on
+/// disk, so any report file present is complete even after a crash or a full disk
+pub fn write_atomically(path: &Path, bytes: &[u8]) -> Result<()> {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = Path::new(&temp_path);
+
+    let written = File::create(temp_path)
+        .context("Failed to create output file")
+        .and_then(|mut file| {
+            file.write_all(bytes).context("Failed to write report to file")?;
+            file.flush().context("Failed to write report to file")?;
+            file.sync_all().context("Failed to flush report to disk")
+        })
+        .and_then(|()| {
+            std::fs::rename(temp_path, path)
+                .context(format!("Failed to move report into place: {}", path.display()))
+        });
+
+    if written.is_err() {
+        let _ = std::fs::remove_file(temp_path);
+    }
+    written
+}
@@ -1,9 +1,16 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use printpdf::*;
+use sha2::{Digest, Sha256};
 use std::io::BufWriter;
-use crate::models::RightSource;
-use crate::report_data::EnhancedReportData;
+use crate::models::{ADGroup, GroupScope, GroupType, RightSource};
+use crate::report_data::{EnhancedReportData, AccountStateSummary, BatchRiskSummary, ReportSection};
 use crate::permission_analyzer::RiskLevel;
+use crate::risk_calculator::{PermissionRedundancy, RiskAssessment, RiskCalculator, RiskFactorType};
+use crate::font_metrics::{fit_to_width, text_width_mm, wrap_to_width};
+use crate::integrity;
+use crate::ad_time;
 
 // Enterprise color palette
 struct Colors;
@@ -14,6 +21,7 @@ impl Colors {
     const LIGHT_GRAY: (u8, u8, u8) = (247, 250, 252);     // #F7FAFC
     const MEDIUM_GRAY: (u8, u8, u8) = (226, 232, 240);    // #E2E8F0
     const DARK_GRAY: (u8, u8, u8) = (113, 128, 150);      // #718096
+    const WATERMARK_GRAY: (u8, u8, u8) = (232, 232, 232); // #E8E8E8
 
     // Risk colors
     const CRITICAL_RED: (u8, u8, u8) = (197, 48, 48);     // #C53030
@@ -34,29 +42,403 @@ impl Colors {
         ))
     }
 
-    fn risk_color(level: &RiskLevel) -> (u8, u8, u8) {
+    const STANDARD: PaletteColors = PaletteColors {
+        critical: Self::CRITICAL_RED,
+        high: Self::HIGH_ORANGE,
+        medium: Self::MEDIUM_YELLOW,
+        low: Self::LOW_GREEN,
+        success: Self::SUCCESS_GREEN,
+        warning: Self::WARNING_RED,
+    };
+
+    // Diverging blue/purple/orange scale from the Okabe-Ito set, distinguishable with
+    // red-green color blindness
+    const COLORBLIND: PaletteColors = PaletteColors {
+        critical: (213, 94, 0),                           // #D55E00 vermillion
+        high: (230, 159, 0),                              // #E69F00 orange
+        medium: (204, 121, 167),                          // #CC79A7 reddish purple
+        low: (0, 114, 178),                               // #0072B2 blue
+        success: (0, 114, 178),                           // #0072B2 blue
+        warning: (213, 94, 0),                            // #D55E00 vermillion
+    };
+}
+
+/// Colors for risk levels and good/bad status values (see `Palette`)
+struct PaletteColors {
+    critical: (u8, u8, u8),
+    high: (u8, u8, u8),
+    medium: (u8, u8, u8),
+    low: (u8, u8, u8),
+    success: (u8, u8, u8),
+    warning: (u8, u8, u8),
+}
+
+impl PaletteColors {
+    fn risk(&self, level: &RiskLevel) -> (u8, u8, u8) {
         match level {
-            RiskLevel::Critical => Self::CRITICAL_RED,
-            RiskLevel::High => Self::HIGH_ORANGE,
-            RiskLevel::Medium => Self::MEDIUM_YELLOW,
-            RiskLevel::Low => Self::LOW_GREEN,
+            RiskLevel::Critical => self.critical,
+            RiskLevel::High => self.high,
+            RiskLevel::Medium => self.medium,
+            RiskLevel::Low => self.low,
         }
     }
 }
 
+/// Color scheme for risk levels and status values
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Palette {
+    /// Red/orange/yellow/green
+    #[default]
+    Standard,
+    /// Blue/purple/orange, safe for red-green color blindness; risk bars also get a
+    /// per-level dash pattern and risk items name their level
+    Colorblind,
+}
+
+impl Palette {
+    fn colors(self) -> &'static PaletteColors {
+        match self {
+            Palette::Standard => &Colors::STANDARD,
+            Palette::Colorblind => &Colors::COLORBLIND,
+        }
+    }
+}
+
+/// Page geometry and text scaling for generated reports (A4 portrait)
+#[derive(Debug, Clone, Copy)]
+pub struct LayoutConfig {
+    pub left_margin: Mm,
+    pub right_margin: Mm,
+    pub top_margin: Mm,
+    pub bottom_margin: Mm,
+    pub line_height: Mm,      // Line spacing at a font scale of 1.0
+    pub font_scale: f32,      // Multiplier applied to every font size
+    pub risk_gauge: bool,     // Draw the score as a donut gauge instead of plain text
+}
+
+impl LayoutConfig {
+    pub const MIN_FONT_SCALE: f32 = 0.5;
+    pub const MAX_FONT_SCALE: f32 = 3.0;
+
+    /// Scale all text (and line spacing) proportionally
+    pub fn with_font_scale(mut self, font_scale: f32) -> Self {
+        self.font_scale = font_scale.clamp(Self::MIN_FONT_SCALE, Self::MAX_FONT_SCALE);
+        self
+    }
+
+    /// Render the overall risk score as text only
+    pub fn without_risk_gauge(mut self) -> Self {
+        self.risk_gauge = false;
+        self
+    }
+
+    /// Width available for content between the margins
+    pub fn content_width(&self) -> Mm {
+        self.right_margin - self.left_margin
+    }
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        Self {
+            left_margin: Mm(20.0),
+            right_margin: Mm(190.0),  // Narrower for portrait
+            top_margin: Mm(277.0),    // Adjusted for portrait height
+            bottom_margin: Mm(25.0),
+            line_height: Mm(5.5),
+            font_scale: 1.0,
+            risk_gauge: true,
+        }
+    }
+}
+
+/// A generated report held in memory, ready to be written to disk, streamed or uploaded
+#[derive(Debug, Clone)]
+pub struct RenderedReport {
+    pub bytes: Vec<u8>,
+    /// Suggested file name, `<sam>_ad_report_<YYYYmmdd_HHMMSS>.pdf`. Only ASCII letters,
+    /// digits, '.', '-' and '_' are used, so it can go straight into a
+    /// `Content-Disposition: attachment; filename="..."` header.
+    pub filename: String,
+}
+
+/// Suggested report file name for a user and generation time (see `RenderedReport::filename`)
+pub fn report_filename(sam_account_name: &str, timestamp: &DateTime<Utc>) -> String {
+    let clean_username: String = sam_account_name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+
+    format!("{}_ad_report_{}.pdf", clean_username, timestamp.format("%Y%m%d_%H%M%S"))
+}
+
+/// Save a document with its dates and file identifier derived from the generation time,
+/// so the same report data always produces the same bytes
+///
+/// printpdf stamps the current time and two counter-based IDs into every document; the
+/// trailer IDs have no setter, so they are overwritten in place in the saved bytes.
+fn save_reproducibly(doc: PdfDocumentReference, generation_time: &DateTime<Utc>, subject: &str) -> Result<Vec<u8>> {
+    let timestamp = OffsetDateTime::from_unix_timestamp(generation_time.timestamp())?;
+    let doc = doc
+        .with_creation_date(timestamp)
+        .with_mod_date(timestamp)
+        .with_metadata_date(timestamp);
+
+    let mut buffer = Vec::new();
+    doc.save(&mut BufWriter::new(&mut buffer))?;
+
+    // Trailer: /ID[(<32 characters>)(<32 characters>)]; a newly written file has both equal
+    const ID_LEN: usize = 32;
+    let digest = Sha256::digest(format!("{}|{}", subject, generation_time.to_rfc3339()).as_bytes());
+    let file_id: String = digest.iter().take(ID_LEN / 2).map(|b| format!("{:02X}", b)).collect();
+    if let Some(start) = buffer.windows(5).rposition(|w| w == b"/ID[(") {
+        let first = start + 5;
+        let second = first + ID_LEN + 2;
+        if buffer.get(first + ID_LEN..second) == Some(b")(".as_slice())
+            && buffer.get(second + ID_LEN) == Some(&b')')
+        {
+            buffer[first..first + ID_LEN].copy_from_slice(file_id.as_bytes());
+            buffer[second..second + ID_LEN].copy_from_slice(file_id.as_bytes());
+        }
+    }
+
+    Ok(buffer)
+}
+
+/// Placement of the risk gauge and the score it shows (the score's level sets the colour)
+struct GaugeLayout<'a> {
+    /// Baseline of the "OVERALL RISK SCORE" heading; the ring hangs below it
+    top: Mm,
+    left_margin: Mm,
+    score: u8,
+    risk_level: &'a RiskLevel,
+}
+
+/// One line of the compact layout, laid out top to bottom
+struct CompactLine {
+    text: String,
+    size: f32,
+    bold: bool,
+    color: (u8, u8, u8),
+    indent: Mm,
+    /// Extra space above the line, in line heights
+    space_before: f32,
+}
+
+impl CompactLine {
+    fn new(text: impl Into<String>, size: f32) -> Self {
+        Self { text: text.into(), size, bold: false, color: (0, 0, 0), indent: Mm(0.0), space_before: 0.0 }
+    }
+
+    fn heading(text: impl Into<String>) -> Self {
+        Self { bold: true, color: Colors::DARK_BLUE, space_before: 0.6, ..Self::new(text, 10.0) }
+    }
+
+    fn item(text: impl Into<String>) -> Self {
+        Self { indent: Mm(4.0), ..Self::new(text, 8.0) }
+    }
+
+    fn colored(mut self, color: (u8, u8, u8)) -> Self {
+        self.color = color;
+        self
+    }
+}
+
 pub struct PdfGenerator {
     total_pages: usize,
+    timezone: Tz,
+    sections: Vec<ReportSection>,
+    layout: LayoutConfig,
+    top_risk_groups: usize,
+    max_groups_shown: Option<usize>,
+    report_title: Option<String>,
+    report_context: Option<String>,
+    watermark: Option<String>,
+    compact: bool,
+    include_legend: bool,
+    palette: Palette,
+    content_hash: Option<String>,
 }
 
 impl PdfGenerator {
+    /// Length of a 100/100 bar next to each risk factor
+    const RISK_BAR_MAX_WIDTH: Mm = Mm(80.0);
+
+    pub const DEFAULT_REPORT_TITLE: &'static str = "ACTIVE DIRECTORY USER ACCESS REPORT";
+    pub const DEFAULT_COMPUTER_REPORT_TITLE: &'static str = "ACTIVE DIRECTORY COMPUTER ACCESS REPORT";
+
+    pub const DEFAULT_TOP_RISK_GROUPS: usize = 5;
+
+    /// Document author when the bind identity is unknown, and the creator application
+    const TOOL_NAME: &'static str = "ad-report";
+
+    /// Watermark size before shrinking to fit the diagonal
+    const WATERMARK_FONT_SIZE: f32 = 96.0;
+    /// Longest watermark run, kept inside the page diagonal with some margin
+    const WATERMARK_MAX_WIDTH: Mm = Mm(280.0);
+
+    /// Groups and findings listed in the compact layout
+    const COMPACT_TOP_GROUPS: usize = 5;
+    const COMPACT_TOP_FINDINGS: usize = 3;
+
     pub fn new() -> Result<Self> {
-        Ok(Self { total_pages: 0 })
+        Ok(Self {
+            total_pages: 0,
+            timezone: Tz::UTC,
+            sections: ReportSection::ALL.to_vec(),
+            layout: LayoutConfig::default(),
+            top_risk_groups: Self::DEFAULT_TOP_RISK_GROUPS,
+            max_groups_shown: None,
+            report_title: None,
+            report_context: None,
+            watermark: None,
+            compact: false,
+            include_legend: false,
+            palette: Palette::default(),
+            content_hash: None,
+        })
+    }
+
+    /// Append a glossary defining the risk levels, risk factor types and group categories
+    /// (ignored by the compact layout)
+    pub fn with_legend(mut self) -> Self {
+        self.include_legend = true;
+        self
+    }
+
+    /// Render a fixed single-page summary (identity, risk score, top groups and findings)
+    /// instead of the full paginated report
+    pub fn compact(mut self) -> Self {
+        self.compact = true;
+        self
+    }
+
+    /// Replace the default cover page title
+    pub fn with_report_title(mut self, title: impl Into<String>) -> Self {
+        self.report_title = Some(title.into());
+        self
+    }
+
+    /// Engagement or ticket reference shown under the cover page title
+    pub fn with_report_context(mut self, context: impl Into<String>) -> Self {
+        self.report_context = Some(context.into());
+        self
+    }
+
+    /// Text drawn diagonally behind the content of every page (e.g., "DRAFT")
+    pub fn with_watermark(mut self, text: impl Into<String>) -> Self {
+        self.watermark = Some(text.into());
+        self
+    }
+
+    /// Number of groups listed under "Highest-Risk Group Memberships" (0 hides the subsection)
+    pub fn with_top_risk_groups(mut self, count: usize) -> Self {
+        self.top_risk_groups = count;
+        self
+    }
+
+    /// List at most `count` direct groups (highest risk first) under Group Memberships;
+    /// summary counts still cover every group
+    pub fn with_max_groups_shown(mut self, count: usize) -> Self {
+        self.max_groups_shown = Some(count);
+        self
+    }
+
+    /// Color scheme for risk levels and status values (default: `Palette::Standard`)
+    pub fn with_palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// SHA-256 of the report content (see `integrity::content_json`), embedded in the
+    /// document metadata and printed on the last page
+    pub fn with_content_hash(mut self, hash: impl Into<String>) -> Self {
+        self.content_hash = Some(hash.into());
+        self
+    }
+
+    fn colors(&self) -> &'static PaletteColors {
+        self.palette.colors()
+    }
+
+    /// Dash pattern telling risk bars apart without color (colorblind palette only;
+    /// Critical stays solid)
+    fn bar_pattern(&self, level: &RiskLevel) -> LineDashPattern {
+        let (dash, gap) = match (self.palette, level) {
+            (Palette::Standard, _) | (_, RiskLevel::Critical) => (None, None),
+            (_, RiskLevel::High) => (Some(6), Some(1)),
+            (_, RiskLevel::Medium) => (Some(3), Some(2)),
+            (_, RiskLevel::Low) => (Some(1), Some(2)),
+        };
+        LineDashPattern { dash_1: dash, gap_1: gap, ..LineDashPattern::default() }
+    }
+
+    /// Use custom margins, line spacing and font scale
+    pub fn with_layout(mut self, layout: LayoutConfig) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Font size in points after applying the configured font scale
+    fn font_size(&self, size: f32) -> f32 {
+        size * self.layout.font_scale
+    }
+
+    /// Vertical distance that tracks the font scale (offsets between stacked text lines)
+    fn scaled(&self, distance: f32) -> Mm {
+        Mm(distance * self.layout.font_scale)
+    }
+
+    fn line_height(&self) -> Mm {
+        self.layout.line_height * self.layout.font_scale
+    }
+
+    /// Only emit the given report sections (default: all)
+    pub fn with_sections(mut self, sections: Vec<ReportSection>) -> Self {
+        self.sections = sections;
+        self
+    }
+
+    fn includes(&self, section: ReportSection) -> bool {
+        self.sections.contains(&section)
+    }
+
+    /// Render all timestamps in the given IANA timezone (default: UTC)
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// The --report-title override, or the default for the kind of account reported on
+    fn title_for<'a>(&'a self, data: &EnhancedReportData) -> &'a str {
+        match (&self.report_title, &data.computer) {
+            (Some(title), _) => title,
+            (None, Some(_)) => Self::DEFAULT_COMPUTER_REPORT_TITLE,
+            (None, None) => Self::DEFAULT_REPORT_TITLE,
+        }
+    }
+
+    /// Format a timestamp in the configured timezone
+    fn format_timestamp(&self, timestamp: &DateTime<Utc>) -> String {
+        ad_time::format_timestamp(timestamp, self.timezone)
+    }
+
+    /// Render a report in memory without touching the filesystem
+    pub fn render(&mut self, data: &EnhancedReportData) -> Result<RenderedReport> {
+        Ok(RenderedReport {
+            bytes: self.generate_report(data)?,
+            filename: report_filename(&data.user().sam_account_name, &data.generation_time()),
+        })
     }
 
     pub fn generate_report(&mut self, data: &EnhancedReportData) -> Result<Vec<u8>> {
+        if self.compact {
+            return self.generate_compact_report(data);
+        }
+
         // Create a PDF document in PORTRAIT orientation
         let (mut doc, page1, layer1) = PdfDocument::new(
-            "Active Directory User Report",
+            if data.computer.is_some() { "Active Directory Computer Report" } else { "Active Directory User Report" },
             Mm(210.0),  // Width - portrait
             Mm(297.0),  // Height - portrait
             "Layer 1"
@@ -71,15 +453,18 @@ impl PdfGenerator {
         let mut current_layer_index = layer1;
         let mut page_number = 1;
 
+        // Watermark first so everything else is drawn over it
+        self.render_watermark(&doc, current_page, current_layer_index, &bold_font);
+
         // Layout constants for PORTRAIT
-        let line_height = Mm(5.5);
-        let left_margin = Mm(20.0);
-        let right_margin = Mm(190.0);  // Narrower for portrait
-        let top_margin = Mm(277.0);    // Adjusted for portrait height
-        let bottom_margin = Mm(25.0);
+        let line_height = self.line_height();
+        let left_margin = self.layout.left_margin;
+        let right_margin = self.layout.right_margin;
+        let top_margin = self.layout.top_margin;
+        let bottom_margin = self.layout.bottom_margin;
 
         // Generate cover page
-        self.render_cover_page(
+        let cover_bottom = self.render_cover_page(
             &mut doc,
             current_page,
             current_layer_index,
@@ -89,8 +474,9 @@ impl PdfGenerator {
         );
 
         // Continue content on same page below cover page header
-        // Cover page content ends around y=234mm, start content with spacing
-        let mut y_position = Mm(220.0);  // Start content 14mm below cover page content
+        // Cover page content ends around y=234mm (225mm with the badge row), start content
+        // with spacing, lower only when a large font scale pushes the cover down
+        let mut y_position = Mm(220.0).min(cover_bottom - Mm(5.0));
 
         // Helper closure for page management
         let mut check_new_page = |doc: &mut PdfDocumentReference,
@@ -110,236 +496,1006 @@ impl PdfGenerator {
                 *page_num += 1;
                 *y = top_margin;
 
+                self.render_watermark(doc, *current_page, *current_layer, &bold_font);
+
                 // Render header on new page
                 self.render_header(doc, *current_page, *current_layer, &bold_font, &font);
             }
         };
 
-        // Executive Summary
-        check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 60.0);
-        y_position = self.render_executive_summary(
-            &doc,
-            current_page,
-            current_layer_index,
-            y_position,
-            line_height,
-            left_margin,
-            data,
-            &bold_font,
-            &font,
-        );
-        y_position = y_position - line_height * 3.0;
+        // Executive Summary (skipped when no included section has a metric)
+        let metrics = self.summary_metrics(data);
+        if !metrics.is_empty() {
+            check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 60.0);
+            y_position = self.render_executive_summary(
+                &doc,
+                current_page,
+                current_layer_index,
+                y_position,
+                line_height,
+                left_margin,
+                &metrics,
+                &bold_font,
+                &font,
+            );
+            y_position -= line_height * 3.0;
+        }
+
+        // Changes since the previous report (--baseline-dir)
+        if let Some(changes) = &data.baseline_changes {
+            check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 40.0);
+            y_position = self.render_section_header(
+                &doc,
+                current_page,
+                current_layer_index,
+                y_position,
+                line_height,
+                left_margin,
+                right_margin,
+                "Changes Since Last Report",
+                &bold_font,
+            );
+
+            let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+            let previous_text = format!("Previous report: {}", self.format_timestamp(&changes.previous_generation_time));
+            current_layer.use_text(&previous_text, self.font_size(9.0), left_margin + Mm(5.0), y_position, &font);
+            y_position -= line_height;
+
+            if let (Some(previous), Some(current), Some(delta)) = (changes.previous_score, changes.current_score, changes.score_delta()) {
+                let score_text = format!("Risk score: {} -> {} ({:+})", previous, current, delta);
+                let score_color = match delta {
+                    d if d > 0 => self.colors().warning,
+                    d if d < 0 => self.colors().success,
+                    _ => Colors::DARK_GRAY,
+                };
+                current_layer.set_fill_color(Colors::to_rgb(score_color));
+                current_layer.use_text(&score_text, self.font_size(10.0), left_margin + Mm(5.0), y_position, &bold_font);
+                current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+                y_position -= line_height * 1.5;
+            }
+
+            if changes.added_groups.is_empty() && changes.removed_groups.is_empty() {
+                current_layer.use_text("No group membership changes", self.font_size(9.0), left_margin + Mm(5.0), y_position, &font);
+                y_position -= line_height;
+            }
+
+            let group_changes = changes.added_groups.iter().map(|g| ("+ Added", g, self.colors().warning))
+                .chain(changes.removed_groups.iter().map(|g| ("- Removed", g, self.colors().success)));
+
+            for (label, group, color) in group_changes {
+                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
+                let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                current_layer.set_fill_color(Colors::to_rgb(color));
+                current_layer.use_text(format!("{}: {}", label, group), self.font_size(9.0), left_margin + Mm(7.0), y_position, &font);
+                current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+                y_position -= line_height;
+            }
+            y_position -= line_height * 2.0;
+        }
 
         // User Information section
-        check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 50.0);
-        y_position = self.render_section_header(
-            &doc,
-            current_page,
-            current_layer_index,
-            y_position,
-            line_height,
-            left_margin,
-            right_margin,
-            "User Information",
-            &bold_font,
-        );
+        if self.includes(ReportSection::User) {
+            check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 50.0);
+            y_position = self.render_section_header(
+                &doc,
+                current_page,
+                current_layer_index,
+                y_position,
+                line_height,
+                left_margin,
+                right_margin,
+                if data.computer.is_some() { "Computer Information" } else { "User Information" },
+                &bold_font,
+            );
 
-        let user_info = vec![
-            ("SAM Account Name", data.user().sam_account_name.clone()),
-            ("Display Name", data.user().display_name.clone().unwrap_or("N/A".to_string())),
-            ("Email", data.user().email.clone().unwrap_or("N/A".to_string())),
-            ("Department", data.user().department.clone().unwrap_or("N/A".to_string())),
-            ("Title", data.user().title.clone().unwrap_or("N/A".to_string())),
-        ];
+            let user_info = match &data.computer {
+                Some(computer) => vec![
+                    ("SAM Account Name", data.user().sam_account_name.clone()),
+                    ("DNS Host Name", computer.dns_host_name.clone().unwrap_or("N/A".to_string())),
+                    ("Operating System", computer.operating_system_summary().unwrap_or("N/A".to_string())),
+                    ("Role", if computer.is_domain_controller { "Domain controller" } else { "Member computer" }.to_string()),
+                    ("Delegation", computer.delegation.summary()),
+                    ("Description", data.user().description.clone().unwrap_or("N/A".to_string())),
+                ],
+                None => vec![
+                    ("SAM Account Name", data.user().sam_account_name.clone()),
+                    ("Display Name", data.user().display_name.clone().unwrap_or("N/A".to_string())),
+                    ("Email", data.user().email.clone().unwrap_or("N/A".to_string())),
+                    ("Department", data.user().department.clone().unwrap_or("N/A".to_string())),
+                    ("Title", data.user().title.clone().unwrap_or("N/A".to_string())),
+                ],
+            };
 
-        for (label, value) in user_info {
-            check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
+            for (label, value) in user_info {
+                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
+                let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                current_layer.use_text(label, self.font_size(10.0), left_margin + Mm(5.0), y_position, &bold_font);
+                current_layer.use_text(&value, self.font_size(10.0), left_margin + Mm(60.0), y_position, &font);
+                y_position = y_position - line_height;
+            }
+
+            // Services this computer may delegate to (constrained delegation)
+            let delegates_to = data.computer.iter().flat_map(|c| &c.delegation.constrained_to);
+            for service in delegates_to {
+                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
+                let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                current_layer.use_text(format!("Delegates to: {}", service), self.font_size(8.0), left_margin + Mm(10.0), y_position, &courier);
+                y_position -= line_height * 0.8;
+            }
+
+            // Identity data-quality notes
+            for issue in &data.identity_issues {
+                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
+                let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                current_layer.set_fill_color(Colors::to_rgb(self.colors().high));
+                current_layer.use_text(format!("! {}", issue), self.font_size(9.0), left_margin + Mm(5.0), y_position, &font);
+                current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+                y_position -= line_height;
+            }
+
+            // Requested attributes that came back missing, by likely cause
+            let missing = &data.user().missing_attributes;
+            let groups = [
+                (&missing.unreadable, "Attributes not readable with current permissions (a higher-privileged bind account may be needed):", self.colors().high),
+                (&missing.not_set, "Attributes not set on this account:", Colors::DARK_GRAY),
+            ];
+            for (attributes, heading, color) in groups.into_iter().filter(|(a, _, _)| !a.is_empty()) {
+                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 15.0);
+                let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                current_layer.set_fill_color(Colors::to_rgb(color));
+                current_layer.use_text(heading, self.font_size(9.0), left_margin + Mm(5.0), y_position, &font);
+                current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+                y_position -= line_height * 0.9;
+                for line in self.wrap_monospace(&attributes.join(", "), 8.0, self.layout.content_width() - Mm(10.0)) {
+                    check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
+                    let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                    current_layer.use_text(&line, self.font_size(8.0), left_margin + Mm(10.0), y_position, &courier);
+                    y_position -= line_height * 0.8;
+                }
+                y_position -= line_height * 0.4;
+            }
+
+            // Distinguished Name (wrapped to the content width)
+            check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 15.0);
             let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
-            current_layer.use_text(label, 10.0, left_margin + Mm(5.0), y_position, &bold_font);
-            current_layer.use_text(&value, 10.0, left_margin + Mm(60.0), y_position, &font);
+            current_layer.use_text("Distinguished Name", self.font_size(10.0), left_margin + Mm(5.0), y_position, &bold_font);
             y_position = y_position - line_height;
+            for line in self.wrap_monospace(&data.user().distinguished_name, 8.0, self.layout.content_width() - Mm(5.0)) {
+                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
+                let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                current_layer.use_text(&line, self.font_size(8.0), left_margin + Mm(5.0), y_position, &courier);
+                y_position -= line_height * 0.8;
+            }
+            y_position -= line_height * 2.2;
         }
 
-        // Distinguished Name (needs wrapping)
-        check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 15.0);
-        let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
-        current_layer.use_text("Distinguished Name", 10.0, left_margin + Mm(5.0), y_position, &bold_font);
-        y_position = y_position - line_height;
-        current_layer.use_text(&data.user().distinguished_name, 8.0, left_margin + Mm(5.0), y_position, &courier);
-        y_position = y_position - line_height * 3.0;
-
         // Account Status section
-        check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 50.0);
-        y_position = self.render_section_header(
-            &doc,
-            current_page,
-            current_layer_index,
-            y_position,
-            line_height,
-            left_margin,
-            right_margin,
-            "Account Status",
-            &bold_font,
+        if self.includes(ReportSection::Status) {
+            check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 50.0);
+            y_position = self.render_section_header(
+                &doc,
+                current_page,
+                current_layer_index,
+                y_position,
+                line_height,
+                left_margin,
+                right_margin,
+                "Account Status",
+                &bold_font,
+            );
+
+            let status_items = vec![
+                ("Account Enabled", data.user().account_enabled, false),
+                ("Account Locked", data.user().account_locked, true),
+                ("Password Expired", data.user().is_password_expired(data.generation_time()), true),
+                ("Password Never Expires", data.user().password_never_expires, true),
+                ("Smartcard Required", data.user().smartcard_required, false),
+                ("Kerberos Preauth Disabled", data.user().no_preauth_required, true),
+            ];
+
+            for (label, value, is_warning) in status_items {
+                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
+                let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+
+                current_layer.use_text(label, self.font_size(10.0), left_margin + Mm(5.0), y_position, &bold_font);
+
+                let status_text = if value { "Yes" } else { "No" };
+                let status_color = if value == is_warning {
+                    Colors::to_rgb(self.colors().warning)
+                } else {
+                    Colors::to_rgb(self.colors().success)
+                };
+
+                current_layer.set_fill_color(status_color);
+                current_layer.use_text(status_text, self.font_size(10.0), left_margin + Mm(60.0), y_position, &bold_font);
+                current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+
+                y_position = y_position - line_height;
+            }
+
+            // The computed expiry wins over a stale PASSWORD_EXPIRED flag; say so when they differ
+            if let Some(discrepancy) = data.user().password_expiry_discrepancy(data.generation_time()) {
+                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
+                let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                let note = format!("Note: {}; the computed date is used", discrepancy);
+                current_layer.set_fill_color(Colors::to_rgb(Colors::DARK_GRAY));
+                current_layer.use_text(&note, self.font_size(8.0), left_margin + Mm(5.0), y_position, &font);
+                current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+                y_position -= line_height;
+            }
+
+            // Timestamps
+            check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 30.0);
+            let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+
+            let created = format!("Created: {}", data.user().created.map(|d| self.format_timestamp(&d))
+                .unwrap_or_else(|| "N/A".to_string()));
+            current_layer.use_text(&created, self.font_size(9.0), left_margin + Mm(5.0), y_position, &font);
+            y_position = y_position - line_height;
+
+            let last_logon = format!("Last Logon: {}", data.user().last_logon.map(|d| self.format_timestamp(&d))
+                .unwrap_or_else(|| "Never".to_string()));
+            current_layer.use_text(&last_logon, self.font_size(9.0), left_margin + Mm(5.0), y_position, &font);
+            y_position -= line_height;
+
+            let password_expires = format!("Password Expires: {}", match data.user().password_expiry {
+                Some(expiry) => self.format_timestamp(&expiry),
+                None if data.user().password_never_expires => "Never".to_string(),
+                None => "N/A".to_string(),
+            });
+            current_layer.use_text(&password_expires, self.font_size(9.0), left_margin + Mm(5.0), y_position, &font);
+            y_position -= line_height;
+
+            // Judge the password age against the policy actually in effect
+            let password_policy = format!("Password Policy: {}", data.user().password_policy.as_ref()
+                .map(|p| p.summary())
+                .unwrap_or_else(|| "Not available".to_string()));
+            current_layer.use_text(&password_policy, self.font_size(9.0), left_margin + Mm(5.0), y_position, &font);
+            y_position -= line_height;
+
+            if let Some(age) = data.user().password_age_days(data.generation_time()) {
+                let over_policy = data.user().days_over_password_policy(data.generation_time());
+                let password_age = match (over_policy, data.user().password_policy.as_ref().and_then(|p| p.max_age_days)) {
+                    (Some(_), Some(max_age)) if data.user().password_never_expires => format!(
+                        "Password Age: {} days - exceeds the {}-day policy (exempt: password never expires)", age, max_age),
+                    (Some(_), Some(max_age)) => format!("Password Age: {} days - exceeds the {}-day policy", age, max_age),
+                    _ => format!("Password Age: {} days", age),
+                };
+                if over_policy.is_some() {
+                    current_layer.set_fill_color(Colors::to_rgb(self.colors().warning));
+                }
+                current_layer.use_text(&password_age, self.font_size(9.0), left_margin + Mm(5.0), y_position, &font);
+                current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+                y_position -= line_height;
+            }
+
+            let logon_hours = format!("Logon Hours: {}", data.user().logon_hours_summary());
+            current_layer.use_text(&logon_hours, self.font_size(9.0), left_margin + Mm(5.0), y_position, &font);
+            y_position = y_position - line_height * 3.0;
+        }
+
+        // Requested but failed risk analysis: say so instead of silently dropping the section
+        if let (None, Some(reason)) = (&data.risk_assessment, &data.risk_unavailable) {
+            if self.includes(ReportSection::Risk) {
+                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 30.0);
+                y_position = self.render_section_header(
+                    &doc,
+                    current_page,
+                    current_layer_index,
+                    y_position,
+                    line_height,
+                    left_margin,
+                    right_margin,
+                    "Risk Assessment",
+                    &bold_font,
+                );
+                let note = format!("Risk analysis unavailable: {}", reason);
+                for line in wrap_to_width(&note, BuiltinFont::Helvetica, self.font_size(9.0), self.layout.content_width() - Mm(5.0)) {
+                    check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
+                    let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                    current_layer.set_fill_color(Colors::to_rgb(self.colors().high));
+                    current_layer.use_text(&line, self.font_size(9.0), left_margin + Mm(5.0), y_position, &font);
+                    current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+                    y_position -= line_height;
+                }
+                y_position -= line_height * 2.0;
+            }
+        }
+
+        // Risk Assessment section
+        let risk_assessment = data.risk_assessment.as_ref()
+            .filter(|_| self.includes(ReportSection::Risk));
+        if let Some(risk) = risk_assessment {
+            check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 70.0);
+            y_position = self.render_section_header(
+                &doc,
+                current_page,
+                current_layer_index,
+                y_position,
+                line_height,
+                left_margin,
+                right_margin,
+                "Risk Assessment",
+                &bold_font,
+            );
+
+            // Risk score as a gauge, or the compact text box when gauges are disabled
+            if self.layout.risk_gauge {
+                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 45.0);
+                let gauge = GaugeLayout {
+                    top: y_position,
+                    left_margin,
+                    score: risk.overall_score,
+                    risk_level: &risk.risk_level,
+                };
+                y_position = self.render_risk_gauge(&doc, current_page, current_layer_index, &gauge, &bold_font);
+            } else {
+                y_position = self.render_risk_score_box(
+                    &doc,
+                    current_page,
+                    current_layer_index,
+                    y_position,
+                    left_margin,
+                    risk.overall_score,
+                    &risk.risk_level,
+                    &bold_font,
+                    &font,
+                );
+            }
+            y_position -= line_height;
+
+            // Legend for the score bands used in this run
+            let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+            let mut legend_x = left_margin + Mm(5.0);
+            current_layer.use_text("Risk bands:", self.font_size(8.0), legend_x, y_position, &bold_font);
+            legend_x += text_width_mm("Risk bands: ", BuiltinFont::HelveticaBold, self.font_size(8.0));
+            for (level, low, high) in risk.thresholds.bands() {
+                let band = format!("{:?} {}-{}   ", level, low, high);
+                current_layer.set_fill_color(Colors::to_rgb(self.colors().risk(&level)));
+                current_layer.use_text(&band, self.font_size(8.0), legend_x, y_position, &font);
+                legend_x += text_width_mm(&band, BuiltinFont::Helvetica, self.font_size(8.0));
+            }
+            current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+            y_position -= line_height * 2.0;
+
+            // Permission redundancy as a bar, with the counts behind the percentage
+            let redundancy = &risk.permission_redundancy;
+            if redundancy.total_permissions > 0 {
+                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 20.0);
+                y_position = self.render_redundancy_bar(&doc, current_page, current_layer_index, y_position, redundancy, &font);
+                y_position -= line_height;
+            }
+
+            // Permission + group combinations most worth investigating, highlighted
+            let combinations = &risk.dangerous_combinations;
+            if !combinations.shown.is_empty() {
+                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 30.0);
+                let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                let header = if combinations.is_truncated() {
+                    format!("Most Dangerous Combinations ({} of {} shown):", combinations.shown.len(), combinations.total)
+                } else {
+                    format!("Most Dangerous Combinations ({}):", combinations.total)
+                };
+                current_layer.use_text(&header, self.font_size(12.0), left_margin + Mm(5.0), y_position, &bold_font);
+                y_position -= line_height * 1.5;
+
+                for combination in &combinations.shown {
+                    check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
+                    let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                    let text = format!("! {}", combination);
+                    let text = fit_to_width(&text, BuiltinFont::Helvetica, self.font_size(9.0), self.layout.content_width() - Mm(7.0))
+                        .unwrap_or(text);
+                    current_layer.set_fill_color(Colors::to_rgb(self.colors().critical));
+                    current_layer.use_text(&text, self.font_size(9.0), left_margin + Mm(7.0), y_position, &font);
+                    current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+                    y_position -= line_height;
+                }
+                y_position -= line_height;
+            }
+
+            // Top risk factors
+            if !risk.contributing_factors.is_empty() {
+                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 30.0);
+                let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                current_layer.use_text("Top Risk Factors:", self.font_size(12.0), left_margin + Mm(5.0), y_position, &bold_font);
+                y_position = y_position - line_height * 1.5;
+
+                for factor in risk.contributing_factors.iter().take(5) {
+                    check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
+                    y_position = self.render_risk_item(
+                        &doc,
+                        current_page,
+                        current_layer_index,
+                        y_position,
+                        left_margin,
+                        &factor.description,
+                        factor.risk_contribution,
+                        &font,
+                    );
+                }
+                y_position = y_position - line_height;
+            }
+
+            // Groups ranked by how much they add, so the score maps back to removals
+            let top_groups: Vec<_> = risk.group_contributions.iter().take(self.top_risk_groups).collect();
+            if !top_groups.is_empty() {
+                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 30.0);
+                let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                current_layer.use_text("Highest-Risk Group Memberships:", self.font_size(12.0), left_margin + Mm(5.0), y_position, &bold_font);
+                y_position -= line_height * 1.5;
+
+                for group in top_groups {
+                    check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
+                    y_position = self.render_risk_item(
+                        &doc,
+                        current_page,
+                        current_layer_index,
+                        y_position,
+                        left_margin,
+                        &group.reason,
+                        group.risk_contribution,
+                        &font,
+                    );
+                }
+                y_position -= line_height;
+            }
+
+            // Mitigating factors lower the score
+            if !risk.mitigating_factors.is_empty() {
+                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 20.0);
+                let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                let mitigation_header = format!("Mitigating Factors (score {} -> {}):", risk.unmitigated_score, risk.overall_score);
+                current_layer.use_text(&mitigation_header, self.font_size(12.0), left_margin + Mm(5.0), y_position, &bold_font);
+                y_position -= line_height * 1.5;
+
+                for mitigation in &risk.mitigating_factors {
+                    check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
+                    let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                    let mitigation_text = format!("+ {} (-{})", mitigation.description, mitigation.score_reduction);
+                    current_layer.set_fill_color(Colors::to_rgb(self.colors().success));
+                    current_layer.use_text(&mitigation_text, self.font_size(9.0), left_margin + Mm(7.0), y_position, &font);
+                    current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+                    y_position -= line_height;
+                }
+            }
+            y_position -= line_height * 2.0;
+        }
+
+        // Group Memberships section
+        if self.includes(ReportSection::Groups) {
+            check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 50.0);
+            y_position = self.render_section_header(
+                &doc,
+                current_page,
+                current_layer_index,
+                y_position,
+                line_height,
+                left_margin,
+                right_margin,
+                "Group Memberships",
+                &bold_font,
+            );
+
+            // Data completeness: groups that could not be read are missing from everything below
+            let warnings = &data.user().resolution_warnings;
+            if !warnings.is_empty() {
+                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 15.0);
+                let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                current_layer.set_fill_color(Colors::to_rgb(self.colors().high));
+                let note = format!("Data completeness: {} group(s) could not be resolved - memberships may be under-reported", warnings.len());
+                current_layer.use_text(&note, self.font_size(10.0), left_margin + Mm(5.0), y_position, &bold_font);
+                current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+                y_position -= line_height;
+
+                for warning in warnings {
+                    for line in self.wrap_monospace(warning, 8.0, self.layout.content_width() - Mm(10.0)) {
+                        check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
+                        let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                        current_layer.use_text(&line, self.font_size(8.0), left_margin + Mm(10.0), y_position, &courier);
+                        y_position -= line_height * 0.8;
+                    }
+                }
+                y_position -= line_height;
+            }
+
+            // Memberships only tokenGroups knew about (e.g., via foreign security principals)
+            let token_only = &data.user().token_only_groups;
+            if !token_only.is_empty() {
+                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 15.0);
+                let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                current_layer.set_fill_color(Colors::to_rgb(Colors::DARK_BLUE));
+                let note = format!("{} membership(s) found only through tokenGroups, not via memberOf (marked *)", token_only.len());
+                current_layer.use_text(&note, self.font_size(10.0), left_margin + Mm(5.0), y_position, &bold_font);
+                current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+                y_position -= line_height * 1.5;
+            }
+
+            if let Some(primary) = &data.user().primary_group {
+                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
+                let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                let primary_text = format!("Primary Group: {}", primary.name);
+                current_layer.use_text(&primary_text, self.font_size(10.0), left_margin + Mm(5.0), y_position, &bold_font);
+                y_position = y_position - line_height * 1.5;
+            }
+
+            // A changed primary group hides membership from memberOf, so call it out
+            if let Some(rid) = data.user().anomalous_primary_group_id() {
+                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 15.0);
+                let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                let expected: Vec<String> = data.user().expected_primary_group_rids().iter().map(u32::to_string).collect();
+                current_layer.set_fill_color(Colors::to_rgb(self.colors().critical));
+                let warning = format!("Non-default primary group (RID {}, expected {})", rid, expected.join(" or "));
+                current_layer.use_text(&warning, self.font_size(10.0), left_margin + Mm(5.0), y_position, &bold_font);
+                current_layer.use_text(
+                    "Membership through the primary group is not listed in memberOf and is easily overlooked",
+                    self.font_size(8.0), left_margin + Mm(5.0), y_position - self.scaled(4.0), &font,
+                );
+                current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+                y_position = y_position - self.scaled(4.0) - line_height * 1.5;
+            }
+
+            if data.user().groups.is_empty() && data.user().primary_group.is_none() {
+                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
+                let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                current_layer.set_fill_color(Colors::to_rgb(Colors::DARK_GRAY));
+                current_layer.use_text("No group memberships found", self.font_size(10.0), left_margin + Mm(5.0), y_position, &font);
+                current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+                y_position -= line_height;
+            } else {
+                let total_groups = data.user().direct_group_count();
+                let total_nested = data.user().nested_group_count();
+
+                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
+                let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                let groups_summary = format!("Direct Groups: {} | Nested Groups: {}", total_groups, total_nested);
+                current_layer.use_text(&groups_summary, self.font_size(10.0), left_margin + Mm(5.0), y_position, &font);
+                y_position = y_position - line_height * 1.5;
+
+                // Group category summary table (all effective groups)
+                let counts = data.user().group_category_counts();
+                let category_rows = [
+                    ("Scope", [("Global", counts.global), ("Domain Local", counts.domain_local), ("Universal", counts.universal)].to_vec()),
+                    ("Type", [("Security", counts.security), ("Distribution", counts.distribution)].to_vec()),
+                ];
+
+                for (label, cells) in category_rows {
+                    check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
+                    let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                    current_layer.use_text(label, self.font_size(9.0), left_margin + Mm(5.0), y_position, &bold_font);
+
+                    for (column, (name, count)) in cells.iter().enumerate() {
+                        let cell_x = left_margin + Mm(30.0) + Mm(40.0) * column as f32;
+                        current_layer.use_text(format!("{}: {}", name, count), self.font_size(9.0), cell_x, y_position, &font);
+                    }
+                    y_position -= line_height;
+                }
+                y_position -= line_height * 0.5;
+
+                let (shown_groups, omitted_groups) = self.groups_to_show(&data.user().groups);
+                if !shown_groups.is_empty() {
+                    for group in shown_groups {
+                        check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
+                        let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+
+                        let marker = if token_only.contains(&group.name) { " *" } else { "" };
+                        let group_info = format!("• {} ({}){}", group.name, group.details(), marker);
+                        current_layer.use_text(&group_info, self.font_size(9.0), left_margin + Mm(7.0), y_position, &font);
+                        y_position = y_position - line_height;
+
+                        // Add nested groups at every depth, each with the path it was inherited through
+                        for nested in group.descendants() {
+                            check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
+                            let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+
+                            let nested_info = format!("  └─ {} ({})", data.user().inheritance_chain(nested), nested.details());
+                            let nested_info = fit_to_width(&nested_info, BuiltinFont::Helvetica, self.font_size(8.0), self.layout.content_width() - Mm(12.0))
+                                .unwrap_or(nested_info);
+                            current_layer.use_text(&nested_info, self.font_size(8.0), left_margin + Mm(12.0), y_position, &font);
+                            y_position = y_position - line_height * 0.9;
+                        }
+                    }
+                }
+
+                if omitted_groups > 0 {
+                    check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
+                    let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                    let overflow = format!("(+{} additional groups not shown)", omitted_groups);
+                    current_layer.set_fill_color(Colors::to_rgb(Colors::DARK_GRAY));
+                    current_layer.use_text(&overflow, self.font_size(9.0), left_margin + Mm(7.0), y_position, &font);
+                    current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+                    y_position -= line_height;
+                }
+            }
+
+            // Footnote for groups removed by name filters
+            if data.hidden_groups > 0 {
+                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
+                let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                let footnote = format!("* {} group(s) hidden by --include-groups/--exclude-groups filters", data.hidden_groups);
+                current_layer.set_fill_color(Colors::to_rgb(Colors::DARK_GRAY));
+                current_layer.use_text(&footnote, self.font_size(8.0), left_margin + Mm(5.0), y_position - line_height * 0.5, &font);
+                current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+                y_position -= line_height * 1.5;
+            }
+            y_position = y_position - line_height * 2.0;
+        }
+
+        // Raw attributes appendix (opt-in)
+        if let Some(raw_attributes) = &data.user().raw_attributes {
+            check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 40.0);
+            y_position = self.render_section_header(
+                &doc,
+                current_page,
+                current_layer_index,
+                y_position,
+                line_height,
+                left_margin,
+                right_margin,
+                "Appendix: Raw Attributes",
+                &bold_font,
+            );
+
+            let mut names: Vec<&String> = raw_attributes.keys().collect();
+            names.sort_by_key(|name| name.to_lowercase());
+
+            for name in names {
+                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 15.0);
+                let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                current_layer.use_text(name.as_str(), self.font_size(9.0), left_margin + Mm(5.0), y_position, &bold_font);
+                y_position -= line_height;
+
+                for value in &raw_attributes[name] {
+                    for line in self.wrap_monospace(value, 8.0, self.layout.content_width() - Mm(10.0)) {
+                        check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
+                        let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                        current_layer.use_text(&line, self.font_size(8.0), left_margin + Mm(10.0), y_position, &courier);
+                        y_position -= line_height * 0.8;
+                    }
+                }
+                y_position -= line_height * 0.4;
+            }
+        }
+
+        // Glossary of risk terms (opt-in), generated from the enums so it tracks new variants
+        if self.include_legend {
+            check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 40.0);
+            y_position = self.render_section_header(
+                &doc,
+                current_page,
+                current_layer_index,
+                y_position,
+                line_height,
+                left_margin,
+                right_margin,
+                "Glossary",
+                &bold_font,
+            );
+
+            let thresholds = data.risk_assessment.as_ref()
+                .map(|risk| risk.thresholds)
+                .unwrap_or_default();
+            let levels: Vec<(String, String, Option<RiskLevel>)> = thresholds.bands().into_iter().rev()
+                .map(|(level, low, high)| (
+                    format!("{:?} ({}-{})", level, low, high),
+                    level.meaning().to_string(),
+                    Some(level),
+                ))
+                .collect();
+            let factors: Vec<(String, String, Option<RiskLevel>)> = RiskFactorType::ALL.iter()
+                .map(|factor| (factor.label().to_string(), factor.description().to_string(), None))
+                .collect();
+            let categories: Vec<(String, String, Option<RiskLevel>)> = GroupScope::ALL.iter()
+                .map(|scope| scope.glossary_entry())
+                .chain(GroupType::ALL.iter().map(|group_type| group_type.glossary_entry()))
+                .map(|(term, definition)| (term.to_string(), definition.to_string(), None))
+                .collect();
+
+            let term_width = Mm(45.0);
+            let definition_width = self.layout.content_width() - Mm(5.0) - term_width;
+            for (heading, entries) in [("Risk Levels", levels), ("Risk Factors", factors), ("Group Categories", categories)] {
+                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 20.0);
+                let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                current_layer.use_text(heading, self.font_size(10.0), left_margin + Mm(5.0), y_position, &bold_font);
+                y_position -= line_height;
+
+                for (term, definition, level) in entries {
+                    let lines = wrap_to_width(&definition, BuiltinFont::Helvetica, self.font_size(8.0), definition_width);
+                    check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number,
+                        10.0 + lines.len() as f32 * 4.0);
+                    let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+                    if let Some(level) = &level {
+                        current_layer.set_fill_color(Colors::to_rgb(self.colors().risk(level)));
+                    }
+                    current_layer.use_text(&term, self.font_size(8.0), left_margin + Mm(5.0), y_position, &bold_font);
+                    current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+                    for line in lines {
+                        current_layer.use_text(&line, self.font_size(8.0), left_margin + Mm(5.0) + term_width, y_position, &font);
+                        y_position -= line_height * 0.8;
+                    }
+                    y_position -= line_height * 0.3;
+                }
+                y_position -= line_height * 0.5;
+            }
+        }
+
+        // Render footer on last page
+        self.render_footer(&doc, current_page, current_layer_index, &font, page_number, data);
+        self.render_content_hash(&doc, current_page, current_layer_index, &font);
+
+        // Save to bytes
+        save_reproducibly(self.with_document_metadata(doc, data), &data.generation_time(), &data.user().sam_account_name)
+    }
+
+    /// Generate a one-page portfolio summary of risk levels across a batch run
+    pub fn generate_batch_summary(&mut self, summary: &BatchRiskSummary, account_states: &AccountStateSummary) -> Result<Vec<u8>> {
+        let (doc, page1, layer1) = PdfDocument::new(
+            "Active Directory Batch Risk Summary",
+            Mm(210.0),
+            Mm(297.0),
+            "Layer 1"
         );
 
-        let status_items = vec![
-            ("Account Enabled", data.user().account_enabled, false),
-            ("Account Locked", data.user().account_locked, true),
-            ("Password Expired", data.user().password_expired, true),
-            ("Password Never Expires", data.user().password_never_expires, true),
-        ];
+        let font = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+        let bold_font = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
 
-        for (label, value, is_warning) in status_items {
-            check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
-            let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+        let line_height = self.line_height();
+        let left_margin = self.layout.left_margin;
+        let right_margin = self.layout.right_margin;
+        let current_layer = doc.get_page(page1).get_layer(layer1);
 
-            current_layer.use_text(label, 10.0, left_margin + Mm(5.0), y_position, &bold_font);
+        // Title block
+        current_layer.set_fill_color(Colors::to_rgb(self.colors().critical));
+        current_layer.use_text("CONFIDENTIAL", self.font_size(12.0), left_margin, Mm(275.0), &bold_font);
+        current_layer.set_fill_color(Colors::to_rgb(Colors::DARK_BLUE));
+        Self::use_text_centered(&current_layer, "ACTIVE DIRECTORY BATCH RISK SUMMARY", self.font_size(16.0),
+            (left_margin, right_margin), Mm(265.0), &bold_font, BuiltinFont::HelveticaBold);
+        current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
 
-            let status_text = if value { "Yes" } else { "No" };
-            let status_color = if value == is_warning {
-                Colors::to_rgb(Colors::WARNING_RED)
-            } else {
-                Colors::to_rgb(Colors::SUCCESS_GREEN)
-            };
+        let generated = format!("Generated: {}", self.format_timestamp(&summary.generation_time));
+        current_layer.use_text(&generated, self.font_size(9.0), left_margin, Mm(255.0), &font);
+        let assessed = format!("Users assessed: {}", summary.entries.len());
+        current_layer.use_text(&assessed, self.font_size(9.0), left_margin, Mm(250.0), &font);
+
+        // Risk distribution bar chart
+        let mut y_position = self.render_section_header(
+            &doc, page1, layer1, Mm(238.0), line_height, left_margin, right_margin,
+            "Risk Distribution", &bold_font,
+        );
+
+        let distribution = summary.distribution();
+        let max_count = distribution.iter().map(|(_, count)| *count).max().unwrap_or(0).max(1);
+        let bar_start = left_margin + Mm(50.0);
+        let max_bar_width = Mm(100.0);
 
-            current_layer.set_fill_color(status_color);
-            current_layer.use_text(status_text, 10.0, left_margin + Mm(60.0), y_position, &bold_font);
+        for (level, count) in &distribution {
+            let level_color = self.colors().risk(level);
+            current_layer.set_fill_color(Colors::to_rgb(level_color));
+            current_layer.use_text(format!("{:?}", level), self.font_size(10.0), left_margin + Mm(5.0), y_position, &bold_font);
             current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+            current_layer.use_text(count.to_string(), self.font_size(10.0), left_margin + Mm(35.0), y_position, &font);
 
-            y_position = y_position - line_height;
+            if *count > 0 {
+                let bar_width = max_bar_width * (*count as f32 / max_count as f32);
+                let bar_y = y_position + Mm(1.2);
+                self.draw_risk_bar(&current_layer, bar_start..bar_start + bar_width, bar_y, level, 10.0);
+            }
+            y_position -= line_height * 1.5;
         }
+        y_position -= line_height;
 
-        // Timestamps
-        check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 15.0);
-        let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
-
-        let created = format!("Created: {}", data.user().created.map(|d| d.format("%d-%m-%Y %H:%M:%S").to_string())
-            .unwrap_or_else(|| "N/A".to_string()));
-        current_layer.use_text(&created, 9.0, left_margin + Mm(5.0), y_position, &font);
-        y_position = y_position - line_height;
+        // Top riskiest users
+        y_position = self.render_section_header(
+            &doc, page1, layer1, y_position, line_height, left_margin, right_margin,
+            "Top 10 Riskiest Users", &bold_font,
+        );
 
-        let last_logon = format!("Last Logon: {}", data.user().last_logon.map(|d| d.format("%d-%m-%Y %H:%M:%S").to_string())
-            .unwrap_or_else(|| "Never".to_string()));
-        current_layer.use_text(&last_logon, 9.0, left_margin + Mm(5.0), y_position, &font);
-        y_position = y_position - line_height * 3.0;
+        for (rank, entry) in summary.top_riskiest(10).into_iter().enumerate() {
+            let description = format!("{}. {} - {:?}", rank + 1, entry.sam_account_name, entry.risk_level);
+            y_position = self.render_risk_item(&doc, page1, layer1, y_position, left_margin, &description, entry.score, &font);
+        }
 
-        // Risk Assessment section
-        if let Some(ref risk) = data.risk_assessment {
-            check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 70.0);
+        // Directory-hygiene snapshot of the accounts processed
+        if !account_states.is_empty() {
+            y_position -= line_height;
             y_position = self.render_section_header(
-                &doc,
-                current_page,
-                current_layer_index,
-                y_position,
-                line_height,
-                left_margin,
-                right_margin,
-                "Risk Assessment",
-                &bold_font,
+                &doc, page1, layer1, y_position, line_height, left_margin, right_margin,
+                "Account States", &bold_font,
             );
+            for (label, count) in account_states.rows() {
+                current_layer.use_text(label, self.font_size(10.0), left_margin + Mm(5.0), y_position, &bold_font);
+                current_layer.use_text(count.to_string(), self.font_size(10.0), left_margin + Mm(60.0), y_position, &font);
+                y_position -= line_height;
+            }
+        }
 
-            // Risk score box
-            y_position = self.render_risk_score_box(
-                &doc,
-                current_page,
-                current_layer_index,
-                y_position,
-                left_margin,
-                risk.overall_score,
-                &risk.risk_level,
-                &bold_font,
-                &font,
-            );
-            y_position = y_position - line_height * 2.0;
+        current_layer.set_fill_color(Colors::to_rgb(Colors::DARK_GRAY));
+        Self::use_text_centered(&current_layer, "This report contains sensitive security information.", self.font_size(8.0),
+            (left_margin, right_margin), Mm(20.0), &font, BuiltinFont::Helvetica);
+        current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
 
-            // Top risk factors
-            if !risk.contributing_factors.is_empty() {
-                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 30.0);
-                let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
-                current_layer.use_text("Top Risk Factors:", 12.0, left_margin + Mm(5.0), y_position, &bold_font);
-                y_position = y_position - line_height * 1.5;
+        save_reproducibly(doc, &summary.generation_time, "batch summary")
+    }
 
-                for factor in risk.contributing_factors.iter().take(5) {
-                    check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
-                    y_position = self.render_risk_item(
-                        &doc,
-                        current_page,
-                        current_layer_index,
-                        y_position,
-                        left_margin,
-                        &factor.description,
-                        factor.risk_contribution,
-                        &font,
-                    );
-                }
-                y_position = y_position - line_height;
-            }
+    /// Single page with no pagination: lines that would run into the footer are dropped and
+    /// text too wide for the page is shortened, and either is noted at the bottom of the page
+    fn generate_compact_report(&mut self, data: &EnhancedReportData) -> Result<Vec<u8>> {
+        let (doc, page1, layer1) = PdfDocument::new(
+            "Active Directory User Summary",
+            Mm(210.0),
+            Mm(297.0),
+            "Layer 1"
+        );
+
+        let font = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+        let bold_font = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
+
+        self.render_watermark(&doc, page1, layer1, &bold_font);
+
+        let left_margin = self.layout.left_margin;
+        let right_margin = self.layout.right_margin;
+        let current_layer = doc.get_page(page1).get_layer(layer1);
+
+        // Title block
+        current_layer.set_fill_color(Colors::to_rgb(self.colors().critical));
+        current_layer.use_text("CONFIDENTIAL", self.font_size(10.0), left_margin, Mm(280.0), &bold_font);
+        current_layer.set_fill_color(Colors::to_rgb(Colors::DARK_BLUE));
+        let title = self.title_for(data);
+        Self::use_text_centered(&current_layer, title, self.font_size(13.0),
+            (left_margin, right_margin), Mm(272.0), &bold_font, BuiltinFont::HelveticaBold);
+        current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+
+        let mut lines = Vec::new();
+        if let Some(context) = &self.report_context {
+            lines.push(CompactLine::new(context.as_str(), 8.0).colored(Colors::DARK_GRAY));
         }
-        y_position = y_position - line_height * 2.0;
+        lines.extend(self.compact_lines(data));
+
+        let line_height = self.line_height() * 0.8;
+        let floor = self.layout.bottom_margin;
+        let mut y_position = Mm(262.0);
+        let mut dropped = 0;
+        let mut shortened = false;
+
+        for line in &lines {
+            let size = self.font_size(line.size);
+            let next_y = y_position - line_height * (line.size / 8.0 + line.space_before);
+            if next_y < floor {
+                dropped += 1;
+                continue;
+            }
+            y_position = next_y;
+
+            let metrics = if line.bold { BuiltinFont::HelveticaBold } else { BuiltinFont::Helvetica };
+            let width = self.layout.content_width() - line.indent;
+            let text = match fit_to_width(&line.text, metrics, size, width) {
+                Some(fitted) => {
+                    shortened = true;
+                    fitted
+                }
+                None => line.text.clone(),
+            };
 
-        // Group Memberships section
-        check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 50.0);
-        y_position = self.render_section_header(
-            &doc,
-            current_page,
-            current_layer_index,
-            y_position,
-            line_height,
-            left_margin,
-            right_margin,
-            "Group Memberships",
-            &bold_font,
-        );
+            current_layer.set_fill_color(Colors::to_rgb(line.color));
+            current_layer.use_text(&text, size, left_margin + line.indent, y_position,
+                if line.bold { &bold_font } else { &font });
+        }
+        current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
 
-        if let Some(primary) = &data.user().primary_group {
-            check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
-            let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
-            let primary_text = format!("Primary Group: {}", primary.name);
-            current_layer.use_text(&primary_text, 10.0, left_margin + Mm(5.0), y_position, &bold_font);
-            y_position = y_position - line_height * 1.5;
+        if dropped > 0 || shortened {
+            let note = if dropped > 0 {
+                format!("Truncated: {} line(s) did not fit on one page - run without --compact for the full report", dropped)
+            } else {
+                "Some entries were shortened to fit - run without --compact for the full report".to_string()
+            };
+            current_layer.set_fill_color(Colors::to_rgb(self.colors().high));
+            current_layer.use_text(&note, self.font_size(7.0), left_margin, Mm(18.0), &font);
+            current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
         }
 
-        let total_groups = data.user().groups.len();
-        let total_nested: usize = data.user().groups.iter()
-            .map(|g| g.nested_groups.len())
-            .sum();
+        self.render_footer(&doc, page1, layer1, &font, 1, data);
+        self.render_content_hash(&doc, page1, layer1, &font);
+        self.total_pages = 1;
 
-        check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
-        let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
-        let groups_summary = format!("Direct Groups: {} | Nested Groups: {}", total_groups, total_nested);
-        current_layer.use_text(&groups_summary, 10.0, left_margin + Mm(5.0), y_position, &font);
-        y_position = y_position - line_height * 1.5;
+        save_reproducibly(self.with_document_metadata(doc, data), &data.generation_time(), &data.user().sam_account_name)
+    }
 
-        if !data.user().groups.is_empty() {
-            for group in &data.user().groups {
-                check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
-                let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+    /// Content of the compact layout: identity, then risk score, top groups and top findings
+    fn compact_lines(&self, data: &EnhancedReportData) -> Vec<CompactLine> {
+        let user = data.user();
+        let or_dash = |value: Option<&String>| value.map(String::as_str).unwrap_or("-").to_string();
+        let timestamp_or_never = |value: Option<&DateTime<Utc>>| {
+            value.map(|t| self.format_timestamp(t)).unwrap_or_else(|| "Never".to_string())
+        };
 
-                let group_info = format!("• {} ({:?}, {:?})", group.name, group.group_type, group.scope);
-                current_layer.use_text(&group_info, 9.0, left_margin + Mm(7.0), y_position, &font);
-                y_position = y_position - line_height;
+        let mut status = vec![if user.account_enabled { "Enabled" } else { "Disabled" }];
+        if user.account_locked {
+            status.push("Locked");
+        }
+        if user.is_password_expired(data.generation_time()) {
+            status.push("Password expired");
+        }
+        if user.password_never_expires {
+            status.push("Password never expires");
+        }
 
-                // Add nested groups
-                for nested in &group.nested_groups {
-                    check_new_page(&mut doc, &mut y_position, &mut current_page, &mut current_layer_index, &mut page_number, 10.0);
-                    let current_layer = doc.get_page(current_page).get_layer(current_layer_index);
+        let mut lines = vec![CompactLine::heading("Identity")];
+        match &data.computer {
+            Some(computer) => lines.extend([
+                CompactLine::item(format!("Host: {}", or_dash(computer.dns_host_name.as_ref()))),
+                CompactLine::item(format!("Account: {}    OS: {}", user.sam_account_name, or_dash(computer.operating_system_summary().as_ref()))),
+                CompactLine::item(format!("Delegation: {}", computer.delegation.summary())),
+            ]),
+            None => lines.extend([
+                CompactLine::item(format!("Name: {}", user.display_name.as_ref().unwrap_or(&user.sam_account_name))),
+                CompactLine::item(format!("Account: {}    UPN: {}", user.sam_account_name, or_dash(user.user_principal_name.as_ref()))),
+                CompactLine::item(format!("Email: {}", or_dash(user.email.as_ref()))),
+                CompactLine::item(format!("Department: {}    Title: {}", or_dash(user.department.as_ref()), or_dash(user.title.as_ref()))),
+            ]),
+        }
+        lines.extend([
+            CompactLine::item(format!("Status: {}", status.join(", "))),
+            CompactLine::item(format!("Last Logon: {}", timestamp_or_never(user.last_logon.as_ref()))),
+            CompactLine::item(format!("Password Last Set: {}", timestamp_or_never(user.password_last_set.as_ref()))),
+        ]);
+        if let Some(rid) = user.anomalous_primary_group_id() {
+            let name = user.primary_group.as_ref().map(|g| g.name.as_str()).unwrap_or("unresolved");
+            lines.push(CompactLine::item(format!("Primary Group: {} (RID {}) - non-default, hidden from memberOf", name, rid))
+                .colored(self.colors().critical));
+        }
 
-                    let nested_info = format!("  └─ {} ({:?}, {:?})", nested.name, nested.group_type, nested.scope);
-                    current_layer.use_text(&nested_info, 8.0, left_margin + Mm(12.0), y_position, &font);
-                    y_position = y_position - line_height * 0.9;
-                }
-            }
+        let risk = data.risk_assessment.as_ref().filter(|_| self.includes(ReportSection::Risk));
+        if let Some(risk) = risk {
+            lines.push(CompactLine {
+                size: 12.0,
+                ..CompactLine::heading(format!("Risk Score: {}/100 ({:?})", risk.overall_score, risk.risk_level))
+            }.colored(self.colors().risk(&risk.risk_level)));
+        } else if let Some(reason) = data.risk_unavailable.as_ref().filter(|_| self.includes(ReportSection::Risk)) {
+            lines.push(CompactLine::item(format!("Risk analysis unavailable: {}", reason)).colored(self.colors().high));
         }
-        y_position = y_position - line_height * 2.0;
 
-        // Render footer on last page
-        self.render_footer(&doc, current_page, current_layer_index, &font, page_number, data);
+        if self.includes(ReportSection::Groups) {
+            let all_groups = user.all_groups();
+            lines.push(CompactLine::heading(format!(
+                "Top Groups ({} direct, {} total)", user.direct_group_count(), all_groups.len()
+            )));
+            match risk.filter(|r| !r.group_contributions.is_empty()) {
+                Some(risk) => lines.extend(risk.group_contributions.iter()
+                    .take(Self::COMPACT_TOP_GROUPS)
+                    .map(|g| CompactLine::item(format!("• {} (+{}) - {}", g.group_name, g.risk_contribution, g.reason)))),
+                None => lines.extend(all_groups.iter()
+                    .take(Self::COMPACT_TOP_GROUPS)
+                    .map(|g| CompactLine::item(format!("• {} ({})", g.name, g.details())))),
+            }
+            if all_groups.is_empty() {
+                lines.push(CompactLine::item("No group memberships").colored(Colors::DARK_GRAY));
+            } else if all_groups.len() > Self::COMPACT_TOP_GROUPS {
+                lines.push(CompactLine::item(format!("(+{} more)", all_groups.len() - Self::COMPACT_TOP_GROUPS))
+                    .colored(Colors::DARK_GRAY));
+            }
+            if !user.resolution_warnings.is_empty() {
+                lines.push(CompactLine::item(format!(
+                    "{} group(s) could not be resolved - memberships may be under-reported", user.resolution_warnings.len()
+                )).colored(self.colors().high));
+            }
+        }
 
-        // Save to bytes
-        let mut buffer = Vec::new();
-        doc.save(&mut BufWriter::new(&mut buffer))?;
+        if let Some(risk) = risk {
+            lines.push(CompactLine::heading("Top Findings"));
+            let mut findings: Vec<_> = risk.contributing_factors.iter().collect();
+            findings.sort_by_key(|f| std::cmp::Reverse(f.risk_contribution));
+            for finding in findings.iter().take(Self::COMPACT_TOP_FINDINGS) {
+                lines.push(CompactLine::item(format!("• [{:?}] {}", finding.severity, finding.description))
+                    .colored(self.colors().risk(&finding.severity)));
+            }
+            if findings.is_empty() {
+                lines.push(CompactLine::item("No risk factors identified").colored(Colors::DARK_GRAY));
+            } else if findings.len() > Self::COMPACT_TOP_FINDINGS {
+                lines.push(CompactLine::item(format!("(+{} more)", findings.len() - Self::COMPACT_TOP_FINDINGS))
+                    .colored(Colors::DARK_GRAY));
+            }
+        }
 
-        Ok(buffer)
+        lines
     }
 
     fn render_cover_page(
@@ -350,53 +1506,178 @@ impl PdfGenerator {
         data: &EnhancedReportData,
         bold_font: &IndirectFontRef,
         font: &IndirectFontRef,
-    ) {
+    ) -> Mm {
         let current_layer = doc.get_page(page).get_layer(layer);
 
         // Classification badge - top margin ~20mm
-        current_layer.set_fill_color(Colors::to_rgb(Colors::CRITICAL_RED));
-        current_layer.use_text("CONFIDENTIAL", 12.0, Mm(20.0), Mm(275.0), bold_font);
+        current_layer.set_fill_color(Colors::to_rgb(self.colors().critical));
+        current_layer.use_text("CONFIDENTIAL", self.font_size(12.0), self.layout.left_margin, Mm(275.0), bold_font);
         current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
 
-        // Title section
+        // Title section - centered between the page margins
         current_layer.set_fill_color(Colors::to_rgb(Colors::DARK_BLUE));
-        current_layer.use_text("ACTIVE DIRECTORY USER ACCESS REPORT", 16.0, Mm(20.0), Mm(265.0), bold_font);
+        let title = self.title_for(data);
+        Self::use_text_centered(&current_layer, title, self.font_size(16.0),
+            (self.layout.left_margin, self.layout.right_margin), Mm(265.0), bold_font, BuiltinFont::HelveticaBold);
         current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
 
-        // Content section - compact layout starting below title
-        let content_y = Mm(250.0);  // Start content 15mm below title
+        // Engagement context (e.g., review name and ticket) directly under the title
+        if let Some(context) = &self.report_context {
+            current_layer.set_fill_color(Colors::to_rgb(Colors::DARK_GRAY));
+            Self::use_text_centered(&current_layer, context, self.font_size(10.0),
+                (self.layout.left_margin, self.layout.right_margin), Mm(258.0), font, BuiltinFont::Helvetica);
+            current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+        }
 
-        let user_display = data.user().display_name.as_ref()
+        // Content section - compact layout starting below title (and context line)
+        let content_y = if self.report_context.is_some() { Mm(246.0) } else { Mm(250.0) };
+
+        let user_display = data.computer.as_ref()
+            .and_then(|c| c.dns_host_name.as_ref())
+            .or(data.user().display_name.as_ref())
             .unwrap_or(&data.user().sam_account_name);
 
         // Subject user section
-        current_layer.use_text("Subject User:", 10.0, Mm(20.0), content_y, bold_font);
+        let subject_label = if data.computer.is_some() { "Subject Computer:" } else { "Subject User:" };
+        current_layer.use_text(subject_label, self.font_size(10.0), self.layout.left_margin, content_y, bold_font);
         current_layer.set_fill_color(Colors::to_rgb(Colors::DARK_BLUE));
-        current_layer.use_text(user_display, 13.0, Mm(20.0), content_y - Mm(6.0), bold_font);
+        current_layer.use_text(user_display, self.font_size(13.0), self.layout.left_margin, content_y - self.scaled(6.0), bold_font);
         current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
 
-        current_layer.use_text("Account:", 9.0, Mm(20.0), content_y - Mm(12.0), font);
-        current_layer.use_text(&data.user().sam_account_name, 9.0, Mm(20.0), content_y - Mm(16.0), font);
+        current_layer.use_text("Account:", self.font_size(9.0), self.layout.left_margin, content_y - self.scaled(12.0), font);
+        current_layer.use_text(&data.user().sam_account_name, self.font_size(9.0), self.layout.left_margin, content_y - self.scaled(16.0), font);
 
         // Report metadata section - positioned next to user info
         let meta_y = content_y;
 
-        current_layer.use_text("Report Details", 10.0, Mm(100.0), meta_y, bold_font);
+        current_layer.use_text("Report Details", self.font_size(10.0), Mm(100.0), meta_y, bold_font);
 
-        let generated = format!("Generated: {}", data.generation_time().format("%d-%m-%Y %H:%M:%S UTC"));
-        current_layer.use_text(&generated, 8.0, Mm(100.0), meta_y - Mm(5.0), font);
+        let generated = format!("Generated: {}", self.format_timestamp(&data.generation_time()));
+        current_layer.use_text(&generated, self.font_size(8.0), Mm(100.0), meta_y - self.scaled(5.0), font);
 
         let dc = format!("Domain Controller: {}", data.domain_controller());
-        current_layer.use_text(&dc, 8.0, Mm(100.0), meta_y - Mm(9.0), font);
+        current_layer.use_text(&dc, self.font_size(8.0), Mm(100.0), meta_y - self.scaled(9.0), font);
 
         let domain = format!("Domain: {}", data.domain_name());
-        current_layer.use_text(&domain, 8.0, Mm(100.0), meta_y - Mm(13.0), font);
+        current_layer.use_text(&domain, self.font_size(8.0), Mm(100.0), meta_y - self.scaled(13.0), font);
+
+        // Headline numbers for readers who stop at the cover
+        let mut cover_bottom = content_y - self.scaled(16.0);
+        if let Some(risk) = &data.risk_assessment {
+            cover_bottom = self.render_cover_badges(doc, page, layer, cover_bottom - self.scaled(7.0), risk, bold_font);
+        }
 
-        // Footer notice - centered (approximate text width compensation)
+        // Footer notice - centered between the page margins
         current_layer.set_fill_color(Colors::to_rgb(Colors::DARK_GRAY));
-        current_layer.use_text("This report contains sensitive security information.", 8.0, Mm(38.0), Mm(20.0), font);
-        current_layer.use_text("Handle according to your organization's data classification policy.", 8.0, Mm(25.0), Mm(15.0), font);
+        Self::use_text_centered(&current_layer, "This report contains sensitive security information.", self.font_size(8.0),
+            (self.layout.left_margin, self.layout.right_margin), Mm(20.0), font, BuiltinFont::Helvetica);
+        Self::use_text_centered(&current_layer, "Handle according to your organization's data classification policy.", self.font_size(8.0),
+            (self.layout.left_margin, self.layout.right_margin), Mm(15.0), font, BuiltinFont::Helvetica);
+        current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+
+        cover_bottom
+    }
+
+    /// Row of filled badges (findings, Critical and High factors, overall level) with the
+    /// text baseline at `y`; returns the bottom edge of the row
+    fn render_cover_badges(
+        &self,
+        doc: &PdfDocumentReference,
+        page: PdfPageIndex,
+        layer: PdfLayerIndex,
+        y: Mm,
+        risk: &RiskAssessment,
+        bold_font: &IndirectFontRef,
+    ) -> Mm {
+        let count = |level: RiskLevel| risk.contributing_factors.iter().filter(|f| f.severity == level).count();
+        let (critical, high) = (count(RiskLevel::Critical), count(RiskLevel::High));
+        let badges = [
+            (format!("{} FINDINGS", risk.contributing_factors.len()), Colors::DARK_BLUE),
+            (format!("{} CRITICAL", critical), if critical > 0 { self.colors().critical } else { Colors::DARK_GRAY }),
+            (format!("{} HIGH", high), if high > 0 { self.colors().high } else { Colors::DARK_GRAY }),
+            (format!("{:?} RISK", risk.risk_level).to_uppercase(), self.colors().risk(&risk.risk_level)),
+        ];
+
+        // Each badge is a thick line behind white text, like the risk bars
+        let size = self.font_size(8.0);
+        let height = self.scaled(5.0);
+        let padding = Mm(2.5);
+        let center_y = y + self.scaled(1.0);
+        let current_layer = doc.get_page(page).get_layer(layer);
+        let mut x = self.layout.left_margin;
+        for (text, color) in &badges {
+            let width = text_width_mm(text, BuiltinFont::HelveticaBold, size) + padding * 2.0;
+            if x + width > self.layout.right_margin {
+                break;
+            }
+            self.draw_line(doc, page, layer, x, center_y, x + width, center_y, *color, Pt::from(height).0);
+            current_layer.set_fill_color(Color::Rgb(Rgb::new(1.0, 1.0, 1.0, None)));
+            current_layer.use_text(text, size, x + padding, y, bold_font);
+            x += width + Mm(3.0);
+        }
         current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+
+        center_y - height / 2.0
+    }
+
+    /// Large light-gray text rotated 45 degrees through the page center; printpdf 0.7 has no
+    /// fill alpha, so the see-through look comes from the pale fill and drawing it first
+    fn render_watermark(&self, doc: &PdfDocumentReference, page: PdfPageIndex, layer: PdfLayerIndex, font: &IndirectFontRef) {
+        let Some(text) = self.watermark.as_deref().filter(|t| !t.trim().is_empty()) else {
+            return;
+        };
+
+        // Shrink long text so it fits along the page diagonal
+        let max_width = Self::WATERMARK_MAX_WIDTH.0;
+        let width_at_max = text_width_mm(text, BuiltinFont::HelveticaBold, Self::WATERMARK_FONT_SIZE).0;
+        let size = if width_at_max > max_width {
+            Self::WATERMARK_FONT_SIZE * max_width / width_at_max
+        } else {
+            Self::WATERMARK_FONT_SIZE
+        };
+        let width = text_width_mm(text, BuiltinFont::HelveticaBold, size).0;
+
+        // Start so the text's midpoint lands on the page center
+        let offset = width / 2.0 * std::f32::consts::FRAC_1_SQRT_2;
+        let x = Mm(210.0 / 2.0 - offset);
+        let y = Mm(297.0 / 2.0 - offset);
+
+        let current_layer = doc.get_page(page).get_layer(layer);
+        current_layer.save_graphics_state();
+        current_layer.set_fill_color(Colors::to_rgb(Colors::WATERMARK_GRAY));
+        current_layer.begin_text_section();
+        current_layer.set_font(font, size);
+        current_layer.set_text_matrix(TextMatrix::TranslateRotate(x.into(), y.into(), 45.0));
+        current_layer.write_text(text, font);
+        current_layer.end_text_section();
+        current_layer.restore_graphics_state();
+    }
+
+    /// Split text into Courier lines that fit `width` at the scaled `size`
+    fn wrap_monospace(&self, text: &str, size: f32, width: Mm) -> Vec<String> {
+        let char_width = text_width_mm("M", BuiltinFont::Courier, self.font_size(size));
+        let chars_per_line = ((width.0 / char_width.0) as usize).max(1);
+
+        let chars: Vec<char> = text.chars().collect();
+        chars.chunks(chars_per_line)
+            .map(|chunk| chunk.iter().collect())
+            .collect()
+    }
+
+    /// Draw text horizontally centered within the (start, end) span
+    /// `metrics` must be the builtin font that `font` was created from
+    fn use_text_centered(
+        layer: &PdfLayerReference,
+        text: &str,
+        size: f32,
+        (span_start, span_end): (Mm, Mm),
+        y: Mm,
+        font: &IndirectFontRef,
+        metrics: BuiltinFont,
+    ) {
+        let width = text_width_mm(text, metrics, size);
+        let x = span_start + (span_end - span_start - width) / 2.0;
+        layer.use_text(text, size, x.max(span_start), y, font);
     }
 
     fn render_header(
@@ -425,17 +1706,106 @@ impl PdfGenerator {
         current_layer.set_fill_color(Colors::to_rgb(Colors::DARK_GRAY));
 
         let page_text = format!("Page {}", page_number);
-        current_layer.use_text(&page_text, 8.0, Mm(20.0), Mm(13.0), font);
+        current_layer.use_text(&page_text, self.font_size(8.0), self.layout.left_margin, Mm(13.0), font);
 
         let footer = format!("{} | {}", data.domain_controller(), data.domain_name());
-        current_layer.use_text(&footer, 8.0, Mm(65.0), Mm(13.0), font);
+        current_layer.use_text(&footer, self.font_size(8.0), Mm(65.0), Mm(13.0), font);
+
+        let timestamp = self.format_timestamp(&data.generation_time());
+        let timestamp_x = self.layout.right_margin - text_width_mm(&timestamp, BuiltinFont::Helvetica, self.font_size(8.0));
+        current_layer.use_text(&timestamp, self.font_size(8.0), timestamp_x, Mm(13.0), font);
+
+        // Provenance under the footer, so every page says who queried what
+        if let Some(provenance) = &data.provenance {
+            let text = format!("Generated by: {} | Search base: {}", provenance.bind_identity, provenance.search_base);
+            let text = fit_to_width(&text, BuiltinFont::Helvetica, self.font_size(7.0), self.layout.content_width())
+                .unwrap_or(text);
+            current_layer.use_text(&text, self.font_size(7.0), self.layout.left_margin, Mm(9.0), font);
+        }
 
-        let timestamp = data.generation_time().format("%d-%m-%Y %H:%M").to_string();
-        current_layer.use_text(&timestamp, 8.0, Mm(165.0), Mm(13.0), font);
+        current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+    }
 
+    /// Content hash under the provenance line of the last page
+    fn render_content_hash(&self, doc: &PdfDocumentReference, page: PdfPageIndex, layer: PdfLayerIndex, font: &IndirectFontRef) {
+        let Some(hash) = &self.content_hash else {
+            return;
+        };
+        let current_layer = doc.get_page(page).get_layer(layer);
+        current_layer.set_fill_color(Colors::to_rgb(Colors::DARK_GRAY));
+        current_layer.use_text(format!("Content SHA-256: {}", hash), self.font_size(7.0), self.layout.left_margin, Mm(5.0), font);
         current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
     }
 
+    /// Document info for indexing in document management systems: author, subject and
+    /// keywords, plus the content hash in `/Identifier` where --verify reads it
+    /// (the dates are set by `save_reproducibly`)
+    fn with_document_metadata(&self, doc: PdfDocumentReference, data: &EnhancedReportData) -> PdfDocumentReference {
+        let author = data.provenance.as_ref()
+            .map(|provenance| provenance.bind_identity.clone())
+            .unwrap_or_else(|| Self::TOOL_NAME.to_string());
+
+        let mut keywords = vec!["Active Directory".to_string(), "access report".to_string()];
+        if let Some(risk) = &data.risk_assessment {
+            keywords.push(format!("{:?} risk", risk.risk_level));
+        }
+        keywords.push(data.domain_name().to_string());
+
+        let doc = doc
+            .with_author(author)
+            .with_creator(format!("{} {}", Self::TOOL_NAME, env!("CARGO_PKG_VERSION")))
+            .with_subject(format!("AD access report for {}", data.user().sam_account_name))
+            .with_keywords(keywords);
+        match &self.content_hash {
+            Some(hash) => doc.with_identifier(integrity::identifier(hash)),
+            None => doc,
+        }
+    }
+
+    /// Direct groups to list (with their nested groups), and how many listed entries were left out
+    ///
+    /// With --max-groups-shown the groups are ranked by the riskiest membership in each
+    /// one's subtree, so a harmless-looking group that nests into Domain Admins survives.
+    fn groups_to_show<'a>(&self, groups: &'a [ADGroup]) -> (Vec<&'a ADGroup>, usize) {
+        let Some(limit) = self.max_groups_shown else {
+            return (groups.iter().collect(), 0);
+        };
+
+        let calculator = RiskCalculator::new();
+        let subtree_risk = |group: &ADGroup| {
+            std::iter::once(group)
+                .chain(group.descendants())
+                .map(|g| calculator.group_contribution(g).risk_contribution)
+                .max()
+                .unwrap_or(0)
+        };
+
+        let mut ranked: Vec<(&ADGroup, u8)> = groups.iter().map(|g| (g, subtree_risk(g))).collect();
+        ranked.sort_by_key(|(_, risk)| std::cmp::Reverse(*risk));
+
+        let omitted = ranked.iter()
+            .skip(limit)
+            .map(|(g, _)| 1 + g.descendants().count())
+            .sum();
+        (ranked.into_iter().take(limit).map(|(g, _)| g).collect(), omitted)
+    }
+
+    /// Executive summary metric boxes (label, value, background) for the included sections
+    fn summary_metrics(&self, data: &EnhancedReportData) -> Vec<(&'static str, String, (u8, u8, u8))> {
+        let mut metrics = Vec::new();
+
+        if self.includes(ReportSection::Groups) {
+            metrics.push(("Direct Groups", data.user().direct_group_count().to_string(), Colors::LIGHT_GRAY));
+            metrics.push(("Nested Groups", data.user().nested_group_count().to_string(), Colors::LIGHT_GRAY));
+        }
+
+        if let Some(risk) = data.risk_assessment.as_ref().filter(|_| self.includes(ReportSection::Risk)) {
+            metrics.push(("Risk Score", format!("{}/100", risk.overall_score), self.colors().risk(&risk.risk_level)));
+        }
+
+        metrics
+    }
+
     fn render_executive_summary(
         &self,
         doc: &PdfDocumentReference,
@@ -444,7 +1814,7 @@ impl PdfGenerator {
         mut y_position: Mm,
         line_height: Mm,
         left_margin: Mm,
-        data: &EnhancedReportData,
+        metrics: &[(&str, String, (u8, u8, u8))],
         bold_font: &IndirectFontRef,
         font: &IndirectFontRef,
     ) -> Mm {
@@ -452,44 +1822,29 @@ impl PdfGenerator {
 
         // Section header
         current_layer.set_fill_color(Colors::to_rgb(Colors::DARK_BLUE));
-        current_layer.use_text("EXECUTIVE SUMMARY", 16.0, left_margin, y_position, bold_font);
+        current_layer.use_text("EXECUTIVE SUMMARY", self.font_size(16.0), left_margin, y_position, bold_font);
         current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
         y_position = y_position - line_height * 2.5;
 
         // Metrics boxes - narrower for portrait
         let box_width = Mm(50.0);  // Narrower boxes for portrait
-        let box_height = Mm(22.0);
+        let box_height = self.scaled(22.0);
         let spacing = Mm(5.0);    // Less spacing
 
-        // Total Groups
-        let x1 = left_margin + Mm(10.0);
-        self.draw_rectangle(doc, page, layer, x1, y_position - box_height, box_width, box_height, Colors::LIGHT_GRAY);
-        current_layer.use_text("Direct Groups", 10.0, x1 + Mm(3.0), y_position - Mm(6.0), font);
-        let group_count = data.user().groups.len().to_string();
-        current_layer.set_fill_color(Colors::to_rgb(Colors::DARK_BLUE));
-        current_layer.use_text(&group_count, 20.0, x1 + Mm(3.0), y_position - Mm(16.0), bold_font);
-        current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
-
-        // Nested Groups
-        let x2 = x1 + box_width + spacing;
-        self.draw_rectangle(doc, page, layer, x2, y_position - box_height, box_width, box_height, Colors::LIGHT_GRAY);
-        current_layer.use_text("Nested Groups", 10.0, x2 + Mm(3.0), y_position - Mm(6.0), font);
-        let nested_count: usize = data.user().groups.iter().map(|g| g.nested_groups.len()).sum();
-        let nested_str = nested_count.to_string();
-        current_layer.set_fill_color(Colors::to_rgb(Colors::DARK_BLUE));
-        current_layer.use_text(&nested_str, 20.0, x2 + Mm(3.0), y_position - Mm(16.0), bold_font);
-        current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
-
-        // Risk Score
-        if let Some(ref risk) = data.risk_assessment {
-            let x3 = x2 + box_width + spacing;
-            let risk_color = Colors::risk_color(&risk.risk_level);
-            self.draw_rectangle(doc, page, layer, x3, y_position - box_height, box_width, box_height, risk_color);
+        for (index, (label, value, background)) in metrics.iter().enumerate() {
+            let x = left_margin + Mm(10.0) + (box_width + spacing) * index as f32;
+            self.draw_rectangle(doc, page, layer, x, y_position - box_height, box_width, box_height, *background);
 
-            current_layer.set_fill_color(Color::Rgb(Rgb::new(1.0, 1.0, 1.0, None)));
-            current_layer.use_text("Risk Score", 10.0, x3 + Mm(3.0), y_position - Mm(6.0), bold_font);
-            let risk_str = format!("{}/100", risk.overall_score);
-            current_layer.use_text(&risk_str, 18.0, x3 + Mm(3.0), y_position - Mm(16.0), bold_font);
+            // Light boxes use dark text, colored (risk) boxes use white text
+            if *background == Colors::LIGHT_GRAY {
+                current_layer.use_text(*label, self.font_size(10.0), x + Mm(3.0), y_position - self.scaled(6.0), font);
+                current_layer.set_fill_color(Colors::to_rgb(Colors::DARK_BLUE));
+                current_layer.use_text(value, self.font_size(20.0), x + Mm(3.0), y_position - self.scaled(16.0), bold_font);
+            } else {
+                current_layer.set_fill_color(Color::Rgb(Rgb::new(1.0, 1.0, 1.0, None)));
+                current_layer.use_text(*label, self.font_size(10.0), x + Mm(3.0), y_position - self.scaled(6.0), bold_font);
+                current_layer.use_text(value, self.font_size(18.0), x + Mm(3.0), y_position - self.scaled(16.0), bold_font);
+            }
             current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
         }
 
@@ -512,7 +1867,7 @@ impl PdfGenerator {
 
         // Section title (no underline)
         current_layer.set_fill_color(Colors::to_rgb(Colors::DARK_BLUE));
-        current_layer.use_text(title, 14.0, left_margin, y_position, bold_font);
+        current_layer.use_text(title, self.font_size(14.0), left_margin, y_position, bold_font);
         current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
 
         y_position - line_height * 2.0
@@ -532,22 +1887,79 @@ impl PdfGenerator {
     ) -> Mm {
         let current_layer = doc.get_page(page).get_layer(layer);
 
-        let risk_color = Colors::risk_color(risk_level);
+        let risk_color = self.colors().risk(risk_level);
 
         // Compact text-only layout (no background box)
         current_layer.set_fill_color(Colors::to_rgb(Colors::DARK_BLUE));
-        current_layer.use_text("OVERALL RISK SCORE", 12.0, left_margin + Mm(5.0), y_position, bold_font);
+        current_layer.use_text("OVERALL RISK SCORE", self.font_size(12.0), left_margin + Mm(5.0), y_position, bold_font);
         current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
 
         let score_text = format!("{}/100", score);
         current_layer.set_fill_color(Colors::to_rgb(risk_color));
-        current_layer.use_text(&score_text, 20.0, left_margin + Mm(5.0), y_position - Mm(8.0), bold_font);
+        current_layer.use_text(&score_text, self.font_size(20.0), left_margin + Mm(5.0), y_position - self.scaled(8.0), bold_font);
+
+        let level_text = format!("{:?} RISK", risk_level).to_uppercase();
+        current_layer.use_text(&level_text, self.font_size(14.0), left_margin + Mm(35.0), y_position - self.scaled(7.0), bold_font);
+        current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+
+        y_position - self.scaled(12.0)
+    }
+
+    /// Donut gauge filled clockwise from 12 o'clock in proportion to the 0-100 score
+    fn render_risk_gauge(
+        &self,
+        doc: &PdfDocumentReference,
+        page: PdfPageIndex,
+        layer: PdfLayerIndex,
+        gauge: &GaugeLayout,
+        bold_font: &IndirectFontRef,
+    ) -> Mm {
+        let GaugeLayout { top: y_position, left_margin, score, risk_level } = *gauge;
+        let current_layer = doc.get_page(page).get_layer(layer);
+        let risk_color = self.colors().risk(risk_level);
+
+        current_layer.set_fill_color(Colors::to_rgb(Colors::DARK_BLUE));
+        current_layer.use_text("OVERALL RISK SCORE", self.font_size(12.0), left_margin + Mm(5.0), y_position, bold_font);
+        current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+
+        let radius = self.scaled(12.0);
+        let ring_width = self.scaled(4.0);
+        let center_x = left_margin + Mm(5.0) + radius + ring_width;
+        let center_y = y_position - self.scaled(6.0) - radius - ring_width;
+
+        // Arc points every 3 degrees, clockwise from the top
+        let arc_points = |fraction: f32| -> Vec<(Mm, Mm)> {
+            let steps = ((120.0 * fraction).ceil() as usize).max(1);
+            (0..=steps)
+                .map(|i| {
+                    let angle = std::f32::consts::FRAC_PI_2 - std::f32::consts::TAU * fraction * i as f32 / steps as f32;
+                    (center_x + radius * angle.cos(), center_y + radius * angle.sin())
+                })
+                .collect()
+        };
+
+        // Line width is in points
+        let stroke = ring_width.0 * 72.0 / 25.4;
+        self.draw_polyline(doc, page, layer, &arc_points(1.0), Colors::MEDIUM_GRAY, stroke);
+        if score > 0 {
+            let fraction = score.min(100) as f32 / 100.0;
+            self.draw_polyline(doc, page, layer, &arc_points(fraction), risk_color, stroke);
+        }
+
+        // Score in the middle of the ring, level to the right
+        let score_text = score.to_string();
+        current_layer.set_fill_color(Colors::to_rgb(risk_color));
+        Self::use_text_centered(&current_layer, &score_text, self.font_size(16.0),
+            (center_x - radius, center_x + radius), center_y - self.scaled(2.0), bold_font, BuiltinFont::HelveticaBold);
 
         let level_text = format!("{:?} RISK", risk_level).to_uppercase();
-        current_layer.use_text(&level_text, 14.0, left_margin + Mm(35.0), y_position - Mm(7.0), bold_font);
+        let label_x = center_x + radius + ring_width + Mm(6.0);
+        current_layer.use_text(&level_text, self.font_size(14.0), label_x, center_y + self.scaled(1.0), bold_font);
+        current_layer.set_fill_color(Colors::to_rgb(Colors::DARK_GRAY));
+        current_layer.use_text("out of 100", self.font_size(9.0), label_x, center_y - self.scaled(4.0), bold_font);
         current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
 
-        y_position - Mm(12.0)
+        center_y - radius - ring_width - self.scaled(4.0)
     }
 
     fn render_risk_item(
@@ -563,29 +1975,93 @@ impl PdfGenerator {
     ) -> Mm {
         let current_layer = doc.get_page(page).get_layer(layer);
 
-        // Risk indicator square
-        let indicator_color = if risk_value >= 75 {
-            Colors::CRITICAL_RED
+        let bar_level = if risk_value >= 75 {
+            RiskLevel::Critical
         } else if risk_value >= 50 {
-            Colors::HIGH_ORANGE
+            RiskLevel::High
         } else if risk_value >= 25 {
-            Colors::MEDIUM_YELLOW
+            RiskLevel::Medium
         } else {
-            Colors::LOW_GREEN
+            RiskLevel::Low
         };
 
-        self.draw_rectangle(doc, page, layer, left_margin + Mm(7.0), y_position - Mm(1.0), Mm(3.0), Mm(3.0), indicator_color);
-
         // Description
-        current_layer.use_text(description, 9.0, left_margin + Mm(12.0), y_position, font);
+        current_layer.use_text(description, self.font_size(9.0), left_margin + Mm(12.0), y_position, font);
+
+        // Risk value, named as well when color alone must not carry the level
+        let risk_text = match self.palette {
+            Palette::Standard => format!("(Risk: {}/100)", risk_value),
+            Palette::Colorblind => format!("(Risk: {}/100, {:?})", risk_value, bar_level),
+        };
+        let label_y = y_position - self.scaled(4.0);
+        current_layer.set_fill_color(Colors::to_rgb(Colors::DARK_GRAY));
+        current_layer.use_text(&risk_text, self.font_size(8.0), left_margin + Mm(12.0), label_y, font);
+        current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+
+        // Bar proportional to the contribution, after the label and clipped at the right margin
+        let bar_start = left_margin + Mm(12.0) + text_width_mm(&risk_text, BuiltinFont::Helvetica, self.font_size(8.0)) + Mm(3.0);
+        let track_width = Mm(Self::RISK_BAR_MAX_WIDTH.0.min((self.layout.right_margin - bar_start).0));
+        if track_width.0 > 0.0 {
+            let bar_y = label_y + self.scaled(1.0);
+            let thickness = Pt::from(self.scaled(2.5)).0;
+            let bar_width = track_width * (risk_value.min(100) as f32 / 100.0);
+
+            self.draw_line(doc, page, layer, bar_start, bar_y, bar_start + track_width, bar_y, Colors::MEDIUM_GRAY, thickness);
+            if risk_value > 0 {
+                self.draw_risk_bar(&current_layer, bar_start..bar_start + bar_width, bar_y, &bar_level, thickness);
+            }
+        }
+
+        y_position - self.scaled(8.0)
+    }
+
+    /// "Permission Redundancy: 62%" with a bar colored against the review/high thresholds,
+    /// and the overlapped/total permission counts underneath
+    fn render_redundancy_bar(
+        &self,
+        doc: &PdfDocumentReference,
+        page: PdfPageIndex,
+        layer: PdfLayerIndex,
+        y_position: Mm,
+        redundancy: &PermissionRedundancy,
+        font: &IndirectFontRef,
+    ) -> Mm {
+        let current_layer = doc.get_page(page).get_layer(layer);
+        let left_margin = self.layout.left_margin;
+        let percent = redundancy.percent.clamp(0.0, 100.0);
+
+        let bar_level = if percent > PermissionRedundancy::HIGH_PERCENT {
+            RiskLevel::Critical
+        } else if percent > PermissionRedundancy::REVIEW_PERCENT {
+            RiskLevel::High
+        } else if percent > 0.0 {
+            RiskLevel::Medium
+        } else {
+            RiskLevel::Low
+        };
+
+        let label = format!("Permission Redundancy: {:.0}%", percent);
+        current_layer.use_text(&label, self.font_size(10.0), left_margin + Mm(5.0), y_position, font);
+
+        let bar_start = left_margin + Mm(5.0) + text_width_mm(&label, BuiltinFont::Helvetica, self.font_size(10.0)) + Mm(4.0);
+        let track_width = Mm(Self::RISK_BAR_MAX_WIDTH.0.min((self.layout.right_margin - bar_start).0));
+        if track_width.0 > 0.0 {
+            let bar_y = y_position + self.scaled(1.2);
+            let thickness = Pt::from(self.scaled(3.0)).0;
+            self.draw_line(doc, page, layer, bar_start, bar_y, bar_start + track_width, bar_y, Colors::MEDIUM_GRAY, thickness);
+            if percent > 0.0 {
+                self.draw_risk_bar(&current_layer, bar_start..bar_start + track_width * (percent / 100.0), bar_y, &bar_level, thickness);
+            }
+        }
 
-        // Risk value
-        let risk_text = format!("(Risk: {}/100)", risk_value);
+        let counts = format!("{} of {} permissions granted by more than one group",
+            redundancy.overlapped_permissions, redundancy.total_permissions);
+        let counts_y = y_position - self.scaled(4.5);
         current_layer.set_fill_color(Colors::to_rgb(Colors::DARK_GRAY));
-        current_layer.use_text(&risk_text, 8.0, left_margin + Mm(12.0), y_position - Mm(4.0), font);
+        current_layer.use_text(&counts, self.font_size(8.0), left_margin + Mm(5.0), counts_y, font);
         current_layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
 
-        y_position - Mm(8.0)
+        counts_y - self.scaled(4.0)
     }
 
     fn draw_rectangle(
@@ -603,6 +2079,40 @@ impl PdfGenerator {
         // Complex shape drawing requires deeper printpdf API integration
     }
 
+    /// Stroke an open path through `points`
+    fn draw_polyline(
+        &self,
+        doc: &PdfDocumentReference,
+        page: PdfPageIndex,
+        layer: PdfLayerIndex,
+        points: &[(Mm, Mm)],
+        color: (u8, u8, u8),
+        width: f32,
+    ) {
+        let current_layer = doc.get_page(page).get_layer(layer);
+
+        let line = Line {
+            points: points.iter().map(|(x, y)| (Point::new(*x, *y), false)).collect(),
+            is_closed: false,
+        };
+
+        current_layer.set_outline_color(Colors::to_rgb(color));
+        current_layer.set_outline_thickness(width);
+        current_layer.add_line(line);
+    }
+
+    /// Horizontal bar in the level's color and, for the colorblind palette, its dash pattern
+    fn draw_risk_bar(&self, layer: &PdfLayerReference, x: std::ops::Range<Mm>, y: Mm, level: &RiskLevel, width: f32) {
+        layer.set_line_dash_pattern(self.bar_pattern(level));
+        layer.set_outline_color(Colors::to_rgb(self.colors().risk(level)));
+        layer.set_outline_thickness(width);
+        layer.add_line(Line {
+            points: vec![(Point::new(x.start, y), false), (Point::new(x.end, y), false)],
+            is_closed: false,
+        });
+        layer.set_line_dash_pattern(LineDashPattern::default());
+    }
+
     fn draw_line(
         &self,
         doc: &PdfDocumentReference,
@@ -1,4 +1,5 @@
-use std::collections::{HashMap, HashSet};
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap};
 use serde::{Deserialize, Serialize};
 use crate::models::{ADUser, ADGroup, UserRight, RightSource};
 
@@ -27,6 +28,18 @@ pub enum RiskLevel {
     Low,
 }
 
+impl RiskLevel {
+    /// What a finding or overall score at this level asks of the reviewer
+    pub fn meaning(&self) -> &'static str {
+        match self {
+            RiskLevel::Critical => "Control over the domain or forest is within reach; remediate immediately",
+            RiskLevel::High => "Significant privileges or weaknesses that should be reviewed within days",
+            RiskLevel::Medium => "Elevated access worth confirming at the next access review",
+            RiskLevel::Low => "Access consistent with a standard account; no action needed",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct OverlapAnalysis {
     pub overlaps: Vec<PermissionOverlap>,
@@ -34,6 +47,23 @@ pub struct OverlapAnalysis {
     pub overlapped_permissions: usize,
     pub redundancy_score: f32,
     pub risk_summary: RiskSummary,
+    /// Operator group memberships already covered by a more privileged group
+    pub redundant_memberships: Vec<RedundantMembership>,
+}
+
+/// A membership whose access a more privileged group of the same account already grants,
+/// so it can be removed without changing what the account can do
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RedundantMembership {
+    pub redundant_group: String,
+    pub covered_by: String,
+}
+
+impl RedundantMembership {
+    /// "Account Operators membership is redundant given Domain Admins"
+    pub fn describe(&self) -> String {
+        format!("{} membership is redundant given {}", self.redundant_group, self.covered_by)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -42,12 +72,25 @@ pub struct RiskSummary {
     pub high_overlaps: usize,
     pub medium_overlaps: usize,
     pub low_overlaps: usize,
+    /// Critical overlaps, then high ones granted by more than two groups, as
+    /// "permission (group, group)"; at most `MAX_DANGEROUS_COMBINATIONS`
     pub most_dangerous_combinations: Vec<String>,
+    /// How many combinations there were before truncation
+    pub dangerous_combination_count: usize,
+}
+
+impl RiskSummary {
+    pub const MAX_DANGEROUS_COMBINATIONS: usize = 5;
 }
 
 pub struct PermissionAnalyzer;
 
 impl PermissionAnalyzer {
+    /// Groups whose rights the second group already holds, as (redundant, covering) name patterns
+    const SUPERSEDED_GROUPS: [(&'static str, &'static str); 1] = [
+        ("Account Operators", "Domain Admins"),
+    ];
+
     pub fn new() -> Self {
         Self
     }
@@ -58,6 +101,8 @@ impl PermissionAnalyzer {
         let overlaps = self.detect_overlaps(&permission_map);
         let risk_summary = self.calculate_risk_summary(&overlaps);
         
+        let redundant_memberships = self.detect_redundant_memberships(user);
+
         let total_permissions = permission_map.len();
         let overlapped_permissions = overlaps.len();
         let redundancy_score = if total_permissions > 0 {
@@ -72,35 +117,58 @@ impl PermissionAnalyzer {
             overlapped_permissions,
             redundancy_score,
             risk_summary,
+            redundant_memberships,
+        }
+    }
+
+    /// Pairs of the account's groups (direct or nested) where one covers the other
+    ///
+    /// The two groups grant differently named permissions, so this is checked on the
+    /// memberships themselves rather than through the permission overlaps.
+    fn detect_redundant_memberships(&self, user: &ADUser) -> Vec<RedundantMembership> {
+        let mut found = BTreeSet::new();
+        for (redundant, covering) in Self::SUPERSEDED_GROUPS {
+            let redundant_groups = user.iter_groups().filter(|g| g.name.contains(redundant));
+            for redundant_group in redundant_groups {
+                if let Some(covering_group) = user.iter_groups().find(|g| g.name.contains(covering)) {
+                    found.insert(RedundantMembership {
+                        redundant_group: redundant_group.name.clone(),
+                        covered_by: covering_group.name.clone(),
+                    });
+                }
+            }
         }
+        found.into_iter().collect()
     }
 
     /// Build a map of permissions to their granting sources
-    fn build_permission_map(&self, user: &ADUser) -> HashMap<String, Vec<String>> {
-        let mut permission_map: HashMap<String, Vec<String>> = HashMap::new();
+    ///
+    /// Keys and sources borrow from the user and the static permission table; only the
+    /// overlaps that are reported get owned copies.
+    fn build_permission_map<'a>(&self, user: &'a ADUser) -> HashMap<Cow<'a, str>, Vec<&'a str>> {
+        let mut permission_map: HashMap<Cow<'a, str>, Vec<&'a str>> = HashMap::with_capacity(user.group_count());
         
         // Add permissions from all groups (direct and nested)
-        for group in user.all_groups() {
-            let group_permissions = self.get_group_permissions(&group.name);
-            for permission in group_permissions {
+        for group in user.iter_groups() {
+            for permission in self.get_group_permissions(&group.name) {
                 permission_map
                     .entry(permission)
-                    .or_insert_with(Vec::new)
-                    .push(group.name.clone());
+                    .or_default()
+                    .push(&group.name);
             }
         }
 
         // Add direct user rights
         for right in &user.user_rights {
             let source = match &right.source {
-                RightSource::DirectAssignment => "Direct Assignment".to_string(),
-                RightSource::GroupMembership(group) => group.clone(),
-                RightSource::Default => "Default".to_string(),
+                RightSource::DirectAssignment => "Direct Assignment",
+                RightSource::GroupMembership(group) => group.as_str(),
+                RightSource::Default => "Default",
             };
             
             permission_map
-                .entry(right.name.clone())
-                .or_insert_with(Vec::new)
+                .entry(Cow::Borrowed(right.name.as_str()))
+                .or_default()
                 .push(source);
         }
 
@@ -108,7 +176,7 @@ impl PermissionAnalyzer {
     }
 
     /// Detect overlapping permissions
-    fn detect_overlaps(&self, permission_map: &HashMap<String, Vec<String>>) -> Vec<PermissionOverlap> {
+    fn detect_overlaps(&self, permission_map: &HashMap<Cow<'_, str>, Vec<&str>>) -> Vec<PermissionOverlap> {
         let mut overlaps = Vec::new();
 
         for (permission, sources) in permission_map {
@@ -118,9 +186,9 @@ impl PermissionAnalyzer {
                 let description = self.get_permission_description(permission);
 
                 overlaps.push(PermissionOverlap {
-                    permission: permission.clone(),
+                    permission: permission.to_string(),
                     description,
-                    granting_groups: sources.clone(),
+                    granting_groups: sources.iter().map(|s| s.to_string()).collect(),
                     overlap_type,
                     risk_level,
                 });
@@ -133,7 +201,7 @@ impl PermissionAnalyzer {
     }
 
     /// Determine the type of overlap
-    fn determine_overlap_type(&self, permission: &str, sources: &[String]) -> OverlapType {
+    fn determine_overlap_type(&self, permission: &str, sources: &[&str]) -> OverlapType {
         // Check for dangerous escalation combinations
         if self.is_escalation_permission(permission) && sources.len() > 2 {
             return OverlapType::Escalation;
@@ -154,7 +222,7 @@ impl PermissionAnalyzer {
     }
 
     /// Assess the risk level of a permission overlap
-    fn assess_permission_risk(&self, permission: &str, sources: &[String], overlap_type: &OverlapType) -> RiskLevel {
+    fn assess_permission_risk(&self, permission: &str, sources: &[&str], overlap_type: &OverlapType) -> RiskLevel {
         // Critical risk permissions
         if self.is_critical_permission(permission) {
             return RiskLevel::Critical;
@@ -186,13 +254,15 @@ impl PermissionAnalyzer {
         let mut high_overlaps = 0;
         let mut medium_overlaps = 0;
         let mut low_overlaps = 0;
-        let mut dangerous_combinations = HashSet::new();
+        // Sorted sets keep the list (and so what truncation drops) stable between runs
+        let mut critical_combinations = BTreeSet::new();
+        let mut high_combinations = BTreeSet::new();
 
         for overlap in overlaps {
             match overlap.risk_level {
                 RiskLevel::Critical => {
                     critical_overlaps += 1;
-                    dangerous_combinations.insert(format!(
+                    critical_combinations.insert(format!(
                         "{} ({})", 
                         overlap.permission, 
                         overlap.granting_groups.join(", ")
@@ -201,7 +271,7 @@ impl PermissionAnalyzer {
                 RiskLevel::High => {
                     high_overlaps += 1;
                     if overlap.granting_groups.len() > 2 {
-                        dangerous_combinations.insert(format!(
+                        high_combinations.insert(format!(
                             "{} ({})", 
                             overlap.permission, 
                             overlap.granting_groups.join(", ")
@@ -213,17 +283,22 @@ impl PermissionAnalyzer {
             }
         }
 
+        let dangerous_combination_count = critical_combinations.len() + high_combinations.len();
         RiskSummary {
             critical_overlaps,
             high_overlaps,
             medium_overlaps,
             low_overlaps,
-            most_dangerous_combinations: dangerous_combinations.into_iter().take(5).collect(),
+            most_dangerous_combinations: critical_combinations.into_iter()
+                .chain(high_combinations)
+                .take(RiskSummary::MAX_DANGEROUS_COMBINATIONS)
+                .collect(),
+            dangerous_combination_count,
         }
     }
 
     /// Get permissions granted by a specific group
-    fn get_group_permissions(&self, group_name: &str) -> Vec<String> {
+    fn get_group_permissions(&self, group_name: &str) -> Vec<Cow<'static, str>> {
         // This would normally query a permission database or AD
         // Enhanced to handle custom business groups with intelligent pattern matching
         let name_lower = group_name.to_lowercase();
@@ -231,134 +306,134 @@ impl PermissionAnalyzer {
         match group_name {
             // Built-in Windows AD groups
             name if name.contains("Domain Admins") => vec![
-                "Full Domain Control".to_string(),
-                "User Management".to_string(),
-                "Computer Management".to_string(),
-                "Group Policy Management".to_string(),
-                "Schema Modification".to_string(),
-                "Directory Service Access".to_string(),
+                "Full Domain Control".into(),
+                "User Management".into(),
+                "Computer Management".into(),
+                "Group Policy Management".into(),
+                "Schema Modification".into(),
+                "Directory Service Access".into(),
             ],
             name if name.contains("Enterprise Admins") => vec![
-                "Forest-wide Administration".to_string(),
-                "Schema Modification".to_string(),
-                "Configuration Container Access".to_string(),
-                "Cross-Domain Access".to_string(),
+                "Forest-wide Administration".into(),
+                "Schema Modification".into(),
+                "Configuration Container Access".into(),
+                "Cross-Domain Access".into(),
             ],
             name if name.contains("Schema Admins") => vec![
-                "Schema Modification".to_string(),
-                "Directory Schema Access".to_string(),
+                "Schema Modification".into(),
+                "Directory Schema Access".into(),
             ],
             name if name.contains("Account Operators") => vec![
-                "User Account Management".to_string(),
-                "Group Management".to_string(),
-                "OU Management".to_string(),
+                "User Account Management".into(),
+                "Group Management".into(),
+                "OU Management".into(),
             ],
             name if name.contains("Server Operators") => vec![
-                "Server Management".to_string(),
-                "Service Management".to_string(),
-                "Backup/Restore Operations".to_string(),
+                "Server Management".into(),
+                "Service Management".into(),
+                "Backup/Restore Operations".into(),
             ],
             name if name.contains("Backup Operators") => vec![
-                "Backup Operations".to_string(),
-                "Restore Operations".to_string(),
-                "File System Access".to_string(),
+                "Backup Operations".into(),
+                "Restore Operations".into(),
+                "File System Access".into(),
             ],
             name if name.contains("Print Operators") => vec![
-                "Print Queue Management".to_string(),
-                "Printer Administration".to_string(),
+                "Print Queue Management".into(),
+                "Printer Administration".into(),
             ],
             name if name.contains("Remote Desktop Users") => vec![
-                "Remote Desktop Access".to_string(),
-                "Interactive Logon Rights".to_string(),
+                "Remote Desktop Access".into(),
+                "Interactive Logon Rights".into(),
             ],
             name if name.contains("Power Users") => vec![
-                "System Configuration".to_string(),
-                "Application Installation".to_string(),
-                "Performance Monitoring".to_string(),
+                "System Configuration".into(),
+                "Application Installation".into(),
+                "Performance Monitoring".into(),
             ],
             
             // Enhanced patterns for custom business groups
             name if name_lower.contains("admin") || name_lower.contains("administrator") => vec![
-                "Administrative Access".to_string(),
-                "System Configuration".to_string(),
-                "User Management".to_string(),
-                if name_lower.contains("database") || name_lower.contains("db") { "Database Administration".to_string() } else { "General Administration".to_string() },
+                "Administrative Access".into(),
+                "System Configuration".into(),
+                "User Management".into(),
+                if name_lower.contains("database") || name_lower.contains("db") { "Database Administration".into() } else { "General Administration".into() },
             ],
             
             name if name_lower.contains("database") || name_lower.contains("db") => vec![
-                "Database Access".to_string(),
-                "Data Query Rights".to_string(),
-                if name_lower.contains("reporting") { "Database Reporting".to_string() } else { "Database Operations".to_string() },
-                if name_lower.contains("rw") || name_lower.contains("write") { "Database Write Access".to_string() } else { "Database Read Access".to_string() },
+                "Database Access".into(),
+                "Data Query Rights".into(),
+                if name_lower.contains("reporting") { "Database Reporting".into() } else { "Database Operations".into() },
+                if name_lower.contains("rw") || name_lower.contains("write") { "Database Write Access".into() } else { "Database Read Access".into() },
             ],
             
             name if name_lower.contains("developer") || name_lower.contains("dev") => vec![
-                "Development Environment Access".to_string(),
-                "Code Repository Access".to_string(),
-                "Application Deployment".to_string(),
-                if name_lower.contains("prod") { "Production Environment Access".to_string() } else { "Development Tools".to_string() },
+                "Development Environment Access".into(),
+                "Code Repository Access".into(),
+                "Application Deployment".into(),
+                if name_lower.contains("prod") { "Production Environment Access".into() } else { "Development Tools".into() },
             ],
             
             name if name_lower.contains("it") && (name_lower.contains("user") || name_lower.contains("staff")) => vec![
-                "IT Administrative Tools".to_string(),
-                "System Monitoring".to_string(),
-                "Technical Support Access".to_string(),
-                "Infrastructure Management".to_string(),
+                "IT Administrative Tools".into(),
+                "System Monitoring".into(),
+                "Technical Support Access".into(),
+                "Infrastructure Management".into(),
             ],
             
             name if name_lower.contains("reporting") || name_lower.contains("report") => vec![
-                "Report Generation".to_string(),
-                "Data Analysis Access".to_string(),
-                "Business Intelligence".to_string(),
+                "Report Generation".into(),
+                "Data Analysis Access".into(),
+                "Business Intelligence".into(),
             ],
             
             name if name_lower.contains("vpn") => vec![
-                "VPN Access".to_string(),
-                "Remote Network Access".to_string(),
-                "Secure Connectivity".to_string(),
+                "VPN Access".into(),
+                "Remote Network Access".into(),
+                "Secure Connectivity".into(),
             ],
             
             name if name_lower.contains("ssl") || name_lower.contains("cert") => vec![
-                "Certificate Management".to_string(),
-                "SSL/TLS Administration".to_string(),
-                "Security Infrastructure".to_string(),
+                "Certificate Management".into(),
+                "SSL/TLS Administration".into(),
+                "Security Infrastructure".into(),
             ],
             
             name if name_lower.contains("print") || name_lower.contains("printer") => vec![
-                "Printer Access".to_string(),
-                "Print Queue Management".to_string(),
-                "Document Processing".to_string(),
+                "Printer Access".into(),
+                "Print Queue Management".into(),
+                "Document Processing".into(),
             ],
             
             name if name_lower.contains("backup") || name_lower.contains("restore") => vec![
-                "Backup Operations".to_string(),
-                "Data Recovery".to_string(),
-                "Archive Management".to_string(),
+                "Backup Operations".into(),
+                "Data Recovery".into(),
+                "Archive Management".into(),
             ],
             
             name if name_lower.contains("breakglass") || name_lower.contains("emergency") => vec![
-                "Emergency Access".to_string(),
-                "Break-Glass Privileges".to_string(),
-                "Critical System Access".to_string(),
+                "Emergency Access".into(),
+                "Break-Glass Privileges".into(),
+                "Critical System Access".into(),
             ],
             
             name if name_lower.contains("uat") || name_lower.contains("test") => vec![
-                "Test Environment Access".to_string(),
-                "Quality Assurance".to_string(),
-                "Pre-Production Access".to_string(),
+                "Test Environment Access".into(),
+                "Quality Assurance".into(),
+                "Pre-Production Access".into(),
             ],
             
             name if name_lower.contains("office") || name_lower.contains("location") => vec![
-                "Physical Location Access".to_string(),
-                "Office Resources".to_string(),
-                "Location-based Services".to_string(),
+                "Physical Location Access".into(),
+                "Office Resources".into(),
+                "Location-based Services".into(),
             ],
             
             // Default for unrecognized groups - still provide some permissions to enable overlap detection
             _ => vec![
-                "Standard User Rights".to_string(),
-                format!("Group Membership: {}", group_name),
-                "Basic Network Access".to_string(),
+                "Standard User Rights".into(),
+                format!("Group Membership: {}", group_name).into(),
+                "Basic Network Access".into(),
             ],
         }
     }
@@ -414,17 +489,15 @@ impl PermissionAnalyzer {
     }
 
     /// Check if sources have conflicting permissions
-    fn has_conflicting_sources(&self, sources: &[String]) -> bool {
-        // Check for conflicting group combinations
-        let has_user_operators = sources.iter().any(|s| s.contains("Account Operators"));
-        let has_domain_admins = sources.iter().any(|s| s.contains("Domain Admins"));
-        
-        // Domain Admins with Account Operators is redundant/conflicting
-        has_user_operators && has_domain_admins
+    fn has_conflicting_sources(&self, sources: &[&str]) -> bool {
+        // e.g., Domain Admins with Account Operators is redundant/conflicting
+        Self::SUPERSEDED_GROUPS.iter().any(|(redundant, covering)| {
+            sources.iter().any(|s| s.contains(redundant)) && sources.iter().any(|s| s.contains(covering))
+        })
     }
 
     /// Check if permission is redundant due to inheritance
-    fn is_redundant_permission(&self, _permission: &str, sources: &[String]) -> bool {
+    fn is_redundant_permission(&self, _permission: &str, sources: &[&str]) -> bool {
         // If Domain Admins is present, most other permissions are redundant
         let has_domain_admins = sources.iter().any(|s| s.contains("Domain Admins"));
         let has_enterprise_admins = sources.iter().any(|s| s.contains("Enterprise Admins"));
@@ -0,0 +1,39 @@
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+/// Spaces LDAP operations evenly to at most `per_second` across the run (`--rate-limit`)
+///
+/// One limiter is shared (behind an `Arc`) by the main connection and every connection it
+/// opens to home domains and referred servers, so the whole run stays under the limit. Each
+/// caller reserves the next free slot and sleeps until it; there is no burst allowance, so
+/// a DC never sees more than one operation per interval.
+#[derive(Debug)]
+pub struct RateLimiter {
+    interval: Duration,
+    next_slot: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    /// Slowest accepted rate (one operation every ~17 minutes)
+    pub const MIN_PER_SECOND: f64 = 0.001;
+
+    pub fn new(per_second: f64) -> Self {
+        Self {
+            interval: Duration::from_secs_f64(1.0 / per_second),
+            next_slot: Mutex::new(None),
+        }
+    }
+
+    /// Wait until the next operation may be sent
+    pub async fn acquire(&self) {
+        let slot = {
+            let mut next_slot = self.next_slot.lock().await;
+            let now = Instant::now();
+            let slot = next_slot.map_or(now, |next| next.max(now));
+            *next_slot = Some(slot + self.interval);
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+}
@@ -0,0 +1,117 @@
+use sha2::{Digest, Sha256};
+use crate::models::ADUser;
+
+/// Masks personally identifiable information before a report is rendered
+///
+/// Redaction is applied to the user record itself, so every output built from it
+/// (PDF, batch summaries, ...) sees the same masked values. Group memberships,
+/// rights and risk findings are left untouched.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Redactor {
+    hash_sam_account_name: bool,
+}
+
+impl Redactor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace SAM account names with a stable hash so cross-references still line up
+    pub fn with_hashed_sam_account_name(mut self, enabled: bool) -> Self {
+        self.hash_sam_account_name = enabled;
+        self
+    }
+
+    pub fn redact_user(&self, mut user: ADUser) -> ADUser {
+        user.email = user.email.as_deref().map(mask_email);
+        user.user_principal_name = user.user_principal_name.as_deref().map(mask_email);
+        user.display_name = user.display_name.as_deref().map(mask_text);
+        user.department = user.department.as_deref().map(mask_text);
+        user.distinguished_name = mask_leading_rdn(&user.distinguished_name);
+
+        // Raw attributes carry every value unmasked, so they are never shared
+        user.raw_attributes = None;
+
+        if self.hash_sam_account_name {
+            user.sam_account_name = hash_identifier(&user.sam_account_name);
+        }
+
+        user
+    }
+
+    /// An account name as it may appear in outputs: hashed with --redact-hash-sam, else as given
+    ///
+    /// Used for users that never produced a record to redact (skipped or failed lookups).
+    pub fn redact_account_name(&self, sam_account_name: &str) -> String {
+        if self.hash_sam_account_name {
+            hash_identifier(sam_account_name)
+        } else {
+            sam_account_name.to_string()
+        }
+    }
+
+    /// Replace mentions of `sam_account_name` in free text, such as an error message
+    pub fn redact_mentions(&self, text: &str, sam_account_name: &str) -> String {
+        if !self.hash_sam_account_name || sam_account_name.is_empty() {
+            return text.to_string();
+        }
+        text.replace(sam_account_name, &hash_identifier(sam_account_name))
+    }
+}
+
+/// "john.doe@corp.com" -> "j***@***"
+pub fn mask_email(value: &str) -> String {
+    match value.split_once('@') {
+        Some((local, _)) => format!("{}@***", mask_text(local)),
+        None => mask_text(value),
+    }
+}
+
+/// Keep the first character and mask the rest ("Engineering" -> "E***")
+pub fn mask_text(value: &str) -> String {
+    match value.trim().chars().next() {
+        Some(first) => format!("{}***", first),
+        None => String::new(),
+    }
+}
+
+/// Mask the value of the first RDN, which usually holds the user's full name
+fn mask_leading_rdn(dn: &str) -> String {
+    let (rdn, rest) = match first_unescaped_comma(dn) {
+        Some(comma) => (&dn[..comma], Some(&dn[comma + 1..])),
+        None => (dn, None),
+    };
+
+    let masked = match rdn.split_once('=') {
+        Some((attr, value)) => format!("{}={}", attr, mask_text(value)),
+        None => mask_text(rdn),
+    };
+
+    match rest {
+        Some(rest) => format!("{},{}", masked, rest),
+        None => masked,
+    }
+}
+
+/// Byte index of the comma that ends the first RDN; a comma inside a value (`CN=Doe\, John`)
+/// is escaped by an odd run of backslashes, while an even run only escapes backslashes
+fn first_unescaped_comma(dn: &str) -> Option<usize> {
+    let mut backslashes = 0;
+    for (i, c) in dn.char_indices() {
+        if c == ',' && backslashes % 2 == 0 {
+            return Some(i);
+        }
+        backslashes = if c == '\\' { backslashes + 1 } else { 0 };
+    }
+    None
+}
+
+/// Stable pseudonym for an account name (case-insensitive, like AD itself)
+pub fn hash_identifier(value: &str) -> String {
+    let digest = Sha256::digest(value.to_lowercase().as_bytes());
+    let hex: String = digest.iter()
+        .take(6)
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!("user-{}", hex)
+}
@@ -1,23 +1,66 @@
 use chrono::{DateTime, Utc};
-use crate::models::{ADUser, ReportData};
+use serde::Serialize;
+use crate::models::{ADComputer, ADUser, ReportData};
 use crate::risk_calculator::RiskAssessment;
+use crate::permission_analyzer::RiskLevel;
+use crate::snapshot::ReportChanges;
 
-#[derive(Debug, Clone)]
+/// Report body sections that can be selected individually
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportSection {
+    User,
+    Status,
+    Risk,
+    Groups,
+}
+
+impl ReportSection {
+    pub const ALL: [ReportSection; 4] = [
+        ReportSection::User,
+        ReportSection::Status,
+        ReportSection::Risk,
+        ReportSection::Groups,
+    ];
+}
+
+/// Who generated a report and what part of the directory they queried (--show-provenance)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReportProvenance {
+    /// Authenticated bind identity: the simple-bind username, or the current user for GSSAPI
+    pub bind_identity: String,
+    /// Base DN the account search ran under
+    pub search_base: String,
+}
+
+/// Everything a report shows; serialized whole for the --integrity-hash content JSON
+#[derive(Debug, Clone, Serialize)]
 pub struct EnhancedReportData {
     pub basic_report: ReportData,
     pub risk_assessment: Option<RiskAssessment>,
+    pub baseline_changes: Option<ReportChanges>,
+    pub hidden_groups: usize,                 // Groups removed by --include/--exclude-groups
+    pub identity_issues: Vec<String>,         // Missing/placeholder displayName or email
+    /// Host and delegation details when the subject is a computer (--target-computer);
+    /// the account itself is reported from `basic_report.user`
+    pub computer: Option<ADComputer>,
+    pub provenance: Option<ReportProvenance>,
+    /// Why risk analysis was requested but produced no assessment
+    pub risk_unavailable: Option<String>,
 }
 
 impl EnhancedReportData {
-    pub fn new(
+    /// Report data generated at `generation_time`, which callers pass explicitly so the
+    /// same data and time always render to the same PDF bytes
+    pub fn new_at(
         user: ADUser,
         domain_name: String,
         domain_controller: String,
         risk_assessment: Option<RiskAssessment>,
+        generation_time: DateTime<Utc>,
     ) -> Self {
         let basic_report = ReportData {
             user,
-            generation_time: Utc::now(),
+            generation_time,
             domain_name,
             domain_controller,
         };
@@ -25,9 +68,51 @@ impl EnhancedReportData {
         Self {
             basic_report,
             risk_assessment,
+            baseline_changes: None,
+            hidden_groups: 0,
+            identity_issues: Vec::new(),
+            computer: None,
+            provenance: None,
+            risk_unavailable: None,
         }
     }
 
+    /// Record how many groups were filtered out of the displayed memberships
+    pub fn with_hidden_groups(mut self, hidden_groups: usize) -> Self {
+        self.hidden_groups = hidden_groups;
+        self
+    }
+
+    /// Note identity data-quality problems in the User Information section
+    pub fn with_identity_issues(mut self, identity_issues: Vec<String>) -> Self {
+        self.identity_issues = identity_issues;
+        self
+    }
+
+    /// Report on a computer account rather than a user
+    pub fn with_computer(mut self, computer: ADComputer) -> Self {
+        self.computer = Some(computer);
+        self
+    }
+
+    /// Attach the differences from the previous report for this user
+    pub fn with_baseline_changes(mut self, changes: ReportChanges) -> Self {
+        self.baseline_changes = Some(changes);
+        self
+    }
+
+    /// State the bind identity and search base alongside the generation time
+    pub fn with_provenance(mut self, provenance: ReportProvenance) -> Self {
+        self.provenance = Some(provenance);
+        self
+    }
+
+    /// Note that risk analysis was requested but failed, so the missing section is explained
+    pub fn with_risk_unavailable(mut self, reason: impl Into<String>) -> Self {
+        self.risk_unavailable = Some(reason.into());
+        self
+    }
+
     pub fn user(&self) -> &ADUser {
         &self.basic_report.user
     }
@@ -43,4 +128,211 @@ impl EnhancedReportData {
     pub fn domain_controller(&self) -> &str {
         &self.basic_report.domain_controller
     }
-}
\ No newline at end of file
+}
+
+/// A Kerberoastable user account found during a batch run
+#[derive(Debug, Clone)]
+pub struct KerberoastableAccount {
+    pub sam_account_name: String,
+    pub service_principal_names: Vec<String>,
+    pub weak_password_settings: Vec<String>,
+}
+
+/// Collects user accounts with SPNs across a batch (computer accounts are ignored)
+#[derive(Debug, Clone, Default)]
+pub struct KerberoastableSummary {
+    pub accounts: Vec<KerberoastableAccount>,
+}
+
+impl KerberoastableSummary {
+    /// Passwords older than this make a roastable hash much more likely to crack
+    const STALE_PASSWORD_DAYS: i64 = 365;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the user if it is Kerberoastable
+    pub fn record(&mut self, user: &ADUser) {
+        if !user.is_kerberoastable() {
+            return;
+        }
+
+        let mut weak_password_settings = Vec::new();
+        if user.password_never_expires {
+            weak_password_settings.push("password never expires".to_string());
+        }
+        if let Some(last_set) = user.password_last_set {
+            let age_days = (Utc::now() - last_set).num_days();
+            if age_days > Self::STALE_PASSWORD_DAYS {
+                weak_password_settings.push(format!("password last set {} days ago", age_days));
+            }
+        }
+        if user.no_preauth_required {
+            weak_password_settings.push("Kerberos preauthentication not required".to_string());
+        }
+
+        self.accounts.push(KerberoastableAccount {
+            sam_account_name: user.sam_account_name.clone(),
+            service_principal_names: user.service_principal_names.clone(),
+            weak_password_settings,
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+}
+
+/// An account that has not logged on since it was created, found during a batch run
+#[derive(Debug, Clone)]
+pub struct UnusedAccount {
+    pub sam_account_name: String,
+    pub created: DateTime<Utc>,
+    pub age_days: i64,
+}
+
+/// Collects accounts provisioned more than `min_age_days` ago that have never logged on,
+/// as one cleanup list for the batch (unlike the per-user "never logged on" risk factor,
+/// this does not depend on group memberships or --risk-analysis)
+#[derive(Debug, Clone)]
+pub struct UnusedAccountsSummary {
+    pub accounts: Vec<UnusedAccount>,
+    pub min_age_days: i64,
+}
+
+impl UnusedAccountsSummary {
+    pub const DEFAULT_MIN_AGE_DAYS: i64 = 30;
+
+    pub fn new(min_age_days: i64) -> Self {
+        Self { accounts: Vec::new(), min_age_days }
+    }
+
+    /// Record the account if it was created at least `min_age_days` before `now` and has
+    /// no logon; accounts without a creation time are left out
+    pub fn record(&mut self, user: &ADUser, now: DateTime<Utc>) {
+        let Some(created) = user.created.filter(|_| user.last_logon.is_none()) else {
+            return;
+        };
+        let age_days = (now - created).num_days();
+        if age_days >= self.min_age_days {
+            self.accounts.push(UnusedAccount {
+                sam_account_name: user.sam_account_name.clone(),
+                created,
+                age_days,
+            });
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+}
+
+/// Account states tallied across a batch, as a directory-hygiene snapshot
+///
+/// Enabled and disabled partition the accounts seen; locked and password-never-expires
+/// overlap with them. Accounts skipped with --skip-disabled only count as disabled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AccountStateSummary {
+    pub enabled: usize,
+    pub disabled: usize,
+    pub locked: usize,
+    pub password_never_expires: usize,
+}
+
+impl AccountStateSummary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, user: &ADUser) {
+        if user.account_enabled {
+            self.enabled += 1;
+        } else {
+            self.disabled += 1;
+        }
+        self.locked += usize::from(user.account_locked);
+        self.password_never_expires += usize::from(user.password_never_expires);
+    }
+
+    /// Count a disabled account that was skipped before the rest of its state was looked at
+    pub fn record_skipped_disabled(&mut self) {
+        self.disabled += 1;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.enabled + self.disabled == 0
+    }
+
+    /// (label, count) rows in display order
+    pub fn rows(&self) -> [(&'static str, usize); 4] {
+        [
+            ("Enabled", self.enabled),
+            ("Disabled", self.disabled),
+            ("Locked out", self.locked),
+            ("Password never expires", self.password_never_expires),
+        ]
+    }
+}
+
+/// Risk outcome for a single user within a batch run
+#[derive(Debug, Clone)]
+pub struct BatchRiskEntry {
+    pub sam_account_name: String,
+    pub score: u8,
+    pub risk_level: RiskLevel,
+}
+
+/// Portfolio view of risk across all users processed in a batch
+#[derive(Debug, Clone)]
+pub struct BatchRiskSummary {
+    pub entries: Vec<BatchRiskEntry>,
+    pub generation_time: DateTime<Utc>,
+}
+
+impl BatchRiskSummary {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            generation_time: Utc::now(),
+        }
+    }
+
+    pub fn record(&mut self, sam_account_name: &str, risk: &RiskAssessment) {
+        self.entries.push(BatchRiskEntry {
+            sam_account_name: sam_account_name.to_string(),
+            score: risk.overall_score,
+            risk_level: risk.risk_level.clone(),
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Number of users in each risk level, most severe first
+    pub fn distribution(&self) -> Vec<(RiskLevel, usize)> {
+        [RiskLevel::Critical, RiskLevel::High, RiskLevel::Medium, RiskLevel::Low]
+            .into_iter()
+            .map(|level| {
+                let count = self.entries.iter().filter(|e| e.risk_level == level).count();
+                (level, count)
+            })
+            .collect()
+    }
+
+    /// The `n` highest-scoring users, riskiest first
+    pub fn top_riskiest(&self, n: usize) -> Vec<&BatchRiskEntry> {
+        let mut ranked: Vec<&BatchRiskEntry> = self.entries.iter().collect();
+        ranked.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.sam_account_name.cmp(&b.sam_account_name)));
+        ranked.truncate(n);
+        ranked
+    }
+}
+
+impl Default for BatchRiskSummary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
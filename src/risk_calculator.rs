@@ -1,7 +1,17 @@
+use anyhow::{Context, Result};
 use chrono::{DateTime, Utc, Duration};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
-use crate::models::{ADUser, ADGroup, UserRight, RightSource};
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use crate::models::{ADComputer, ADUser, ADGroup, UserRight, RightSource};
 use crate::permission_analyzer::{PermissionAnalyzer, OverlapAnalysis, RiskLevel};
+use crate::sid::{rid_of, well_known_rid_name};
+use crate::data_quality::IdentityQualityCheck;
+use crate::risk_rules::CustomRiskRule;
+use crate::ad_time;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RiskAssessment {
@@ -10,6 +20,131 @@ pub struct RiskAssessment {
     pub contributing_factors: Vec<RiskFactor>,
     pub recommendations: Vec<String>,
     pub risk_breakdown: RiskBreakdown,
+    pub unmitigated_score: u8,                // Weighted score before mitigations
+    pub mitigating_factors: Vec<MitigatingFactor>,
+    #[serde(default)]
+    pub thresholds: RiskThresholds,           // Score bands used to pick risk_level
+    #[serde(default)]
+    pub group_contributions: Vec<GroupRiskContribution>, // Per-group risk, highest first
+    #[serde(default)]
+    pub permission_redundancy: PermissionRedundancy,
+    #[serde(default)]
+    pub dangerous_combinations: DangerousCombinations,
+}
+
+/// The permission + group combinations most worth investigating (critical overlaps, then
+/// high ones granted by more than two groups)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DangerousCombinations {
+    /// "permission (group, group)", at most `RiskSummary::MAX_DANGEROUS_COMBINATIONS`
+    pub shown: Vec<String>,
+    /// Combinations found, including any left out of `shown`
+    pub total: usize,
+}
+
+impl DangerousCombinations {
+    /// Some combinations were left out of `shown`
+    pub fn is_truncated(&self) -> bool {
+        self.total > self.shown.len()
+    }
+}
+
+/// How many of the user's permissions are granted by more than one group
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct PermissionRedundancy {
+    pub percent: f32,                         // 0-100
+    pub overlapped_permissions: usize,
+    pub total_permissions: usize,
+}
+
+impl PermissionRedundancy {
+    /// Redundancy above this is a contributing risk factor
+    pub const HIGH_PERCENT: f32 = 50.0;
+    /// Redundancy above this earns a cleanup recommendation
+    pub const REVIEW_PERCENT: f32 = 30.0;
+}
+
+/// Lowest score of each risk band above Low (scores below `medium` are Low)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RiskThresholds {
+    pub medium: u8,
+    pub high: u8,
+    pub critical: u8,
+}
+
+impl RiskThresholds {
+    /// Validate that the bands increase strictly and stay within 1-100
+    pub fn new(medium: u8, high: u8, critical: u8) -> Result<Self> {
+        if medium == 0 || critical > 100 {
+            return Err(anyhow::anyhow!(
+                "Risk thresholds must be within 1-100 so every band is non-empty (got {}, {}, {})",
+                medium, high, critical
+            ));
+        }
+        if !(medium < high && high < critical) {
+            return Err(anyhow::anyhow!(
+                "Risk thresholds must increase: medium < high < critical (got {}, {}, {})",
+                medium, high, critical
+            ));
+        }
+
+        Ok(Self { medium, high, critical })
+    }
+
+    /// Read thresholds from a JSON file: {"medium": 30, "high": 60, "critical": 80}
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .context(format!("Failed to read risk thresholds file: {}", path.display()))?;
+        let thresholds: Self = serde_json::from_str(&contents)
+            .context(format!("Failed to parse risk thresholds file: {}", path.display()))?;
+        Self::new(thresholds.medium, thresholds.high, thresholds.critical)
+    }
+
+    pub fn level_for(&self, score: u8) -> RiskLevel {
+        if score >= self.critical {
+            RiskLevel::Critical
+        } else if score >= self.high {
+            RiskLevel::High
+        } else if score >= self.medium {
+            RiskLevel::Medium
+        } else {
+            RiskLevel::Low
+        }
+    }
+
+    /// Inclusive score range covered by each level, lowest band first
+    pub fn bands(&self) -> [(RiskLevel, u8, u8); 4] {
+        [
+            (RiskLevel::Low, 0, self.medium - 1),
+            (RiskLevel::Medium, self.medium, self.high - 1),
+            (RiskLevel::High, self.high, self.critical - 1),
+            (RiskLevel::Critical, self.critical, 100),
+        ]
+    }
+}
+
+impl Default for RiskThresholds {
+    fn default() -> Self {
+        Self { medium: 30, high: 60, critical: 80 }
+    }
+}
+
+/// Parses "medium,high,critical", e.g. "30,60,80"
+impl FromStr for RiskThresholds {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let numbers = value
+            .split(',')
+            .map(|n| n.trim().parse::<u8>()
+                .map_err(|_| anyhow::anyhow!("Invalid risk threshold '{}' (expected 0-100)", n.trim())))
+            .collect::<Result<Vec<u8>>>()?;
+
+        match numbers.as_slice() {
+            [medium, high, critical] => Self::new(*medium, *high, *critical),
+            _ => Err(anyhow::anyhow!("Expected three risk thresholds (medium,high,critical), got {}", numbers.len())),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,6 +155,104 @@ pub struct RiskFactor {
     pub severity: RiskLevel,
 }
 
+/// How much a single group membership adds to the administrative risk, and why
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupRiskContribution {
+    pub group_name: String,
+    pub distinguished_name: String,
+    pub risk_contribution: u8,                // 0-100
+    pub severity: RiskLevel,
+    pub reason: String,
+}
+
+/// Risk context for one group in JSON output: what the membership adds to the
+/// administrative risk and the risk factor type it matched, if any
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupRiskAnnotation {
+    pub risk_contribution: u8,                // 0-100
+    pub severity: RiskLevel,
+    pub category: Option<RiskFactorType>,
+    pub reason: String,
+}
+
+/// A combination of group memberships that is dangerous together even when each
+/// membership is tolerable on its own; matched when the user holds every group
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EscalationRule {
+    pub groups: Vec<String>,                  // Group names, compared case-insensitively
+    pub description: String,
+    pub risk_contribution: u8,                // 0-100
+    pub severity: RiskLevel,
+}
+
+impl EscalationRule {
+    pub fn new(groups: &[&str], description: &str, risk_contribution: u8, severity: RiskLevel) -> Self {
+        Self {
+            groups: groups.iter().map(|g| g.to_string()).collect(),
+            description: description.to_string(),
+            risk_contribution,
+            severity,
+        }
+    }
+
+    /// Built-in toxic combinations of default AD groups
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::new(&["Account Operators", "Group Policy Creator Owners"],
+                "can create GPOs and edit the accounts and groups they apply to - a path to code execution on other users' machines",
+                35, RiskLevel::High),
+            Self::new(&["Account Operators", "Server Operators"],
+                "can create or reset accounts and log on to domain controllers to use them",
+                40, RiskLevel::High),
+            Self::new(&["Backup Operators", "Server Operators"],
+                "can back up NTDS.dit on a domain controller and read every credential in the domain",
+                45, RiskLevel::Critical),
+            Self::new(&["DnsAdmins", "Server Operators"],
+                "can load a DLL into the DNS service and restart it on a domain controller as SYSTEM",
+                45, RiskLevel::Critical),
+            Self::new(&["Account Operators", "Key Admins"],
+                "can add shadow credentials to accounts they manage and authenticate as them",
+                35, RiskLevel::High),
+            Self::new(&["Print Operators", "Group Policy Creator Owners"],
+                "can load drivers on domain controllers and stage GPOs to persist them",
+                30, RiskLevel::High),
+        ]
+    }
+
+    /// Read rules from a JSON file: [{"groups": ["A", "B"], "description": "...",
+    /// "risk_contribution": 40, "severity": "High"}, ...]
+    pub fn from_file(path: &Path) -> Result<Vec<Self>> {
+        let contents = fs::read_to_string(path)
+            .context(format!("Failed to read escalation rules file: {}", path.display()))?;
+        let rules: Vec<Self> = serde_json::from_str(&contents)
+            .context(format!("Failed to parse escalation rules file: {}", path.display()))?;
+
+        for rule in &rules {
+            if rule.groups.len() < 2 {
+                return Err(anyhow::anyhow!(
+                    "Escalation rule '{}' must combine at least two groups", rule.description));
+            }
+            if rule.risk_contribution > 100 {
+                return Err(anyhow::anyhow!(
+                    "Escalation rule '{}' has risk contribution {} (expected 0-100)", rule.description, rule.risk_contribution));
+            }
+        }
+        Ok(rules)
+    }
+
+    /// Every group of the rule is among the user's effective memberships
+    fn matches(&self, group_names: &HashSet<String>) -> bool {
+        !self.groups.is_empty() && self.groups.iter().all(|g| group_names.contains(&g.to_lowercase()))
+    }
+}
+
+/// Good security hygiene that lowers the overall score
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MitigatingFactor {
+    pub description: String,
+    pub score_reduction: u8,                  // Points removed from the combined score
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum RiskFactorType {
     AdministrativeAccess,
@@ -32,6 +265,70 @@ pub enum RiskFactorType {
     CrossDomainAccess,
     DataAccess,
     PrivilegeEscalation,
+    IncompleteGroupData,
+    IdentityDataQuality,
+    PrimaryGroupAnomaly,
+    Delegation,
+}
+
+impl RiskFactorType {
+    /// Every factor type, in the order the glossary lists them
+    pub const ALL: [RiskFactorType; 14] = [
+        RiskFactorType::AdministrativeAccess,
+        RiskFactorType::PrivilegedGroups,
+        RiskFactorType::ServiceAccount,
+        RiskFactorType::DormantAccount,
+        RiskFactorType::PermissionOverlap,
+        RiskFactorType::ExcessivePrivileges,
+        RiskFactorType::WeakAccountSecurity,
+        RiskFactorType::CrossDomainAccess,
+        RiskFactorType::DataAccess,
+        RiskFactorType::PrivilegeEscalation,
+        RiskFactorType::IncompleteGroupData,
+        RiskFactorType::IdentityDataQuality,
+        RiskFactorType::PrimaryGroupAnomaly,
+        RiskFactorType::Delegation,
+    ];
+
+    /// Human-readable name, e.g. "Administrative Access"
+    pub fn label(&self) -> &'static str {
+        match self {
+            RiskFactorType::AdministrativeAccess => "Administrative Access",
+            RiskFactorType::PrivilegedGroups => "Privileged Groups",
+            RiskFactorType::ServiceAccount => "Service Account",
+            RiskFactorType::DormantAccount => "Dormant Account",
+            RiskFactorType::PermissionOverlap => "Permission Overlap",
+            RiskFactorType::ExcessivePrivileges => "Excessive Privileges",
+            RiskFactorType::WeakAccountSecurity => "Weak Account Security",
+            RiskFactorType::CrossDomainAccess => "Cross-Domain Access",
+            RiskFactorType::DataAccess => "Data Access",
+            RiskFactorType::PrivilegeEscalation => "Privilege Escalation",
+            RiskFactorType::IncompleteGroupData => "Incomplete Group Data",
+            RiskFactorType::IdentityDataQuality => "Identity Data Quality",
+            RiskFactorType::PrimaryGroupAnomaly => "Primary Group Anomaly",
+            RiskFactorType::Delegation => "Delegation",
+        }
+    }
+
+    /// One-sentence definition for the report glossary
+    pub fn description(&self) -> &'static str {
+        match self {
+            RiskFactorType::AdministrativeAccess => "Membership in groups that administer the domain or forest, such as Domain Admins.",
+            RiskFactorType::PrivilegedGroups => "Membership in built-in operator groups that can manage servers, backups or accounts.",
+            RiskFactorType::ServiceAccount => "The account looks like a service identity, which often has a static password and broad access.",
+            RiskFactorType::DormantAccount => "The account has not logged on recently but can still authenticate.",
+            RiskFactorType::PermissionOverlap => "Several groups grant the same permission, so removing one does not revoke access.",
+            RiskFactorType::ExcessivePrivileges => "The total number of group memberships is well above what a single role needs.",
+            RiskFactorType::WeakAccountSecurity => "Account flags weaken authentication, e.g. a password that never expires or is not required.",
+            RiskFactorType::CrossDomainAccess => "Membership in groups from other domains in the forest.",
+            RiskFactorType::DataAccess => "Groups that grant access to databases, source code or other sensitive data.",
+            RiskFactorType::PrivilegeEscalation => "Group combinations that allow the account to grant itself further privileges.",
+            RiskFactorType::IncompleteGroupData => "Some nested groups could not be read, so the assessment may understate access.",
+            RiskFactorType::IdentityDataQuality => "Identity attributes are missing or inconsistent, making ownership harder to confirm.",
+            RiskFactorType::PrimaryGroupAnomaly => "The primary group is not Domain Users, which hides membership from member lists.",
+            RiskFactorType::Delegation => "Kerberos delegation lets the account act on behalf of other users to some or all services.",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,17 +341,124 @@ pub struct RiskBreakdown {
 
 pub struct RiskCalculator {
     permission_analyzer: PermissionAnalyzer,
+    hardening_groups: Vec<String>,
+    escalation_rules: Vec<EscalationRule>,
+    custom_rules: Vec<CustomRiskRule>,
+    thresholds: RiskThresholds,
+    identity_check: IdentityQualityCheck,
+    timezone: Tz,
 }
 
 impl RiskCalculator {
+    /// Protected Users (RID 525) hardens credential handling for its members
+    pub const PROTECTED_USERS_GROUP: &'static str = "Protected Users";
+
+    /// Points credited per hardening group membership
+    const HARDENING_CREDIT_PER_GROUP: u8 = 10;
+    /// Passwords expiring within this many days are flagged
+    pub const PASSWORD_EXPIRY_WARNING_DAYS: i64 = 14;
+
+    /// Upper bound on the total reduction from all mitigating factors
+    const MAX_MITIGATION_CREDIT: u8 = 30;
+
+    /// Points added per group homed in a domain other than the user's
+    const FOREIGN_GROUP_RISK: u8 = 8;
+    /// Points added per membership a more privileged group already covers
+    const REDUNDANT_MEMBERSHIP_RISK: u8 = 5;
+    /// Upper bound on the cross-domain membership contribution
+    const MAX_FOREIGN_DOMAIN_RISK: u8 = 30;
+
+    /// Non-default primary group that grants administrative or DC rights
+    const PRIVILEGED_PRIMARY_GROUP_RISK: u8 = 40;
+    /// Any other non-default primary group
+    const PRIMARY_GROUP_ANOMALY_RISK: u8 = 25;
+    /// Domain-relative RIDs whose membership is privileged when used as a primary group
+    const PRIVILEGED_GROUP_RIDS: [u32; 7] = [498, 512, 516, 518, 519, 520, 521];
+
     pub fn new() -> Self {
         Self {
             permission_analyzer: PermissionAnalyzer::new(),
+            hardening_groups: vec![Self::PROTECTED_USERS_GROUP.to_string()],
+            escalation_rules: EscalationRule::defaults(),
+            custom_rules: Vec::new(),
+            thresholds: RiskThresholds::default(),
+            identity_check: IdentityQualityCheck::default(),
+            timezone: Tz::UTC,
+        }
+    }
+
+    /// Render dates in finding descriptions in the given IANA timezone (default: UTC)
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// Use a custom identity data-quality check (e.g., org-specific placeholder patterns)
+    pub fn with_identity_check(mut self, identity_check: IdentityQualityCheck) -> Self {
+        self.identity_check = identity_check;
+        self
+    }
+
+    /// Use custom score bands when mapping scores to risk levels
+    pub fn with_thresholds(mut self, thresholds: RiskThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// Replace the list of groups whose membership reduces the risk score
+    pub fn with_hardening_groups(mut self, hardening_groups: Vec<String>) -> Self {
+        self.hardening_groups = hardening_groups;
+        self
+    }
+
+    /// Replace the toxic group combinations flagged as privilege escalation paths
+    pub fn with_escalation_rules(mut self, escalation_rules: Vec<EscalationRule>) -> Self {
+        self.escalation_rules = escalation_rules;
+        self
+    }
+
+    /// Organization-specific rules evaluated alongside the built-in checks
+    pub fn with_custom_rules(mut self, custom_rules: Vec<CustomRiskRule>) -> Self {
+        self.custom_rules = custom_rules;
+        self
+    }
+
+    /// Attach a `GroupRiskAnnotation` to every group in the user's tree, so exported JSON
+    /// carries the same per-group scoring the assessment used
+    pub fn annotate_groups(&self, user: &mut ADUser) {
+        for group in &mut user.groups {
+            self.annotate_group(group);
+        }
+    }
+
+    fn annotate_group(&self, group: &mut ADGroup) {
+        let (contribution, category) = self.classify_group(group);
+        group.risk = Some(GroupRiskAnnotation {
+            risk_contribution: contribution.risk_contribution,
+            severity: contribution.severity,
+            category,
+            reason: contribution.reason,
+        });
+        for nested in &mut group.nested_groups {
+            self.annotate_group(nested);
         }
     }
 
     /// Calculate comprehensive risk assessment for a user
-    pub fn calculate_risk(&self, user: &ADUser) -> RiskAssessment {
+    ///
+    /// Fails only when data the assessment depends on cannot be used; callers should
+    /// still report on the account without a risk section in that case
+    pub fn calculate_risk(&self, user: &ADUser) -> Result<RiskAssessment> {
+        self.assess(user, None)
+    }
+
+    /// Risk assessment for a computer account: the user assessment of the account plus
+    /// its Kerberos delegation settings
+    pub fn calculate_computer_risk(&self, computer: &ADComputer) -> Result<RiskAssessment> {
+        self.assess(&computer.account, Some(computer))
+    }
+
+    fn assess(&self, user: &ADUser, computer: Option<&ADComputer>) -> Result<RiskAssessment> {
         let mut risk_factors = Vec::new();
         let mut total_risk_score = 0u8;
 
@@ -62,16 +466,33 @@ impl RiskCalculator {
         let overlap_analysis = self.permission_analyzer.analyze_overlaps(user);
         
         // Calculate individual risk components
-        let admin_risk = self.calculate_administrative_risk(user, &mut risk_factors);
+        let classified_groups = self.classify_unique_groups(user);
+        let mut admin_risk = self.calculate_administrative_risk(user, &classified_groups, &mut risk_factors);
+        if let Some(computer) = computer {
+            admin_risk = admin_risk.saturating_add(self.delegation_risk(computer, &mut risk_factors)).min(100);
+        }
         let overlap_risk = self.calculate_overlap_risk(&overlap_analysis, &mut risk_factors);
         let security_risk = self.calculate_account_security_risk(user, &mut risk_factors);
         let activity_risk = self.calculate_activity_risk(user, &mut risk_factors);
 
+        // Custom rules add to the component their factor type belongs to
+        let custom = self.calculate_custom_rule_risk(user, &mut risk_factors);
+        let admin_risk = admin_risk.saturating_add(custom.administrative_risk).min(100);
+        let overlap_risk = overlap_risk.saturating_add(custom.permission_overlap_risk).min(100);
+        let security_risk = security_risk.saturating_add(custom.account_security_risk).min(100);
+        let activity_risk = activity_risk.saturating_add(custom.activity_risk).min(100);
+
         // Combine risk scores with weights
         total_risk_score = self.combine_risk_scores(admin_risk, overlap_risk, security_risk, activity_risk);
 
+        // Credit good hygiene after combination
+        let unmitigated_score = total_risk_score;
+        let mitigating_factors = self.find_mitigating_factors(user, &risk_factors);
+        total_risk_score = self.apply_mitigations(total_risk_score, &mitigating_factors);
+
         let risk_level = self.determine_risk_level(total_risk_score);
         let recommendations = self.generate_recommendations(user, &risk_factors, &overlap_analysis);
+        let group_contributions = self.rank_group_contributions(classified_groups);
 
         let risk_breakdown = RiskBreakdown {
             administrative_risk: admin_risk,
@@ -80,140 +501,135 @@ impl RiskCalculator {
             activity_risk,
         };
 
-        RiskAssessment {
+        Ok(RiskAssessment {
             overall_score: total_risk_score,
             risk_level,
             contributing_factors: risk_factors,
             recommendations,
             risk_breakdown,
+            unmitigated_score,
+            mitigating_factors,
+            thresholds: self.thresholds,
+            group_contributions,
+            permission_redundancy: PermissionRedundancy {
+                percent: overlap_analysis.redundancy_score,
+                overlapped_permissions: overlap_analysis.overlapped_permissions,
+                total_permissions: overlap_analysis.total_permissions,
+            },
+            dangerous_combinations: DangerousCombinations {
+                shown: overlap_analysis.risk_summary.most_dangerous_combinations,
+                total: overlap_analysis.risk_summary.dangerous_combination_count,
+            },
+        })
+    }
+
+    /// Collect hygiene controls that lower the user's risk
+    fn find_mitigating_factors(&self, user: &ADUser, risk_factors: &[RiskFactor]) -> Vec<MitigatingFactor> {
+        let mut mitigations = Vec::new();
+        let now = Utc::now();
+
+        // Smartcard-required logon on a privileged account
+        if user.smartcard_required && self.is_privileged(risk_factors) {
+            mitigations.push(MitigatingFactor {
+                description: "Smartcard required for interactive logon".to_string(),
+                score_reduction: 10,
+            });
         }
+
+        // Hardening group memberships (e.g., Protected Users)
+        for group_name in self.find_hardening_groups(user) {
+            mitigations.push(MitigatingFactor {
+                description: format!("Member of hardening group '{}'", group_name),
+                score_reduction: Self::HARDENING_CREDIT_PER_GROUP,
+            });
+        }
+
+        // Recently rotated password
+        if let Some(password_last_set) = user.password_last_set {
+            let password_age_days = (now - password_last_set).num_days();
+            if (0..=90).contains(&password_age_days) {
+                mitigations.push(MitigatingFactor {
+                    description: format!("Password rotated {} days ago", password_age_days),
+                    score_reduction: 5,
+                });
+            }
+        }
+
+        // Account expiry date set
+        if let Some(account_expires) = user.account_expires {
+            if account_expires > now {
+                mitigations.push(MitigatingFactor {
+                    description: format!("Account expires on {}", ad_time::format_date(&account_expires, self.timezone)),
+                    score_reduction: 5,
+                });
+            }
+        }
+
+        mitigations
+    }
+
+    /// Reduce the combined score by the (capped) mitigation credit, never below 0
+    fn apply_mitigations(&self, score: u8, mitigations: &[MitigatingFactor]) -> u8 {
+        let credit = mitigations
+            .iter()
+            .fold(0u8, |acc, m| acc.saturating_add(m.score_reduction))
+            .min(Self::MAX_MITIGATION_CREDIT);
+        score.saturating_sub(credit)
+    }
+
+    /// Names of the configured hardening groups the user is a member of
+    fn find_hardening_groups(&self, user: &ADUser) -> Vec<String> {
+        let mut found: Vec<String> = Vec::new();
+
+        for group in user.iter_groups() {
+            let is_hardening = self.hardening_groups
+                .iter()
+                .any(|h| group.name.eq_ignore_ascii_case(h));
+            if is_hardening && !found.iter().any(|f| f.eq_ignore_ascii_case(&group.name)) {
+                found.push(group.name.clone());
+            }
+        }
+
+        found
+    }
+
+    /// Whether administrative or privileged-group factors have been raised
+    fn is_privileged(&self, risk_factors: &[RiskFactor]) -> bool {
+        risk_factors.iter().any(|rf| matches!(
+            rf.factor_type,
+            RiskFactorType::AdministrativeAccess | RiskFactorType::PrivilegedGroups
+        ))
+    }
+
+    fn is_in_protected_users(&self, user: &ADUser) -> bool {
+        user.iter_groups()
+            .any(|g| g.name.eq_ignore_ascii_case(Self::PROTECTED_USERS_GROUP))
     }
 
     /// Calculate risk from administrative group memberships
-    fn calculate_administrative_risk(&self, user: &ADUser, risk_factors: &mut Vec<RiskFactor>) -> u8 {
+    fn calculate_administrative_risk(
+        &self,
+        user: &ADUser,
+        classified_groups: &[(GroupRiskContribution, Option<RiskFactorType>)],
+        risk_factors: &mut Vec<RiskFactor>,
+    ) -> u8 {
         let mut admin_risk = 0u8;
 
-        for group in user.all_groups() {
-            let (risk_contribution, severity) = match group.name.as_str() {
-                name if name.contains("Domain Admins") => {
-                    risk_factors.push(RiskFactor {
-                        factor_type: RiskFactorType::AdministrativeAccess,
-                        description: "Member of Domain Admins group - full domain control".to_string(),
-                        risk_contribution: 90,
-                        severity: RiskLevel::Critical,
-                    });
-                    (90, RiskLevel::Critical)
-                },
-                name if name.contains("Enterprise Admins") => {
-                    risk_factors.push(RiskFactor {
-                        factor_type: RiskFactorType::AdministrativeAccess,
-                        description: "Member of Enterprise Admins group - forest-wide control".to_string(),
-                        risk_contribution: 95,
-                        severity: RiskLevel::Critical,
-                    });
-                    (95, RiskLevel::Critical)
-                },
-                name if name.contains("Schema Admins") => {
-                    risk_factors.push(RiskFactor {
-                        factor_type: RiskFactorType::AdministrativeAccess,
-                        description: "Member of Schema Admins group - can modify AD schema".to_string(),
-                        risk_contribution: 80,
-                        severity: RiskLevel::Critical,
-                    });
-                    (80, RiskLevel::Critical)
-                },
-                name if name.contains("Account Operators") => {
-                    risk_factors.push(RiskFactor {
-                        factor_type: RiskFactorType::PrivilegedGroups,
-                        description: "Member of Account Operators - can manage user accounts".to_string(),
-                        risk_contribution: 60,
-                        severity: RiskLevel::High,
-                    });
-                    (60, RiskLevel::High)
-                },
-                name if name.contains("Server Operators") => {
-                    risk_factors.push(RiskFactor {
-                        factor_type: RiskFactorType::PrivilegedGroups,
-                        description: "Member of Server Operators - can manage domain servers".to_string(),
-                        risk_contribution: 65,
-                        severity: RiskLevel::High,
-                    });
-                    (65, RiskLevel::High)
-                },
-                name if name.contains("Backup Operators") => {
-                    risk_factors.push(RiskFactor {
-                        factor_type: RiskFactorType::PrivilegedGroups,
-                        description: "Member of Backup Operators - backup/restore privileges".to_string(),
-                        risk_contribution: 45,
-                        severity: RiskLevel::Medium,
-                    });
-                    (45, RiskLevel::Medium)
-                },
-                
-                // Enhanced risk assessment for custom business groups
-                name if group.name.to_lowercase().contains("breakglass") || group.name.to_lowercase().contains("emergency") => {
-                    risk_factors.push(RiskFactor {
-                        factor_type: RiskFactorType::AdministrativeAccess,
-                        description: format!("Emergency access group '{}' - critical system access", group.name),
-                        risk_contribution: 70,
-                        severity: RiskLevel::High,
-                    });
-                    (70, RiskLevel::High)
-                },
-                
-                name if group.name.to_lowercase().contains("admin") || group.name.to_lowercase().contains("administrator") => {
-                    let risk = if group.name.to_lowercase().contains("database") || group.name.to_lowercase().contains("db") { 50 } else { 40 };
-                    risk_factors.push(RiskFactor {
-                        factor_type: RiskFactorType::AdministrativeAccess,
-                        description: format!("Administrative group '{}' - elevated privileges", group.name),
-                        risk_contribution: risk,
-                        severity: if risk >= 50 { RiskLevel::High } else { RiskLevel::Medium },
-                    });
-                    (risk, if risk >= 50 { RiskLevel::High } else { RiskLevel::Medium })
-                },
-                
-                name if group.name.to_lowercase().contains("developer") || group.name.to_lowercase().contains("dev") => {
-                    let risk = if group.name.to_lowercase().contains("prod") { 45 } else { 25 };
-                    risk_factors.push(RiskFactor {
-                        factor_type: RiskFactorType::DataAccess,
-                        description: format!("Developer group '{}' - code/system access", group.name),
-                        risk_contribution: risk,
-                        severity: if risk >= 40 { RiskLevel::Medium } else { RiskLevel::Low },
-                    });
-                    (risk, if risk >= 40 { RiskLevel::Medium } else { RiskLevel::Low })
-                },
-                
-                name if group.name.to_lowercase().contains("database") || group.name.to_lowercase().contains("db") => {
-                    let risk = if group.name.to_lowercase().contains("rw") || group.name.to_lowercase().contains("write") { 35 } else { 20 };
-                    if risk >= 30 {
-                        risk_factors.push(RiskFactor {
-                            factor_type: RiskFactorType::DataAccess,
-                            description: format!("Database access group '{}' - sensitive data access", group.name),
-                            risk_contribution: risk,
-                            severity: RiskLevel::Medium,
-                        });
-                    }
-                    (risk, if risk >= 30 { RiskLevel::Medium } else { RiskLevel::Low })
-                },
-                
-                name if group.name.to_lowercase().contains("it") && (group.name.to_lowercase().contains("user") || group.name.to_lowercase().contains("staff")) => {
-                    risk_factors.push(RiskFactor {
-                        factor_type: RiskFactorType::AdministrativeAccess,
-                        description: format!("IT administrative group '{}' - technical privileges", group.name),
-                        risk_contribution: 30,
-                        severity: RiskLevel::Medium,
-                    });
-                    (30, RiskLevel::Medium)
-                },
-                
-                _ => (5, RiskLevel::Low), // Default minor risk for any group membership
-            };
+        for (contribution, factor_type) in classified_groups {
+            if let Some(factor_type) = factor_type {
+                risk_factors.push(RiskFactor {
+                    factor_type: factor_type.clone(),
+                    description: contribution.reason.clone(),
+                    risk_contribution: contribution.risk_contribution,
+                    severity: contribution.severity.clone(),
+                });
+            }
 
-            admin_risk = admin_risk.saturating_add(risk_contribution);
+            admin_risk = admin_risk.saturating_add(contribution.risk_contribution);
         }
         
         // Add risk factor for excessive group memberships
-        let total_groups = user.groups.len() + if user.primary_group.is_some() { 1 } else { 0 };
+        let total_groups = user.direct_group_count() + if user.primary_group.is_some() { 1 } else { 0 };
         if total_groups > 15 {
             risk_factors.push(RiskFactor {
                 factor_type: RiskFactorType::PrivilegeEscalation,
@@ -224,10 +640,236 @@ impl RiskCalculator {
             admin_risk = admin_risk.saturating_add(((total_groups - 15) as u8).min(25));
         }
 
+        // Universal groups span the forest - many of them widens cross-domain exposure
+        let universal_groups = user.group_category_counts().universal;
+        if universal_groups > 3 {
+            let cross_domain_risk = ((universal_groups - 3) * 2).min(10) as u8;
+            risk_factors.push(RiskFactor {
+                factor_type: RiskFactorType::CrossDomainAccess,
+                description: format!("Member of {} Universal groups - access may span the forest", universal_groups),
+                risk_contribution: cross_domain_risk,
+                severity: RiskLevel::Low,
+            });
+            admin_risk = admin_risk.saturating_add(cross_domain_risk);
+        }
+
+        admin_risk = admin_risk.saturating_add(self.escalation_combination_risk(user, risk_factors));
+
+        // Groups homed in another domain grant access across a trust
+        if let Some(cross_domain_risk) = self.foreign_domain_risk(user, risk_factors) {
+            admin_risk = admin_risk.saturating_add(cross_domain_risk);
+        }
+
+        if let Some(primary_group_risk) = self.primary_group_anomaly_risk(user, risk_factors) {
+            admin_risk = admin_risk.saturating_add(primary_group_risk);
+        }
+
         // Cap at 100
         admin_risk.min(100)
     }
 
+    /// Flag each escalation rule whose full group combination the user holds
+    fn escalation_combination_risk(&self, user: &ADUser, risk_factors: &mut Vec<RiskFactor>) -> u8 {
+        let mut escalation_risk = 0u8;
+        for rule in self.matched_escalation_rules(user) {
+            risk_factors.push(RiskFactor {
+                factor_type: RiskFactorType::PrivilegeEscalation,
+                description: format!("Member of {} - {}", rule.groups.join(" + "), rule.description),
+                risk_contribution: rule.risk_contribution,
+                severity: rule.severity.clone(),
+            });
+            escalation_risk = escalation_risk.saturating_add(rule.risk_contribution);
+        }
+        escalation_risk
+    }
+
+    /// Escalation rules whose full group combination is among the user's effective memberships
+    fn matched_escalation_rules(&self, user: &ADUser) -> Vec<&EscalationRule> {
+        let group_names: HashSet<String> = user.iter_groups().map(|g| g.name.to_lowercase()).collect();
+        self.escalation_rules.iter().filter(|r| r.matches(&group_names)).collect()
+    }
+
+    /// Flag a primaryGroupID other than the account type's default; membership through the
+    /// primary group is not listed in memberOf, which makes it a way to hide privileges
+    fn primary_group_anomaly_risk(&self, user: &ADUser, risk_factors: &mut Vec<RiskFactor>) -> Option<u8> {
+        let rid = user.anomalous_primary_group_id()?;
+        let name = user.primary_group.as_ref()
+            .map(|g| g.name.clone())
+            .or_else(|| well_known_rid_name(rid).map(str::to_string))
+            .unwrap_or_else(|| "unknown group".to_string());
+        let expected: Vec<String> = user.expected_primary_group_rids().iter()
+            .map(|rid| format!("{} ({})", well_known_rid_name(*rid).unwrap_or("unknown"), rid))
+            .collect();
+
+        let risk = if Self::PRIVILEGED_GROUP_RIDS.contains(&rid) {
+            Self::PRIVILEGED_PRIMARY_GROUP_RISK
+        } else {
+            Self::PRIMARY_GROUP_ANOMALY_RISK
+        };
+        risk_factors.push(RiskFactor {
+            factor_type: RiskFactorType::PrimaryGroupAnomaly,
+            description: format!(
+                "Primary group is {} (RID {}) instead of {} - this membership is hidden from memberOf",
+                name, rid, expected.join(" or ")
+            ),
+            risk_contribution: risk,
+            severity: RiskLevel::High,
+        });
+        Some(risk)
+    }
+
+    /// Flag memberships in groups whose DN places them outside the user's own domain
+    fn foreign_domain_risk(&self, user: &ADUser, risk_factors: &mut Vec<RiskFactor>) -> Option<u8> {
+        let home = user.domain()?;
+
+        let mut seen = HashSet::new();
+        let mut foreign_groups = 0usize;
+        let mut foreign_domains = BTreeSet::new();
+        for group in user.iter_groups().filter(|g| seen.insert(g.distinguished_name.to_lowercase())) {
+            match group.domain() {
+                Some(domain) if domain != home => {
+                    foreign_groups += 1;
+                    foreign_domains.insert(domain);
+                }
+                _ => {}
+            }
+        }
+        if foreign_groups == 0 {
+            return None;
+        }
+
+        let cross_domain_risk = (foreign_groups * Self::FOREIGN_GROUP_RISK as usize).min(Self::MAX_FOREIGN_DOMAIN_RISK as usize) as u8;
+        let severity = match foreign_groups {
+            1 => RiskLevel::Low,
+            2..=4 => RiskLevel::Medium,
+            _ => RiskLevel::High,
+        };
+        risk_factors.push(RiskFactor {
+            factor_type: RiskFactorType::CrossDomainAccess,
+            description: format!(
+                "Member of {} group(s) from other domains ({}) - access crosses domain/forest trusts",
+                foreign_groups,
+                foreign_domains.into_iter().collect::<Vec<_>>().join(", ")
+            ),
+            risk_contribution: cross_domain_risk,
+            severity,
+        });
+        Some(cross_domain_risk)
+    }
+
+    /// Risk a single group membership adds on its own (ignores nesting and other memberships)
+    pub fn group_contribution(&self, group: &ADGroup) -> GroupRiskContribution {
+        self.classify_group(group).0
+    }
+
+    /// Risk a single group membership adds, and the factor type it is reported under
+    /// (None for memberships too minor to list as a contributing factor)
+    fn classify_group(&self, group: &ADGroup) -> (GroupRiskContribution, Option<RiskFactorType>) {
+        let name = group.name.as_str();
+        let lower = name.to_lowercase();
+
+        let (factor_type, reason, risk_contribution, severity) = if name.contains("Domain Admins") {
+            (Some(RiskFactorType::AdministrativeAccess), "Member of Domain Admins group - full domain control".to_string(), 90, RiskLevel::Critical)
+        } else if name.contains("Enterprise Admins") {
+            (Some(RiskFactorType::AdministrativeAccess), "Member of Enterprise Admins group - forest-wide control".to_string(), 95, RiskLevel::Critical)
+        } else if name.contains("Schema Admins") {
+            (Some(RiskFactorType::AdministrativeAccess), "Member of Schema Admins group - can modify AD schema".to_string(), 80, RiskLevel::Critical)
+        } else if name.contains("Account Operators") {
+            (Some(RiskFactorType::PrivilegedGroups), "Member of Account Operators - can manage user accounts".to_string(), 60, RiskLevel::High)
+        } else if name.contains("Server Operators") {
+            (Some(RiskFactorType::PrivilegedGroups), "Member of Server Operators - can manage domain servers".to_string(), 65, RiskLevel::High)
+        } else if name.contains("Backup Operators") {
+            (Some(RiskFactorType::PrivilegedGroups), "Member of Backup Operators - backup/restore privileges".to_string(), 45, RiskLevel::Medium)
+
+        // Enhanced risk assessment for custom business groups
+        } else if lower.contains("breakglass") || lower.contains("emergency") {
+            (Some(RiskFactorType::AdministrativeAccess), format!("Emergency access group '{}' - critical system access", name), 70, RiskLevel::High)
+        } else if lower.contains("admin") || lower.contains("administrator") {
+            let risk = if lower.contains("database") || lower.contains("db") { 50 } else { 40 };
+            let severity = if risk >= 50 { RiskLevel::High } else { RiskLevel::Medium };
+            (Some(RiskFactorType::AdministrativeAccess), format!("Administrative group '{}' - elevated privileges", name), risk, severity)
+        } else if lower.contains("developer") || lower.contains("dev") {
+            let risk = if lower.contains("prod") { 45 } else { 25 };
+            let severity = if risk >= 40 { RiskLevel::Medium } else { RiskLevel::Low };
+            (Some(RiskFactorType::DataAccess), format!("Developer group '{}' - code/system access", name), risk, severity)
+        } else if lower.contains("database") || lower.contains("db") {
+            if lower.contains("rw") || lower.contains("write") {
+                (Some(RiskFactorType::DataAccess), format!("Database access group '{}' - sensitive data access", name), 35, RiskLevel::Medium)
+            } else {
+                (None, format!("Database access group '{}' - read access", name), 20, RiskLevel::Low)
+            }
+        } else if lower.contains("it") && (lower.contains("user") || lower.contains("staff")) {
+            (Some(RiskFactorType::AdministrativeAccess), format!("IT administrative group '{}' - technical privileges", name), 30, RiskLevel::Medium)
+        } else {
+            // Default minor risk for any group membership
+            (None, format!("Group '{}' - standard membership", name), 5, RiskLevel::Low)
+        };
+
+        let contribution = GroupRiskContribution {
+            group_name: group.name.clone(),
+            distinguished_name: group.distinguished_name.clone(),
+            risk_contribution,
+            severity,
+            reason,
+        };
+        (contribution, factor_type)
+    }
+
+    /// Classify each effective group once, however many paths lead to it
+    fn classify_unique_groups(&self, user: &ADUser) -> Vec<(GroupRiskContribution, Option<RiskFactorType>)> {
+        let mut seen = HashSet::with_capacity(user.group_count());
+        user.iter_groups()
+            .filter(|g| seen.insert(g.distinguished_name.to_lowercase()))
+            .map(|g| self.classify_group(g))
+            .collect()
+    }
+
+    /// Every effective group membership ranked by its individual contribution, highest first
+    fn rank_group_contributions(&self, classified_groups: Vec<(GroupRiskContribution, Option<RiskFactorType>)>) -> Vec<GroupRiskContribution> {
+        let mut ranked: Vec<GroupRiskContribution> = classified_groups
+            .into_iter()
+            .map(|(contribution, _)| contribution)
+            .collect();
+
+        ranked.sort_by(|a, b| b.risk_contribution.cmp(&a.risk_contribution)
+            .then_with(|| a.group_name.cmp(&b.group_name)));
+        ranked
+    }
+
+    /// Kerberos delegation on a computer other than a domain controller (DCs are trusted
+    /// for unconstrained delegation by design)
+    fn delegation_risk(&self, computer: &ADComputer, risk_factors: &mut Vec<RiskFactor>) -> u8 {
+        let delegation = &computer.delegation;
+        if computer.is_domain_controller || !delegation.is_configured() {
+            return 0;
+        }
+
+        let mut findings = Vec::new();
+        if delegation.unconstrained {
+            findings.push(("Unconstrained delegation - tickets of any user authenticating to this host can be reused domain-wide".to_string(), 70, RiskLevel::Critical));
+        }
+        if delegation.protocol_transition {
+            findings.push(("Constrained delegation with protocol transition - can impersonate any user to the allowed services".to_string(), 40, RiskLevel::High));
+        } else if !delegation.constrained_to.is_empty() {
+            findings.push((format!("Constrained delegation to {} service(s)", delegation.constrained_to.len()), 20, RiskLevel::Medium));
+        }
+        if delegation.resource_based {
+            findings.push(("Resource-based delegation configured - other accounts may impersonate users to this host".to_string(), 15, RiskLevel::Low));
+        }
+
+        let mut risk = 0u8;
+        for (description, risk_contribution, severity) in findings {
+            risk = risk.saturating_add(risk_contribution);
+            risk_factors.push(RiskFactor {
+                factor_type: RiskFactorType::Delegation,
+                description,
+                risk_contribution,
+                severity,
+            });
+        }
+        risk
+    }
+
     /// Calculate risk from permission overlaps
     fn calculate_overlap_risk(&self, overlap_analysis: &OverlapAnalysis, risk_factors: &mut Vec<RiskFactor>) -> u8 {
         let mut overlap_risk = 0u8;
@@ -264,8 +906,19 @@ impl RiskCalculator {
             });
         }
 
+        // Memberships a more privileged group already covers: trimming them loses nothing
+        for membership in &overlap_analysis.redundant_memberships {
+            overlap_risk = overlap_risk.saturating_add(Self::REDUNDANT_MEMBERSHIP_RISK);
+            risk_factors.push(RiskFactor {
+                factor_type: RiskFactorType::PermissionOverlap,
+                description: membership.describe(),
+                risk_contribution: Self::REDUNDANT_MEMBERSHIP_RISK,
+                severity: RiskLevel::Medium,
+            });
+        }
+
         // High redundancy risk
-        if overlap_analysis.redundancy_score > 50.0 {
+        if overlap_analysis.redundancy_score > PermissionRedundancy::HIGH_PERCENT {
             risk_factors.push(RiskFactor {
                 factor_type: RiskFactorType::ExcessivePrivileges,
                 description: format!("High permission redundancy: {:.1}%", overlap_analysis.redundancy_score),
@@ -292,9 +945,56 @@ impl RiskCalculator {
             });
         }
 
+        // Password about to expire (computed expiry honors fine-grained policies)
+        if let Some(password_expiry) = user.password_expiry {
+            let days_until_expiry = (password_expiry - Utc::now()).num_days();
+            if user.account_enabled && (0..=Self::PASSWORD_EXPIRY_WARNING_DAYS).contains(&days_until_expiry) {
+                security_risk = security_risk.saturating_add(5);
+                risk_factors.push(RiskFactor {
+                    factor_type: RiskFactorType::WeakAccountSecurity,
+                    description: format!("Password expires within {} days", days_until_expiry),
+                    risk_contribution: 5,
+                    severity: RiskLevel::Low,
+                });
+            }
+        }
+
+        // Past expiry but still enabled: whoever knows the old password can set a new one
+        if user.account_enabled && user.is_password_expired(Utc::now()) {
+            security_risk = security_risk.saturating_add(15);
+            risk_factors.push(RiskFactor {
+                factor_type: RiskFactorType::WeakAccountSecurity,
+                description: "Password has expired but the account is still enabled".to_string(),
+                risk_contribution: 15,
+                severity: RiskLevel::Medium,
+            });
+        }
+
+        // The policy in effect (a PSO over the domain default) lets the password be simple
+        if let Some(policy) = user.password_policy.as_ref().filter(|p| p.complexity_required == Some(false)) {
+            security_risk = security_risk.saturating_add(10);
+            risk_factors.push(RiskFactor {
+                factor_type: RiskFactorType::WeakAccountSecurity,
+                description: format!("Password complexity not required ({})", policy.source),
+                risk_contribution: 10,
+                severity: RiskLevel::Low,
+            });
+        }
+
+        // No Kerberos preauthentication: anyone can request an AS-REP and crack it offline
+        if user.no_preauth_required {
+            security_risk = security_risk.saturating_add(40);
+            risk_factors.push(RiskFactor {
+                factor_type: RiskFactorType::WeakAccountSecurity,
+                description: "Kerberos preauthentication not required (AS-REP roastable)".to_string(),
+                risk_contribution: 40,
+                severity: RiskLevel::High,
+            });
+        }
+
         // Account disabled but with high privileges
-        if !user.account_enabled && !user.all_groups().is_empty() {
-            let disabled_risk = if user.all_groups().iter().any(|g| g.name.contains("Admin")) { 40 } else { 20 };
+        if !user.account_enabled && user.has_groups() {
+            let disabled_risk = if user.iter_groups().any(|g| g.name.contains("Admin")) { 40 } else { 20 };
             security_risk = security_risk.saturating_add(disabled_risk);
             risk_factors.push(RiskFactor {
                 factor_type: RiskFactorType::DormantAccount,
@@ -315,6 +1015,77 @@ impl RiskCalculator {
             });
         }
 
+        // Privileged accounts should be in Protected Users
+        if self.is_privileged(risk_factors) && !self.is_in_protected_users(user) {
+            security_risk = security_risk.saturating_add(10);
+            risk_factors.push(RiskFactor {
+                factor_type: RiskFactorType::WeakAccountSecurity,
+                description: "Privileged account is not a member of Protected Users".to_string(),
+                risk_contribution: 10,
+                severity: RiskLevel::Low,
+            });
+        }
+
+        // Administrative accounts should require a smartcard
+        let is_admin = risk_factors.iter().any(|rf| matches!(rf.factor_type, RiskFactorType::AdministrativeAccess));
+        if is_admin && !user.smartcard_required {
+            security_risk = security_risk.saturating_add(5);
+            risk_factors.push(RiskFactor {
+                factor_type: RiskFactorType::WeakAccountSecurity,
+                description: "Administrative account does not require smartcard logon".to_string(),
+                risk_contribution: 5,
+                severity: RiskLevel::Low,
+            });
+        }
+
+        // Privileged logons outside working hours are harder to spot and rarely needed
+        if user.account_enabled && self.is_privileged(risk_factors) && !user.has_logon_hour_restriction() {
+            security_risk = security_risk.saturating_add(5);
+            risk_factors.push(RiskFactor {
+                factor_type: RiskFactorType::WeakAccountSecurity,
+                description: "Privileged account has no logon-hour restriction".to_string(),
+                risk_contribution: 5,
+                severity: RiskLevel::Low,
+            });
+        }
+
+        // Unresolved groups may hide privileged memberships
+        if !user.resolution_warnings.is_empty() {
+            security_risk = security_risk.saturating_add(10);
+            risk_factors.push(RiskFactor {
+                factor_type: RiskFactorType::IncompleteGroupData,
+                description: format!("{} group(s) could not be resolved - memberships may be under-reported", user.resolution_warnings.len()),
+                risk_contribution: 10,
+                severity: RiskLevel::Medium,
+            });
+        }
+
+        // A privileged-looking account with no groups at all usually means the
+        // memberships could not be read, so the score above may be understated
+        if self.looks_privileged_without_groups(user) {
+            security_risk = security_risk.saturating_add(20);
+            risk_factors.push(RiskFactor {
+                factor_type: RiskFactorType::IncompleteGroupData,
+                description: "Account looks privileged but no group memberships were returned".to_string(),
+                risk_contribution: 20,
+                severity: RiskLevel::Medium,
+            });
+        }
+
+        // Privileged, enabled accounts should be attributable to a person (computers have no owner fields)
+        if user.account_enabled && user.non_user_kind().is_none() && self.is_privileged(risk_factors) {
+            let issues = self.identity_check.issues(user);
+            if !issues.is_empty() {
+                security_risk = security_risk.saturating_add(5);
+                risk_factors.push(RiskFactor {
+                    factor_type: RiskFactorType::IdentityDataQuality,
+                    description: format!("Privileged account has unclear ownership: {}", issues.join("; ")),
+                    risk_contribution: 5,
+                    severity: RiskLevel::Low,
+                });
+            }
+        }
+
         // Service account indicators
         if self.is_service_account(user) {
             security_risk = security_risk.saturating_add(25);
@@ -338,7 +1109,7 @@ impl RiskCalculator {
         if let Some(last_logon) = user.last_logon {
             let days_since_logon = (now - last_logon).num_days();
             
-            if days_since_logon > 90 && !user.all_groups().is_empty() {
+            if days_since_logon > 90 && user.has_groups() {
                 let dormant_risk = if days_since_logon > 365 { 50 } else { 30 };
                 activity_risk = activity_risk.saturating_add(dormant_risk);
                 
@@ -351,7 +1122,7 @@ impl RiskCalculator {
             }
         } else {
             // Never logged on but has privileges
-            if !user.all_groups().is_empty() {
+            if user.has_groups() {
                 activity_risk = activity_risk.saturating_add(40);
                 risk_factors.push(RiskFactor {
                     factor_type: RiskFactorType::DormantAccount,
@@ -365,6 +1136,39 @@ impl RiskCalculator {
         activity_risk.min(100)
     }
 
+    /// Risk from matching custom rules, split by score component
+    fn calculate_custom_rule_risk(&self, user: &ADUser, risk_factors: &mut Vec<RiskFactor>) -> RiskBreakdown {
+        let mut risk = RiskBreakdown {
+            administrative_risk: 0,
+            permission_overlap_risk: 0,
+            account_security_risk: 0,
+            activity_risk: 0,
+        };
+        let now = Utc::now();
+
+        for factor in self.custom_rules.iter().filter_map(|rule| rule.evaluate(user, now)) {
+            let component = match factor.factor_type {
+                RiskFactorType::PermissionOverlap | RiskFactorType::ExcessivePrivileges => &mut risk.permission_overlap_risk,
+                RiskFactorType::ServiceAccount
+                | RiskFactorType::WeakAccountSecurity
+                | RiskFactorType::IncompleteGroupData
+                | RiskFactorType::IdentityDataQuality => &mut risk.account_security_risk,
+                RiskFactorType::DormantAccount => &mut risk.activity_risk,
+                RiskFactorType::AdministrativeAccess
+                | RiskFactorType::PrivilegedGroups
+                | RiskFactorType::CrossDomainAccess
+                | RiskFactorType::DataAccess
+                | RiskFactorType::PrivilegeEscalation
+                | RiskFactorType::PrimaryGroupAnomaly
+                | RiskFactorType::Delegation => &mut risk.administrative_risk,
+            };
+            *component = component.saturating_add(factor.risk_contribution);
+            risk_factors.push(factor);
+        }
+
+        risk
+    }
+
     /// Combine risk scores with appropriate weights
     fn combine_risk_scores(&self, admin_risk: u8, overlap_risk: u8, security_risk: u8, activity_risk: u8) -> u8 {
         // Weighted combination: admin risk has highest weight
@@ -378,12 +1182,7 @@ impl RiskCalculator {
 
     /// Determine overall risk level from score
     fn determine_risk_level(&self, score: u8) -> RiskLevel {
-        match score {
-            80..=100 => RiskLevel::Critical,
-            60..=79 => RiskLevel::High,
-            30..=59 => RiskLevel::Medium,
-            _ => RiskLevel::Low,
-        }
+        self.thresholds.level_for(score)
     }
 
     /// Generate recommendations based on risk factors
@@ -397,23 +1196,67 @@ impl RiskCalculator {
             recommendations.push("Implement just-in-time access for administrative tasks".to_string());
         }
 
+        for membership in &overlap_analysis.redundant_memberships {
+            recommendations.push(format!(
+                "Remove the {} membership; {} already grants its access",
+                membership.redundant_group, membership.covered_by
+            ));
+        }
+
         // Permission overlap recommendations
-        if overlap_analysis.redundancy_score > 30.0 {
+        if overlap_analysis.redundancy_score > PermissionRedundancy::REVIEW_PERCENT {
             recommendations.push("Remove redundant group memberships".to_string());
             recommendations.push("Implement principle of least privilege".to_string());
         }
 
         // Account security recommendations
+        if self.is_privileged(risk_factors) && !self.is_in_protected_users(user) {
+            recommendations.push("Add privileged accounts to the Protected Users group".to_string());
+        }
+
         if user.password_never_expires {
             recommendations.push("Enable password expiration policy".to_string());
         }
 
+        if user.account_enabled && self.is_privileged(risk_factors) && !user.has_logon_hour_restriction() {
+            recommendations.push("Limit privileged account logon hours to when administration is expected".to_string());
+        }
+
+        if user.no_preauth_required {
+            recommendations.push("Clear 'Do not require Kerberos preauthentication' on this account".to_string());
+        }
+
         // Dormant account recommendations
         if risk_factors.iter().any(|rf| matches!(rf.factor_type, RiskFactorType::DormantAccount)) {
             recommendations.push("Disable or remove unused accounts".to_string());
             recommendations.push("Implement regular account review processes".to_string());
         }
 
+        if risk_factors.iter().any(|rf| matches!(rf.factor_type, RiskFactorType::Delegation)) {
+            recommendations.push("Replace unconstrained delegation with constrained or resource-based delegation limited to the services needed".to_string());
+            recommendations.push("Mark privileged accounts as sensitive or add them to Protected Users so their credentials cannot be delegated".to_string());
+        }
+
+        if user.anomalous_primary_group_id().is_some() {
+            recommendations.push("Reset primaryGroupID to the default for the account and grant access through explicit group memberships".to_string());
+        }
+
+        if !self.matched_escalation_rules(user).is_empty() {
+            recommendations.push("Split toxic group combinations across separate accounts or remove one of the memberships".to_string());
+        }
+
+        if risk_factors.iter().any(|rf| matches!(rf.factor_type, RiskFactorType::CrossDomainAccess)) {
+            recommendations.push("Confirm memberships granted through other domains or forests are still required".to_string());
+        }
+
+        if risk_factors.iter().any(|rf| matches!(rf.factor_type, RiskFactorType::IncompleteGroupData)) {
+            recommendations.push("Verify group memberships directly in AD; the report may be missing privileged groups".to_string());
+        }
+
+        if risk_factors.iter().any(|rf| matches!(rf.factor_type, RiskFactorType::IdentityDataQuality)) {
+            recommendations.push("Record a real display name and email so the account owner can be identified".to_string());
+        }
+
         // Service account recommendations
         if risk_factors.iter().any(|rf| matches!(rf.factor_type, RiskFactorType::ServiceAccount)) {
             recommendations.push("Use Managed Service Accounts where possible".to_string());
@@ -427,6 +1270,22 @@ impl RiskCalculator {
         recommendations
     }
 
+    /// Account has no memberOf or primary group, yet its identity suggests admin use
+    fn looks_privileged_without_groups(&self, user: &ADUser) -> bool {
+        if !user.groups.is_empty() || user.primary_group.is_some() {
+            return false;
+        }
+
+        // Built-in Administrator (RID 500) or admin-style naming
+        let is_builtin_admin = user.object_sid.as_deref().and_then(rid_of) == Some(500);
+        let admin_named = [Some(&user.sam_account_name), user.title.as_ref(), user.description.as_ref()]
+            .into_iter()
+            .flatten()
+            .any(|value| value.to_lowercase().contains("admin"));
+
+        is_builtin_admin || admin_named
+    }
+
     /// Check if account appears to be a service account
     fn is_service_account(&self, user: &ADUser) -> bool {
         // Service account indicators
@@ -0,0 +1,160 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use crate::models::ADUser;
+use crate::permission_analyzer::RiskLevel;
+use crate::risk_calculator::{RiskFactor, RiskFactorType};
+
+/// userAccountControl-derived account flags a rule can test for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UacFlag {
+    Disabled,
+    Locked,
+    PasswordExpired,
+    PasswordNeverExpires,
+    SmartcardRequired,
+    NoPreauthRequired,
+}
+
+impl UacFlag {
+    fn is_set(self, user: &ADUser) -> bool {
+        match self {
+            UacFlag::Disabled => !user.account_enabled,
+            UacFlag::Locked => user.account_locked,
+            UacFlag::PasswordExpired => user.password_expired,
+            UacFlag::PasswordNeverExpires => user.password_never_expires,
+            UacFlag::SmartcardRequired => user.smartcard_required,
+            UacFlag::NoPreauthRequired => user.no_preauth_required,
+        }
+    }
+}
+
+/// Conditions of a custom rule; every condition that is set must hold
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuleConditions {
+    /// Case-insensitive regex matched against the names of all effective groups
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uac_flag: Option<UacFlag>,
+    /// No logon for at least this many days (an account that never logged on qualifies)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inactive_days: Option<i64>,
+}
+
+/// An organization-specific risk factor, e.g. "member of Legacy-VPN: +20, High"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomRiskRule {
+    pub name: String,
+    pub when: RuleConditions,
+    pub description: String,
+    pub risk_contribution: u8,                // 0-100
+    pub severity: RiskLevel,
+    pub factor_type: RiskFactorType,
+    #[serde(skip)]
+    group_regex: Option<Regex>,
+}
+
+/// Top level of a rules file: one `[[rule]]` table per rule
+#[derive(Debug, Deserialize)]
+struct RulesFile {
+    #[serde(default, rename = "rule")]
+    rules: Vec<CustomRiskRule>,
+}
+
+impl CustomRiskRule {
+    /// Read rules from a TOML file:
+    ///
+    /// ```toml
+    /// [[rule]]
+    /// name = "Legacy VPN"
+    /// when = { group = "^Legacy-VPN$" }
+    /// description = "access through the unsupported VPN concentrator"
+    /// risk_contribution = 20
+    /// severity = "High"
+    /// factor_type = "PrivilegedGroups"
+    /// ```
+    pub fn from_file(path: &Path) -> Result<Vec<Self>> {
+        let contents = fs::read_to_string(path)
+            .context(format!("Failed to read risk rules file: {}", path.display()))?;
+        Self::from_toml(&contents)
+            .context(format!("Invalid risk rules file: {}", path.display()))
+    }
+
+    /// Parse and validate rules from TOML text in the `from_file` format
+    pub fn from_toml(contents: &str) -> Result<Vec<Self>> {
+        let mut rules = toml::from_str::<RulesFile>(contents)
+            .context("Failed to parse risk rules")?
+            .rules;
+
+        for rule in &mut rules {
+            rule.validate()?;
+        }
+        Ok(rules)
+    }
+
+    /// Check the rule and compile its group pattern
+    fn validate(&mut self) -> Result<()> {
+        let when = &self.when;
+        if when.group.is_none() && when.uac_flag.is_none() && when.inactive_days.is_none() {
+            return Err(anyhow::anyhow!(
+                "Risk rule '{}' needs at least one condition (group, uac_flag or inactive_days)", self.name));
+        }
+        if self.risk_contribution > 100 {
+            return Err(anyhow::anyhow!(
+                "Risk rule '{}' has risk contribution {} (expected 0-100)", self.name, self.risk_contribution));
+        }
+        if when.inactive_days.is_some_and(|days| days < 0) {
+            return Err(anyhow::anyhow!("Risk rule '{}' has a negative inactive_days", self.name));
+        }
+
+        self.group_regex = when.group.as_deref()
+            .map(|pattern| RegexBuilder::new(pattern).case_insensitive(true).build())
+            .transpose()
+            .context(format!("Invalid group pattern in risk rule '{}'", self.name))?;
+        Ok(())
+    }
+
+    /// The factor this rule raises for `user`, if all of its conditions hold at `now`
+    pub fn evaluate(&self, user: &ADUser, now: DateTime<Utc>) -> Option<RiskFactor> {
+        let mut matched_groups = Vec::new();
+        if let Some(regex) = &self.group_regex {
+            for group in user.all_groups() {
+                if regex.is_match(&group.name) && !matched_groups.contains(&group.name) {
+                    matched_groups.push(group.name.clone());
+                }
+            }
+            if matched_groups.is_empty() {
+                return None;
+            }
+        }
+
+        if self.when.uac_flag.is_some_and(|flag| !flag.is_set(user)) {
+            return None;
+        }
+
+        if let Some(days) = self.when.inactive_days {
+            let inactive = user.last_logon.is_none_or(|last| (now - last).num_days() >= days);
+            if !inactive {
+                return None;
+            }
+        }
+
+        let description = if matched_groups.is_empty() {
+            format!("{}: {}", self.name, self.description)
+        } else {
+            format!("{}: {} ({})", self.name, self.description, matched_groups.join(", "))
+        };
+
+        Some(RiskFactor {
+            factor_type: self.factor_type.clone(),
+            description,
+            risk_contribution: self.risk_contribution,
+            severity: self.severity.clone(),
+        })
+    }
+}
@@ -0,0 +1,100 @@
+use anyhow::{Context, Result};
+
+/// Convert a binary security identifier (as stored in objectSid) to its
+/// canonical string form, e.g. "S-1-5-21-3623811015-3361044348-30300820-512"
+///
+/// Layout: revision (1 byte), sub-authority count (1 byte), identifier
+/// authority (6 bytes, big-endian), then count x sub-authorities (u32, little-endian)
+pub fn binary_sid_to_string(bytes: &[u8]) -> Result<String> {
+    if bytes.len() < 8 {
+        return Err(anyhow::anyhow!("SID is too short ({} bytes, need at least 8)", bytes.len()));
+    }
+
+    let revision = bytes[0];
+    let sub_authority_count = bytes[1] as usize;
+    let expected_len = 8 + sub_authority_count * 4;
+    if bytes.len() != expected_len {
+        return Err(anyhow::anyhow!(
+            "SID length mismatch: {} sub-authorities need {} bytes, got {}",
+            sub_authority_count, expected_len, bytes.len()
+        ));
+    }
+
+    let authority = bytes[2..8]
+        .iter()
+        .fold(0u64, |acc, b| (acc << 8) | *b as u64);
+
+    // Authorities that don't fit in 32 bits are written in hex
+    let mut sid = if authority >= 1 << 32 {
+        format!("S-{}-0x{:012X}", revision, authority)
+    } else {
+        format!("S-{}-{}", revision, authority)
+    };
+
+    for chunk in bytes[8..].chunks_exact(4) {
+        let sub_authority = u32::from_le_bytes(
+            chunk.try_into().context("Invalid SID sub-authority")?
+        );
+        sid.push_str(&format!("-{}", sub_authority));
+    }
+
+    Ok(sid)
+}
+
+/// Relative identifier (the last sub-authority) of a string SID
+pub fn rid_of(sid: &str) -> Option<u32> {
+    let parts: Vec<&str> = sid.split('-').collect();
+
+    // "S", revision, authority, and at least one sub-authority
+    if parts.len() < 4 || !parts[0].eq_ignore_ascii_case("S") {
+        return None;
+    }
+
+    parts.last()?.parse().ok()
+}
+
+/// Name of a well-known domain or builtin RID (e.g., 512 -> "Domain Admins")
+pub fn well_known_rid_name(rid: u32) -> Option<&'static str> {
+    let name = match rid {
+        // Domain-relative accounts and groups (S-1-5-21-<domain>-RID)
+        500 => "Administrator",
+        501 => "Guest",
+        502 => "krbtgt",
+        512 => "Domain Admins",
+        513 => "Domain Users",
+        514 => "Domain Guests",
+        515 => "Domain Computers",
+        516 => "Domain Controllers",
+        517 => "Cert Publishers",
+        518 => "Schema Admins",
+        519 => "Enterprise Admins",
+        520 => "Group Policy Creator Owners",
+        521 => "Read-only Domain Controllers",
+        522 => "Cloneable Domain Controllers",
+        525 => "Protected Users",
+        526 => "Key Admins",
+        527 => "Enterprise Key Admins",
+        553 => "RAS and IAS Servers",
+        571 => "Allowed RODC Password Replication Group",
+        572 => "Denied RODC Password Replication Group",
+
+        // Builtin domain groups (S-1-5-32-RID)
+        544 => "Administrators",
+        545 => "Users",
+        546 => "Guests",
+        547 => "Power Users",
+        548 => "Account Operators",
+        549 => "Server Operators",
+        550 => "Print Operators",
+        551 => "Backup Operators",
+        552 => "Replicator",
+        555 => "Remote Desktop Users",
+        556 => "Network Configuration Operators",
+        562 => "Distributed COM Users",
+        580 => "Remote Management Users",
+
+        _ => return None,
+    };
+
+    Some(name)
+}
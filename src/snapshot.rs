@@ -0,0 +1,115 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::models::ADUser;
+use crate::report_data::EnhancedReportData;
+use crate::risk_calculator::RiskAssessment;
+
+/// JSON record of a generated report, kept so the next run can show what changed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportSnapshot {
+    pub generation_time: DateTime<Utc>,
+    pub domain_name: String,
+    pub user: ADUser,
+    pub risk_assessment: Option<RiskAssessment>,
+}
+
+/// Differences between a previous snapshot and the current report
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportChanges {
+    pub previous_generation_time: DateTime<Utc>,
+    pub added_groups: Vec<String>,
+    pub removed_groups: Vec<String>,
+    pub previous_score: Option<u8>,
+    pub current_score: Option<u8>,
+}
+
+impl ReportSnapshot {
+    pub fn from_report(data: &EnhancedReportData) -> Self {
+        Self {
+            generation_time: data.generation_time(),
+            domain_name: data.domain_name().to_string(),
+            user: data.user().clone(),
+            risk_assessment: data.risk_assessment.clone(),
+        }
+    }
+
+    /// Snapshot location for a user inside a baseline directory (one file per SAM name)
+    pub fn path_in(dir: &Path, sam_account_name: &str) -> PathBuf {
+        let file_stem: String = sam_account_name
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+            .collect();
+        dir.join(format!("{}.json", file_stem))
+    }
+
+    /// Load a snapshot, returning None when no previous report exists
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let contents = fs::read_to_string(path)
+            .context(format!("Failed to read snapshot: {}", path.display()))?;
+        let snapshot = serde_json::from_str(&contents)
+            .context(format!("Failed to parse snapshot: {}", path.display()))?;
+
+        Ok(Some(snapshot))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .context(format!("Failed to create baseline directory: {}", parent.display()))?;
+        }
+
+        let json = serde_json::to_string_pretty(self)
+            .context("Failed to serialize snapshot")?;
+        fs::write(path, json)
+            .context(format!("Failed to write snapshot: {}", path.display()))
+    }
+}
+
+impl ReportChanges {
+    /// Compare effective groups (by DN) and risk score against a previous snapshot
+    pub fn between(previous: &ReportSnapshot, current: &EnhancedReportData) -> Self {
+        let previous_groups = Self::group_names_by_dn(&previous.user);
+        let current_groups = Self::group_names_by_dn(current.user());
+
+        let added_groups = current_groups.iter()
+            .filter(|(dn, _)| !previous_groups.contains_key(*dn))
+            .map(|(_, name)| name.clone())
+            .collect();
+        let removed_groups = previous_groups.iter()
+            .filter(|(dn, _)| !current_groups.contains_key(*dn))
+            .map(|(_, name)| name.clone())
+            .collect();
+
+        Self {
+            previous_generation_time: previous.generation_time,
+            added_groups,
+            removed_groups,
+            previous_score: previous.risk_assessment.as_ref().map(|r| r.overall_score),
+            current_score: current.risk_assessment.as_ref().map(|r| r.overall_score),
+        }
+    }
+
+    /// Risk score change, when both reports include a risk assessment
+    pub fn score_delta(&self) -> Option<i16> {
+        match (self.previous_score, self.current_score) {
+            (Some(previous), Some(current)) => Some(current as i16 - previous as i16),
+            _ => None,
+        }
+    }
+
+    fn group_names_by_dn(user: &ADUser) -> BTreeMap<String, String> {
+        user.all_groups()
+            .into_iter()
+            .map(|g| (g.distinguished_name.to_lowercase(), g.name.clone()))
+            .collect()
+    }
+}
@@ -0,0 +1,168 @@
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_socks::tcp::Socks5Stream;
+use crate::error::{AdReportError, Result};
+use crate::ldap_client::TlsMinVersion;
+
+/// How long the tunnel waits for the LDAP client to pick up the forwarded connection
+const ACCEPT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Whether the local end of a tunnel only accepts this process (see `LocalEndpoint`)
+///
+/// Where it does not, anything sent through the tunnel in the clear could be read by another
+/// local process, so simple binds (which carry the password) are refused over it.
+pub const PEER_VERIFIED: bool = cfg!(unix);
+
+/// Reach `host:port` through the SOCKS5 proxy at `proxy` ("host:port") and hand the
+/// connection to ldap3, which can only dial URLs itself; returns the URL to connect to
+///
+/// The proxy resolves `host`, so internal DC names work without local DNS. With `tls`
+/// (the oldest version to accept) the TLS handshake and certificate check against `host`
+/// happen here, and ldap3 speaks plain LDAP to the local end. The local end accepts a
+/// single connection from this process and then closes.
+pub async fn open(proxy: &str, host: &str, port: u16, tls: Option<TlsMinVersion>) -> Result<String> {
+    let stream = Socks5Stream::connect(proxy, (host, port))
+        .await
+        .map_err(|e| match &e {
+            tokio_socks::Error::Io(_) | tokio_socks::Error::ProxyServerUnreachable => AdReportError::ConnectionFailed(format!(
+                "SOCKS5 proxy {} is unreachable: {}", proxy, e
+            )),
+            _ => AdReportError::ConnectionFailed(format!(
+                "SOCKS5 proxy {} could not connect to {}:{}: {}", proxy, host, port, e
+            )),
+        })?;
+
+    let endpoint = LocalEndpoint::bind()
+        .await
+        .map_err(|e| AdReportError::ConnectionFailed(format!("Failed to open a local tunnel endpoint: {}", e)))?;
+    let url = endpoint.url();
+
+    if let Some(min_version) = tls {
+        let tls = tokio_native_tls::TlsConnector::from(min_version.connector()?)
+            .connect(host, stream)
+            .await
+            .map_err(|e| {
+                let message = e.to_string();
+                AdReportError::ConnectionFailed(min_version.explain_rejection(&format!("{}:{}", host, port), &message)
+                    .unwrap_or_else(|| format!("TLS handshake with {}:{} through SOCKS5 proxy {} failed: {}", host, port, proxy, message)))
+            })?;
+        tokio::spawn(forward_once(endpoint, tls));
+    } else {
+        tokio::spawn(forward_once(endpoint, stream));
+    }
+
+    tracing::debug!("Tunnelling {} to {}:{} via SOCKS5 proxy {}", url, host, port, proxy);
+    Ok(url)
+}
+
+/// Accept the LDAP client's connection and copy bytes both ways until either side closes
+async fn forward_once<S>(endpoint: LocalEndpoint, mut remote: S)
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let accepted = tokio::time::timeout(ACCEPT_TIMEOUT, endpoint.accept()).await;
+    drop(endpoint);
+
+    let Ok(Ok(mut local)) = accepted else {
+        tracing::warn!("Tunnel was not used by the LDAP client; closing it");
+        return;
+    };
+    if let Err(e) = tokio::io::copy_bidirectional(&mut local, &mut remote).await {
+        tracing::debug!("Tunnel closed: {}", e);
+    }
+}
+
+/// Unix socket in a fresh directory only this user can enter, reached by ldap3 as `ldapi://`
+///
+/// Connections from any process other than this one (checked through the peer credentials)
+/// are dropped, so another local user can neither read nor race the decrypted stream.
+#[cfg(unix)]
+struct LocalEndpoint {
+    listener: tokio::net::UnixListener,
+    dir: std::path::PathBuf,
+}
+
+#[cfg(unix)]
+impl LocalEndpoint {
+    async fn bind() -> std::io::Result<Self> {
+        use std::os::unix::fs::DirBuilderExt;
+        use std::sync::atomic::{AtomicU64, Ordering};
+        static NEXT_TUNNEL: AtomicU64 = AtomicU64::new(0);
+
+        // `create` (not `create_all`) fails if the path already exists, e.g. as a planted symlink
+        let dir = std::env::temp_dir().join(format!(
+            "ad-report-tunnel-{}-{}", std::process::id(), NEXT_TUNNEL.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::DirBuilder::new().mode(0o700).create(&dir)?;
+        match tokio::net::UnixListener::bind(dir.join("ldap.sock")) {
+            Ok(listener) => Ok(Self { listener, dir }),
+            Err(e) => {
+                let _ = std::fs::remove_dir(&dir);
+                Err(e)
+            }
+        }
+    }
+
+    /// `ldapi://` URL of the socket, with the path percent-encoded as the host
+    fn url(&self) -> String {
+        let path = self.dir.join("ldap.sock");
+        let encoded: String = path.to_string_lossy().bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+                _ => format!("%{:02X}", b),
+            })
+            .collect();
+        format!("ldapi://{}", encoded)
+    }
+
+    async fn accept(&self) -> std::io::Result<tokio::net::UnixStream> {
+        loop {
+            let (stream, _) = self.listener.accept().await?;
+            if Self::is_this_process(&stream) {
+                return Ok(stream);
+            }
+            tracing::warn!("Rejected a connection to the SOCKS5 tunnel from another process");
+        }
+    }
+
+    fn is_this_process(stream: &tokio::net::UnixStream) -> bool {
+        // SAFETY: getuid has no preconditions and cannot fail
+        let uid = unsafe { libc::getuid() };
+        stream.peer_cred().is_ok_and(|cred| {
+            cred.uid() == uid && cred.pid().is_none_or(|pid| pid as u32 == std::process::id())
+        })
+    }
+}
+
+#[cfg(unix)]
+impl Drop for LocalEndpoint {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(self.dir.join("ldap.sock"));
+        let _ = std::fs::remove_dir(&self.dir);
+    }
+}
+
+/// Loopback TCP port (ldap3 has no `ldapi://` on Windows); the peer cannot be verified, so
+/// callers refuse simple binds through it (`PEER_VERIFIED`)
+#[cfg(not(unix))]
+struct LocalEndpoint {
+    listener: tokio::net::TcpListener,
+    port: u16,
+}
+
+#[cfg(not(unix))]
+impl LocalEndpoint {
+    async fn bind() -> std::io::Result<Self> {
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0)).await?;
+        let port = listener.local_addr()?.port();
+        Ok(Self { listener, port })
+    }
+
+    fn url(&self) -> String {
+        format!("ldap://127.0.0.1:{}", self.port)
+    }
+
+    async fn accept(&self) -> std::io::Result<tokio::net::TcpStream> {
+        self.listener.accept().await.map(|(stream, _)| stream)
+    }
+}
@@ -0,0 +1,190 @@
+use anyhow::{Context, Result};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, ErrorKind, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use crate::report_data::EnhancedReportData;
+use crate::risk_calculator::RiskBreakdown;
+
+/// The four sub-scores follow the overall score so GRC tools can pivot on the dimension
+/// that drives each user's risk
+const HEADER: &str = "sam_account_name,status,risk_score,risk_level,\
+administrative_risk,permission_overlap_risk,account_security_risk,activity_risk,output_path,error";
+
+/// Status column of a summary row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SummaryStatus {
+    Ok,
+    Failed,
+    Skipped,
+}
+
+impl SummaryStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SummaryStatus::Ok => "ok",
+            SummaryStatus::Failed => "failed",
+            SummaryStatus::Skipped => "skipped",
+        }
+    }
+}
+
+/// Outcome of one user in a batch, written as a single CSV line
+#[derive(Debug, Clone)]
+pub struct SummaryRow {
+    pub sam_account_name: String,
+    pub status: SummaryStatus,
+    pub risk_score: Option<u8>,
+    pub risk_level: Option<String>,
+    pub risk_breakdown: Option<RiskBreakdown>,
+    pub output_path: Option<String>,
+    pub error: Option<String>,
+}
+
+impl SummaryRow {
+    pub fn success(data: &EnhancedReportData, output_path: &str) -> Self {
+        let risk = data.risk_assessment.as_ref();
+        Self {
+            sam_account_name: data.user().sam_account_name.clone(),
+            status: SummaryStatus::Ok,
+            risk_score: risk.map(|r| r.overall_score),
+            risk_level: risk.map(|r| format!("{:?}", r.risk_level)),
+            risk_breakdown: risk.map(|r| r.risk_breakdown.clone()),
+            output_path: Some(output_path.to_string()),
+            error: None,
+        }
+    }
+
+    pub fn failure(username: &str, error: &anyhow::Error) -> Self {
+        Self {
+            sam_account_name: username.to_string(),
+            status: SummaryStatus::Failed,
+            risk_score: None,
+            risk_level: None,
+            risk_breakdown: None,
+            output_path: None,
+            error: Some(format!("{:#}", error)),
+        }
+    }
+
+    /// A user left out on purpose; `reason` goes in the error column
+    pub fn skipped(username: &str, reason: &str) -> Self {
+        Self {
+            sam_account_name: username.to_string(),
+            status: SummaryStatus::Skipped,
+            risk_score: None,
+            risk_level: None,
+            risk_breakdown: None,
+            output_path: None,
+            error: Some(reason.to_string()),
+        }
+    }
+
+    fn to_csv_line(&self) -> String {
+        let breakdown = self.risk_breakdown.as_ref();
+        let sub_score = |score: fn(&RiskBreakdown) -> u8| breakdown.map(|b| score(b).to_string()).unwrap_or_default();
+        let fields = [
+            self.sam_account_name.clone(),
+            self.status.as_str().to_string(),
+            self.risk_score.map(|s| s.to_string()).unwrap_or_default(),
+            self.risk_level.clone().unwrap_or_default(),
+            sub_score(|b| b.administrative_risk),
+            sub_score(|b| b.permission_overlap_risk),
+            sub_score(|b| b.account_security_risk),
+            sub_score(|b| b.activity_risk),
+            self.output_path.clone().unwrap_or_default(),
+            self.error.clone().unwrap_or_default(),
+        ];
+        let escaped: Vec<String> = fields.iter().map(|f| escape_field(f)).collect();
+        escaped.join(",")
+    }
+}
+
+/// Batch summary CSV that is flushed after every row, so an interrupted run keeps partial results
+///
+/// Rows are written whole under a lock, so the writer can be shared (e.g. in an `Arc`)
+/// between concurrent workers without losing or interleaving lines.
+pub struct SummaryCsvWriter {
+    file: Mutex<BufWriter<File>>,
+}
+
+impl SummaryCsvWriter {
+    /// Open the CSV, appending to an existing file when `append` is set (e.g. with --resume)
+    ///
+    /// An existing file must carry the same header, so rows with different columns are
+    /// never mixed (e.g. a CSV written by a version without the risk breakdown).
+    pub fn create(path: &Path, append: bool) -> Result<Self> {
+        if append {
+            Self::check_header(path)?;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(path)
+            .context(format!("Failed to open summary CSV: {}", path.display()))?;
+
+        let is_empty = file.metadata()
+            .map(|m| m.len() == 0)
+            .unwrap_or(true);
+
+        let writer = Self {
+            file: Mutex::new(BufWriter::new(file)),
+        };
+        if is_empty {
+            writer.write_line(HEADER)?;
+        }
+        Ok(writer)
+    }
+
+    /// Fail if `path` exists, is not empty and starts with a header other than `HEADER`
+    fn check_header(path: &Path) -> Result<()> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e).context(format!("Failed to read summary CSV: {}", path.display())),
+        };
+        let mut header = String::new();
+        BufReader::new(file).read_line(&mut header)
+            .context(format!("Failed to read summary CSV: {}", path.display()))?;
+
+        let header = header.trim_end_matches(['\r', '\n']);
+        if header.is_empty() || header == HEADER {
+            return Ok(());
+        }
+        Err(anyhow::anyhow!(
+            "Summary CSV {} has different columns than this version writes; \
+             pass a new --summary-csv path to resume",
+            path.display()
+        ))
+    }
+
+    pub fn append(&self, row: &SummaryRow) -> Result<()> {
+        self.write_line(&row.to_csv_line())
+    }
+
+    fn write_line(&self, line: &str) -> Result<()> {
+        let mut file = self.file.lock()
+            .map_err(|_| anyhow::anyhow!("Summary CSV writer lock poisoned"))?;
+        writeln!(file, "{}", line)
+            .and_then(|_| file.flush())
+            .context("Failed to write summary CSV row")
+    }
+}
+
+/// Quote fields containing separators, and defuse values a spreadsheet would run as a formula
+fn escape_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
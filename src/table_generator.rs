@@ -0,0 +1,160 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use comfy_table::presets::UTF8_FULL_CONDENSED;
+use comfy_table::{CellAlignment, Table};
+use std::fmt::Write;
+use crate::ad_time;
+use crate::report_data::{EnhancedReportData, ReportSection};
+
+/// Renders the facts of one report as plain terminal tables (`--format table`): identity,
+/// status flags, risk score, top groups and top findings, for quick lookups without a file
+pub struct TableGenerator {
+    timezone: Tz,
+    sections: Vec<ReportSection>,
+}
+
+impl TableGenerator {
+    /// Groups and findings listed, as in the compact PDF layout
+    const TOP_GROUPS: usize = 5;
+    const TOP_FINDINGS: usize = 3;
+
+    pub fn new() -> Self {
+        Self {
+            timezone: Tz::UTC,
+            sections: ReportSection::ALL.to_vec(),
+        }
+    }
+
+    /// Only emit the given report sections (default: all)
+    pub fn with_sections(mut self, sections: Vec<ReportSection>) -> Self {
+        self.sections = sections;
+        self
+    }
+
+    /// Render all timestamps in the given IANA timezone (default: UTC)
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    fn includes(&self, section: ReportSection) -> bool {
+        self.sections.contains(&section)
+    }
+
+    fn table(header: &[&str]) -> Table {
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL_CONDENSED).set_header(header.to_vec());
+        table
+    }
+
+    pub fn generate_report(&self, data: &EnhancedReportData) -> Result<String> {
+        let user = data.user();
+        let or_dash = |value: Option<&String>| value.cloned().unwrap_or_else(|| "-".to_string());
+        let timestamp_or_never = |value: Option<&DateTime<Utc>>| {
+            value.map(|t| ad_time::format_timestamp(t, self.timezone)).unwrap_or_else(|| "Never".to_string())
+        };
+        let mut out = String::new();
+
+        if self.includes(ReportSection::User) {
+            let mut status = vec![if user.account_enabled { "Enabled" } else { "Disabled" }];
+            if user.account_locked {
+                status.push("Locked");
+            }
+            if user.is_password_expired(data.generation_time()) {
+                status.push("Password expired");
+            }
+            if user.password_never_expires {
+                status.push("Password never expires");
+            }
+
+            let mut identity = Self::table(&["Field", "Value"]);
+            identity.add_row(vec!["Account".to_string(), user.sam_account_name.clone()]);
+            match &data.computer {
+                Some(computer) => {
+                    identity.add_row(vec!["Host".to_string(), or_dash(computer.dns_host_name.as_ref())]);
+                    identity.add_row(vec!["Operating System".to_string(), or_dash(computer.operating_system_summary().as_ref())]);
+                    identity.add_row(vec!["Delegation".to_string(), computer.delegation.summary()]);
+                }
+                None => {
+                    identity.add_row(vec!["Name".to_string(), or_dash(user.display_name.as_ref())]);
+                    identity.add_row(vec!["UPN".to_string(), or_dash(user.user_principal_name.as_ref())]);
+                    identity.add_row(vec!["Email".to_string(), or_dash(user.email.as_ref())]);
+                    identity.add_row(vec!["Department".to_string(), or_dash(user.department.as_ref())]);
+                    identity.add_row(vec!["Title".to_string(), or_dash(user.title.as_ref())]);
+                }
+            }
+            identity.add_row(vec!["Domain".to_string(), data.domain_name().to_string()]);
+            identity.add_row(vec!["Status".to_string(), status.join(", ")]);
+            identity.add_row(vec!["Last Logon".to_string(), timestamp_or_never(user.last_logon.as_ref())]);
+            identity.add_row(vec!["Password Last Set".to_string(), timestamp_or_never(user.password_last_set.as_ref())]);
+            writeln!(out, "{}", identity)?;
+        }
+
+        let risk = data.risk_assessment.as_ref().filter(|_| self.includes(ReportSection::Risk));
+        if let Some(risk) = risk {
+            writeln!(out, "Risk Score: {}/100 ({:?})", risk.overall_score, risk.risk_level)?;
+        } else if let Some(reason) = data.risk_unavailable.as_ref().filter(|_| self.includes(ReportSection::Risk)) {
+            writeln!(out, "Risk analysis unavailable: {}", reason)?;
+        }
+
+        if self.includes(ReportSection::Groups) {
+            let all_groups = user.all_groups();
+            writeln!(out, "\nTop Groups ({} direct, {} total)", user.direct_group_count(), all_groups.len())?;
+            match risk.filter(|r| !r.group_contributions.is_empty()) {
+                Some(risk) => {
+                    let mut groups = Self::table(&["Group", "Risk", "Reason"]);
+                    for group in risk.group_contributions.iter().take(Self::TOP_GROUPS) {
+                        groups.add_row(vec![group.group_name.clone(), format!("+{}", group.risk_contribution), group.reason.clone()]);
+                    }
+                    if let Some(column) = groups.column_mut(1) {
+                        column.set_cell_alignment(CellAlignment::Right);
+                    }
+                    writeln!(out, "{}", groups)?;
+                }
+                None if !all_groups.is_empty() => {
+                    let mut groups = Self::table(&["Group", "Details"]);
+                    for group in all_groups.iter().take(Self::TOP_GROUPS) {
+                        groups.add_row(vec![group.name.clone(), group.details()]);
+                    }
+                    writeln!(out, "{}", groups)?;
+                }
+                None => {
+                    writeln!(out, "No group memberships")?;
+                }
+            }
+            if all_groups.len() > Self::TOP_GROUPS {
+                writeln!(out, "(+{} more)", all_groups.len() - Self::TOP_GROUPS)?;
+            }
+        }
+
+        if let Some(risk) = risk {
+            writeln!(out, "\nTop Findings")?;
+            let mut findings: Vec<_> = risk.contributing_factors.iter().collect();
+            findings.sort_by_key(|f| std::cmp::Reverse(f.risk_contribution));
+            if findings.is_empty() {
+                writeln!(out, "No risk factors identified")?;
+            } else {
+                let mut table = Self::table(&["Severity", "Finding", "Points"]);
+                for finding in findings.iter().take(Self::TOP_FINDINGS) {
+                    table.add_row(vec![format!("{:?}", finding.severity), finding.description.clone(), finding.risk_contribution.to_string()]);
+                }
+                if let Some(column) = table.column_mut(2) {
+                    column.set_cell_alignment(CellAlignment::Right);
+                }
+                writeln!(out, "{}", table)?;
+                if findings.len() > Self::TOP_FINDINGS {
+                    writeln!(out, "(+{} more)", findings.len() - Self::TOP_FINDINGS)?;
+                }
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+impl Default for TableGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
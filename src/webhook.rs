@@ -0,0 +1,95 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::time::Duration;
+use crate::permission_analyzer::RiskLevel;
+use crate::report_data::EnhancedReportData;
+
+/// Number of risk findings at each severity
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct FindingCounts {
+    pub critical: usize,
+    pub high: usize,
+    pub medium: usize,
+    pub low: usize,
+}
+
+/// JSON summary of one generated report, POSTed to --webhook-url
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportNotification {
+    pub user: String,
+    pub risk_score: Option<u8>,
+    pub risk_level: Option<String>,
+    pub findings: FindingCounts,
+    pub output_path: String,
+}
+
+impl ReportNotification {
+    pub fn new(data: &EnhancedReportData, output_path: &str) -> Self {
+        let risk = data.risk_assessment.as_ref();
+        let mut findings = FindingCounts::default();
+        for factor in risk.iter().flat_map(|r| &r.contributing_factors) {
+            match factor.severity {
+                RiskLevel::Critical => findings.critical += 1,
+                RiskLevel::High => findings.high += 1,
+                RiskLevel::Medium => findings.medium += 1,
+                RiskLevel::Low => findings.low += 1,
+            }
+        }
+
+        Self {
+            user: data.user().sam_account_name.clone(),
+            risk_score: risk.map(|r| r.overall_score),
+            risk_level: risk.map(|r| format!("{:?}", r.risk_level)),
+            findings,
+            output_path: output_path.to_string(),
+        }
+    }
+}
+
+/// Every report of a run in one payload, sent at the end with --webhook-batch
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchNotification<'a> {
+    pub successful: usize,
+    pub failed: usize,
+    pub reports: &'a [ReportNotification],
+}
+
+/// POSTs JSON summaries to a ticketing/SOAR endpoint
+///
+/// Delivery is best effort: a slow or failing endpoint is logged and never fails the run.
+pub struct Webhook {
+    client: reqwest::Client,
+    url: reqwest::Url,
+}
+
+impl Webhook {
+    pub const DEFAULT_TIMEOUT_SECS: u64 = 10;
+
+    pub fn new(url: &str, timeout: Duration) -> Result<Self> {
+        let url = reqwest::Url::parse(url).context(format!("Invalid webhook URL: {}", url))?;
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .context("Failed to set up the webhook HTTP client")?;
+        Ok(Self { client, url })
+    }
+
+    /// POST `payload` as JSON, logging (not returning) any failure
+    pub async fn notify(&self, payload: &impl Serialize) {
+        if let Err(e) = self.post(payload).await {
+            tracing::warn!("Webhook {} failed: {:#}", self.url, e);
+        }
+    }
+
+    async fn post(&self, payload: &impl Serialize) -> Result<()> {
+        self.client
+            .post(self.url.clone())
+            .json(payload)
+            .send()
+            .await
+            .context("Request failed")?
+            .error_for_status()
+            .context("Endpoint rejected the payload")?;
+        Ok(())
+    }
+}
@@ -11,26 +11,102 @@ impl WindowsAuth {
             // Check if we're running on a domain-joined Windows machine
             std::env::var("USERDOMAIN").is_ok() && std::env::var("USERNAME").is_ok()
         }
-        #[cfg(not(windows))]
+        #[cfg(all(not(windows), feature = "gssapi"))]
+        {
+            // Usable when a Kerberos credential cache exists (e.g., after `kinit`)
+            Self::find_kerberos_ccache().is_some()
+        }
+        #[cfg(all(not(windows), not(feature = "gssapi")))]
         {
             false
         }
     }
 
-    /// Get current Windows user information
-    pub fn get_current_user() -> Result<(String, String)> {
+    /// Locate the Kerberos credential cache for the current user (Unix)
+    /// Honors KRB5CCNAME and falls back to the MIT default /tmp/krb5cc_<uid>
+    #[cfg(not(windows))]
+    pub fn find_kerberos_ccache() -> Option<String> {
+        if let Ok(ccname) = std::env::var("KRB5CCNAME") {
+            if ccname.is_empty() {
+                return None;
+            }
+            // Only file-based caches can be checked; KEYRING:, KCM:, etc. are trusted as-is
+            return match ccname.strip_prefix("FILE:") {
+                Some(path) => std::path::Path::new(path).is_file().then(|| ccname.clone()),
+                None if ccname.contains(':') => Some(ccname),
+                None => std::path::Path::new(&ccname).is_file().then(|| ccname.clone()),
+            };
+        }
+
+        let default_path = format!("/tmp/krb5cc_{}", Self::current_uid());
+        std::path::Path::new(&default_path).is_file().then_some(default_path)
+    }
+
+    /// Get the numeric uid of the current process
+    #[cfg(not(windows))]
+    fn current_uid() -> u32 {
+        // SAFETY: getuid has no preconditions and cannot fail
+        unsafe { libc::getuid() }
+    }
+
+    /// Default principal of the file-based credential cache, as (realm, name)
+    ///
+    /// KEYRING:, KCM: and other non-file caches cannot be read directly, so they yield None.
+    #[cfg(all(not(windows), feature = "gssapi"))]
+    pub fn ccache_principal() -> Option<(String, String)> {
+        let ccache = Self::find_kerberos_ccache()?;
+        let path = match ccache.strip_prefix("FILE:") {
+            Some(path) => path,
+            None if ccache.contains(':') => return None,
+            None => &ccache,
+        };
+        parse_ccache_principal(&std::fs::read(path).ok()?)
+    }
+
+    /// Read the default Kerberos realm from krb5.conf (KRB5_CONFIG or /etc/krb5.conf)
+    #[cfg(not(windows))]
+    pub fn default_kerberos_realm() -> Option<String> {
+        let config_path = std::env::var("KRB5_CONFIG")
+            .unwrap_or_else(|_| "/etc/krb5.conf".to_string());
+        let contents = std::fs::read_to_string(config_path).ok()?;
+
+        contents.lines()
+            .map(|line| line.trim())
+            .filter_map(|line| line.strip_prefix("default_realm"))
+            .filter_map(|rest| rest.trim_start().strip_prefix('='))
+            .map(|realm| realm.trim().to_string())
+            .find(|realm| !realm.is_empty())
+    }
+
+    /// Get the current user as (domain, username)
+    ///
+    /// On Unix the principal comes from the credential cache; without a readable cache it falls
+    /// back to the login name and the default realm, which may be unknown (e.g. realms found
+    /// through DNS), so the domain is optional.
+    pub fn get_current_user() -> Result<(Option<String>, String)> {
         #[cfg(windows)]
         {
             let username = std::env::var("USERNAME")
                 .context("Failed to get current username from environment")?;
             let domain = std::env::var("USERDOMAIN")
                 .context("Failed to get current user domain from environment")?;
-            Ok((domain, username))
+            Ok((Some(domain), username))
         }
-        #[cfg(not(windows))]
+        #[cfg(all(not(windows), feature = "gssapi"))]
+        {
+            if let Some((realm, name)) = Self::ccache_principal() {
+                return Ok((Some(realm), name));
+            }
+            let username = std::env::var("USER")
+                .or_else(|_| std::env::var("LOGNAME"))
+                .context("Failed to get current username from environment")?;
+            Ok((Self::default_kerberos_realm(), username))
+        }
+        #[cfg(all(not(windows), not(feature = "gssapi")))]
         {
             Err(anyhow::anyhow!(
-                "Kerberos integrated authentication is only available on Windows platforms"
+                "Kerberos integrated authentication is only available on Windows platforms \
+                 (or Linux/macOS builds with the 'gssapi' feature)"
             ))
         }
     }
@@ -47,18 +123,24 @@ impl WindowsAuth {
         }
     }
 
-    /// Get the current user's full DN format (DOMAIN\username)
+    /// Get the current user's full DN format (DOMAIN\username, or username when the domain is unknown)
     pub fn get_current_user_dn() -> Result<String> {
         let (domain, username) = Self::get_current_user()?;
-        Ok(format!("{}\\{}", domain, username))
+        Ok(match domain {
+            Some(domain) => format!("{}\\{}", domain, username),
+            None => username,
+        })
     }
 
-    /// Get the current user's UPN format (username@domain)
+    /// Get the current user's UPN format (username@domain, or username when the domain is unknown)
     pub fn get_current_user_upn() -> Result<String> {
         let (domain, username) = Self::get_current_user()?;
-        let dns_domain = std::env::var("USERDNSDOMAIN")
-            .unwrap_or_else(|_| domain.to_lowercase());
-        Ok(format!("{}@{}", username, dns_domain))
+        let dns_domain = std::env::var("USERDNSDOMAIN").ok()
+            .or_else(|| domain.map(|d| d.to_lowercase()));
+        Ok(match dns_domain {
+            Some(dns_domain) => format!("{}@{}", username, dns_domain),
+            None => username,
+        })
     }
 
     /// Get default LDAP server from Windows environment
@@ -96,6 +178,49 @@ impl WindowsAuth {
     }
 }
 
+/// Default principal of an MIT file credential cache (format versions 3 and 4), as (realm, name)
+///
+/// Multi-component names are joined with '/', as `klist` prints them.
+#[cfg(all(not(windows), feature = "gssapi"))]
+pub fn parse_ccache_principal(bytes: &[u8]) -> Option<(String, String)> {
+    fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+        let (head, rest) = (bytes.get(..len)?, bytes.get(len..)?);
+        *bytes = rest;
+        Some(head)
+    }
+    fn u16_be(bytes: &mut &[u8]) -> Option<usize> {
+        take(bytes, 2).map(|b| u16::from_be_bytes([b[0], b[1]]) as usize)
+    }
+    fn u32_be(bytes: &mut &[u8]) -> Option<usize> {
+        take(bytes, 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as usize)
+    }
+    fn counted_string(bytes: &mut &[u8]) -> Option<String> {
+        let len = u32_be(bytes)?;
+        take(bytes, len).map(|b| String::from_utf8_lossy(b).into_owned())
+    }
+
+    let mut rest = bytes;
+    match take(&mut rest, 2)? {
+        [0x05, 0x04] => {
+            let header_len = u16_be(&mut rest)?;
+            take(&mut rest, header_len)?;
+        }
+        [0x05, 0x03] => {}
+        // Versions 1 and 2 use native byte order and are no longer written by any krb5
+        _ => return None,
+    }
+
+    let _name_type = u32_be(&mut rest)?;
+    let components = u32_be(&mut rest)?;
+    let realm = counted_string(&mut rest)?;
+    let name = (0..components)
+        .map(|_| counted_string(&mut rest))
+        .collect::<Option<Vec<_>>>()?
+        .join("/");
+
+    (!realm.is_empty() && !name.is_empty()).then_some((realm, name))
+}
+
 /// Helper function to determine if we should attempt Kerberos authentication
 pub fn should_use_gssapi(username: &Option<String>, use_gssapi_flag: bool) -> bool {
     if !use_gssapi_flag {
@@ -104,7 +229,7 @@ pub fn should_use_gssapi(username: &Option<String>, use_gssapi_flag: bool) -> bo
 
     // Use GSSAPI if:
     // 1. Explicitly requested AND
-    // 2. Platform supports it (Windows currently)
+    // 2. Platform supports it (Windows, or Unix with a Kerberos credential cache)
     WindowsAuth::is_available()
 }
 
@@ -0,0 +1,116 @@
+use ad_report::ad_time::{format_timestamp, parse_filetime, parse_filetime_interval, parse_generalized_time};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+
+fn utc(y: i32, mo: u32, d: u32, h: u32, mi: u32, s: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(y, mo, d, h, mi, s).unwrap()
+}
+
+#[test]
+fn filetime_converts_to_utc() {
+    // 2024-01-15T10:30:00Z
+    assert_eq!(parse_filetime("133497882000000000"), Some(utc(2024, 1, 15, 10, 30, 0)));
+    // Unix epoch
+    assert_eq!(parse_filetime("116444736000000000"), Some(utc(1970, 1, 1, 0, 0, 0)));
+    // Surrounding whitespace is tolerated
+    assert_eq!(parse_filetime(" 116444736000000000 "), Some(utc(1970, 1, 1, 0, 0, 0)));
+}
+
+#[test]
+fn filetime_truncates_sub_second_ticks() {
+    assert_eq!(parse_filetime("133497882009999999"), Some(utc(2024, 1, 15, 10, 30, 0)));
+    // Before the Unix epoch, truncation still rounds towards the earlier second
+    assert_eq!(parse_filetime("116444735995000000"), Some(utc(1969, 12, 31, 23, 59, 59)));
+}
+
+#[test]
+fn filetime_sentinels_mean_never() {
+    assert_eq!(parse_filetime("0"), None);
+    assert_eq!(parse_filetime("9223372036854775807"), None);
+}
+
+#[test]
+fn filetime_rejects_invalid_values() {
+    assert_eq!(parse_filetime(""), None);
+    assert_eq!(parse_filetime("-1"), None);
+    assert_eq!(parse_filetime("never"), None);
+    assert_eq!(parse_filetime("9223372036854775808"), None);
+}
+
+#[test]
+fn filetime_earliest_tick_is_1601() {
+    assert_eq!(parse_filetime("1"), Some(utc(1601, 1, 1, 0, 0, 0)));
+}
+
+#[test]
+fn generalized_time_with_zero_fraction() {
+    assert_eq!(parse_generalized_time("20230301120000.0Z"), Some(utc(2023, 3, 1, 12, 0, 0)));
+}
+
+#[test]
+fn generalized_time_without_fraction() {
+    assert_eq!(parse_generalized_time("20230301120000Z"), Some(utc(2023, 3, 1, 12, 0, 0)));
+}
+
+#[test]
+fn generalized_time_with_fractional_seconds() {
+    let parsed = parse_generalized_time("20230301120000.123Z").unwrap();
+    assert_eq!(parsed.timestamp(), utc(2023, 3, 1, 12, 0, 0).timestamp());
+    assert_eq!(parsed.timestamp_subsec_millis(), 123);
+
+    // Comma separator and more than nanosecond precision
+    let parsed = parse_generalized_time("20230301120000,1234567891Z").unwrap();
+    assert_eq!(parsed.timestamp_subsec_nanos(), 123_456_789);
+}
+
+#[test]
+fn generalized_time_with_offsets() {
+    assert_eq!(parse_generalized_time("20230301140000+0200"), Some(utc(2023, 3, 1, 12, 0, 0)));
+    assert_eq!(parse_generalized_time("20230301070000.0-0500"), Some(utc(2023, 3, 1, 12, 0, 0)));
+    assert_eq!(parse_generalized_time("20230301133000+0130"), Some(utc(2023, 3, 1, 12, 0, 0)));
+    assert_eq!(parse_generalized_time("20230301020000-10"), Some(utc(2023, 3, 1, 12, 0, 0)));
+    // Offsets can cross the date line
+    assert_eq!(parse_generalized_time("20230101010000+0200"), Some(utc(2022, 12, 31, 23, 0, 0)));
+}
+
+#[test]
+fn generalized_time_rejects_malformed_values() {
+    // No time zone: server-local time, ambiguous
+    assert_eq!(parse_generalized_time("20230301120000"), None);
+    assert_eq!(parse_generalized_time("20230301120000.0"), None);
+    // Empty fraction, bad zone, bad offsets
+    assert_eq!(parse_generalized_time("20230301120000.Z"), None);
+    assert_eq!(parse_generalized_time("20230301120000UTC"), None);
+    assert_eq!(parse_generalized_time("20230301120000+2400"), None);
+    assert_eq!(parse_generalized_time("20230301120000+0260"), None);
+    assert_eq!(parse_generalized_time("20230301120000+2"), None);
+    // Truncated or invalid dates
+    assert_eq!(parse_generalized_time("202303011200Z"), None);
+    assert_eq!(parse_generalized_time("20230230120000Z"), None);
+    assert_eq!(parse_generalized_time("2023-03-01T12:00:00Z"), None);
+    assert_eq!(parse_generalized_time(""), None);
+}
+
+#[test]
+fn generalized_time_boundaries() {
+    assert_eq!(parse_generalized_time("16010101000000.0Z"), Some(utc(1601, 1, 1, 0, 0, 0)));
+    assert_eq!(parse_generalized_time("99991231235959.0Z"), Some(utc(9999, 12, 31, 23, 59, 59)));
+    assert_eq!(parse_generalized_time("20240229000000Z"), Some(utc(2024, 2, 29, 0, 0, 0)));
+}
+
+#[test]
+fn filetime_interval_is_a_positive_duration() {
+    // maxPwdAge of 42 days, as stored by AD
+    assert_eq!(parse_filetime_interval("-36288000000000"), Some(Duration::days(42)));
+    assert_eq!(parse_filetime_interval("36288000000000"), Some(Duration::days(42)));
+    // "No limit" sentinels and garbage
+    assert_eq!(parse_filetime_interval("0"), None);
+    assert_eq!(parse_filetime_interval("-9223372036854775808"), None);
+    assert_eq!(parse_filetime_interval("forever"), None);
+}
+
+#[test]
+fn display_timestamps_carry_the_zone_and_offset() {
+    let timestamp = utc(2024, 7, 1, 9, 0, 0);
+    assert_eq!(format_timestamp(&timestamp, chrono_tz::Tz::UTC), "01-07-2024 09:00:00 UTC (UTC+00:00)");
+    assert_eq!(format_timestamp(&timestamp, chrono_tz::Europe::London), "01-07-2024 10:00:00 BST (UTC+01:00)");
+}
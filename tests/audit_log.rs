@@ -0,0 +1,91 @@
+use ad_report::audit_log::{line_hash, AuditEntry, AuditLog, AuditStatus};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+fn temp_log(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("ad-report-audit-{}-{}.jsonl", name, std::process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn lines(path: &PathBuf) -> Vec<String> {
+    fs::read_to_string(path).unwrap().lines().map(str::to_string).collect()
+}
+
+fn assert_chained(lines: &[String]) {
+    let mut previous = String::new();
+    for line in lines {
+        let entry: AuditEntry = serde_json::from_str(line).expect("each line is a JSON entry");
+        assert_eq!(entry.prev_hash, previous);
+        previous = line_hash(line);
+    }
+}
+
+#[test]
+fn entries_are_appended_as_chained_json_lines() {
+    let path = temp_log("chain");
+    let log = AuditLog::open(&path, "CORP\\auditor").unwrap();
+    log.append(AuditEntry::skipped("svc.backup", "account disabled")).unwrap();
+    log.append(AuditEntry::failure("ghost", &anyhow::anyhow!("User 'ghost' not found"))).unwrap();
+
+    // A later run appends to the same chain
+    let log = AuditLog::open(&path, "CORP\\other").unwrap();
+    log.append(AuditEntry::skipped("ws01$", "computer account")).unwrap();
+
+    let lines = lines(&path);
+    assert_eq!(lines.len(), 3);
+    assert_chained(&lines);
+
+    let first: AuditEntry = serde_json::from_str(&lines[0]).unwrap();
+    assert_eq!(first.operator, "CORP\\auditor");
+    assert_eq!(first.sam_account_name, "svc.backup");
+    assert_eq!(first.status, AuditStatus::Skipped);
+    assert!(first.prev_hash.is_empty());
+    assert!(lines[1].contains(r#""status":"failed""#));
+    assert!(lines[1].contains("User 'ghost' not found"));
+    let third: AuditEntry = serde_json::from_str(&lines[2]).unwrap();
+    assert_eq!(third.operator, "CORP\\other");
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn editing_an_entry_breaks_the_chain() {
+    let path = temp_log("tamper");
+    let log = AuditLog::open(&path, "CORP\\auditor").unwrap();
+    for user in ["a", "b", "c"] {
+        log.append(AuditEntry::skipped(user, "account disabled")).unwrap();
+    }
+
+    let mut lines = lines(&path);
+    lines[1] = lines[1].replace("account disabled", "computer account");
+    let next: AuditEntry = serde_json::from_str(&lines[2]).unwrap();
+    assert_ne!(next.prev_hash, line_hash(&lines[1]));
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn concurrent_writers_keep_whole_lines_and_one_chain() {
+    let path = temp_log("concurrent");
+    let writers: Vec<_> = (0..4)
+        .map(|w| {
+            let log = Arc::new(AuditLog::open(&path, format!("CORP\\writer{}", w)).unwrap());
+            std::thread::spawn(move || {
+                for i in 0..25 {
+                    log.append(AuditEntry::skipped(&format!("user{}-{}", w, i), "account disabled")).unwrap();
+                }
+            })
+        })
+        .collect();
+    for writer in writers {
+        writer.join().unwrap();
+    }
+
+    let lines = lines(&path);
+    assert_eq!(lines.len(), 100);
+    assert_chained(&lines);
+
+    fs::remove_file(&path).unwrap();
+}
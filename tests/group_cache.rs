@@ -0,0 +1,68 @@
+use ad_report::group_cache::GroupCache;
+use ad_report::models::ADGroup;
+use std::time::Duration;
+
+const ADMINS_DN: &str = "CN=Domain Admins,CN=Users,DC=corp,DC=example,DC=com";
+
+fn admins() -> ADGroup {
+    ADGroup::new(ADMINS_DN.to_string(), "Domain Admins".to_string())
+}
+
+fn parents() -> Vec<String> {
+    vec!["CN=Administrators,CN=Builtin,DC=corp,DC=example,DC=com".to_string()]
+}
+
+#[test]
+fn cached_groups_are_hits_and_unknown_ones_misses() {
+    let mut cache = GroupCache::default();
+    assert!(cache.get(ADMINS_DN).is_none());
+
+    cache.insert(ADMINS_DN, admins(), parents());
+    let cached = cache.get(ADMINS_DN).expect("cached group");
+    assert_eq!(cached.group.name, "Domain Admins");
+    assert_eq!(cached.member_of, parents());
+    assert!(cache.get("CN=Backup Operators,CN=Builtin,DC=corp,DC=example,DC=com").is_none());
+
+    assert_eq!(cache.stats(), (1, 2, 1));
+}
+
+#[test]
+fn lookups_ignore_dn_case() {
+    let mut cache = GroupCache::default();
+    cache.insert(ADMINS_DN, admins(), parents());
+
+    assert!(cache.get("cn=domain admins,cn=users,dc=corp,dc=example,dc=com").is_some());
+    assert!(cache.get("CN=DOMAIN ADMINS,CN=USERS,DC=CORP,DC=EXAMPLE,DC=COM").is_some());
+}
+
+#[test]
+fn entries_are_keyed_by_the_requested_dn() {
+    // The server may spell the DN differently from the memberOf value that was looked up
+    let mut cache = GroupCache::default();
+    let mut group = admins();
+    group.distinguished_name = "CN=Domain Admins,CN=Users,DC=CORP,DC=example,DC=com".to_string();
+    cache.insert("cn=domain admins,cn=users,dc=corp,dc=example,dc=com", group, parents());
+
+    assert!(cache.get(ADMINS_DN).is_some());
+    assert_eq!(cache.stats(), (1, 0, 1));
+}
+
+#[test]
+fn expired_and_evicted_entries_miss() {
+    let mut expired = GroupCache::new(10, Duration::ZERO);
+    expired.insert(ADMINS_DN, admins(), parents());
+    std::thread::sleep(Duration::from_millis(5));
+    assert!(expired.get(ADMINS_DN).is_none());
+    assert_eq!(expired.stats(), (0, 1, 0));
+
+    let mut full = GroupCache::new(1, Duration::from_secs(60));
+    full.insert(ADMINS_DN, admins(), parents());
+    let operators = "CN=Account Operators,CN=Builtin,DC=corp,DC=example,DC=com";
+    full.insert(operators, ADGroup::new(operators.to_string(), "Account Operators".to_string()), Vec::new());
+    assert!(full.get(ADMINS_DN).is_none());
+    assert!(full.get(operators).is_some());
+
+    let mut disabled = GroupCache::new(0, Duration::from_secs(60));
+    disabled.insert(ADMINS_DN, admins(), parents());
+    assert!(disabled.get(ADMINS_DN).is_none());
+}
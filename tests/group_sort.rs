@@ -0,0 +1,67 @@
+use ad_report::group_sort::GroupSortOrder;
+use ad_report::models::{ADGroup, ADUser, GroupScope, GroupType};
+
+fn group(name: &str, scope: GroupScope, group_type: GroupType, nested: Vec<ADGroup>) -> ADGroup {
+    let mut group = ADGroup::new(format!("CN={},OU=Groups,DC=corp,DC=example,DC=com", name), name.to_string());
+    group.scope = scope;
+    group.group_type = group_type;
+    group.nested_groups = nested;
+    group
+}
+
+fn names(groups: &[ADGroup]) -> Vec<&str> {
+    groups.iter().map(|g| g.name.as_str()).collect()
+}
+
+/// "wiki editors" is harmless on its own but nests into Domain Admins
+fn user() -> ADUser {
+    let mut user = ADUser::new("CN=jdoe,OU=Staff,DC=corp,DC=example,DC=com".to_string(), "jdoe".to_string());
+    user.groups = vec![
+        group("Mail List", GroupScope::Universal, GroupType::Distribution, vec![]),
+        group("wiki editors", GroupScope::DomainLocal, GroupType::Security, vec![
+            group("Zeta", GroupScope::Global, GroupType::Security, vec![]),
+            group("Domain Admins", GroupScope::Global, GroupType::Security, vec![]),
+            group("alpha", GroupScope::Global, GroupType::Security, vec![]),
+        ]),
+        group("Backup Operators", GroupScope::DomainLocal, GroupType::Security, vec![]),
+        group("Accounting", GroupScope::Global, GroupType::Security, vec![]),
+    ];
+    user
+}
+
+fn sorted(order: GroupSortOrder) -> ADUser {
+    let mut user = user();
+    order.apply(&mut user);
+    user
+}
+
+#[test]
+fn default_order_is_risk() {
+    assert_eq!(GroupSortOrder::default(), GroupSortOrder::Risk);
+}
+
+#[test]
+fn risk_counts_nested_groups_and_breaks_ties_by_name() {
+    let user = sorted(GroupSortOrder::Risk);
+    assert_eq!(names(&user.groups), vec!["wiki editors", "Backup Operators", "Accounting", "Mail List"]);
+    assert_eq!(names(&user.groups[0].nested_groups), vec!["Domain Admins", "alpha", "Zeta"]);
+}
+
+#[test]
+fn name_is_case_insensitive_at_every_level() {
+    let user = sorted(GroupSortOrder::Name);
+    assert_eq!(names(&user.groups), vec!["Accounting", "Backup Operators", "Mail List", "wiki editors"]);
+    assert_eq!(names(&user.groups[3].nested_groups), vec!["alpha", "Domain Admins", "Zeta"]);
+}
+
+#[test]
+fn scope_lists_universal_then_global_then_domain_local() {
+    let user = sorted(GroupSortOrder::Scope);
+    assert_eq!(names(&user.groups), vec!["Mail List", "Accounting", "Backup Operators", "wiki editors"]);
+}
+
+#[test]
+fn type_lists_security_before_distribution() {
+    let user = sorted(GroupSortOrder::Type);
+    assert_eq!(names(&user.groups), vec!["Accounting", "Backup Operators", "wiki editors", "Mail List"]);
+}
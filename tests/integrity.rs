@@ -0,0 +1,84 @@
+use ad_report::integrity::{self, Verification};
+use ad_report::models::{ADGroup, ADUser};
+use ad_report::pdf_generator::PdfGenerator;
+use ad_report::report_data::{EnhancedReportData, ReportProvenance};
+use chrono::{TimeZone, Utc};
+use std::path::Path;
+
+fn fixture() -> EnhancedReportData {
+    let mut user = ADUser::new("CN=John Doe,OU=Staff,DC=corp,DC=example,DC=com".to_string(), "jdoe".to_string());
+    user.groups.push(ADGroup::new(
+        "CN=Helpdesk,OU=Groups,DC=corp,DC=example,DC=com".to_string(),
+        "Helpdesk".to_string(),
+    ));
+    let generation_time = Utc.with_ymd_and_hms(2024, 7, 1, 9, 0, 0).unwrap();
+    EnhancedReportData::new_at(user, "corp.example.com".to_string(), "dc1.corp.example.com".to_string(), None, generation_time)
+}
+
+fn render_with_hash(data: &EnhancedReportData) -> (Vec<u8>, Vec<u8>, String) {
+    let json = integrity::content_json(data).unwrap();
+    let hash = integrity::sha256_hex(&json);
+    let pdf = PdfGenerator::new().unwrap().with_content_hash(&hash).generate_report(data).unwrap();
+    (pdf, json, hash)
+}
+
+#[test]
+fn embedded_hash_matches_the_content_json() {
+    let (pdf, json, hash) = render_with_hash(&fixture());
+
+    assert_eq!(hash.len(), 64);
+    assert_eq!(integrity::embedded_hash(&pdf).unwrap(), Some(hash.clone()));
+    assert_eq!(integrity::verify(&pdf, &json).unwrap(), Verification::Match { hash });
+}
+
+#[test]
+fn altered_content_fails_verification() {
+    let (pdf, json, hash) = render_with_hash(&fixture());
+    let tampered = String::from_utf8(json).unwrap().replace("Helpdesk", "Domain Admins");
+
+    match integrity::verify(&pdf, tampered.as_bytes()).unwrap() {
+        Verification::Mismatch { embedded, computed } => {
+            assert_eq!(embedded, hash);
+            assert_ne!(computed, hash);
+        }
+        other => panic!("expected a mismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn reports_without_a_hash_are_flagged() {
+    let data = fixture();
+    let pdf = PdfGenerator::new().unwrap().generate_report(&data).unwrap();
+    let json = integrity::content_json(&data).unwrap();
+
+    assert_eq!(integrity::embedded_hash(&pdf).unwrap(), None);
+    assert_eq!(integrity::verify(&pdf, &json).unwrap(), Verification::NoEmbeddedHash);
+}
+
+#[test]
+fn checksum_file_uses_sha256sum_format() {
+    let (json_path, checksum_path) = integrity::sidecar_paths(Path::new("out/jdoe_ad_report_20240701_090000.pdf"));
+
+    assert_eq!(json_path, Path::new("out/jdoe_ad_report_20240701_090000.json"));
+    assert_eq!(checksum_path, Path::new("out/jdoe_ad_report_20240701_090000.sha256"));
+    assert_eq!(integrity::checksum_line("ab12", &json_path), "ab12  jdoe_ad_report_20240701_090000.json\n");
+}
+
+#[test]
+fn content_beyond_the_baseline_snapshot_is_covered() {
+    let data = fixture().with_provenance(ReportProvenance {
+        bind_identity: "CORP\\reader".to_string(),
+        search_base: "DC=corp,DC=example,DC=com".to_string(),
+    });
+    let (pdf, json, hash) = render_with_hash(&data);
+    let json = String::from_utf8(json).unwrap();
+    assert!(json.contains("dc1.corp.example.com") && json.contains("reader"));
+
+    for (original, altered) in [("dc1.corp.example.com", "dc9.corp.example.com"), ("reader", "auditor")] {
+        let tampered = json.replace(original, altered);
+        match integrity::verify(&pdf, tampered.as_bytes()).unwrap() {
+            Verification::Mismatch { embedded, .. } => assert_eq!(embedded, hash),
+            other => panic!("changing {} should fail verification, got {:?}", original, other),
+        }
+    }
+}
@@ -0,0 +1,831 @@
+mod mock_ldap;
+
+use ad_report::error::AdReportError;
+use ad_report::ldap_client::{LdapClient, TlsMinVersion};
+use ad_report::models::{GroupScope, GroupType};
+use chrono::{DateTime, TimeZone, Utc};
+use mock_ldap::{MockDirectory, MockEntry, MockLdapServer, REJECTED_PASSWORD};
+use secrecy::SecretString;
+
+const BASE_DN: &str = "DC=corp,DC=example,DC=com";
+const JDOE_DN: &str = "CN=John Doe,OU=Staff,DC=corp,DC=example,DC=com";
+const APP_ADMINS_DN: &str = "CN=App Admins,OU=Groups,DC=corp,DC=example,DC=com";
+const DOMAIN_ADMINS_DN: &str = "CN=Domain Admins,CN=Users,DC=corp,DC=example,DC=com";
+const OPS_TEAM_DN: &str = "CN=Ops Team,OU=Groups,DC=corp,DC=example,DC=com";
+const OPS_LEADS_DN: &str = "CN=Ops Leads,OU=Groups,DC=corp,DC=example,DC=com";
+const OPS_ALL_DN: &str = "CN=Ops All,OU=Groups,DC=corp,DC=example,DC=com";
+const ADMIN_PSO_DN: &str = "CN=Admin PSO,CN=Password Settings Container,CN=System,DC=corp,DC=example,DC=com";
+
+/// Security + global scope (0x80000002)
+const GLOBAL_SECURITY_GROUP: &str = "-2147483646";
+
+/// AD FILETIME: 100ns intervals since 1601-01-01
+fn filetime(time: DateTime<Utc>) -> String {
+    (time.timestamp() * 10_000_000 + 116_444_736_000_000_000).to_string()
+}
+
+/// Binary objectSid for S-1-5-21-1-2-3-<rid>
+fn object_sid(rid: u32) -> Vec<u8> {
+    let mut sid = vec![1, 5, 0, 0, 0, 0, 0, 5];
+    for sub_authority in [21, 1, 2, 3, rid] {
+        sid.extend_from_slice(&u32::to_le_bytes(sub_authority));
+    }
+    sid
+}
+
+fn password_last_set() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 1, 15, 10, 30, 0).unwrap()
+}
+
+fn last_logon() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 6, 1, 8, 0, 0).unwrap()
+}
+
+/// AD interval attributes are negative 100ns tick counts
+fn interval_days(days: i64) -> String {
+    (-days * 864_000_000_000).to_string()
+}
+
+fn directory() -> MockDirectory {
+    MockDirectory::new(BASE_DN)
+        .with_entry(MockEntry::new(JDOE_DN)
+            .attr("objectClass", &["top", "person", "organizationalPerson", "user"])
+            .attr("distinguishedName", &[JDOE_DN])
+            .attr("sAMAccountName", &["jdoe"])
+            .attr("userPrincipalName", &["jdoe@corp.example.com"])
+            .attr("servicePrincipalName", &["HTTP/app.corp.example.com"])
+            .attr("displayName", &["John Doe"])
+            .attr("mail", &["john.doe@corp.example.com"])
+            .attr("department", &["Engineering"])
+            .attr("title", &["Developer"])
+            // ACCOUNTDISABLE | NORMAL_ACCOUNT | DONT_EXPIRE_PASSWD
+            .attr("userAccountControl", &["66050"])
+            .attr("pwdLastSet", &[&filetime(password_last_set())])
+            .attr("lastLogonTimestamp", &[&filetime(last_logon())])
+            .attr("accountExpires", &["9223372036854775807"])
+            .attr("whenCreated", &["20230301120000.0Z"])
+            .attr("memberOf", &[APP_ADMINS_DN])
+            .attr("primaryGroupID", &["513"])
+            .bin_attr("objectSid", &object_sid(1105)))
+        .with_entry(MockEntry::new("CN=Locked User,OU=Staff,DC=corp,DC=example,DC=com")
+            .attr("objectClass", &["top", "person", "organizationalPerson", "user"])
+            .attr("sAMAccountName", &["locked.user"])
+            // LOCKOUT | NORMAL_ACCOUNT | SMARTCARD_REQUIRED | DONT_REQUIRE_PREAUTH
+            .attr("userAccountControl", &["4456976"])
+            .attr("pwdLastSet", &["0"]))
+        .with_entry(MockEntry::new(APP_ADMINS_DN)
+            .attr("objectClass", &["top", "group"])
+            .attr("cn", &["App Admins"])
+            .attr("description", &["Application administrators"])
+            .attr("groupType", &[GLOBAL_SECURITY_GROUP])
+            .attr("memberOf", &[DOMAIN_ADMINS_DN]))
+        .with_entry(MockEntry::new(DOMAIN_ADMINS_DN)
+            .attr("objectClass", &["top", "group"])
+            .attr("cn", &["Domain Admins"])
+            .attr("groupType", &[GLOBAL_SECURITY_GROUP]))
+        // Overlapping graph: Ops Team and Ops Leads are both direct, Ops Team is also
+        // nested in Ops Leads, and both are nested in Ops All
+        .with_entry(MockEntry::new("CN=Ops User,OU=Staff,DC=corp,DC=example,DC=com")
+            .attr("objectClass", &["top", "person", "organizationalPerson", "user"])
+            .attr("sAMAccountName", &["ops.user"])
+            .attr("userAccountControl", &["512"])
+            .attr("memberOf", &[OPS_TEAM_DN, OPS_LEADS_DN]))
+        .with_entry(MockEntry::new(OPS_TEAM_DN)
+            .attr("objectClass", &["top", "group"])
+            .attr("cn", &["Ops Team"])
+            .attr("groupType", &[GLOBAL_SECURITY_GROUP])
+            .bin_attr("objectSid", &object_sid(1201))
+            .attr("primaryGroupToken", &["1201"])
+            .attr("memberOf", &[OPS_LEADS_DN, OPS_ALL_DN]))
+        .with_entry(MockEntry::new(OPS_LEADS_DN)
+            .attr("objectClass", &["top", "group"])
+            .attr("cn", &["Ops Leads"])
+            .attr("groupType", &[GLOBAL_SECURITY_GROUP])
+            .bin_attr("objectSid", &object_sid(1202))
+            .attr("primaryGroupToken", &["1202"])
+            .attr("memberOf", &[OPS_ALL_DN]))
+        .with_entry(MockEntry::new(OPS_ALL_DN)
+            .attr("objectClass", &["top", "group"])
+            .attr("cn", &["Ops All"])
+            .attr("groupType", &[GLOBAL_SECURITY_GROUP])
+            .bin_attr("objectSid", &object_sid(1203))
+            .attr("primaryGroupToken", &["1203"]))
+}
+
+async fn bound_client() -> LdapClient {
+    bound_client_for(directory()).await
+}
+
+async fn bound_client_for(directory: MockDirectory) -> LdapClient {
+    let server = MockLdapServer::start(directory).await;
+    let mut client = LdapClient::connect("127.0.0.1", Some(server.port), false, TlsMinVersion::default())
+        .await
+        .expect("connect to mock LDAP server");
+    client.bind_simple("CN=reader,DC=corp,DC=example,DC=com", &SecretString::from("secret"))
+        .await
+        .expect("simple bind");
+    client
+}
+
+#[tokio::test]
+async fn discovers_base_dn_from_root_dse() {
+    let client = bound_client().await;
+    assert_eq!(client.user_search_base(), BASE_DN);
+}
+
+#[tokio::test]
+async fn get_user_parses_identity_attributes() {
+    let mut client = bound_client().await;
+    let user = client.get_user("jdoe").await.expect("jdoe exists");
+
+    assert_eq!(user.distinguished_name, JDOE_DN);
+    assert_eq!(user.sam_account_name, "jdoe");
+    assert_eq!(user.object_sid.as_deref(), Some("S-1-5-21-1-2-3-1105"));
+    assert_eq!(user.user_principal_name.as_deref(), Some("jdoe@corp.example.com"));
+    assert_eq!(user.display_name.as_deref(), Some("John Doe"));
+    assert_eq!(user.email.as_deref(), Some("john.doe@corp.example.com"));
+    assert_eq!(user.department.as_deref(), Some("Engineering"));
+    assert_eq!(user.title.as_deref(), Some("Developer"));
+    assert_eq!(user.service_principal_names, vec!["HTTP/app.corp.example.com"]);
+    assert!(user.raw_attributes.is_none());
+}
+
+#[tokio::test]
+async fn get_user_decodes_uac_flags() {
+    let mut client = bound_client().await;
+
+    let jdoe = client.get_user("jdoe").await.unwrap();
+    assert!(!jdoe.account_enabled);
+    assert!(jdoe.password_never_expires);
+    assert!(!jdoe.account_locked);
+    assert!(!jdoe.smartcard_required);
+    assert!(!jdoe.no_preauth_required);
+
+    let locked = client.get_user("locked.user").await.unwrap();
+    assert!(locked.account_enabled);
+    assert!(locked.account_locked);
+    assert!(locked.smartcard_required);
+    assert!(locked.no_preauth_required);
+    assert!(!locked.password_never_expires);
+}
+
+#[tokio::test]
+async fn get_user_converts_timestamps() {
+    let mut client = bound_client().await;
+
+    let jdoe = client.get_user("jdoe").await.unwrap();
+    assert_eq!(jdoe.password_last_set, Some(password_last_set()));
+    assert_eq!(jdoe.last_logon, Some(last_logon()));
+    assert_eq!(jdoe.created, Some(Utc.with_ymd_and_hms(2023, 3, 1, 12, 0, 0).unwrap()));
+    // i64::MAX means "never expires"
+    assert_eq!(jdoe.account_expires, None);
+
+    // pwdLastSet of 0 means "must change at next logon", not 1601-01-01
+    let locked = client.get_user("locked.user").await.unwrap();
+    assert_eq!(locked.password_last_set, None);
+    assert_eq!(locked.last_logon, None);
+}
+
+#[tokio::test]
+async fn last_logon_falls_back_when_the_replicated_timestamp_is_missing() {
+    let directory = directory()
+        .with_entry(MockEntry::new("CN=New Starter,OU=Staff,DC=corp,DC=example,DC=com")
+            .attr("objectClass", &["top", "person", "organizationalPerson", "user"])
+            .attr("sAMAccountName", &["new.starter"])
+            .attr("userAccountControl", &["512"])
+            .attr("lastLogon", &[&filetime(last_logon())])
+            .attr("memberOf", &[APP_ADMINS_DN]));
+    let mut client = bound_client_for(directory).await;
+
+    let user = client.get_user("new.starter").await.unwrap();
+    assert_eq!(user.last_logon, Some(last_logon()));
+
+    // Neither attribute set: still never logged on
+    let locked = client.get_user("locked.user").await.unwrap();
+    assert_eq!(locked.last_logon, None);
+}
+
+#[tokio::test]
+async fn extra_attributes_are_fetched_into_the_raw_map() {
+    let directory = directory()
+        .with_entry(MockEntry::new("CN=Cost Centre,OU=Staff,DC=corp,DC=example,DC=com")
+            .attr("objectClass", &["top", "person", "organizationalPerson", "user"])
+            .attr("sAMAccountName", &["cc.user"])
+            .attr("userAccountControl", &["512"])
+            .attr("extensionAttribute1", &["CC-1234"])
+            .attr("employeeID", &["E-42"]));
+    let mut client = bound_client_for(directory).await;
+    client.set_extra_attributes(vec!["extensionAttribute1".to_string()]);
+
+    let user = client.get_user("cc.user").await.unwrap();
+    let raw = user.raw_attributes.expect("extras land in the raw map");
+    assert_eq!(raw.get("extensionAttribute1"), Some(&vec!["CC-1234".to_string()]));
+    // Only what was asked for; core fields are still parsed as usual
+    assert_eq!(raw.len(), 1);
+    assert_eq!(user.sam_account_name, "cc.user");
+    assert!(user.account_enabled);
+}
+
+#[tokio::test]
+async fn get_user_resolves_nested_groups() {
+    let mut client = bound_client().await;
+    let user = client.get_user("jdoe").await.unwrap();
+
+    assert_eq!(user.groups.len(), 1);
+    let app_admins = &user.groups[0];
+    assert_eq!(app_admins.name, "App Admins");
+    assert_eq!(app_admins.description.as_deref(), Some("Application administrators"));
+    assert!(matches!(app_admins.group_type, GroupType::Security));
+    assert!(matches!(app_admins.scope, GroupScope::Global));
+
+    assert_eq!(app_admins.nested_groups.len(), 1);
+    assert_eq!(app_admins.nested_groups[0].name, "Domain Admins");
+    assert_eq!(app_admins.nested_groups[0].distinguished_name, DOMAIN_ADMINS_DN);
+
+    // The path each membership was inherited through is recorded as it is resolved
+    assert!(app_admins.inheritance_path.is_empty());
+    assert_eq!(app_admins.nested_groups[0].inheritance_path, vec![APP_ADMINS_DN.to_string()]);
+    assert_eq!(user.inheritance_chain(&app_admins.nested_groups[0]), "Domain Admins <- App Admins <- jdoe");
+
+    let names: Vec<&str> = user.all_groups().iter().map(|g| g.name.as_str()).collect();
+    assert!(names.contains(&"Domain Admins"));
+}
+
+#[tokio::test]
+async fn get_user_collapses_overlapping_memberships() {
+    let mut client = bound_client().await;
+    let user = client.get_user("ops.user").await.unwrap();
+
+    let direct: Vec<&str> = user.groups.iter().map(|g| g.name.as_str()).collect();
+    assert_eq!(direct, vec!["Ops Team", "Ops Leads"]);
+
+    // Ops Leads stays direct only; Ops All is kept once, under the first parent reaching it
+    let ops_team = &user.groups[0];
+    assert_eq!(ops_team.nested_groups.len(), 1);
+    assert_eq!(ops_team.nested_groups[0].name, "Ops All");
+    assert!(user.groups[1].nested_groups.is_empty());
+
+    assert_eq!(user.direct_group_count(), 2);
+    assert_eq!(user.nested_group_count(), 1);
+    assert_eq!(user.all_groups().len(), 3);
+}
+
+#[tokio::test]
+async fn primary_group_falls_back_to_well_known_rid() {
+    let mut client = bound_client().await;
+    let user = client.get_user("jdoe").await.unwrap();
+
+    // No group entry carries the domain SID + 513, so the well-known name is used
+    let primary = user.primary_group.as_ref().expect("primary group");
+    assert_eq!(primary.name, "Domain Users");
+    assert_eq!(primary.distinguished_name, "S-1-5-21-1-2-3-513");
+    assert_eq!(user.primary_group_id, Some(513));
+    assert_eq!(user.anomalous_primary_group_id(), None);
+}
+
+#[tokio::test]
+async fn missing_user_is_not_found_with_suggestion() {
+    let mut client = bound_client().await;
+
+    match client.get_user("jdeo").await {
+        Err(AdReportError::UserNotFound(message)) => {
+            assert!(message.contains("Did you mean: jdoe?"), "{}", message);
+        }
+        other => panic!("expected UserNotFound, got {:?}", other.map(|u| u.sam_account_name)),
+    }
+}
+
+#[tokio::test]
+async fn duplicate_accounts_must_be_disambiguated() {
+    const STALE_DN: &str = "CN=John Doe,OU=Disabled Accounts,DC=corp,DC=example,DC=com";
+    let directory = directory().with_entry(MockEntry::new(STALE_DN)
+        .attr("objectClass", &["top", "person", "organizationalPerson", "user"])
+        .attr("sAMAccountName", &["jdoe"])
+        .attr("userAccountControl", &["514"]));
+    let mut client = bound_client_for(directory).await;
+
+    match client.get_user("jdoe").await {
+        Err(AdReportError::AmbiguousAccount(message)) => {
+            assert!(message.contains("2 accounts match 'jdoe'"), "{}", message);
+            assert!(message.contains(JDOE_DN) && message.contains(STALE_DN), "{}", message);
+            assert!(message.contains("--disambiguate"), "{}", message);
+        }
+        other => panic!("expected AmbiguousAccount, got {:?}", other.map(|u| u.distinguished_name)),
+    }
+
+    client.set_disambiguate(Some(STALE_DN.to_lowercase()));
+    let user = client.get_user("jdoe").await.expect("disambiguated lookup");
+    assert_eq!(user.distinguished_name, STALE_DN);
+
+    client.set_disambiguate(Some("CN=Someone Else,DC=corp,DC=example,DC=com".to_string()));
+    assert!(matches!(client.get_user("jdoe").await, Err(AdReportError::AmbiguousAccount(_))));
+}
+
+#[tokio::test]
+async fn rate_limiter_paces_every_lookup() {
+    use ad_report::rate_limit::RateLimiter;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    let mut client = bound_client().await;
+    client.get_user("jdoe").await.expect("warm-up lookup");
+
+    client.set_rate_limiter(Some(Arc::new(RateLimiter::new(10.0))));
+    let start = Instant::now();
+    client.get_user("jdoe").await.expect("rate-limited lookup");
+    // The user search and the reads that follow it go out at least 100ms apart
+    assert!(start.elapsed() >= Duration::from_millis(100), "{:?}", start.elapsed());
+}
+
+const CHILD_DN: &str = "DC=child,DC=corp,DC=example,DC=com";
+
+/// A server holding only the child partition, with one user in it
+fn child_directory() -> MockDirectory {
+    MockDirectory::new(CHILD_DN)
+        .with_entry(MockEntry::new("CN=Child User,OU=Staff,DC=child,DC=corp,DC=example,DC=com")
+            .attr("objectClass", &["top", "person", "organizationalPerson", "user"])
+            .attr("sAMAccountName", &["child.user"])
+            .attr("userAccountControl", &["512"]))
+}
+
+/// The first of `hops` servers that each refer subtree searches to the next, ending at
+/// the child partition
+async fn referral_chain(hops: usize) -> MockLdapServer {
+    let mut server = MockLdapServer::start(child_directory()).await;
+    for _ in 0..hops {
+        let url = format!("ldap://127.0.0.1:{}/{}", server.port, CHILD_DN.replace(',', "%2C"));
+        server = MockLdapServer::start(MockDirectory::new(BASE_DN).with_referral(&url)).await;
+    }
+    server
+}
+
+async fn get_user_via(server: &MockLdapServer, follow_referrals: bool, username: &str) -> Result<String, AdReportError> {
+    let mut client = LdapClient::connect("127.0.0.1", Some(server.port), false, TlsMinVersion::default()).await.unwrap();
+    client.set_follow_referrals(follow_referrals);
+    client.bind_simple("CN=reader,DC=corp,DC=example,DC=com", &SecretString::from("secret")).await.unwrap();
+    client.get_user(username).await.map(|user| user.distinguished_name)
+}
+
+#[tokio::test]
+async fn referrals_are_followed_only_when_enabled() {
+    let server = referral_chain(1).await;
+
+    let dn = get_user_via(&server, true, "child.user").await.unwrap();
+    assert_eq!(dn, "CN=Child User,OU=Staff,DC=child,DC=corp,DC=example,DC=com");
+
+    let err = get_user_via(&server, false, "child.user").await.unwrap_err();
+    assert!(matches!(err, AdReportError::UserNotFound(_)), "{:?}", err);
+}
+
+#[tokio::test]
+async fn referral_chains_stop_after_three_hops() {
+    assert!(get_user_via(&referral_chain(3).await, true, "child.user").await.is_ok());
+
+    let err = get_user_via(&referral_chain(4).await, true, "child.user").await.unwrap_err();
+    assert!(matches!(err, AdReportError::UserNotFound(_)), "{:?}", err);
+}
+
+#[tokio::test]
+async fn ldaps_referrals_are_never_followed_in_plaintext() {
+    // The child server only speaks plain LDAP, so an ldaps:// referral to it must fail the
+    // TLS handshake and be skipped rather than answer the search
+    let child = MockLdapServer::start(child_directory()).await;
+    let url = format!("ldaps://127.0.0.1:{}/{}", child.port, CHILD_DN.replace(',', "%2C"));
+    let server = MockLdapServer::start(MockDirectory::new(BASE_DN).with_referral(&url)).await;
+
+    let err = get_user_via(&server, true, "child.user").await.unwrap_err();
+    assert!(matches!(err, AdReportError::UserNotFound(_)), "{:?}", err);
+}
+
+/// No-auth SOCKS5 proxy that serves CONNECT requests to IPv4 or domain targets; host
+/// names in `routes` go to the paired local port instead, whatever port was asked for
+async fn start_socks5_proxy(routes: &[(&str, u16)]) -> u16 {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let routes: Vec<(String, u16)> = routes.iter().map(|(host, port)| (host.to_string(), *port)).collect();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((mut client, _)) = listener.accept().await {
+            let routes = routes.clone();
+            tokio::spawn(async move {
+                let mut greeting = [0u8; 2];
+                client.read_exact(&mut greeting).await.unwrap();
+                let mut methods = vec![0u8; greeting[1] as usize];
+                client.read_exact(&mut methods).await.unwrap();
+                client.write_all(&[5, 0]).await.unwrap();
+
+                let mut request = [0u8; 4];
+                client.read_exact(&mut request).await.unwrap();
+                let mut host = match request[3] {
+                    1 => {
+                        let mut ip = [0u8; 4];
+                        client.read_exact(&mut ip).await.unwrap();
+                        std::net::Ipv4Addr::from(ip).to_string()
+                    }
+                    _ => {
+                        let mut name = vec![0u8; client.read_u8().await.unwrap() as usize];
+                        client.read_exact(&mut name).await.unwrap();
+                        String::from_utf8(name).unwrap()
+                    }
+                };
+                let mut target_port = client.read_u16().await.unwrap();
+                if let Some((_, port)) = routes.iter().find(|(name, _)| *name == host) {
+                    (host, target_port) = ("127.0.0.1".to_string(), *port);
+                }
+
+                let mut target = tokio::net::TcpStream::connect((host.as_str(), target_port)).await.unwrap();
+                client.write_all(&[5, 0, 0, 1, 127, 0, 0, 1, 0, 0]).await.unwrap();
+                let _ = tokio::io::copy_bidirectional(&mut client, &mut target).await;
+            });
+        }
+    });
+    port
+}
+
+// Simple binds are refused through the tunnel where its peer cannot be verified
+#[cfg(unix)]
+#[tokio::test]
+async fn connections_can_go_through_a_socks5_proxy() {
+    let server = MockLdapServer::start(directory()).await;
+    let proxy = format!("127.0.0.1:{}", start_socks5_proxy(&[]).await);
+
+    let mut client = LdapClient::connect_via_socks5("localhost", Some(server.port), false, TlsMinVersion::default(), false, &proxy)
+        .await
+        .expect("connect through proxy");
+    client.bind_simple("CN=reader,DC=corp,DC=example,DC=com", &SecretString::from("secret")).await.unwrap();
+    assert_eq!(client.get_user("jdoe").await.unwrap().sam_account_name, "jdoe");
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn socks5_tunnel_is_a_private_unix_socket() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let server = MockLdapServer::start(directory()).await;
+    let proxy = format!("127.0.0.1:{}", start_socks5_proxy(&[]).await);
+    let url = ad_report::socks_tunnel::open(&proxy, "localhost", server.port, None).await.expect("open tunnel");
+
+    // Never a TCP port another local user could connect to first
+    let encoded = url.strip_prefix("ldapi://").expect("ldapi URL");
+    let path = std::path::PathBuf::from(encoded.replace("%2F", "/"));
+    let dir = path.parent().unwrap();
+    assert_eq!(std::fs::metadata(dir).unwrap().permissions().mode() & 0o777, 0o700);
+
+    // The one connection it hands out reaches the directory
+    let (conn, mut ldap) = ldap3::LdapConnAsync::new(&url).await.expect("connect to tunnel");
+    ldap3::drive!(conn);
+    ldap.simple_bind("CN=reader,DC=corp,DC=example,DC=com", "secret").await.unwrap().success().unwrap();
+}
+
+#[tokio::test]
+async fn unreachable_socks5_proxy_is_a_connection_failure() {
+    // Bind and drop a listener to get a port nothing is listening on
+    let port = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap().port();
+    let proxy = format!("127.0.0.1:{}", port);
+
+    match LdapClient::connect_via_socks5("dc01.corp.example.com", None, true, TlsMinVersion::default(), false, &proxy).await {
+        Err(AdReportError::ConnectionFailed(message)) => {
+            assert!(message.contains("SOCKS5 proxy 127.0.0.1:") && message.contains("unreachable"), "{}", message);
+        }
+        Err(other) => panic!("expected ConnectionFailed, got {:?}", other),
+        Ok(_) => panic!("expected ConnectionFailed, got a connection"),
+    }
+}
+
+#[tokio::test]
+async fn server_offering_only_old_tls_fails_with_a_clear_message() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // Answer the ClientHello the way a TLS 1.0-only server does: a fatal protocol_version alert
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut hello = [0u8; 1024];
+        let _ = socket.read(&mut hello).await;
+        let _ = socket.write_all(&[0x15, 0x03, 0x01, 0x00, 0x02, 0x02, 0x46]).await;
+    });
+
+    match LdapClient::connect("127.0.0.1", Some(port), true, TlsMinVersion::Tls12).await {
+        Err(AdReportError::ConnectionFailed(message)) => {
+            assert!(message.contains("does not offer TLS 1.2 or later"), "{}", message);
+        }
+        Err(other) => panic!("expected ConnectionFailed, got {:?}", other),
+        Ok(_) => panic!("expected the handshake to fail"),
+    }
+}
+
+#[tokio::test]
+async fn filter_metacharacters_in_username_are_escaped() {
+    let mut client = bound_client().await;
+
+    // Unescaped, "*" would match every account
+    assert!(matches!(client.get_user("*").await, Err(AdReportError::UserNotFound(_))));
+}
+
+#[tokio::test]
+async fn rejected_password_is_bind_failure() {
+    let server = MockLdapServer::start(directory()).await;
+    let mut client = LdapClient::connect("127.0.0.1", Some(server.port), false, TlsMinVersion::default()).await.unwrap();
+
+    let result = client.bind_simple("CN=reader,DC=corp,DC=example,DC=com", &SecretString::from(REJECTED_PASSWORD)).await;
+    assert!(matches!(result, Err(AdReportError::BindFailed(_))));
+}
+
+#[tokio::test]
+async fn password_policy_comes_from_the_domain_root() {
+    let mut client = bound_client().await;
+    // No readable domain root in the default directory
+    assert!(client.get_user("jdoe").await.unwrap().password_policy.is_none());
+
+    let directory = directory()
+        .with_entry(MockEntry::new(BASE_DN)
+            .attr("objectClass", &["top", "domain", "domainDNS"])
+            .attr("maxPwdAge", &[&interval_days(90)])
+            .attr("minPwdLength", &["12"])
+            // DOMAIN_PASSWORD_COMPLEX | DOMAIN_LOCKOUT_ADMINS
+            .attr("pwdProperties", &["9"]));
+    let mut client = bound_client_for(directory).await;
+
+    let policy = client.get_user("jdoe").await.unwrap().password_policy.expect("domain policy");
+    assert_eq!(policy.source, "Default domain policy");
+    assert_eq!(policy.max_age_days, Some(90));
+    assert_eq!(policy.min_length, Some(12));
+    assert_eq!(policy.complexity_required, Some(true));
+}
+
+#[tokio::test]
+async fn fine_grained_policy_overrides_the_domain_policy() {
+    let directory = directory()
+        .with_entry(MockEntry::new(BASE_DN)
+            .attr("objectClass", &["top", "domain", "domainDNS"])
+            .attr("maxPwdAge", &[&interval_days(90)])
+            .attr("minPwdLength", &["12"]))
+        .with_entry(MockEntry::new(ADMIN_PSO_DN)
+            .attr("objectClass", &["top", "msDS-PasswordSettings"])
+            .attr("cn", &["Admin PSO"])
+            // Never expires
+            .attr("msDS-MaximumPasswordAge", &["-9223372036854775808"])
+            .attr("msDS-MinimumPasswordLength", &["20"])
+            .attr("msDS-PasswordComplexityEnabled", &["FALSE"]))
+        .with_entry(MockEntry::new("CN=Svc Admin,OU=Staff,DC=corp,DC=example,DC=com")
+            .attr("objectClass", &["top", "person", "organizationalPerson", "user"])
+            .attr("sAMAccountName", &["svc.admin"])
+            .attr("userAccountControl", &["512"])
+            .attr("msDS-ResultantPSO", &[ADMIN_PSO_DN]));
+    let mut client = bound_client_for(directory).await;
+
+    let policy = client.get_user("svc.admin").await.unwrap().password_policy.expect("PSO");
+    assert_eq!(policy.source, "Fine-grained policy 'Admin PSO'");
+    assert_eq!(policy.max_age_days, None);
+    assert_eq!(policy.min_length, Some(20));
+    assert_eq!(policy.complexity_required, Some(false));
+
+    // Users without a PSO still get the domain policy
+    let policy = client.get_user("jdoe").await.unwrap().password_policy.unwrap();
+    assert_eq!(policy.max_age_days, Some(90));
+}
+
+#[tokio::test]
+async fn unresolvable_groups_are_recorded_as_warnings() {
+    const MISSING_DN: &str = "CN=Deleted Group,OU=Groups,DC=corp,DC=example,DC=com";
+    const MISSING_NESTED_DN: &str = "CN=Gone,OU=Groups,DC=corp,DC=example,DC=com";
+    let directory = directory()
+        .with_entry(MockEntry::new("CN=Partial User,OU=Staff,DC=corp,DC=example,DC=com")
+            .attr("objectClass", &["top", "person", "organizationalPerson", "user"])
+            .attr("sAMAccountName", &["partial.user"])
+            .attr("userAccountControl", &["512"])
+            .attr("memberOf", &[MISSING_DN, OPS_ALL_DN, "CN=Looped,OU=Groups,DC=corp,DC=example,DC=com"]))
+        .with_entry(MockEntry::new("CN=Looped,OU=Groups,DC=corp,DC=example,DC=com")
+            .attr("objectClass", &["top", "group"])
+            .attr("cn", &["Looped"])
+            .attr("groupType", &[GLOBAL_SECURITY_GROUP])
+            // A cycle back to itself is not missing data
+            .attr("memberOf", &["CN=Looped,OU=Groups,DC=corp,DC=example,DC=com", MISSING_NESTED_DN]));
+    let mut client = bound_client_for(directory).await;
+
+    let user = client.get_user("partial.user").await.unwrap();
+    let names: Vec<&str> = user.groups.iter().map(|g| g.name.as_str()).collect();
+    assert_eq!(names, vec!["Ops All", "Looped"]);
+
+    assert_eq!(user.resolution_warnings.len(), 2, "{:?}", user.resolution_warnings);
+    assert!(user.resolution_warnings[0].starts_with(MISSING_DN));
+    assert!(user.resolution_warnings[1].starts_with(&format!("{} (nested in Looped)", MISSING_NESTED_DN)),
+        "{}", user.resolution_warnings[1]);
+
+    // Fully resolved users carry no warnings
+    assert!(client.get_user("ops.user").await.unwrap().resolution_warnings.is_empty());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn groups_of_trusting_domains_are_found_through_foreign_principals() {
+    use ad_report::risk_calculator::{RiskCalculator, RiskFactorType};
+
+    const PARTNER_DN: &str = "DC=partner,DC=example";
+    const PARTNER_ACCESS_DN: &str = "CN=Partner Access,OU=Groups,DC=partner,DC=example";
+    const PARTNER_ADMINS_DN: &str = "CN=Partner Admins,OU=Groups,DC=partner,DC=example";
+    // partner.example records jdoe (S-1-5-21-1-2-3-1105) as a foreign security principal of
+    // its own; jdoe's memberOf never mentions it
+    let trusting = MockLdapServer::start(MockDirectory::new(PARTNER_DN)
+        .with_entry(MockEntry::new("CN=S-1-5-21-1-2-3-1105,CN=ForeignSecurityPrincipals,DC=partner,DC=example")
+            .attr("objectClass", &["top", "foreignSecurityPrincipal"])
+            .attr("memberOf", &[PARTNER_ACCESS_DN]))
+        .with_entry(MockEntry::new(PARTNER_ACCESS_DN)
+            .attr("objectClass", &["top", "group"])
+            .attr("cn", &["Partner Access"])
+            .attr("groupType", &[GLOBAL_SECURITY_GROUP])
+            .attr("memberOf", &[PARTNER_ADMINS_DN]))
+        .with_entry(MockEntry::new(PARTNER_ADMINS_DN)
+            .attr("objectClass", &["top", "group"])
+            .attr("cn", &["Partner Admins"])
+            .attr("groupType", &[GLOBAL_SECURITY_GROUP]))).await;
+    let home = MockLdapServer::start(directory()
+        // TRUST_DIRECTION_INBOUND: partner.example trusts corp.example.com
+        .with_entry(MockEntry::new("CN=partner.example,CN=System,DC=corp,DC=example,DC=com")
+            .attr("objectClass", &["top", "trustedDomain"])
+            .attr("trustPartner", &["partner.example"])
+            .attr("trustDirection", &["1"]))
+        // TRUST_DIRECTION_OUTBOUND only: other.example cannot grant jdoe anything, and is
+        // never contacted (the proxy has no route to it)
+        .with_entry(MockEntry::new("CN=other.example,CN=System,DC=corp,DC=example,DC=com")
+            .attr("objectClass", &["top", "trustedDomain"])
+            .attr("trustPartner", &["other.example"])
+            .attr("trustDirection", &["2"]))).await;
+    let proxy = format!("127.0.0.1:{}", start_socks5_proxy(&[("partner.example", trusting.port)]).await);
+
+    let mut client = LdapClient::connect_via_socks5("127.0.0.1", Some(home.port), false, TlsMinVersion::default(), false, &proxy)
+        .await
+        .unwrap();
+    client.bind_simple("CN=reader,DC=corp,DC=example,DC=com", &SecretString::from("secret")).await.unwrap();
+
+    let user = client.get_user("jdoe").await.unwrap();
+    assert!(user.resolution_warnings.is_empty(), "{:?}", user.resolution_warnings);
+    let partner = user.groups.iter().find(|g| g.name == "Partner Access").expect("trusting domain group");
+    assert_eq!(partner.foreign_sid.as_deref(), Some("S-1-5-21-1-2-3-1105"));
+    assert_eq!(partner.nested_groups[0].name, "Partner Admins");
+
+    // The trust crossing counts as cross-domain access
+    let risk = RiskCalculator::new().calculate_risk(&user).unwrap();
+    let cross_domain = risk.contributing_factors.iter()
+        .find(|f| matches!(f.factor_type, RiskFactorType::CrossDomainAccess))
+        .expect("cross-domain factor");
+    assert!(cross_domain.description.contains("partner.example"), "{}", cross_domain.description);
+}
+
+#[tokio::test]
+async fn object_classes_identify_computer_accounts() {
+    let directory = directory()
+        .with_entry(MockEntry::new("CN=WS01,OU=Computers,DC=corp,DC=example,DC=com")
+            .attr("objectClass", &["top", "person", "organizationalPerson", "user", "computer"])
+            .attr("sAMAccountName", &["WS01$"])
+            // WORKSTATION_TRUST_ACCOUNT
+            .attr("userAccountControl", &["4096"]));
+    let mut client = bound_client_for(directory).await;
+
+    let computer = client.get_user("WS01$").await.unwrap();
+    assert_eq!(computer.object_classes.last().map(String::as_str), Some("computer"));
+    assert_eq!(computer.non_user_kind(), Some("computer account"));
+
+    let jdoe = client.get_user("jdoe").await.unwrap();
+    assert_eq!(jdoe.object_classes, vec!["top", "person", "organizationalPerson", "user"]);
+    assert_eq!(jdoe.non_user_kind(), None);
+}
+
+#[tokio::test]
+async fn member_counts_are_only_fetched_when_asked_for() {
+    let mut client = bound_client().await;
+    let user = client.get_user("ops.user").await.unwrap();
+    assert!(user.all_groups().iter().all(|g| g.member_count.is_none()));
+
+    let mut client = bound_client().await;
+    client.set_count_members(true);
+    let user = client.get_user("ops.user").await.unwrap();
+    let count = |name: &str| user.all_groups().iter()
+        .find(|g| g.name == name)
+        .and_then(|g| g.member_count);
+
+    // Direct members only: users and nested groups alike
+    assert_eq!(count("Ops Team"), Some(1));
+    assert_eq!(count("Ops Leads"), Some(2));
+    assert_eq!(count("Ops All"), Some(2));
+}
+
+#[tokio::test]
+async fn member_counts_include_primary_group_members() {
+    // AD keeps an account's primary group out of both its memberOf and the group's member
+    let directory = (1..=2).fold(directory(), |directory, n| directory
+        .with_entry(MockEntry::new(&format!("CN=Ops Primary {},OU=Staff,DC=corp,DC=example,DC=com", n))
+            .attr("objectClass", &["top", "person", "organizationalPerson", "user"])
+            .attr("sAMAccountName", &[&format!("ops.primary{}", n)])
+            .attr("userAccountControl", &["512"])
+            .attr("primaryGroupID", &["1203"])));
+    let mut client = bound_client_for(directory).await;
+    client.set_count_members(true);
+
+    let user = client.get_user("ops.user").await.unwrap();
+    let ops_all = user.all_groups().into_iter().find(|g| g.name == "Ops All").unwrap();
+    assert_eq!(ops_all.member_count, Some(4));
+}
+
+#[tokio::test]
+async fn server_identity_comes_from_root_dse() {
+    let mut client = bound_client_for(directory().with_host_name("DC01.corp.example.com")).await;
+    let identity = client.server_identity().await.unwrap();
+    assert_eq!(identity.domain.as_deref(), Some("corp.example.com"));
+    assert_eq!(identity.host_name.as_deref(), Some("dc01.corp.example.com"));
+
+    let mut client = bound_client().await;
+    let identity = client.server_identity().await.unwrap();
+    assert_eq!(identity.domain.as_deref(), Some("corp.example.com"));
+    assert_eq!(identity.host_name, None);
+}
+
+#[tokio::test]
+async fn token_groups_add_memberships_the_member_of_walk_misses() {
+    let mut foreign_sid = vec![1, 5, 0, 0, 0, 0, 0, 5];
+    for sub_authority in [21, 9, 9, 9, 1000] {
+        foreign_sid.extend_from_slice(&u32::to_le_bytes(sub_authority));
+    }
+    let token_groups: Vec<Vec<u8>> = vec![
+        object_sid(513), object_sid(1201), object_sid(1202), object_sid(1203), object_sid(1300), foreign_sid,
+    ];
+    let token_groups: Vec<&[u8]> = token_groups.iter().map(Vec::as_slice).collect();
+
+    let directory = directory()
+        .with_entry(MockEntry::new("CN=Token User,OU=Staff,DC=corp,DC=example,DC=com")
+            .attr("objectClass", &["top", "person", "organizationalPerson", "user"])
+            .attr("sAMAccountName", &["token.user"])
+            .attr("userAccountControl", &["512"])
+            .attr("memberOf", &[OPS_TEAM_DN])
+            .attr("primaryGroupID", &["513"])
+            .bin_attr("objectSid", &object_sid(1150))
+            .bin_attrs("tokenGroups", &token_groups))
+        .with_entry(MockEntry::new("CN=Hidden Admins,OU=Groups,DC=corp,DC=example,DC=com")
+            .attr("objectClass", &["top", "group"])
+            .attr("cn", &["Hidden Admins"])
+            .attr("groupType", &[GLOBAL_SECURITY_GROUP])
+            .bin_attr("objectSid", &object_sid(1300)));
+
+    let mut client = bound_client_for(directory.clone()).await;
+    let user = client.get_user("token.user").await.unwrap();
+    assert!(user.token_only_groups.is_empty());
+    assert!(!user.all_groups().iter().any(|g| g.name == "Hidden Admins"));
+
+    let mut client = bound_client_for(directory).await;
+    client.set_use_token_groups(true);
+    let user = client.get_user("token.user").await.unwrap();
+
+    // Groups already reached through memberOf and the primary group are not repeated
+    let mut token_only = user.token_only_groups.clone();
+    token_only.sort();
+    assert_eq!(token_only, vec!["Hidden Admins", "S-1-5-21-9-9-9-1000"]);
+    assert_eq!(user.groups.len(), 3);
+    assert_eq!(user.groups[0].name, "Ops Team");
+    assert!(user.resolution_warnings.is_empty(), "{:?}", user.resolution_warnings);
+}
+
+#[tokio::test]
+async fn missing_attributes_are_split_by_likely_cause() {
+    let mut client = bound_client().await;
+    let user = client.get_user("jdoe").await.unwrap();
+
+    // whenChanged is on every AD user, so the fixture's lack of it reads as a permissions gap
+    assert_eq!(user.missing_attributes.unreadable, vec!["whenChanged"]);
+    assert!(user.missing_attributes.not_set.contains(&"description".to_string()));
+    assert!(user.missing_attributes.not_set.contains(&"logonHours".to_string()));
+    assert!(!user.missing_attributes.not_set.contains(&"mail".to_string()));
+}
+
+#[tokio::test]
+async fn get_computer_reads_host_and_delegation_settings() {
+    let directory = directory()
+        .with_entry(MockEntry::new("CN=WS01,OU=Workstations,DC=corp,DC=example,DC=com")
+            .attr("objectClass", &["top", "person", "organizationalPerson", "user", "computer"])
+            .attr("sAMAccountName", &["WS01$"])
+            .attr("dNSHostName", &["ws01.corp.example.com"])
+            .attr("operatingSystem", &["Windows 11 Enterprise"])
+            .attr("operatingSystemVersion", &["10.0 (22631)"])
+            // WORKSTATION_TRUST_ACCOUNT | TRUSTED_FOR_DELEGATION | TRUSTED_TO_AUTH_FOR_DELEGATION
+            .attr("userAccountControl", &["17305600"])
+            .attr("msDS-AllowedToDelegateTo", &["cifs/fs01.corp.example.com", "http/app.corp.example.com"])
+            .attr("memberOf", &[OPS_TEAM_DN])
+            .attr("primaryGroupID", &["515"]));
+    let mut client = bound_client_for(directory).await;
+
+    let computer = client.get_computer("ws01").await.expect("WS01$ exists");
+    assert_eq!(computer.account.sam_account_name, "WS01$");
+    assert_eq!(computer.dns_host_name.as_deref(), Some("ws01.corp.example.com"));
+    assert_eq!(computer.operating_system_summary().as_deref(), Some("Windows 11 Enterprise (10.0 (22631))"));
+    assert!(!computer.is_domain_controller);
+    assert!(computer.delegation.unconstrained);
+    assert!(computer.delegation.protocol_transition);
+    assert_eq!(computer.delegation.constrained_to.len(), 2);
+    assert!(!computer.delegation.resource_based);
+    assert_eq!(computer.account.groups[0].name, "Ops Team");
+
+    // Users are not matched by the computer filter
+    let err = client.get_computer("jdoe").await.unwrap_err();
+    assert!(matches!(err, AdReportError::UserNotFound(_)), "{:?}", err);
+}
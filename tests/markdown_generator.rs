@@ -0,0 +1,89 @@
+use ad_report::markdown_generator::MarkdownGenerator;
+use ad_report::models::{ADGroup, ADUser};
+use ad_report::report_data::EnhancedReportData;
+use ad_report::risk_calculator::RiskCalculator;
+use chrono::{TimeZone, Utc};
+
+const DOMAIN: &str = "corp.example.com";
+
+fn fixture() -> EnhancedReportData {
+    let mut user = ADUser::new("CN=John Doe,OU=Staff,DC=corp,DC=example,DC=com".to_string(), "jdoe".to_string());
+    user.display_name = Some("John Doe".to_string());
+
+    let mut admins = ADGroup::new(
+        "CN=Domain Admins,CN=Users,DC=corp,DC=example,DC=com".to_string(),
+        "Domain Admins".to_string(),
+    );
+    let mut builtin_admins = ADGroup::new(
+        "CN=Administrators,CN=Builtin,DC=corp,DC=example,DC=com".to_string(),
+        "Administrators".to_string(),
+    );
+    builtin_admins.inheritance_path = vec![admins.distinguished_name.clone()];
+    admins.nested_groups.push(builtin_admins);
+    user.groups.push(admins);
+    user.groups.push(ADGroup::new(
+        "CN=Ops | Support,OU=Groups,DC=corp,DC=example,DC=com".to_string(),
+        "Ops | Support".to_string(),
+    ));
+
+    let risk = RiskCalculator::new().calculate_risk(&user).unwrap();
+    let generation_time = Utc.with_ymd_and_hms(2024, 7, 1, 9, 0, 0).unwrap();
+    EnhancedReportData::new_at(user, DOMAIN.to_string(), format!("dc1.{}", DOMAIN), Some(risk), generation_time)
+}
+
+#[test]
+fn markdown_tables_carry_the_same_groups_and_findings_as_the_pdf() {
+    let data = fixture();
+    let rendered = MarkdownGenerator::new().render(&data).expect("render markdown");
+    let md = String::from_utf8(rendered.bytes).expect("markdown is UTF-8");
+
+    assert_eq!(rendered.filename, "jdoe_ad_report_20240701_090000.md");
+    assert!(md.starts_with("# ACTIVE DIRECTORY USER ACCESS REPORT"));
+    for heading in ["## User Information", "## Account Status", "## Risk Assessment", "### Findings", "## Group Memberships"] {
+        assert!(md.contains(heading), "missing {}", heading);
+    }
+
+    let risk = data.risk_assessment.as_ref().unwrap();
+    assert!(md.contains(&format!("**Risk score: {}/100 ({:?})**", risk.overall_score, risk.risk_level)));
+    assert!(!risk.contributing_factors.is_empty());
+    for factor in &risk.contributing_factors {
+        assert!(md.contains(&factor.risk_contribution.to_string()));
+        assert!(md.contains(factor.factor_type.label()));
+    }
+    for group in &risk.group_contributions {
+        assert!(md.contains(&format!("| {} |", group.group_name.replace('|', "\\|"))));
+    }
+
+    assert!(md.contains("- Password Policy: Not available"));
+    assert!(md.contains("| Administrators | Security | Global | - | Nested: Administrators \\<- Domain Admins \\<- jdoe |"));
+    // Pipes in directory data must not split table cells
+    assert!(md.contains("| Ops \\| Support |"));
+}
+
+#[test]
+fn markdown_respects_section_selection() {
+    use ad_report::report_data::ReportSection;
+
+    let md = MarkdownGenerator::new()
+        .with_sections(vec![ReportSection::Groups])
+        .generate_report(&fixture())
+        .expect("render markdown");
+
+    assert!(md.contains("## Group Memberships"));
+    assert!(!md.contains("## Risk Assessment"));
+    assert!(!md.contains("## Account Status"));
+}
+
+#[test]
+fn distinguished_names_with_backticks_stay_in_one_code_span() {
+    let user = ADUser::new("CN=J`Doe``,OU=Staff,DC=corp,DC=example,DC=com".to_string(), "jdoe".to_string());
+    let data = EnhancedReportData::new_at(user, DOMAIN.to_string(), format!("dc1.{}", DOMAIN), None, Utc::now());
+    let md = MarkdownGenerator::new().generate_report(&data).expect("render markdown");
+
+    assert!(md.contains("Distinguished Name: ```CN=J`Doe``,OU=Staff,DC=corp,DC=example,DC=com```"), "{}", md);
+
+    let user = ADUser::new("`CN=Edge".to_string(), "edge".to_string());
+    let data = EnhancedReportData::new_at(user, DOMAIN.to_string(), format!("dc1.{}", DOMAIN), None, Utc::now());
+    let md = MarkdownGenerator::new().generate_report(&data).expect("render markdown");
+    assert!(md.contains("Distinguished Name: `` `CN=Edge ``"), "{}", md);
+}
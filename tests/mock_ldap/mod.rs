@@ -0,0 +1,339 @@
+//! Minimal in-process LDAP server for integration tests
+//!
+//! Speaks just enough LDAPv3 (bind, search, unbind) for `LdapClient`: entries are
+//! matched against and/or/not, equality, substring and presence filters, and
+//! searches honour base/subtree scope. Any password except `wrong` binds. Subtree
+//! searches end with a continuation reference for each configured referral.
+
+use ad_report::sid::binary_sid_to_string;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Password that the mock server rejects with invalidCredentials
+pub const REJECTED_PASSWORD: &str = "wrong";
+
+// BER tag classes
+const UNIVERSAL: u8 = 0;
+const APPLICATION: u8 = 1;
+const CONTEXT: u8 = 2;
+
+// LDAP protocol operations (APPLICATION tags)
+const BIND_REQUEST: u64 = 0;
+const BIND_RESPONSE: u64 = 1;
+const UNBIND_REQUEST: u64 = 2;
+const SEARCH_REQUEST: u64 = 3;
+const SEARCH_RESULT_ENTRY: u64 = 4;
+const SEARCH_RESULT_DONE: u64 = 5;
+const SEARCH_RESULT_REFERENCE: u64 = 19;
+
+/// A directory entry with string or binary attribute values
+#[derive(Debug, Clone)]
+pub struct MockEntry {
+    dn: String,
+    attrs: Vec<(String, Vec<Vec<u8>>)>,
+}
+
+impl MockEntry {
+    pub fn new(dn: &str) -> Self {
+        Self { dn: dn.to_string(), attrs: Vec::new() }
+    }
+
+    pub fn attr(mut self, name: &str, values: &[&str]) -> Self {
+        let values = values.iter().map(|v| v.as_bytes().to_vec()).collect();
+        self.attrs.push((name.to_string(), values));
+        self
+    }
+
+    pub fn bin_attr(self, name: &str, value: &[u8]) -> Self {
+        self.bin_attrs(name, &[value])
+    }
+
+    pub fn bin_attrs(mut self, name: &str, values: &[&[u8]]) -> Self {
+        self.attrs.push((name.to_string(), values.iter().map(|v| v.to_vec()).collect()));
+        self
+    }
+
+    fn values(&self, name: &str) -> Option<&Vec<Vec<u8>>> {
+        self.attrs.iter()
+            .find(|(attr, _)| attr.eq_ignore_ascii_case(name))
+            .map(|(_, values)| values)
+    }
+}
+
+/// Entries served below `base_dn`, which is also advertised as defaultNamingContext
+#[derive(Debug, Clone)]
+pub struct MockDirectory {
+    base_dn: String,
+    host_name: Option<String>,
+    entries: Vec<MockEntry>,
+    referrals: Vec<String>,
+}
+
+impl MockDirectory {
+    pub fn new(base_dn: &str) -> Self {
+        Self { base_dn: base_dn.to_string(), host_name: None, entries: Vec::new(), referrals: Vec::new() }
+    }
+
+    /// Advertise `host_name` as the server's dnsHostName in rootDSE
+    pub fn with_host_name(mut self, host_name: &str) -> Self {
+        self.host_name = Some(host_name.to_string());
+        self
+    }
+
+    pub fn with_entry(mut self, entry: MockEntry) -> Self {
+        self.entries.push(entry);
+        self
+    }
+
+    /// Return `url` as a continuation reference from every subtree search
+    pub fn with_referral(mut self, url: &str) -> Self {
+        self.referrals.push(url.to_string());
+        self
+    }
+}
+
+/// A running mock server on 127.0.0.1 (stopped when the test runtime shuts down)
+pub struct MockLdapServer {
+    pub port: u16,
+}
+
+impl MockLdapServer {
+    pub async fn start(directory: MockDirectory) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind mock LDAP listener");
+        let port = listener.local_addr().expect("listener address").port();
+        let directory = Arc::new(directory);
+
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve_connection(stream, directory.clone()));
+            }
+        });
+
+        Self { port }
+    }
+}
+
+async fn serve_connection(mut stream: TcpStream, directory: Arc<MockDirectory>) {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    loop {
+        while let Some((message, used)) = Ber::decode(&buffer) {
+            buffer.drain(..used);
+            let Some(responses) = handle_message(&message, &directory) else {
+                return; // unbind
+            };
+            for response in responses {
+                if stream.write_all(&response).await.is_err() {
+                    return;
+                }
+            }
+        }
+
+        match stream.read(&mut chunk).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+        }
+    }
+}
+
+/// Responses to one LDAPMessage, or None when the client unbinds
+fn handle_message(message: &Ber, directory: &MockDirectory) -> Option<Vec<Vec<u8>>> {
+    let parts = message.children();
+    let (message_id, op) = (parts.first()?, parts.get(1)?);
+    let reply = |op: Vec<u8>| Ber::encode(UNIVERSAL, true, 16, &[message_id.encoded(), op].concat());
+
+    match (op.class, op.tag) {
+        (APPLICATION, BIND_REQUEST) => {
+            let fields = op.children();
+            let password = fields.get(2).map(|p| p.content.clone()).unwrap_or_default();
+            let code = if password == REJECTED_PASSWORD.as_bytes() { 49 } else { 0 };
+            Some(vec![reply(ldap_result(BIND_RESPONSE, code))])
+        }
+        (APPLICATION, UNBIND_REQUEST) => None,
+        (APPLICATION, SEARCH_REQUEST) => {
+            let fields = op.children();
+            let base = String::from_utf8_lossy(&fields.first()?.content).to_string();
+            let scope = fields.get(1)?.content.first().copied().unwrap_or(2);
+            let filter = fields.get(6)?;
+            let requested: Vec<String> = fields.get(7)
+                .map(|attrs| attrs.children().iter()
+                    .map(|a| String::from_utf8_lossy(&a.content).to_string())
+                    .collect())
+                .unwrap_or_default();
+
+            let mut responses: Vec<Vec<u8>> = search(directory, &base, scope, filter)
+                .into_iter()
+                .map(|entry| reply(encode_entry(&entry, &requested)))
+                .collect();
+            if scope == 2 {
+                responses.extend(directory.referrals.iter().map(|url| {
+                    reply(Ber::encode(APPLICATION, true, SEARCH_RESULT_REFERENCE, &octet_string(url.as_bytes())))
+                }));
+            }
+            responses.push(reply(ldap_result(SEARCH_RESULT_DONE, 0)));
+            Some(responses)
+        }
+        _ => Some(Vec::new()),
+    }
+}
+
+fn search(directory: &MockDirectory, base: &str, scope: u8, filter: &Ber) -> Vec<MockEntry> {
+    // rootDSE
+    if base.is_empty() && scope == 0 {
+        let root = MockEntry::new("").attr("defaultNamingContext", &[&directory.base_dn]);
+        return match &directory.host_name {
+            Some(host_name) => vec![root.attr("dnsHostName", &[host_name])],
+            None => vec![root],
+        };
+    }
+
+    let base = base.to_lowercase();
+    directory.entries.iter()
+        .filter(|entry| {
+            let dn = entry.dn.to_lowercase();
+            match scope {
+                0 => dn == base,
+                1 => dn.split_once(',').is_some_and(|(_, parent)| parent == base),
+                _ => base.is_empty() || dn == base || dn.ends_with(&format!(",{}", base)),
+            }
+        })
+        .filter(|entry| matches_filter(entry, filter))
+        .cloned()
+        .collect()
+}
+
+fn matches_filter(entry: &MockEntry, filter: &Ber) -> bool {
+    let lower = |bytes: &[u8]| bytes.to_ascii_lowercase();
+    let text = |ber: &Ber| String::from_utf8_lossy(&ber.content).to_string();
+
+    if filter.class != CONTEXT {
+        return false;
+    }
+
+    match filter.tag {
+        0 => filter.children().iter().all(|f| matches_filter(entry, f)),
+        1 => filter.children().iter().any(|f| matches_filter(entry, f)),
+        2 => !filter.children().first().is_some_and(|f| matches_filter(entry, f)),
+        3 => {
+            let parts = filter.children();
+            let (Some(attr), Some(value)) = (parts.first(), parts.get(1)) else { return false };
+            // Like AD, objectSid can be matched with a string SID
+            let is_sid = text(attr).eq_ignore_ascii_case("objectSid");
+            entry.values(&text(attr)).is_some_and(|values| values.iter().any(|v| {
+                let v = match binary_sid_to_string(v) {
+                    Ok(sid) if is_sid => sid.into_bytes(),
+                    _ => v.clone(),
+                };
+                lower(&v) == lower(&value.content)
+            }))
+        }
+        4 => {
+            let parts = filter.children();
+            let (Some(attr), Some(substrings)) = (parts.first(), parts.get(1)) else { return false };
+            entry.values(&text(attr)).is_some_and(|values| values.iter().any(|v| {
+                let value = String::from_utf8_lossy(&lower(v)).to_string();
+                substrings.children().iter().all(|s| {
+                    let needle = String::from_utf8_lossy(&lower(&s.content)).to_string();
+                    match s.tag {
+                        0 => value.starts_with(&needle),
+                        2 => value.ends_with(&needle),
+                        _ => value.contains(&needle),
+                    }
+                })
+            }))
+        }
+        7 => entry.values(&text(filter)).is_some(),
+        _ => false,
+    }
+}
+
+fn encode_entry(entry: &MockEntry, requested: &[String]) -> Vec<u8> {
+    let wants = |name: &str| requested.is_empty()
+        || requested.iter().any(|r| r == "*" || r.eq_ignore_ascii_case(name));
+
+    let attributes: Vec<u8> = entry.attrs.iter()
+        .filter(|(name, _)| wants(name))
+        .flat_map(|(name, values)| {
+            let values: Vec<u8> = values.iter().flat_map(|v| octet_string(v)).collect();
+            let attribute = [octet_string(name.as_bytes()), Ber::encode(UNIVERSAL, true, 17, &values)].concat();
+            Ber::encode(UNIVERSAL, true, 16, &attribute)
+        })
+        .collect();
+
+    let content = [octet_string(entry.dn.as_bytes()), Ber::encode(UNIVERSAL, true, 16, &attributes)].concat();
+    Ber::encode(APPLICATION, true, SEARCH_RESULT_ENTRY, &content)
+}
+
+/// LDAPResult-shaped response: resultCode, matchedDN, diagnosticMessage
+fn ldap_result(op: u64, code: u8) -> Vec<u8> {
+    let content = [Ber::encode(UNIVERSAL, false, 10, &[code]), octet_string(b""), octet_string(b"")].concat();
+    Ber::encode(APPLICATION, true, op, &content)
+}
+
+fn octet_string(value: &[u8]) -> Vec<u8> {
+    Ber::encode(UNIVERSAL, false, 4, value)
+}
+
+/// One BER element (single-byte identifiers only, which covers all LDAP tags)
+#[derive(Debug, Clone)]
+struct Ber {
+    class: u8,
+    constructed: bool,
+    tag: u64,
+    content: Vec<u8>,
+}
+
+impl Ber {
+    /// Decode the element at the start of `input`, or None if it is incomplete
+    fn decode(input: &[u8]) -> Option<(Ber, usize)> {
+        let identifier = *input.first()?;
+        let first_length = *input.get(1)?;
+
+        let (length, header) = if first_length < 0x80 {
+            (first_length as usize, 2)
+        } else {
+            let count = (first_length & 0x7F) as usize;
+            let bytes = input.get(2..2 + count)?;
+            (bytes.iter().fold(0usize, |acc, b| (acc << 8) | *b as usize), 2 + count)
+        };
+
+        let content = input.get(header..header + length)?.to_vec();
+        let ber = Ber {
+            class: identifier >> 6,
+            constructed: identifier & 0x20 != 0,
+            tag: (identifier & 0x1F) as u64,
+            content,
+        };
+        Some((ber, header + length))
+    }
+
+    fn children(&self) -> Vec<Ber> {
+        let mut children = Vec::new();
+        let mut rest = self.content.as_slice();
+        while let Some((child, used)) = Ber::decode(rest) {
+            children.push(child);
+            rest = &rest[used..];
+        }
+        children
+    }
+
+    fn encoded(&self) -> Vec<u8> {
+        Ber::encode(self.class, self.constructed, self.tag, &self.content)
+    }
+
+    fn encode(class: u8, constructed: bool, tag: u64, content: &[u8]) -> Vec<u8> {
+        let mut out = vec![(class << 6) | if constructed { 0x20 } else { 0 } | tag as u8];
+        let length = content.len();
+        if length < 0x80 {
+            out.push(length as u8);
+        } else {
+            let bytes: Vec<u8> = length.to_be_bytes().into_iter().skip_while(|b| *b == 0).collect();
+            out.push(0x80 | bytes.len() as u8);
+            out.extend(bytes);
+        }
+        out.extend_from_slice(content);
+        out
+    }
+}
@@ -0,0 +1,174 @@
+use ad_report::models::{ADGroup, ADUser};
+use chrono::{Duration, Utc};
+
+fn group(name: &str, nested: Vec<ADGroup>) -> ADGroup {
+    let mut group = ADGroup::new(format!("CN={},OU=Groups,DC=corp,DC=example,DC=com", name), name.to_string());
+    group.nested_groups = nested;
+    group
+}
+
+fn names(groups: &[ADGroup]) -> Vec<&str> {
+    groups.iter().map(|g| g.name.as_str()).collect()
+}
+
+fn names_of_all(user: &ADUser) -> Vec<&str> {
+    user.all_groups().iter().map(|g| g.name.as_str()).collect()
+}
+
+/// User directly in A and B, where A is nested in B and C, B is nested in C,
+/// and C is nested in Root; the memberOf resolution repeats B, C and Root
+fn overlapping_user() -> ADUser {
+    let root = || group("Root", vec![]);
+    let c = || group("C", vec![root()]);
+    let b = || group("B", vec![c()]);
+    let a = group("A", vec![b(), c()]);
+
+    let mut user = ADUser::new("CN=jdoe,OU=Staff,DC=corp,DC=example,DC=com".to_string(), "jdoe".to_string());
+    user.groups = vec![a, b()];
+    user
+}
+
+#[test]
+fn dedupe_collapses_overlapping_memberships() {
+    let mut user = overlapping_user();
+    assert_eq!(user.all_groups().len(), 9);
+
+    user.dedupe_groups();
+
+    // Direct memberships win over the same group reached through nesting
+    assert_eq!(names(&user.groups), vec!["A", "B"]);
+    let a = &user.groups[0];
+    assert_eq!(names(&a.nested_groups), vec!["C"]);
+    assert_eq!(names(&a.nested_groups[0].nested_groups), vec!["Root"]);
+    assert!(user.groups[1].nested_groups.is_empty());
+
+    let mut all = names_of_all(&user);
+    all.sort();
+    assert_eq!(all, vec!["A", "B", "C", "Root"]);
+}
+
+#[test]
+fn counts_are_unique_before_and_after_dedupe() {
+    let mut user = overlapping_user();
+    assert_eq!(user.direct_group_count(), 2);
+    assert_eq!(user.nested_group_count(), 2);
+
+    user.dedupe_groups();
+    assert_eq!(user.direct_group_count(), 2);
+    assert_eq!(user.nested_group_count(), 2);
+}
+
+#[test]
+fn dedupe_matches_distinguished_names_case_insensitively() {
+    let mut user = overlapping_user();
+    let mut upper = group("A", vec![]);
+    upper.distinguished_name = upper.distinguished_name.to_uppercase();
+    user.groups.push(upper);
+
+    user.dedupe_groups();
+    assert_eq!(names(&user.groups), vec!["A", "B"]);
+}
+
+#[test]
+fn primary_group_is_not_repeated_as_direct_or_nested() {
+    let mut user = overlapping_user();
+    user.primary_group = Some(group("C", vec![]));
+    user.groups.push(group("C", vec![]));
+
+    assert_eq!(user.direct_group_count(), 2);
+    assert_eq!(user.nested_group_count(), 1);
+
+    user.dedupe_groups();
+    assert_eq!(names(&user.groups), vec!["A", "B"]);
+    assert!(user.groups[0].nested_groups.is_empty());
+    assert!(user.groups[1].nested_groups.is_empty());
+
+    // The primary group takes over the nesting it was found with
+    let primary = user.primary_group.as_ref().unwrap();
+    assert_eq!(names(&primary.nested_groups), vec!["Root"]);
+    assert_eq!(user.all_groups().len(), 4);
+    assert_eq!(user.nested_group_count(), 1);
+}
+
+#[test]
+fn non_user_kind_comes_from_object_classes() {
+    let mut user = ADUser::new("CN=x,DC=corp,DC=example,DC=com".to_string(), "x".to_string());
+    assert_eq!(user.non_user_kind(), None);
+
+    user.object_classes = ["top", "person", "organizationalPerson", "user"].map(String::from).to_vec();
+    assert_eq!(user.non_user_kind(), None);
+
+    user.object_classes.push("Computer".to_string());
+    assert_eq!(user.non_user_kind(), Some("computer account"));
+
+    user.object_classes = vec!["top".to_string(), "leaf".to_string(), "trustedDomain".to_string()];
+    assert_eq!(user.non_user_kind(), Some("trust account"));
+}
+
+#[test]
+fn group_details_include_member_count_when_known() {
+    let mut g = group("Staff", vec![]);
+    assert_eq!(g.details(), "Security, Global");
+    g.member_count = Some(1);
+    assert_eq!(g.details(), "Security, Global, 1 member");
+    g.member_count = Some(5000);
+    assert_eq!(g.details(), "Security, Global, 5000 members");
+}
+
+#[test]
+fn primary_group_default_depends_on_account_kind() {
+    let mut user = ADUser::new("CN=jdoe,DC=corp,DC=example,DC=com".to_string(), "jdoe".to_string());
+    assert_eq!(user.anomalous_primary_group_id(), None, "no primaryGroupID read");
+
+    user.primary_group_id = Some(513);
+    assert_eq!(user.anomalous_primary_group_id(), None);
+    user.primary_group_id = Some(512);
+    assert_eq!(user.anomalous_primary_group_id(), Some(512));
+
+    // The built-in Guest account defaults to Domain Guests
+    user.object_sid = Some("S-1-5-21-1-2-3-501".to_string());
+    user.primary_group_id = Some(514);
+    assert_eq!(user.anomalous_primary_group_id(), None);
+
+    let mut computer = ADUser::new("CN=DC01,OU=Domain Controllers,DC=corp,DC=example,DC=com".to_string(), "DC01$".to_string());
+    computer.object_classes = vec!["top".into(), "person".into(), "user".into(), "computer".into()];
+    computer.primary_group_id = Some(516);
+    assert_eq!(computer.anomalous_primary_group_id(), None);
+    computer.primary_group_id = Some(513);
+    assert_eq!(computer.anomalous_primary_group_id(), Some(513));
+}
+
+#[test]
+fn iter_groups_walks_all_groups_in_order() {
+    let mut user = overlapping_user();
+    user.primary_group = Some(group("Domain Users", vec![group("Staff", vec![])]));
+
+    let walked: Vec<&str> = user.iter_groups().map(|g| g.name.as_str()).collect();
+    assert_eq!(walked, names_of_all(&user));
+    assert_eq!(walked[..3], ["Domain Users", "Staff", "A"]);
+    assert_eq!(user.group_count(), walked.len());
+    assert!(user.has_groups());
+    assert!(!ADUser::new("CN=x,DC=corp".to_string(), "x".to_string()).has_groups());
+}
+
+#[test]
+fn computed_password_expiry_overrides_the_uac_flag() {
+    let now = Utc::now();
+    let mut user = ADUser::new("CN=jdoe,OU=Staff,DC=corp,DC=example,DC=com".to_string(), "jdoe".to_string());
+
+    // Without a computed date the flag is all there is
+    user.password_expired = true;
+    assert!(user.is_password_expired(now));
+    assert!(user.password_expiry_discrepancy(now).is_none());
+
+    // Stale flag: the date is still ahead
+    user.password_expiry = Some(now + Duration::days(10));
+    assert!(!user.is_password_expired(now));
+    assert!(user.password_expiry_discrepancy(now).unwrap().contains("flag is set"));
+
+    // Flag never set although the date has passed
+    user.password_expired = false;
+    user.password_expiry = Some(now - Duration::days(1));
+    assert!(user.is_password_expired(now));
+    assert!(user.password_expiry_discrepancy(now).unwrap().contains("not set"));
+}
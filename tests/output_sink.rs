@@ -0,0 +1,74 @@
+use ad_report::output_sink::{DirSink, FileSink, OutputSink, ZipSink};
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+
+fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("ad-report-sink-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&path);
+    let _ = fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn dir_sink_creates_the_directory_and_uses_the_suggested_name() {
+    let dir = temp_path("dir").join("nested");
+    let sink = DirSink::new(&dir);
+
+    sink.write("jdoe_ad_report_20240701_090000.pdf", b"%PDF-1.3").unwrap();
+
+    let written = dir.join("jdoe_ad_report_20240701_090000.pdf");
+    assert_eq!(fs::read(&written).unwrap(), b"%PDF-1.3");
+    assert_eq!(sink.location("jdoe_ad_report_20240701_090000.pdf"), written.display().to_string());
+    // Only the finished report is left behind
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+}
+
+#[test]
+fn file_sink_ignores_the_suggested_name() {
+    let path = temp_path("file.pdf");
+    let sink = FileSink::new(&path);
+
+    sink.write("jdoe_ad_report_20240701_090000.pdf", b"first").unwrap();
+    sink.write("other_ad_report_20240701_090000.pdf", b"second").unwrap();
+
+    assert_eq!(fs::read(&path).unwrap(), b"second");
+    assert_eq!(sink.location("anything.pdf"), path.display().to_string());
+}
+
+#[test]
+fn default_dir_sink_writes_relative_to_the_current_directory() {
+    assert_eq!(DirSink::default().location("jdoe.pdf"), "jdoe.pdf");
+}
+
+#[test]
+fn zip_sink_collects_reports_and_companion_files_into_one_archive() {
+    let dir = temp_path("zip");
+    fs::create_dir_all(&dir).unwrap();
+    let archive = dir.join("reports.zip");
+    let summary = dir.join("summary.csv");
+    fs::write(&summary, "user,status\njdoe,generated\n").unwrap();
+
+    let sink = ZipSink::create(&archive).unwrap();
+    sink.write("jdoe_ad_report_20240701_090000.pdf", b"%PDF-1.3 jdoe").unwrap();
+    sink.write_alongside("jdoe_ad_report_20240701_090000.pdf", "json", b"{}").unwrap();
+    sink.write("asmith_ad_report_20240701_090000.pdf", b"%PDF-1.3 asmith").unwrap();
+    assert!(!archive.exists(), "archive appears before it is complete");
+    sink.add_file(&summary).unwrap();
+    sink.finish().unwrap();
+
+    assert_eq!(
+        sink.location("jdoe_ad_report_20240701_090000.pdf"),
+        archive.join("jdoe_ad_report_20240701_090000.pdf").display().to_string()
+    );
+    let mut zip = zip::ZipArchive::new(fs::File::open(&archive).unwrap()).unwrap();
+    let names: Vec<&str> = zip.file_names().collect();
+    assert_eq!(names.len(), 4, "{:?}", names);
+    let mut contents = String::new();
+    zip.by_name("jdoe_ad_report_20240701_090000.pdf").unwrap().read_to_string(&mut contents).unwrap();
+    assert_eq!(contents, "%PDF-1.3 jdoe");
+    assert!(zip.by_name("jdoe_ad_report_20240701_090000.json").is_ok());
+    assert!(zip.by_name("summary.csv").is_ok());
+    // Only the finished archive and the summary are left behind
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+}
@@ -0,0 +1,469 @@
+use ad_report::models::{ADComputer, ADGroup, ADUser, PasswordPolicy};
+use ad_report::pdf_generator::{Palette, PdfGenerator};
+use ad_report::permission_analyzer::RiskLevel;
+use ad_report::report_data::{EnhancedReportData, ReportProvenance};
+use ad_report::risk_calculator::{RiskCalculator, RiskFactorType, RiskThresholds};
+use chrono::{TimeZone, Utc};
+use lopdf::content::Content;
+use lopdf::{Document, Object};
+
+const DOMAIN: &str = "corp.example.com";
+
+/// Report fixture with a fixed generation time and `group_count` plain groups
+/// alongside a Domain Admins membership
+fn fixture(group_count: usize) -> EnhancedReportData {
+    let mut user = ADUser::new("CN=John Doe,OU=Staff,DC=corp,DC=example,DC=com".to_string(), "jdoe".to_string());
+    user.display_name = Some("John Doe".to_string());
+    user.email = Some("john.doe@corp.example.com".to_string());
+    user.department = Some("Engineering".to_string());
+    user.password_last_set = Some(Utc.with_ymd_and_hms(2024, 1, 15, 10, 30, 0).unwrap());
+    user.last_logon = Some(Utc.with_ymd_and_hms(2024, 6, 1, 8, 0, 0).unwrap());
+
+    user.groups.push(ADGroup::new(
+        "CN=Domain Admins,CN=Users,DC=corp,DC=example,DC=com".to_string(),
+        "Domain Admins".to_string(),
+    ));
+    for i in 0..group_count {
+        user.groups.push(ADGroup::new(
+            format!("CN=App Group {:03},OU=Groups,DC=corp,DC=example,DC=com", i),
+            format!("App Group {:03}", i),
+        ));
+    }
+
+    let risk = RiskCalculator::new().calculate_risk(&user).unwrap();
+    let generation_time = Utc.with_ymd_and_hms(2024, 7, 1, 9, 0, 0).unwrap();
+    EnhancedReportData::new_at(user, DOMAIN.to_string(), format!("dc1.{}", DOMAIN), Some(risk), generation_time)
+}
+
+fn render(data: &EnhancedReportData) -> Vec<u8> {
+    PdfGenerator::new()
+        .expect("create generator")
+        .generate_report(data)
+        .expect("generate report")
+}
+
+/// Text shown on each page, one string per page (Tj/TJ operands from the decoded content streams)
+fn page_texts(bytes: &[u8]) -> Vec<String> {
+    let doc = Document::load_mem(bytes).expect("output parses as PDF");
+    doc.get_pages()
+        .values()
+        .map(|page_id| {
+            let content = doc.get_page_content(*page_id).expect("page content");
+            let content = Content::decode(&content).expect("decode content stream");
+            let mut text = String::new();
+            for op in content.operations.iter().filter(|op| op.operator == "Tj" || op.operator == "TJ") {
+                collect_strings(&op.operands, &mut text);
+            }
+            text
+        })
+        .collect()
+}
+
+fn collect_strings(operands: &[Object], text: &mut String) {
+    for operand in operands {
+        match operand {
+            Object::String(bytes, _) => {
+                text.push_str(&String::from_utf8_lossy(bytes));
+                text.push('\n');
+            }
+            Object::Array(items) => collect_strings(items, text),
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn report_is_well_formed_pdf() {
+    let bytes = render(&fixture(5));
+
+    assert!(!bytes.is_empty());
+    assert!(bytes.starts_with(b"%PDF-"), "missing PDF header");
+    let tail = String::from_utf8_lossy(&bytes[bytes.len().saturating_sub(32)..]).to_string();
+    assert!(tail.trim_end().ends_with("%%EOF"), "missing %%EOF trailer: {:?}", tail);
+    assert!(Document::load_mem(&bytes).is_ok());
+}
+
+#[test]
+fn report_contains_key_strings() {
+    let pages = page_texts(&render(&fixture(5)));
+    let text = pages.join("\n");
+
+    assert!(text.contains("jdoe"), "SAM account name missing");
+    assert!(text.contains("EXECUTIVE SUMMARY"));
+    assert!(text.contains("Domain Admins"));
+    assert!(text.contains("App Group 004"));
+}
+
+#[test]
+fn cover_page_shows_finding_badges() {
+    let data = fixture(5);
+    let risk = data.risk_assessment.as_ref().unwrap();
+    let critical = risk.contributing_factors.iter().filter(|f| f.severity == RiskLevel::Critical).count();
+
+    let cover = page_texts(&render(&data)).remove(0);
+    assert!(cover.contains(&format!("{} FINDINGS", risk.contributing_factors.len())), "{}", cover);
+    assert!(cover.contains(&format!("{} CRITICAL", critical)), "{}", cover);
+    assert!(cover.contains(" HIGH"), "{}", cover);
+    assert!(cover.contains(&format!("{:?} RISK", risk.risk_level).to_uppercase()), "{}", cover);
+
+    // No assessment, no badges
+    let mut data = fixture(5);
+    data.risk_assessment = None;
+    assert!(!page_texts(&render(&data))[0].contains("FINDINGS"));
+}
+
+#[test]
+fn small_report_page_count() {
+    assert_eq!(page_texts(&render(&fixture(0))).len(), 2);
+}
+
+#[test]
+fn large_group_list_spills_onto_expected_pages() {
+    let pages = page_texts(&render(&fixture(150)));
+
+    // Update deliberately if the layout changes: a silent change here usually means
+    // rows are being dropped or overlapping.
+    assert_eq!(pages.len(), 6);
+
+    // Every group appears exactly once in the membership list across all pages
+    let text = pages.join("\n");
+    for i in 0..150 {
+        let row = format!("App Group {:03} (Security, Global)", i);
+        assert_eq!(text.matches(&row).count(), 1, "{:?} not listed once", row);
+    }
+
+    // Every page is numbered in order
+    for (index, page) in pages.iter().enumerate() {
+        assert!(page.contains(&format!("Page {}", index + 1)), "page {} has no footer", index + 1);
+    }
+}
+
+#[test]
+fn output_is_deterministic_apart_from_metadata() {
+    let data = fixture(40);
+    assert_eq!(page_texts(&render(&data)), page_texts(&render(&data)));
+}
+
+#[test]
+fn max_groups_shown_keeps_riskiest_and_notes_overflow() {
+    let data = fixture(150);
+    let bytes = PdfGenerator::new()
+        .unwrap()
+        .with_max_groups_shown(10)
+        .generate_report(&data)
+        .unwrap();
+    let pages = page_texts(&bytes);
+    let text = pages.join("\n");
+
+    // Domain Admins outranks the plain groups, and 151 - 10 entries are left out
+    assert!(text.contains("Domain Admins (Security, Global)"));
+    assert!(text.contains("(+141 additional groups not shown)"));
+    assert_eq!(text.matches("(Security, Global)").count(), 10);
+
+    // Summary counts still cover every group
+    assert!(text.contains("Direct Groups: 151"));
+    assert!(pages.len() < page_texts(&render(&data)).len());
+}
+
+#[test]
+fn password_age_is_judged_against_the_policy() {
+    let mut data = fixture(1);
+    let text = page_texts(&render(&data)).join("\n");
+    assert!(text.contains("Password Policy: Not available"));
+    assert!(text.contains("Password Age: 167 days\n"));
+
+    data.basic_report.user.password_policy = Some(PasswordPolicy {
+        source: "Default domain policy".to_string(),
+        max_age_days: Some(90),
+        min_length: Some(12),
+        complexity_required: Some(true),
+    });
+    let text = page_texts(&render(&data)).join("\n");
+    assert!(text.contains("Password Policy: max age 90 days, min length 12, complexity required (Default domain policy)"), "{}", text);
+    assert!(text.contains("Password Age: 167 days - exceeds the 90-day policy"), "{}", text);
+}
+
+#[test]
+fn unresolved_groups_add_a_data_completeness_note() {
+    let mut data = fixture(1);
+    assert!(!page_texts(&render(&data)).join("\n").contains("Data completeness"));
+
+    data.basic_report.user.resolution_warnings.push("CN=Gone,DC=corp,DC=example,DC=com: Group not found".to_string());
+    let text = page_texts(&render(&data)).join("\n");
+    assert!(text.contains("Data completeness: 1 group(s) could not be resolved"), "{}", text);
+    assert!(text.contains("CN=Gone,DC=corp,DC=example,DC=com: Group not found"), "{}", text);
+}
+
+#[test]
+fn watermark_is_drawn_first_on_every_page() {
+    let data = fixture(150);
+    let bytes = PdfGenerator::new().unwrap().with_watermark("DRAFT").generate_report(&data).unwrap();
+    let pages = page_texts(&bytes);
+    assert_eq!(pages.len(), 6);
+    for (i, page) in pages.iter().enumerate() {
+        assert!(page.starts_with("DRAFT\n"), "page {} does not start with the watermark", i + 1);
+    }
+
+    // Without the option nothing is added
+    assert!(!page_texts(&render(&data)).iter().any(|page| page.contains("DRAFT")));
+}
+
+#[test]
+fn legend_defines_every_risk_level_and_factor_type() {
+    let data = fixture(1);
+    let bytes = PdfGenerator::new().unwrap().with_legend().generate_report(&data).unwrap();
+    let text = page_texts(&bytes).join("\n");
+    assert!(text.contains("Glossary"), "{}", text);
+    for factor in RiskFactorType::ALL {
+        assert!(text.contains(factor.label()), "missing {:?}", factor);
+    }
+    for (level, low, high) in RiskThresholds::default().bands() {
+        assert!(text.contains(&format!("{:?} ({}-{})", level, low, high)), "missing {:?}", level);
+        assert!(text.contains(level.meaning()), "missing {:?}", level);
+    }
+    assert!(text.contains("Domain Local"), "{}", text);
+    assert!(text.contains("Distribution group"), "{}", text);
+
+    assert!(!page_texts(&render(&data)).join("\n").contains("Glossary"));
+}
+
+#[test]
+fn colorblind_palette_names_the_level_of_each_risk_item() {
+    let data = fixture(1);
+    let bytes = PdfGenerator::new().unwrap().with_palette(Palette::Colorblind).generate_report(&data).unwrap();
+    let text = page_texts(&bytes).join("\n");
+    assert!(text.contains("(Risk: 90/100, Critical)"), "{}", text);
+
+    // Patterned bars are drawn with a dash operator; the standard palette has none
+    let dashes = |bytes: &[u8]| {
+        let doc = Document::load_mem(bytes).unwrap();
+        doc.get_pages().values()
+            .flat_map(|id| Content::decode(&doc.get_page_content(*id).unwrap()).unwrap().operations)
+            .filter(|op| op.operator == "d" && op.operands.first().and_then(|o| o.as_array().ok()).is_some_and(|a| !a.is_empty()))
+            .count()
+    };
+    assert!(dashes(&bytes) > 0);
+    let standard = render(&data);
+    assert_eq!(dashes(&standard), 0);
+    assert!(!page_texts(&standard).join("\n").contains("/100, Critical)"));
+}
+
+#[test]
+fn provenance_is_stated_on_every_page() {
+    let data = fixture(60).with_provenance(ReportProvenance {
+        bind_identity: "CORP\\auditor".to_string(),
+        search_base: "OU=Staff,DC=corp,DC=example,DC=com".to_string(),
+    });
+    let pages = page_texts(&render(&data));
+    assert!(pages.len() > 1);
+    for page in &pages {
+        assert!(page.contains("Generated by: CORP\\auditor | Search base: OU=Staff,DC=corp,DC=example,DC=com"), "{}", page);
+    }
+
+    assert!(!page_texts(&render(&fixture(1))).join("\n").contains("Generated by:"));
+}
+
+#[test]
+fn failed_risk_analysis_is_noted_instead_of_dropped() {
+    let mut data = fixture(1).with_risk_unavailable("permission map unreadable");
+    data.risk_assessment = None;
+
+    let text = page_texts(&render(&data)).join("\n");
+    assert!(text.contains("Risk Assessment"), "{}", text);
+    assert!(text.contains("Risk analysis unavailable: permission map unreadable"), "{}", text);
+    assert!(text.contains("Group Memberships"), "{}", text);
+}
+
+#[test]
+fn risk_section_shows_permission_redundancy() {
+    let mut data = fixture(1);
+    let risk = data.risk_assessment.as_mut().unwrap();
+    risk.permission_redundancy.percent = 62.4;
+    risk.permission_redundancy.overlapped_permissions = 5;
+    risk.permission_redundancy.total_permissions = 8;
+
+    let text = page_texts(&render(&data)).join("\n");
+    assert!(text.contains("Permission Redundancy: 62%"), "{}", text);
+    assert!(text.contains("5 of 8 permissions granted by more than one group"), "{}", text);
+
+    // Nothing to measure without permissions
+    data.risk_assessment.as_mut().unwrap().permission_redundancy.total_permissions = 0;
+    assert!(!page_texts(&render(&data)).join("\n").contains("Permission Redundancy"));
+}
+
+#[test]
+fn dangerous_combinations_are_listed_with_the_total() {
+    let mut data = fixture(1);
+    let combinations = &mut data.risk_assessment.as_mut().unwrap().dangerous_combinations;
+    combinations.shown = vec!["DCSync (Domain Admins, Enterprise Admins)".to_string()];
+    combinations.total = 1;
+    let text = page_texts(&render(&data)).join("\n");
+    assert!(text.contains("Most Dangerous Combinations (1):"), "{}", text);
+    assert!(text.contains("! DCSync (Domain Admins, Enterprise Admins)"), "{}", text);
+
+    // A truncated list says how many were left out
+    data.risk_assessment.as_mut().unwrap().dangerous_combinations.total = 12;
+    let text = page_texts(&render(&data)).join("\n");
+    assert!(text.contains("Most Dangerous Combinations (1 of 12 shown):"), "{}", text);
+}
+
+#[test]
+fn batch_summary_lists_account_states() {
+    use ad_report::report_data::{AccountStateSummary, BatchRiskSummary};
+
+    let mut summary = BatchRiskSummary::new();
+    let mut states = AccountStateSummary::new();
+    for _ in 0..12 {
+        let data = fixture(1);
+        summary.record(&data.user().sam_account_name, data.risk_assessment.as_ref().unwrap());
+        states.record(data.user());
+    }
+    states.record_skipped_disabled();
+
+    let bytes = PdfGenerator::new().unwrap().generate_batch_summary(&summary, &states).unwrap();
+    let pages = page_texts(&bytes);
+    assert_eq!(pages.len(), 1);
+    assert!(pages[0].contains("Account States"), "{}", pages[0]);
+    assert!(pages[0].contains("Password never expires"), "{}", pages[0]);
+
+    // Nothing recorded, nothing shown
+    let bytes = PdfGenerator::new().unwrap().generate_batch_summary(&summary, &AccountStateSummary::new()).unwrap();
+    assert!(!page_texts(&bytes)[0].contains("Account States"));
+}
+
+#[test]
+fn compact_report_is_a_single_summary_page() {
+    let data = fixture(40);
+    let bytes = PdfGenerator::new().unwrap().compact().generate_report(&data).unwrap();
+    let pages = page_texts(&bytes);
+
+    assert_eq!(pages.len(), 1);
+    let text = &pages[0];
+    assert!(text.contains("Name: John Doe"), "{}", text);
+    assert!(text.contains("Risk Score:"), "{}", text);
+    assert!(text.contains("Top Groups (41 direct, 41 total)"), "{}", text);
+    assert!(text.contains("Domain Admins"), "{}", text);
+    assert!(text.contains("(+36 more)"), "{}", text);
+    assert!(text.contains("Top Findings"), "{}", text);
+    assert!(!text.contains("Truncated"), "{}", text);
+}
+
+#[test]
+fn compact_report_notes_content_that_does_not_fit() {
+    let mut data = fixture(3);
+    data.basic_report.user.display_name = Some("A Very Long Display Name ".repeat(10));
+    let text = page_texts(&PdfGenerator::new().unwrap().compact().generate_report(&data).unwrap()).join("\n");
+    assert!(text.contains("shortened to fit"), "{}", text);
+    assert!(!text.contains(&"A Very Long Display Name ".repeat(10)), "{}", text);
+
+    // At triple font size the lower lines run out of page
+    let layout = ad_report::pdf_generator::LayoutConfig::default().with_font_scale(3.0);
+    let bytes = PdfGenerator::new().unwrap().with_layout(layout).compact().generate_report(&fixture(3)).unwrap();
+    let pages = page_texts(&bytes);
+    assert_eq!(pages.len(), 1);
+    assert!(pages[0].contains("Truncated:"), "{}", pages[0]);
+}
+
+#[test]
+fn non_default_primary_group_is_called_out() {
+    let mut data = fixture(1);
+    data.basic_report.user.primary_group_id = Some(513);
+    assert!(!page_texts(&render(&data)).join("\n").contains("Non-default primary group"));
+
+    data.basic_report.user.primary_group_id = Some(512);
+    let text = page_texts(&render(&data)).join("\n");
+    assert!(text.contains("Non-default primary group (RID 512, expected 513)"), "{}", text);
+}
+
+#[test]
+fn token_only_groups_are_marked() {
+    let mut data = fixture(1);
+    data.basic_report.user.token_only_groups.push("App Group 000".to_string());
+    let text = page_texts(&render(&data)).join("\n");
+    assert!(text.contains("1 membership(s) found only through tokenGroups"), "{}", text);
+    assert!(text.contains("App Group 000 (Security, Global) *"), "{}", text);
+}
+
+#[test]
+fn missing_attributes_are_listed_by_cause() {
+    let mut data = fixture(1);
+    assert!(!page_texts(&render(&data)).join("\n").contains("Attributes not"));
+
+    data.basic_report.user.missing_attributes.unreadable = vec!["pwdLastSet".to_string(), "objectSid".to_string()];
+    data.basic_report.user.missing_attributes.not_set = vec!["title".to_string()];
+    let text = page_texts(&render(&data)).join("\n");
+    assert!(text.contains("Attributes not readable with current permissions"), "{}", text);
+    assert!(text.contains("pwdLastSet, objectSid"), "{}", text);
+    assert!(text.contains("Attributes not set on this account:"), "{}", text);
+}
+
+#[test]
+fn same_data_and_generation_time_render_identical_bytes() {
+    let data = fixture(3);
+    let first = render(&data);
+    assert!(first == render(&data), "rendering the same data twice differs");
+
+    let doc = Document::load_mem(&first).expect("output parses as PDF");
+    let info = doc.trailer.get(b"Info").and_then(Object::as_reference).unwrap();
+    let created = doc.get_dictionary(info).unwrap().get(b"CreationDate").unwrap();
+    assert!(String::from_utf8_lossy(created.as_str().unwrap()).starts_with("D:20240701090000"), "{:?}", created);
+
+    let mut later = fixture(3);
+    later.basic_report.generation_time = Utc.with_ymd_and_hms(2024, 7, 2, 9, 0, 0).unwrap();
+    assert!(first != render(&later));
+}
+
+#[test]
+fn computer_reports_show_host_and_delegation() {
+    let data = fixture(1);
+    let mut computer = ADComputer::new(data.user().clone());
+    computer.dns_host_name = Some("ws01.corp.example.com".to_string());
+    computer.operating_system = Some("Windows 11 Enterprise".to_string());
+    computer.delegation.constrained_to = vec!["cifs/fs01.corp.example.com".to_string()];
+    let text = page_texts(&render(&data.with_computer(computer))).join("\n");
+
+    assert!(text.contains("ACTIVE DIRECTORY COMPUTER ACCESS REPORT"), "{}", text);
+    assert!(text.contains("Subject Computer:"), "{}", text);
+    assert!(text.contains("Computer Information"), "{}", text);
+    assert!(text.contains("Windows 11 Enterprise"), "{}", text);
+    assert!(text.contains("Constrained (1 services)"), "{}", text);
+    assert!(text.contains("Delegates to: cifs/fs01.corp.example.com"), "{}", text);
+    assert!(!text.contains("User Information"), "{}", text);
+}
+
+#[test]
+fn content_hash_is_printed_on_the_last_page_only() {
+    let hash = "0f".repeat(32);
+    let pages = page_texts(
+        &PdfGenerator::new().unwrap().with_content_hash(&hash).generate_report(&fixture(80)).unwrap(),
+    );
+
+    assert!(pages.len() > 1);
+    let (last, rest) = pages.split_last().unwrap();
+    assert!(last.contains(&format!("Content SHA-256: {}", hash)), "{}", last);
+    assert!(rest.iter().all(|page| !page.contains("Content SHA-256")));
+}
+
+#[test]
+fn document_info_describes_the_report_for_indexing() {
+    let info_text = |bytes: &[u8], key: &[u8]| {
+        let doc = Document::load_mem(bytes).expect("output parses as PDF");
+        let info = doc.trailer.get(b"Info").and_then(Object::as_reference).unwrap();
+        let value = doc.get_dictionary(info).unwrap().get(key).unwrap().as_str().unwrap().to_vec();
+        String::from_utf8(value).unwrap()
+    };
+
+    let data = fixture(1).with_provenance(ReportProvenance {
+        bind_identity: "CORP\\auditor".to_string(),
+        search_base: "DC=corp,DC=example,DC=com".to_string(),
+    });
+    let bytes = render(&data);
+    assert_eq!(info_text(&bytes, b"Author"), "CORP\\auditor");
+    assert_eq!(info_text(&bytes, b"Subject"), "AD access report for jdoe");
+    let level = data.risk_assessment.as_ref().unwrap().risk_level.clone();
+    assert_eq!(info_text(&bytes, b"Keywords"), format!("Active Directory,access report,{:?} risk,{}", level, DOMAIN));
+    assert!(info_text(&bytes, b"CreationDate").starts_with("D:20240701090000"));
+
+    // Without provenance the tool is the author
+    assert_eq!(info_text(&render(&fixture(1)), b"Author"), "ad-report");
+}
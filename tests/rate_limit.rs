@@ -0,0 +1,53 @@
+use ad_report::rate_limit::RateLimiter;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[tokio::test]
+async fn operations_are_spaced_by_the_interval() {
+    // 20 per second: one operation every 50ms
+    let limiter = RateLimiter::new(20.0);
+
+    let start = Instant::now();
+    for _ in 0..5 {
+        limiter.acquire().await;
+    }
+    // The first operation goes out immediately, the other four wait one interval each
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(200), "{:?}", elapsed);
+    assert!(elapsed < Duration::from_millis(600), "{:?}", elapsed);
+}
+
+#[tokio::test]
+async fn concurrent_callers_share_one_budget() {
+    let limiter = Arc::new(RateLimiter::new(20.0));
+    let start = Instant::now();
+
+    let workers: Vec<_> = (0..3)
+        .map(|_| {
+            let limiter = Arc::clone(&limiter);
+            tokio::spawn(async move {
+                for _ in 0..2 {
+                    limiter.acquire().await;
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.await.unwrap();
+    }
+
+    // Six operations across three workers still take five intervals
+    assert!(start.elapsed() >= Duration::from_millis(250), "{:?}", start.elapsed());
+}
+
+#[tokio::test]
+async fn idle_time_is_not_saved_up_as_a_burst() {
+    let limiter = RateLimiter::new(20.0);
+    limiter.acquire().await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let start = Instant::now();
+    limiter.acquire().await;
+    limiter.acquire().await;
+    assert!(start.elapsed() >= Duration::from_millis(50), "{:?}", start.elapsed());
+}
@@ -0,0 +1,22 @@
+use ad_report::models::ADUser;
+use ad_report::redaction::Redactor;
+
+fn redacted_dn(dn: &str) -> String {
+    Redactor::new().redact_user(ADUser::new(dn.to_string(), "jdoe".to_string())).distinguished_name
+}
+
+#[test]
+fn only_the_leading_rdn_value_is_masked() {
+    assert_eq!(redacted_dn("CN=John Doe,OU=Staff,DC=corp,DC=example,DC=com"), "CN=J***,OU=Staff,DC=corp,DC=example,DC=com");
+    assert_eq!(redacted_dn("CN=John Doe"), "CN=J***");
+}
+
+#[test]
+fn escaped_commas_stay_inside_the_leading_rdn() {
+    // "Doe, John" as AD stores it: the comma is part of the name
+    assert_eq!(redacted_dn(r"CN=Doe\, John,OU=Staff,DC=corp,DC=example,DC=com"), "CN=D***,OU=Staff,DC=corp,DC=example,DC=com");
+    assert_eq!(redacted_dn(r"CN=Doe\\\, John,OU=Staff,DC=corp"), "CN=D***,OU=Staff,DC=corp");
+
+    // An escaped backslash before the comma leaves the comma unescaped
+    assert_eq!(redacted_dn(r"CN=Doe\\,OU=Staff,DC=corp"), "CN=D***,OU=Staff,DC=corp");
+}
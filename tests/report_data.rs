@@ -0,0 +1,52 @@
+use ad_report::models::ADUser;
+use ad_report::report_data::{AccountStateSummary, UnusedAccountsSummary};
+use chrono::{Duration, TimeZone, Utc};
+
+fn account(sam: &str, created_days_ago: Option<i64>, logged_on: bool) -> ADUser {
+    let now = Utc.with_ymd_and_hms(2024, 7, 1, 9, 0, 0).unwrap();
+    let mut user = ADUser::new(format!("CN={},OU=Staff,DC=corp,DC=example,DC=com", sam), sam.to_string());
+    user.created = created_days_ago.map(|days| now - Duration::days(days));
+    user.last_logon = logged_on.then(|| now - Duration::days(1));
+    user
+}
+
+#[test]
+fn unused_accounts_are_never_logged_on_and_older_than_the_threshold() {
+    let now = Utc.with_ymd_and_hms(2024, 7, 1, 9, 0, 0).unwrap();
+    let mut summary = UnusedAccountsSummary::new(UnusedAccountsSummary::DEFAULT_MIN_AGE_DAYS);
+
+    summary.record(&account("stale", Some(45), false), now);
+    summary.record(&account("boundary", Some(30), false), now);
+    summary.record(&account("new.starter", Some(3), false), now);
+    summary.record(&account("active", Some(400), true), now);
+    summary.record(&account("unknown.created", None, false), now);
+
+    let names: Vec<&str> = summary.accounts.iter().map(|a| a.sam_account_name.as_str()).collect();
+    assert_eq!(names, vec!["stale", "boundary"]);
+    assert_eq!(summary.accounts[0].age_days, 45);
+}
+
+#[test]
+fn account_states_are_tallied_across_the_batch() {
+    let mut summary = AccountStateSummary::new();
+    assert!(summary.is_empty());
+
+    let mut active = account("active", None, true);
+    active.password_never_expires = true;
+    let mut locked = account("locked", None, true);
+    locked.account_locked = true;
+    let mut disabled = account("disabled", None, false);
+    disabled.account_enabled = false;
+
+    for user in [&active, &locked, &disabled] {
+        summary.record(user);
+    }
+    summary.record_skipped_disabled();
+
+    assert_eq!(summary.rows(), [
+        ("Enabled", 2),
+        ("Disabled", 2),
+        ("Locked out", 1),
+        ("Password never expires", 1),
+    ]);
+}
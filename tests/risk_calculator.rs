@@ -0,0 +1,508 @@
+use ad_report::models::{ADComputer, ADGroup, ADUser, PasswordPolicy};
+use ad_report::permission_analyzer::RiskLevel;
+use ad_report::risk_calculator::{EscalationRule, RiskAssessment, RiskCalculator, RiskFactor, RiskFactorType, RiskThresholds};
+use chrono::{Duration, Utc};
+
+/// Enabled account that logged on today, with no groups and no risky flags
+fn user(sam: &str) -> ADUser {
+    let mut user = ADUser::new(format!("CN={},OU=Staff,DC=corp,DC=example,DC=com", sam), sam.to_string());
+    user.display_name = Some("Test User".to_string());
+    user.email = Some(format!("{}@corp.example.com", sam));
+    user.last_logon = Some(Utc::now());
+    user
+}
+
+fn group(name: &str) -> ADGroup {
+    ADGroup::new(format!("CN={},OU=Groups,DC=corp,DC=example,DC=com", name), name.to_string())
+}
+
+fn with_plain_groups(mut user: ADUser, count: usize) -> ADUser {
+    for i in 0..count {
+        user.groups.push(group(&format!("Team {:02}", i)));
+    }
+    user
+}
+
+fn assess(user: &ADUser) -> RiskAssessment {
+    RiskCalculator::new().calculate_risk(user).unwrap()
+}
+
+/// The weighted combination documented on `combine_risk_scores`
+fn weighted(assessment: &RiskAssessment) -> u8 {
+    let b = &assessment.risk_breakdown;
+    (b.administrative_risk as f32 * 0.4
+        + b.permission_overlap_risk as f32 * 0.25
+        + b.account_security_risk as f32 * 0.20
+        + b.activity_risk as f32 * 0.15)
+        .round() as u8
+}
+
+fn excessive_membership(assessment: &RiskAssessment) -> Option<(u8, RiskLevel)> {
+    assessment.contributing_factors.iter()
+        .find(|f| matches!(f.factor_type, RiskFactorType::PrivilegeEscalation)
+            && f.description.starts_with("Excessive group memberships"))
+        .map(|f| (f.risk_contribution, f.severity.clone()))
+}
+
+#[test]
+fn domain_admins_membership_is_critical() {
+    let mut admin = user("jdoe");
+    admin.groups.push(group("Domain Admins"));
+    let assessment = assess(&admin);
+
+    let factor = assessment.contributing_factors.iter()
+        .find(|f| matches!(f.factor_type, RiskFactorType::AdministrativeAccess))
+        .expect("administrative access factor");
+    assert_eq!(factor.severity, RiskLevel::Critical);
+    assert_eq!(factor.risk_contribution, 90);
+
+    let top = &assessment.group_contributions[0];
+    assert_eq!(top.group_name, "Domain Admins");
+    assert_eq!(top.severity, RiskLevel::Critical);
+}
+
+#[test]
+fn domain_admin_with_weak_account_security_scores_high() {
+    let mut admin = user("jdoe");
+    admin.groups.push(group("Domain Admins"));
+    admin.groups.push(group("Enterprise Admins"));
+    admin.groups.push(group("Schema Admins"));
+    admin.groups.push(group("Account Operators"));
+    admin.groups.push(group("Server Operators"));
+    admin.groups.push(group("Backup Operators"));
+    admin.password_never_expires = true;
+    admin.no_preauth_required = true;
+    admin.account_locked = true;
+    admin.last_logon = Some(Utc::now() - Duration::days(400));
+    let assessment = assess(&admin);
+
+    // Administrative and account-security components both saturate; without permission
+    // overlaps the weighted total stays just below the Critical band
+    assert_eq!(assessment.risk_breakdown.administrative_risk, 100);
+    assert_eq!(assessment.risk_breakdown.account_security_risk, 100);
+    assert!(assessment.overall_score >= RiskThresholds::default().high, "score {}", assessment.overall_score);
+    assert!(assessment.risk_level >= RiskLevel::High);
+}
+
+#[test]
+fn dormant_privileged_account() {
+    let mut admin = user("jdoe");
+    admin.groups.push(group("Domain Admins"));
+
+    admin.last_logon = Some(Utc::now() - Duration::days(200));
+    let assessment = assess(&admin);
+    let dormant = assessment.contributing_factors.iter()
+        .find(|f| matches!(f.factor_type, RiskFactorType::DormantAccount))
+        .expect("dormant factor after 200 days");
+    assert_eq!((dormant.risk_contribution, dormant.severity.clone()), (30, RiskLevel::Medium));
+    assert_eq!(assessment.risk_breakdown.activity_risk, 30);
+
+    admin.last_logon = Some(Utc::now() - Duration::days(400));
+    let assessment = assess(&admin);
+    assert_eq!(assessment.risk_breakdown.activity_risk, 50);
+    assert!(assessment.contributing_factors.iter()
+        .any(|f| matches!(f.factor_type, RiskFactorType::DormantAccount) && f.severity == RiskLevel::High));
+
+    admin.last_logon = None;
+    assert_eq!(assess(&admin).risk_breakdown.activity_risk, 40);
+
+    // Recent logons are not dormant
+    admin.last_logon = Some(Utc::now() - Duration::days(30));
+    let assessment = assess(&admin);
+    assert_eq!(assessment.risk_breakdown.activity_risk, 0);
+    assert!(!assessment.contributing_factors.iter()
+        .any(|f| matches!(f.factor_type, RiskFactorType::DormantAccount)));
+}
+
+#[test]
+fn excessive_membership_thresholds() {
+    assert_eq!(excessive_membership(&assess(&with_plain_groups(user("jdoe"), 15))), None);
+    assert_eq!(excessive_membership(&assess(&with_plain_groups(user("jdoe"), 16))), Some((1, RiskLevel::Medium)));
+    assert_eq!(excessive_membership(&assess(&with_plain_groups(user("jdoe"), 25))), Some((10, RiskLevel::Medium)));
+    assert_eq!(excessive_membership(&assess(&with_plain_groups(user("jdoe"), 26))), Some((11, RiskLevel::High)));
+    // Contribution is capped at 25
+    assert_eq!(excessive_membership(&assess(&with_plain_groups(user("jdoe"), 60))), Some((25, RiskLevel::High)));
+}
+
+#[test]
+fn primary_group_counts_towards_excessive_membership() {
+    let mut member = with_plain_groups(user("jdoe"), 15);
+    member.primary_group = Some(group("Domain Users"));
+    assert_eq!(excessive_membership(&assess(&member)), Some((1, RiskLevel::Medium)));
+}
+
+#[test]
+fn component_scores_saturate_at_100() {
+    let mut admin = with_plain_groups(user("jdoe"), 60);
+    for name in ["Domain Admins", "Enterprise Admins", "Schema Admins", "Account Operators", "Server Operators"] {
+        admin.groups.push(group(name));
+    }
+    admin.password_never_expires = true;
+    admin.no_preauth_required = true;
+    admin.account_locked = true;
+    admin.last_logon = Some(Utc::now() - Duration::days(400));
+    let assessment = assess(&admin);
+
+    assert_eq!(assessment.risk_breakdown.administrative_risk, 100);
+    assert!(assessment.risk_breakdown.account_security_risk <= 100);
+    assert!(assessment.risk_breakdown.permission_overlap_risk <= 100);
+    assert!(assessment.unmitigated_score <= 100);
+    assert!(assessment.overall_score <= 100);
+}
+
+#[test]
+fn components_are_combined_with_weights() {
+    // Only "password never expires" (30 security risk): 30 * 0.20 = 6
+    let mut member = user("jdoe");
+    member.password_never_expires = true;
+    let assessment = assess(&member);
+    assert_eq!(assessment.risk_breakdown.administrative_risk, 0);
+    assert_eq!(assessment.risk_breakdown.account_security_risk, 30);
+    assert_eq!(assessment.unmitigated_score, 6);
+
+    let mut admin = user("jdoe");
+    admin.groups.push(group("Domain Admins"));
+    admin.last_logon = Some(Utc::now() - Duration::days(400));
+    let assessment = assess(&admin);
+    assert_eq!(assessment.unmitigated_score, weighted(&assessment));
+
+    for count in [0, 16, 30] {
+        let assessment = assess(&with_plain_groups(user("jdoe"), count));
+        assert_eq!(assessment.unmitigated_score, weighted(&assessment), "{} groups", count);
+    }
+}
+
+#[test]
+fn mitigations_reduce_the_combined_score() {
+    let mut admin = user("jdoe");
+    admin.groups.push(group("Domain Admins"));
+    admin.groups.push(group("Protected Users"));
+    admin.smartcard_required = true;
+    let assessment = assess(&admin);
+
+    let credit: u8 = assessment.mitigating_factors.iter().map(|m| m.score_reduction).sum();
+    assert_eq!(credit, 20);
+    assert_eq!(assessment.overall_score, assessment.unmitigated_score - credit);
+}
+
+#[test]
+fn account_expiry_date_follows_the_report_timezone() {
+    let mut account = user("contractor");
+    let expires = Utc::now() + Duration::days(30);
+    account.account_expires = Some(expires);
+    let tokyo = chrono_tz::Asia::Tokyo;
+    let expiry = |calculator: RiskCalculator| {
+        calculator.calculate_risk(&account).unwrap().mitigating_factors.into_iter()
+            .find(|m| m.description.starts_with("Account expires on "))
+            .expect("expiry mitigation")
+            .description
+    };
+
+    assert_eq!(expiry(RiskCalculator::new()), format!("Account expires on {}", expires.format("%d-%m-%Y")));
+    assert_eq!(
+        expiry(RiskCalculator::new().with_timezone(tokyo)),
+        format!("Account expires on {}", expires.with_timezone(&tokyo).format("%d-%m-%Y"))
+    );
+}
+
+#[test]
+fn risk_level_boundaries() {
+    let thresholds = RiskThresholds::default();
+
+    assert_eq!(thresholds.level_for(0), RiskLevel::Low);
+    assert_eq!(thresholds.level_for(29), RiskLevel::Low);
+    assert_eq!(thresholds.level_for(30), RiskLevel::Medium);
+    assert_eq!(thresholds.level_for(59), RiskLevel::Medium);
+    assert_eq!(thresholds.level_for(60), RiskLevel::High);
+    assert_eq!(thresholds.level_for(79), RiskLevel::High);
+    assert_eq!(thresholds.level_for(80), RiskLevel::Critical);
+    assert_eq!(thresholds.level_for(100), RiskLevel::Critical);
+}
+
+#[test]
+fn assessment_level_uses_configured_thresholds() {
+    let mut member = user("jdoe");
+    member.password_never_expires = true;
+
+    // Score 6 is Low with the default bands ...
+    let assessment = assess(&member);
+    assert_eq!(assessment.overall_score, 6);
+    assert_eq!(assessment.risk_level, RiskLevel::Low);
+
+    // ... and Critical when the bands start that low
+    let thresholds = RiskThresholds::new(2, 4, 6).unwrap();
+    let assessment = RiskCalculator::new().with_thresholds(thresholds).calculate_risk(&member).unwrap();
+    assert_eq!(assessment.risk_level, RiskLevel::Critical);
+    let assessment = RiskCalculator::new()
+        .with_thresholds(RiskThresholds::new(2, 4, 7).unwrap())
+        .calculate_risk(&member).unwrap();
+    assert_eq!(assessment.risk_level, RiskLevel::High);
+}
+
+fn cross_domain(assessment: &RiskAssessment) -> Option<&RiskFactor> {
+    assessment.contributing_factors.iter()
+        .find(|f| matches!(f.factor_type, RiskFactorType::CrossDomainAccess))
+}
+
+#[test]
+fn groups_from_other_domains_are_cross_domain_access() {
+    let mut member = user("jdoe");
+    member.groups.push(group("Team 01"));
+    assert!(cross_domain(&assess(&member)).is_none());
+
+    member.groups.push(ADGroup::new("CN=Payroll,OU=Groups,DC=emea,DC=corp,DC=example,DC=com".to_string(), "Payroll".to_string()));
+    let assessment = assess(&member);
+    let factor = cross_domain(&assessment).expect("cross-domain factor");
+    assert_eq!((factor.risk_contribution, factor.severity.clone()), (8, RiskLevel::Low));
+    assert!(factor.description.contains("emea.corp.example.com"), "{}", factor.description);
+
+    // Weighted by the number of foreign groups and capped
+    for i in 0..5 {
+        member.groups.push(ADGroup::new(format!("CN=Partner {},OU=Groups,DC=partner,DC=net", i), format!("Partner {}", i)));
+    }
+    let assessment = assess(&member);
+    let factor = cross_domain(&assessment).unwrap();
+    assert_eq!((factor.risk_contribution, factor.severity.clone()), (30, RiskLevel::High));
+    assert!(factor.description.starts_with("Member of 6 group(s)"), "{}", factor.description);
+}
+
+#[test]
+fn domain_comparison_ignores_case_and_sid_only_groups() {
+    let mut member = user("jdoe");
+    member.groups.push(ADGroup::new("CN=Ops,OU=Groups,DC=CORP,DC=Example,DC=com".to_string(), "Ops".to_string()));
+    // Unresolved primary group, known only by SID
+    member.primary_group = Some(ADGroup::new("S-1-5-21-1-2-3-513".to_string(), "Domain Users".to_string()));
+    assert!(cross_domain(&assess(&member)).is_none());
+}
+
+fn escalations(assessment: &RiskAssessment) -> Vec<&RiskFactor> {
+    assessment.contributing_factors.iter()
+        .filter(|f| matches!(f.factor_type, RiskFactorType::PrivilegeEscalation)
+            && !f.description.starts_with("Excessive group memberships"))
+        .collect()
+}
+
+#[test]
+fn toxic_group_pair_is_privilege_escalation() {
+    let mut operator = user("jdoe");
+    operator.groups.push(group("Backup Operators"));
+    assert!(escalations(&assess(&operator)).is_empty());
+
+    // The second half of the pair may be reached through nesting
+    let mut nested = group("Tier 0 Ops");
+    nested.nested_groups.push(group("Server Operators"));
+    operator.groups.push(nested);
+    let assessment = assess(&operator);
+    let found = escalations(&assessment);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].severity, RiskLevel::Critical);
+    assert_eq!(found[0].risk_contribution, 45);
+    assert!(found[0].description.starts_with("Member of Backup Operators + Server Operators"), "{}", found[0].description);
+    assert!(assessment.recommendations.iter().any(|r| r.contains("toxic group combinations")));
+}
+
+#[test]
+fn escalation_rules_are_configurable() {
+    let mut member = user("jdoe");
+    member.groups.push(group("Payments Approvers"));
+    member.groups.push(group("payments submitters"));
+    assert!(escalations(&assess(&member)).is_empty());
+
+    let rule = EscalationRule::new(&["Payments Submitters", "Payments Approvers"], "can approve their own payments", 20, RiskLevel::Medium);
+    let assessment = RiskCalculator::new().with_escalation_rules(vec![rule]).calculate_risk(&member).unwrap();
+    let found = escalations(&assessment);
+    assert_eq!(found.len(), 1);
+    assert_eq!((found[0].risk_contribution, found[0].severity.clone()), (20, RiskLevel::Medium));
+
+    // With no rules, even a default toxic pair is not reported
+    let mut operator = user("jdoe");
+    operator.groups.push(group("Account Operators"));
+    operator.groups.push(group("Server Operators"));
+    assert_eq!(escalations(&assess(&operator)).len(), 1);
+    let assessment = RiskCalculator::new().with_escalation_rules(Vec::new()).calculate_risk(&operator).unwrap();
+    assert!(escalations(&assessment).is_empty());
+}
+
+#[test]
+fn unresolved_groups_are_incomplete_group_data() {
+    let mut member = user("jdoe");
+    member.groups.push(group("Team 01"));
+    member.resolution_warnings.push("CN=Gone,OU=Groups,DC=corp,DC=example,DC=com: Group not found".to_string());
+    let assessment = assess(&member);
+
+    let factor = assessment.contributing_factors.iter()
+        .find(|f| matches!(f.factor_type, RiskFactorType::IncompleteGroupData))
+        .expect("incomplete group data factor");
+    assert!(factor.description.starts_with("1 group(s) could not be resolved"));
+    assert!(assessment.recommendations.iter().any(|r| r.starts_with("Verify group memberships directly in AD")));
+}
+
+#[test]
+fn assessment_carries_permission_redundancy() {
+    let assessment = assess(&user("jdoe"));
+    assert_eq!(assessment.permission_redundancy.total_permissions, 0);
+    assert_eq!(assessment.permission_redundancy.percent, 0.0);
+
+    let mut admin = user("jdoe");
+    admin.groups.push(group("Domain Admins"));
+    admin.groups.push(group("Enterprise Admins"));
+    let redundancy = assess(&admin).permission_redundancy;
+    assert!(redundancy.total_permissions > 0);
+    assert!(redundancy.overlapped_permissions <= redundancy.total_permissions);
+    let expected = redundancy.overlapped_permissions as f32 / redundancy.total_permissions as f32 * 100.0;
+    assert!((redundancy.percent - expected).abs() < f32::EPSILON);
+}
+
+#[test]
+fn operator_membership_covered_by_domain_admins_is_redundant() {
+    let redundant = |assessment: &RiskAssessment| -> Vec<String> {
+        assessment.contributing_factors.iter()
+            .filter(|f| matches!(f.factor_type, RiskFactorType::PermissionOverlap) && f.description.contains("is redundant given"))
+            .map(|f| f.description.clone())
+            .collect()
+    };
+
+    let mut operator = user("jdoe");
+    operator.groups.push(group("Account Operators"));
+    assert!(redundant(&assess(&operator)).is_empty());
+
+    operator.groups.push(group("Domain Admins"));
+    let assessment = assess(&operator);
+    assert_eq!(redundant(&assessment), vec!["Account Operators membership is redundant given Domain Admins"]);
+    assert!(assessment.recommendations.iter().any(|r| r == "Remove the Account Operators membership; Domain Admins already grants its access"));
+}
+
+#[test]
+fn dangerous_combinations_are_carried_with_their_count() {
+    use ad_report::permission_analyzer::RiskSummary;
+
+    assert_eq!(assess(&user("jdoe")).dangerous_combinations.total, 0);
+
+    let mut admin = user("jdoe");
+    for name in ["Domain Admins", "Enterprise Admins", "Administrators", "Schema Admins"] {
+        admin.groups.push(group(name));
+    }
+    let combinations = assess(&admin).dangerous_combinations;
+    assert!(!combinations.shown.is_empty());
+    assert!(combinations.shown.len() <= RiskSummary::MAX_DANGEROUS_COMBINATIONS);
+    assert!(combinations.total >= combinations.shown.len());
+    assert_eq!(combinations.is_truncated(), combinations.total > RiskSummary::MAX_DANGEROUS_COMBINATIONS);
+    // The same input always lists the same combinations
+    assert_eq!(assess(&admin).dangerous_combinations.shown, combinations.shown);
+}
+
+#[test]
+fn non_default_primary_group_is_a_high_severity_finding() {
+    let mut normal = user("jdoe");
+    normal.primary_group_id = Some(513);
+    let assessment = assess(&normal);
+    assert!(!assessment.contributing_factors.iter().any(|f| matches!(f.factor_type, RiskFactorType::PrimaryGroupAnomaly)));
+
+    let mut hidden_admin = user("jdoe");
+    hidden_admin.primary_group_id = Some(512);
+    let assessment = assess(&hidden_admin);
+    let factor = assessment.contributing_factors.iter()
+        .find(|f| matches!(f.factor_type, RiskFactorType::PrimaryGroupAnomaly))
+        .expect("primary group anomaly");
+    assert_eq!(factor.severity, RiskLevel::High);
+    assert_eq!(factor.risk_contribution, 40);
+    assert!(factor.description.contains("Domain Admins (RID 512) instead of Domain Users (513)"), "{}", factor.description);
+    assert!(assessment.recommendations.iter().any(|r| r.starts_with("Reset primaryGroupID")));
+
+    let mut odd = user("jdoe");
+    odd.primary_group_id = Some(1234);
+    let factor = assess(&odd).contributing_factors.into_iter()
+        .find(|f| matches!(f.factor_type, RiskFactorType::PrimaryGroupAnomaly))
+        .expect("primary group anomaly");
+    assert_eq!(factor.risk_contribution, 25);
+}
+
+#[test]
+fn delegation_on_member_computers_is_scored_but_not_on_domain_controllers() {
+    let mut account = user("WS01$");
+    account.object_classes = vec!["user".to_string(), "computer".to_string()];
+    let mut computer = ADComputer::new(account);
+    let calculator = RiskCalculator::new();
+    let baseline = calculator.calculate_computer_risk(&computer).unwrap();
+    assert!(!baseline.contributing_factors.iter().any(|f| matches!(f.factor_type, RiskFactorType::Delegation)));
+
+    computer.delegation.unconstrained = true;
+    let assessment = calculator.calculate_computer_risk(&computer).unwrap();
+    let factor = assessment.contributing_factors.iter()
+        .find(|f| matches!(f.factor_type, RiskFactorType::Delegation))
+        .expect("delegation finding");
+    assert_eq!(factor.severity, RiskLevel::Critical);
+    assert_eq!(assessment.risk_breakdown.administrative_risk, baseline.risk_breakdown.administrative_risk + 70);
+    assert!(assessment.recommendations.iter().any(|r| r.starts_with("Replace unconstrained delegation")));
+
+    computer.is_domain_controller = true;
+    let dc = calculator.calculate_computer_risk(&computer).unwrap();
+    assert!(!dc.contributing_factors.iter().any(|f| matches!(f.factor_type, RiskFactorType::Delegation)));
+}
+
+#[test]
+fn annotate_groups_attaches_scoring_to_every_group() {
+    let mut admins = group("Domain Admins");
+    admins.nested_groups.push(group("Team 01"));
+    let mut user = user("jdoe");
+    user.groups.push(admins);
+
+    RiskCalculator::new().annotate_groups(&mut user);
+
+    let admins = user.groups[0].risk.as_ref().unwrap();
+    assert_eq!(admins.risk_contribution, 90);
+    assert_eq!(admins.severity, RiskLevel::Critical);
+    assert!(matches!(admins.category, Some(RiskFactorType::AdministrativeAccess)));
+
+    let team = user.groups[0].nested_groups[0].risk.as_ref().unwrap();
+    assert!(team.category.is_none());
+
+    let json = serde_json::to_value(&user).unwrap();
+    assert_eq!(json["groups"][0]["risk"]["category"], "AdministrativeAccess");
+    assert_eq!(json["groups"][0]["risk"]["risk_contribution"], 90);
+}
+
+#[test]
+fn enabled_account_past_password_expiry_is_a_finding() {
+    let finding = |user: &ADUser| assess(user).contributing_factors.into_iter()
+        .find(|f| f.description.contains("Password has expired"));
+
+    let mut user = user("jdoe");
+    user.password_expiry = Some(Utc::now() - Duration::days(3));
+    let factor = finding(&user).expect("expired password finding");
+    assert_eq!(factor.severity, RiskLevel::Medium);
+
+    // A stale PASSWORD_EXPIRED flag does not count when the computed date is ahead
+    user.password_expired = true;
+    user.password_expiry = Some(Utc::now() + Duration::days(30));
+    assert!(finding(&user).is_none());
+
+    user.password_expiry = Some(Utc::now() - Duration::days(3));
+    user.account_enabled = false;
+    assert!(finding(&user).is_none());
+}
+
+#[test]
+fn fine_grained_policy_without_complexity_is_a_finding() {
+    let finding = |user: &ADUser| assess(user).contributing_factors.into_iter()
+        .find(|f| f.description.starts_with("Password complexity not required"));
+    let policy = |complexity_required| PasswordPolicy {
+        source: "Fine-grained policy 'Svc PSO'".to_string(),
+        max_age_days: None,
+        min_length: Some(8),
+        complexity_required,
+    };
+
+    let mut user = user("svc.app");
+    assert!(finding(&user).is_none());
+
+    user.password_policy = Some(policy(Some(false)));
+    let factor = finding(&user).expect("complexity finding");
+    assert_eq!(factor.description, "Password complexity not required (Fine-grained policy 'Svc PSO')");
+    assert_eq!(factor.severity, RiskLevel::Low);
+
+    user.password_policy = Some(policy(Some(true)));
+    assert!(finding(&user).is_none());
+    user.password_policy = Some(policy(None));
+    assert!(finding(&user).is_none());
+}
@@ -0,0 +1,151 @@
+use ad_report::models::{ADGroup, ADUser};
+use ad_report::permission_analyzer::RiskLevel;
+use ad_report::risk_calculator::{RiskCalculator, RiskFactorType};
+use ad_report::risk_rules::CustomRiskRule;
+use chrono::{Duration, Utc};
+use std::fs;
+use std::path::PathBuf;
+
+/// Enabled account that logged on today
+fn user(groups: &[&str]) -> ADUser {
+    let mut user = ADUser::new("CN=jdoe,OU=Staff,DC=corp,DC=example,DC=com".to_string(), "jdoe".to_string());
+    user.account_enabled = true;
+    user.last_logon = Some(Utc::now());
+    for name in groups {
+        user.groups.push(ADGroup::new(format!("CN={},OU=Groups,DC=corp,DC=example,DC=com", name), name.to_string()));
+    }
+    user
+}
+
+fn rule(toml: &str) -> CustomRiskRule {
+    CustomRiskRule::from_toml(&format!("[[rule]]\n{}", toml)).unwrap().remove(0)
+}
+
+fn legacy_vpn() -> CustomRiskRule {
+    rule(r#"name = "Legacy VPN"
+        when = { group = "^legacy-vpn" }
+        description = "access through the unsupported VPN concentrator"
+        risk_contribution = 20
+        severity = "High"
+        factor_type = "PrivilegedGroups""#)
+}
+
+fn temp_rules(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("ad-report-rules-{}-{}.toml", name, std::process::id()));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn group_rule_raises_a_named_factor() {
+    let rule = legacy_vpn();
+    let now = Utc::now();
+
+    assert!(rule.evaluate(&user(&["Staff"]), now).is_none());
+
+    let factor = rule.evaluate(&user(&["Staff", "Legacy-VPN-Users"]), now).unwrap();
+    assert_eq!(factor.risk_contribution, 20);
+    assert_eq!(factor.severity, RiskLevel::High);
+    assert!(matches!(factor.factor_type, RiskFactorType::PrivilegedGroups));
+    assert_eq!(factor.description, "Legacy VPN: access through the unsupported VPN concentrator (Legacy-VPN-Users)");
+}
+
+#[test]
+fn every_condition_must_hold() {
+    let rule = rule(r#"name = "Stale non-expiring password"
+        when = { uac_flag = "password_never_expires", inactive_days = 60 }
+        description = "idle account whose password never rotates"
+        risk_contribution = 15
+        severity = "Medium"
+        factor_type = "WeakAccountSecurity""#);
+    let now = Utc::now();
+
+    let mut account = user(&[]);
+    account.password_never_expires = true;
+    assert!(rule.evaluate(&account, now).is_none(), "logged on today");
+
+    account.last_logon = Some(now - Duration::days(60));
+    assert!(rule.evaluate(&account, now).is_some());
+
+    account.last_logon = None;
+    assert!(rule.evaluate(&account, now).is_some(), "never logged on counts as inactive");
+
+    account.password_never_expires = false;
+    assert!(rule.evaluate(&account, now).is_none());
+}
+
+#[test]
+fn calculator_scores_custom_rules_alongside_built_ins() {
+    let account = user(&["Legacy-VPN"]);
+    let baseline = RiskCalculator::new().calculate_risk(&account).unwrap();
+    let assessment = RiskCalculator::new()
+        .with_custom_rules(vec![legacy_vpn()])
+        .calculate_risk(&account).unwrap();
+
+    assert!(assessment.contributing_factors.iter().any(|f| f.description.starts_with("Legacy VPN: ")));
+    assert_eq!(
+        assessment.risk_breakdown.administrative_risk,
+        baseline.risk_breakdown.administrative_risk + 20
+    );
+    assert_eq!(assessment.risk_breakdown.activity_risk, baseline.risk_breakdown.activity_risk);
+    assert!(assessment.overall_score > baseline.overall_score);
+}
+
+#[test]
+fn rules_file_is_loaded_and_validated() {
+    let path = temp_rules("valid", r#"
+        [[rule]]
+        name = "Legacy VPN"
+        when = { group = "^Legacy-VPN$" }
+        description = "old VPN"
+        risk_contribution = 20
+        severity = "High"
+        factor_type = "PrivilegedGroups"
+
+        [[rule]]
+        name = "Dormant"
+        description = "idle"
+        risk_contribution = 5
+        severity = "Low"
+        factor_type = "DormantAccount"
+
+        [rule.when]
+        inactive_days = 30
+        uac_flag = "disabled"
+    "#);
+    let rules = CustomRiskRule::from_file(&path).unwrap();
+    assert_eq!(rules.len(), 2);
+    assert!(rules[0].evaluate(&user(&["legacy-vpn"]), Utc::now()).is_some(), "patterns are case-insensitive");
+
+    let invalid = [
+        ("no-conditions", r#"[[rule]]
+            name = "Empty"
+            when = {}
+            description = "x"
+            risk_contribution = 5
+            severity = "Low"
+            factor_type = "DataAccess""#),
+        ("bad-regex", r#"[[rule]]
+            name = "Bad"
+            when = { group = "(" }
+            description = "x"
+            risk_contribution = 5
+            severity = "Low"
+            factor_type = "DataAccess""#),
+        ("too-much", r#"[[rule]]
+            name = "Big"
+            when = { group = "x" }
+            description = "x"
+            risk_contribution = 101
+            severity = "Low"
+            factor_type = "DataAccess""#),
+        ("json", r#"[{"name": "Legacy VPN", "when": {"group": "x"}, "description": "x",
+            "risk_contribution": 5, "severity": "Low", "factor_type": "DataAccess"}]"#),
+    ];
+    for (name, contents) in invalid {
+        let path = temp_rules(name, contents);
+        assert!(CustomRiskRule::from_file(&path).is_err(), "{} should be rejected", name);
+        let _ = fs::remove_file(path);
+    }
+    let _ = fs::remove_file(path);
+}
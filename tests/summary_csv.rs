@@ -0,0 +1,81 @@
+use ad_report::models::ADUser;
+use ad_report::redaction::{hash_identifier, Redactor};
+use ad_report::report_data::EnhancedReportData;
+use ad_report::risk_calculator::RiskCalculator;
+use ad_report::summary_csv::{SummaryCsvWriter, SummaryRow};
+use chrono::{TimeZone, Utc};
+
+#[test]
+fn risk_breakdown_columns_follow_the_overall_score() {
+    let path = std::env::temp_dir().join(format!("ad-report-summary-{}.csv", std::process::id()));
+    let user = ADUser::new("CN=John Doe,OU=Staff,DC=corp,DC=example,DC=com".to_string(), "jdoe".to_string());
+    let risk = RiskCalculator::new().calculate_risk(&user).unwrap();
+    let breakdown = risk.risk_breakdown.clone();
+    let generation_time = Utc.with_ymd_and_hms(2024, 7, 1, 9, 0, 0).unwrap();
+    let data = EnhancedReportData::new_at(user, "corp.example.com".to_string(), "dc1".to_string(), Some(risk), generation_time);
+
+    let csv = SummaryCsvWriter::create(&path, false).unwrap();
+    csv.append(&SummaryRow::success(&data, "jdoe.pdf")).unwrap();
+    csv.append(&SummaryRow::failure("ghost", &anyhow::anyhow!("not found"))).unwrap();
+    drop(csv);
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(lines[0], "sam_account_name,status,risk_score,risk_level,administrative_risk,\
+permission_overlap_risk,account_security_risk,activity_risk,output_path,error");
+
+    let risk = data.risk_assessment.as_ref().unwrap();
+    assert_eq!(lines[1], format!("jdoe,ok,{},{:?},{},{},{},{},jdoe.pdf,",
+        risk.overall_score, risk.risk_level, breakdown.administrative_risk, breakdown.permission_overlap_risk,
+        breakdown.account_security_risk, breakdown.activity_risk));
+    // Users without a risk assessment leave the sub-scores blank
+    assert_eq!(lines[2], "ghost,failed,,,,,,,,not found");
+}
+
+#[test]
+fn failed_users_are_hashed_with_redact_hash_sam() {
+    let path = std::env::temp_dir().join(format!("ad-report-summary-redacted-{}.csv", std::process::id()));
+    let redactor = Redactor::new().with_hashed_sam_account_name(true);
+    let error = anyhow::anyhow!(redactor.redact_mentions("User 'ghost' not found", "ghost"));
+
+    let csv = SummaryCsvWriter::create(&path, false).unwrap();
+    csv.append(&SummaryRow::failure(&redactor.redact_account_name("ghost"), &error)).unwrap();
+    csv.append(&SummaryRow::skipped(&redactor.redact_account_name("svc.backup"), "account disabled")).unwrap();
+    drop(csv);
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    let lines: Vec<&str> = contents.lines().collect();
+    let hashed = hash_identifier("ghost");
+    assert_eq!(lines[1], format!("{},failed,,,,,,,,User '{}' not found", hashed, hashed));
+    assert!(lines[2].starts_with(&format!("{},skipped,", hash_identifier("svc.backup"))));
+    assert!(!contents.contains("ghost") && !contents.contains("svc.backup"));
+
+    // Without hashing, redaction leaves account names as given
+    let plain = Redactor::new();
+    assert_eq!(plain.redact_account_name("ghost"), "ghost");
+    assert_eq!(plain.redact_mentions("User 'ghost' not found", "ghost"), "User 'ghost' not found");
+}
+
+#[test]
+fn resuming_refuses_a_csv_with_other_columns() {
+    let path = std::env::temp_dir().join(format!("ad-report-summary-resume-{}.csv", std::process::id()));
+
+    // Resuming into a CSV this version wrote keeps appending below the same header
+    SummaryCsvWriter::create(&path, false).unwrap()
+        .append(&SummaryRow::skipped("svc.backup", "account disabled")).unwrap();
+    SummaryCsvWriter::create(&path, true).unwrap()
+        .append(&SummaryRow::skipped("ws01$", "computer account")).unwrap();
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(contents.lines().count(), 3);
+
+    // A CSV from before the risk breakdown columns is left untouched
+    let old = "sam_account_name,status,risk_score,risk_level,output_path,error\njdoe,ok,40,Medium,jdoe.pdf,\n";
+    std::fs::write(&path, old).unwrap();
+    let error = SummaryCsvWriter::create(&path, true).err().expect("mismatched header is refused");
+    assert!(format!("{:#}", error).contains("different columns"), "{:#}", error);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), old);
+
+    let _ = std::fs::remove_file(&path);
+}
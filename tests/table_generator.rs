@@ -0,0 +1,55 @@
+use ad_report::models::{ADGroup, ADUser};
+use ad_report::report_data::{EnhancedReportData, ReportSection};
+use ad_report::risk_calculator::RiskCalculator;
+use ad_report::table_generator::TableGenerator;
+use chrono::{TimeZone, Utc};
+
+fn fixture(group_count: usize) -> EnhancedReportData {
+    let mut user = ADUser::new("CN=John Doe,OU=Staff,DC=corp,DC=example,DC=com".to_string(), "jdoe".to_string());
+    user.display_name = Some("John Doe".to_string());
+    user.account_locked = true;
+    user.groups.push(ADGroup::new(
+        "CN=Domain Admins,CN=Users,DC=corp,DC=example,DC=com".to_string(),
+        "Domain Admins".to_string(),
+    ));
+    for i in 0..group_count {
+        user.groups.push(ADGroup::new(
+            format!("CN=App Group {:03},OU=Groups,DC=corp,DC=example,DC=com", i),
+            format!("App Group {:03}", i),
+        ));
+    }
+
+    let risk = RiskCalculator::new().calculate_risk(&user).unwrap();
+    let generation_time = Utc.with_ymd_and_hms(2024, 7, 1, 9, 0, 0).unwrap();
+    EnhancedReportData::new_at(user, "corp.example.com".to_string(), "dc1.corp.example.com".to_string(), Some(risk), generation_time)
+}
+
+#[test]
+fn table_shows_identity_status_score_groups_and_findings() {
+    let data = fixture(8);
+    let table = TableGenerator::new().generate_report(&data).unwrap();
+    let risk = data.risk_assessment.as_ref().unwrap();
+
+    assert!(table.contains("│ Name"), "{}", table);
+    assert!(table.contains("John Doe"), "{}", table);
+    assert!(table.contains("Enabled, Locked"), "{}", table);
+    assert!(table.contains(&format!("Risk Score: {}/100 ({:?})", risk.overall_score, risk.risk_level)), "{}", table);
+    assert!(table.contains("Top Groups (9 direct, 9 total)"), "{}", table);
+    assert!(table.contains("Domain Admins"), "{}", table);
+    assert!(table.contains("(+4 more)"), "{}", table);
+    assert!(table.contains("Top Findings"), "{}", table);
+    let top_finding = risk.contributing_factors.iter().max_by_key(|f| f.risk_contribution).unwrap();
+    assert!(table.contains(&top_finding.description), "{}", table);
+}
+
+#[test]
+fn table_respects_section_selection() {
+    let table = TableGenerator::new()
+        .with_sections(vec![ReportSection::Groups])
+        .generate_report(&fixture(0))
+        .unwrap();
+
+    assert!(table.contains("Top Groups"));
+    assert!(!table.contains("Risk Score"));
+    assert!(!table.contains("John Doe"));
+}
@@ -0,0 +1,71 @@
+use ad_report::models::{ADGroup, ADUser};
+use ad_report::report_data::EnhancedReportData;
+use ad_report::risk_calculator::RiskCalculator;
+use ad_report::webhook::{ReportNotification, Webhook};
+use chrono::{TimeZone, Utc};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+fn report_data() -> EnhancedReportData {
+    let mut user = ADUser::new("CN=John Doe,OU=Staff,DC=corp,DC=example,DC=com".to_string(), "jdoe".to_string());
+    user.groups.push(ADGroup::new(
+        "CN=Domain Admins,CN=Users,DC=corp,DC=example,DC=com".to_string(),
+        "Domain Admins".to_string(),
+    ));
+    let risk = RiskCalculator::new().calculate_risk(&user).unwrap();
+    let generation_time = Utc.with_ymd_and_hms(2024, 7, 1, 9, 0, 0).unwrap();
+    EnhancedReportData::new_at(user, "corp.example.com".to_string(), "dc1".to_string(), Some(risk), generation_time)
+}
+
+/// Accept one HTTP request, answer 200 and return its body
+async fn receive_one(listener: TcpListener) -> String {
+    let (mut socket, _) = listener.accept().await.unwrap();
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 4096];
+    loop {
+        let read = socket.read(&mut buffer).await.unwrap();
+        request.extend_from_slice(&buffer[..read]);
+        let text = String::from_utf8_lossy(&request);
+        if let Some((headers, body)) = text.split_once("\r\n\r\n") {
+            let length = headers.lines()
+                .find_map(|l| l.to_ascii_lowercase().strip_prefix("content-length:").map(|v| v.trim().parse::<usize>().unwrap()))
+                .unwrap_or(0);
+            if body.len() >= length {
+                socket.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n").await.unwrap();
+                return body.to_string();
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn report_summary_is_posted_as_json() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let server = tokio::spawn(receive_one(listener));
+
+    let data = report_data();
+    let webhook = Webhook::new(&url, Duration::from_secs(5)).unwrap();
+    webhook.notify(&ReportNotification::new(&data, "jdoe.pdf")).await;
+
+    let body: serde_json::Value = serde_json::from_str(&server.await.unwrap()).unwrap();
+    let risk = data.risk_assessment.as_ref().unwrap();
+    assert_eq!(body["user"], "jdoe");
+    assert_eq!(body["risk_score"], risk.overall_score);
+    assert_eq!(body["risk_level"], format!("{:?}", risk.risk_level));
+    assert_eq!(body["output_path"], "jdoe.pdf");
+    let findings = &body["findings"];
+    let total: u64 = ["critical", "high", "medium", "low"].iter().map(|s| findings[s].as_u64().unwrap()).sum();
+    assert_eq!(total as usize, risk.contributing_factors.len());
+}
+
+#[tokio::test]
+async fn unreachable_endpoint_does_not_fail() {
+    // Bind and drop a listener so the port is (almost certainly) closed
+    let port = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap().port();
+    let webhook = Webhook::new(&format!("http://127.0.0.1:{}/hook", port), Duration::from_secs(1)).unwrap();
+    webhook.notify(&ReportNotification::new(&report_data(), "jdoe.pdf")).await;
+
+    assert!(Webhook::new("not a url", Duration::from_secs(1)).is_err());
+}
@@ -0,0 +1,42 @@
+// The credential cache is only read by Unix builds with the `gssapi` feature
+#![cfg(all(not(windows), feature = "gssapi"))]
+
+use ad_report::windows_auth::parse_ccache_principal;
+
+/// File credential cache header and default principal, as written by MIT krb5 `kinit`
+fn ccache(version: u8, realm: &str, components: &[&str]) -> Vec<u8> {
+    let mut bytes = vec![0x05, version];
+    if version == 4 {
+        // One 8-byte header field (KDC time offset)
+        bytes.extend_from_slice(&12u16.to_be_bytes());
+        bytes.extend_from_slice(&[0x00, 0x01, 0x00, 0x08, 0, 0, 0, 0, 0, 0, 0, 0]);
+    }
+    bytes.extend_from_slice(&1u32.to_be_bytes());
+    bytes.extend_from_slice(&(components.len() as u32).to_be_bytes());
+    for value in std::iter::once(&realm).chain(components) {
+        bytes.extend_from_slice(&(value.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(value.as_bytes());
+    }
+    // Credentials follow the principal and are ignored
+    bytes.extend_from_slice(&[0xde, 0xad]);
+    bytes
+}
+
+#[test]
+fn principal_is_read_from_the_credential_cache() {
+    let expected = Some(("CORP.EXAMPLE.COM".to_string(), "jdoe".to_string()));
+    assert_eq!(parse_ccache_principal(&ccache(4, "CORP.EXAMPLE.COM", &["jdoe"])), expected);
+    assert_eq!(parse_ccache_principal(&ccache(3, "CORP.EXAMPLE.COM", &["jdoe"])), expected);
+    assert_eq!(
+        parse_ccache_principal(&ccache(4, "CORP.EXAMPLE.COM", &["svc", "host01"])),
+        Some(("CORP.EXAMPLE.COM".to_string(), "svc/host01".to_string()))
+    );
+}
+
+#[test]
+fn unreadable_caches_yield_no_principal() {
+    let valid = ccache(4, "CORP.EXAMPLE.COM", &["jdoe"]);
+    assert_eq!(parse_ccache_principal(&valid[..20]), None);
+    assert_eq!(parse_ccache_principal(&ccache(2, "CORP.EXAMPLE.COM", &["jdoe"])), None);
+    assert_eq!(parse_ccache_principal(b""), None);
+}